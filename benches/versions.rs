@@ -0,0 +1,104 @@
+use criterion::{criterion_group, criterion_main, BatchSize, Criterion, Throughput};
+use pyver::collections::sort_versions;
+use pyver::{PackageVersion, PackageVersionRef, Validator};
+use std::hint::black_box;
+
+/// Version strings as published on PyPI, see `benches/data/versions.txt`
+fn corpus() -> Vec<&'static str> {
+    include_str!("data/versions.txt")
+        .lines()
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .collect()
+}
+
+/// The corpus repeated to `len` versions in a fixed scrambled order,
+/// as an index listing many projects would be
+fn scrambled(len: usize) -> Vec<&'static str> {
+    let corpus = corpus();
+    (0..len)
+        .map(|i| corpus[i.wrapping_mul(7919) % corpus.len()])
+        .collect()
+}
+
+fn parse(c: &mut Criterion) {
+    let mut group = c.benchmark_group("parse");
+    for version in ["2.28.1", "v1!2.0.0-RC.1.post2.dev3+ubuntu.1"] {
+        group.bench_function(version, |b| {
+            b.iter(|| PackageVersion::new(black_box(version)))
+        });
+    }
+
+    let corpus = corpus();
+    group.throughput(Throughput::Elements(corpus.len() as u64));
+    group.bench_function("corpus", |b| {
+        b.iter(|| {
+            for version in &corpus {
+                black_box(PackageVersion::new(version).unwrap());
+            }
+        })
+    });
+    group.bench_function("corpus borrowed", |b| {
+        b.iter(|| {
+            for version in &corpus {
+                black_box(PackageVersionRef::new(version).unwrap());
+            }
+        })
+    });
+    group.bench_function("corpus validated", |b| {
+        let validator = Validator::new();
+        b.iter(|| validator.validate_many(black_box(&corpus)))
+    });
+    group.finish();
+}
+
+fn compare(c: &mut Criterion) {
+    let versions: Vec<PackageVersion> = corpus()
+        .iter()
+        .map(|v| PackageVersion::new(v).unwrap())
+        .collect();
+    let keys: Vec<_> = versions.iter().map(|v| v.cmp_key()).collect();
+
+    let mut group = c.benchmark_group("compare");
+    group.throughput(Throughput::Elements(versions.len() as u64 - 1));
+    group.bench_function("versions", |b| {
+        b.iter(|| versions.windows(2).filter(|w| w[0] < w[1]).count())
+    });
+    group.bench_function("keys", |b| {
+        b.iter(|| keys.windows(2).filter(|w| w[0] < w[1]).count())
+    });
+    group.finish();
+}
+
+fn sort(c: &mut Criterion) {
+    let mut group = c.benchmark_group("sort");
+    for len in [1_000, 100_000] {
+        let strings = scrambled(len);
+        let versions: Vec<PackageVersion> = strings
+            .iter()
+            .map(|v| PackageVersion::new(v).unwrap())
+            .collect();
+
+        group.throughput(Throughput::Elements(len as u64));
+        group.bench_function(format!("parsed {}", len), |b| {
+            b.iter_batched(
+                || versions.clone(),
+                |mut versions| versions.sort(),
+                BatchSize::LargeInput,
+            )
+        });
+        group.bench_function(format!("cached keys {}", len), |b| {
+            b.iter_batched(
+                || versions.clone(),
+                |mut versions| versions.sort_by_cached_key(|v| v.cmp_key()),
+                BatchSize::LargeInput,
+            )
+        });
+        group.bench_function(format!("strings {}", len), |b| {
+            b.iter(|| sort_versions(black_box(&strings).iter().copied()))
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, parse, compare, sort);
+criterion_main!(benches);
@@ -0,0 +1,43 @@
+//! Ordering, equality, hashing and sort keys of versions agree
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use pyver::specifiers::Specifier;
+use pyver::PackageVersion;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+
+fn hash(version: &PackageVersion) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    version.hash(&mut hasher);
+    hasher.finish()
+}
+
+fuzz_target!(|versions: (PackageVersion, PackageVersion)| {
+    let (a, b) = versions;
+    let ordering = a.cmp(&b);
+    assert_eq!(ordering, b.cmp(&a).reverse());
+    assert_eq!(ordering, a.cmp_key().cmp(&b.cmp_key()));
+    assert_eq!(ordering.is_eq(), a == b);
+    if a == b {
+        assert_eq!(hash(&a), hash(&b));
+    }
+
+    // Only the local versions of `a` lie between it and its successor
+    if let Some(next) = a.smallest_greater_than() {
+        assert!(next > a);
+        let local_of_a = b.without_local() == a.without_local();
+        assert!(b <= a || b >= next || local_of_a, "{} < {} < {}", a, b, next);
+    }
+
+    // `==` also matches the local versions of a public version
+    let pinned = Specifier::new(&format!("=={}", a)).unwrap();
+    assert!(pinned.contains(&a));
+    if a == b {
+        assert!(pinned.contains(&b));
+    }
+    if pinned.contains(&b) {
+        assert_eq!(b.without_local(), a.without_local());
+    }
+});
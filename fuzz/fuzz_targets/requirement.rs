@@ -0,0 +1,14 @@
+//! Any string accepted as a requirement reads back as the same
+//! requirement from the string it's written as
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use pyver::Requirement;
+
+fuzz_target!(|requirement: &str| {
+    if let Ok(parsed) = Requirement::new(requirement) {
+        let written = parsed.to_string();
+        assert_eq!(Requirement::new(&written).unwrap(), parsed, "{}", written);
+    }
+});
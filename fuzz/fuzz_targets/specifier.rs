@@ -0,0 +1,17 @@
+//! Any string accepted as a specifier or a set of them reads back
+//! as the same specifiers from the string it's written as
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use pyver::specifiers::{Specifier, SpecifierSet};
+
+fuzz_target!(|specifiers: &str| {
+    if let Ok(specifier) = Specifier::new(specifiers) {
+        assert_eq!(Specifier::new(&specifier.to_string()).unwrap(), specifier);
+    }
+    if let Ok(set) = SpecifierSet::new(specifiers) {
+        assert_eq!(SpecifierSet::new(&set.to_string()).unwrap(), set);
+        assert!(set.len() <= specifiers.len());
+    }
+});
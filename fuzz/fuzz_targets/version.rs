@@ -0,0 +1,33 @@
+//! Any string accepted as a version reads back as the same version,
+//! both as written and in its normal form
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use pyver::{PackageVersion, PackageVersionRef};
+
+fuzz_target!(|version: &str| {
+    let borrowed = PackageVersionRef::new(version);
+    let parsed = match PackageVersion::new(version) {
+        Ok(parsed) => parsed,
+        Err(_e) => {
+            assert!(borrowed.is_err(), "{:?}", version);
+            return;
+        }
+    };
+    assert_eq!(borrowed.unwrap().to_version(), parsed);
+
+    let normalized = parsed.to_normalized_string();
+    assert_eq!(PackageVersion::new(&normalized).unwrap(), parsed);
+    assert_eq!(PackageVersion::new(&parsed.to_string()).unwrap(), parsed);
+    assert!(PackageVersion::new(&normalized).unwrap().is_canonical());
+
+    // Normalizing only spells out implicit numbers and keywords,
+    // so it never grows a version by more than a few characters
+    assert!(
+        normalized.len() <= version.len() + 16,
+        "{:?} normalized to {:?}",
+        version,
+        normalized
+    );
+});
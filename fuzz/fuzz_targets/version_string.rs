@@ -0,0 +1,20 @@
+//! Hand-written spellings of versions, some of them broken, are either
+//! rejected or read as the version of their normal form
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use pyver::fuzzing::VersionString;
+use pyver::{PackageVersion, Validator};
+
+fuzz_target!(|written: VersionString| {
+    let VersionString(written) = written;
+    if let Ok(parsed) = PackageVersion::new(&written) {
+        // The validator only checks the syntax, not whether numbers overflow
+        assert!(Validator::new().validate(&written).is_ok());
+
+        let normalized = PackageVersion::new(&parsed.to_normalized_string()).unwrap();
+        assert_eq!(normalized, parsed);
+        assert_eq!(normalized.cmp_key(), parsed.cmp_key());
+    }
+});
@@ -0,0 +1,76 @@
+#ifndef PYVER_H
+#define PYVER_H
+
+/* Generated by cbindgen from src/ffi.rs, don't edit it by hand */
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+/**
+ * # C version
+ * A parsed version, only handled through pointers from [`pyver_parse`]
+ */
+typedef struct PyverVersion PyverVersion;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+/**
+ * Parses a version, returning null if it isn't valid
+ *
+ * # Safety
+ * `version` is null or points to a NUL-terminated string
+ */
+struct PyverVersion *pyver_parse(const char *version);
+
+/**
+ * Frees a version from [`pyver_parse`], null is ignored
+ *
+ * # Safety
+ * `version` is null or came from [`pyver_parse`] and wasn't freed yet
+ */
+void pyver_version_free(struct PyverVersion *version);
+
+/**
+ * `-1`, `0` or `1` as `a` sorts before, the same as or after `b`
+ *
+ * # Safety
+ * `a` and `b` are versions from [`pyver_parse`]
+ */
+int pyver_compare(const struct PyverVersion *a, const struct PyverVersion *b);
+
+/**
+ * `1` if the version matches every specifier (`>=1.0, <2.0`), `0` if it
+ * doesn't and `-1` if the specifiers aren't valid
+ *
+ * # Safety
+ * `version` is a version from [`pyver_parse`] and `specifiers` is null or
+ * points to a NUL-terminated string
+ */
+int pyver_satisfies(const struct PyverVersion *version, const char *specifiers);
+
+/**
+ * The version in normal form (`1.0rc1` for `v1.0-RC1`), freed with
+ * [`pyver_string_free`]
+ *
+ * # Safety
+ * `version` is a version from [`pyver_parse`]
+ */
+char *pyver_normalize(const struct PyverVersion *version);
+
+/**
+ * Frees a string returned by the library, null is ignored
+ *
+ * # Safety
+ * `string` is null or came from [`pyver_normalize`] and wasn't freed yet
+ */
+void pyver_string_free(char *string);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* PYVER_H */
@@ -0,0 +1,130 @@
+/*!
+# Version literals for `pyver`
+The `pyver!` macro, checking `PEP-440` version literals at compile time.
+
+Use it through the `macros` feature of the `pyver` crate,
+which re-exports it as `pyver::pyver`.
+*/
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use regex::{Captures, Regex};
+use syn::{parse_macro_input, LitStr};
+
+/// The version regex of `pyver`'s validator
+/// (<https://peps.python.org/pep-0440/#appendix-b-parsing-version-strings-with-regular-expressions>)
+static VERSION_PATTERN: &str = r"^(?i:v?(?:(?P<epoch>[0-9]+)!)?(?P<release>[0-9]+(?:\.[0-9]+)*)(?P<pre>[-_.]?(?P<pre_l>preview|alpha|beta|pre|rc|a|b|c)[-_.]?(?P<pre_n>[0-9]+)?)?(?P<post>-(?P<post_n1>[0-9]+)|[-_.]?(?P<post_l>post|rev|r)[-_.]?(?P<post_n2>[0-9]+)?)?(?P<dev>[-_.]?(?P<dev_l>dev)[-_.]?(?P<dev_n>[0-9]+)?)?(?:\+(?P<local>[a-z0-9]+(?:(?:[-_.][a-z0-9]+)+)?))?)$";
+
+/// A `PackageVersion` from a string literal, invalid versions
+/// are rejected by the compiler
+///
+/// The literal is parsed while compiling, so creating the version
+/// at runtime doesn't run the validator.
+#[proc_macro]
+pub fn pyver(input: TokenStream) -> TokenStream {
+    let literal = parse_macro_input!(input as LitStr);
+    match expand(&literal.value()) {
+        Ok(tokens) => tokens.into(),
+        Err(message) => syn::Error::new(literal.span(), message)
+            .to_compile_error()
+            .into(),
+    }
+}
+
+fn expand(version: &str) -> Result<TokenStream2, String> {
+    // Safe to unwrap since the pattern is predefined
+    let validator = Regex::new(VERSION_PATTERN).unwrap();
+    let captures = match validator.captures(version.trim()) {
+        Some(captures) => captures,
+        None => return Err(format!("Failed to decode version {}", version)),
+    };
+
+    let epoch = option(number(&captures, "epoch")?);
+    let release = captures["release"]
+        .split('.')
+        .map(parse)
+        .collect::<Result<Vec<u64>, String>>()?;
+
+    let pre = match captures.name("pre_l") {
+        Some(label) => {
+            let n = option(number(&captures, "pre_n")?);
+            let variant = match label.as_str().to_ascii_lowercase().as_str() {
+                "alpha" | "a" => quote!(Alpha),
+                "beta" | "b" => quote!(Beta),
+                "rc" | "c" => quote!(ReleaseCandidate),
+                _ => quote!(Preview),
+            };
+            quote!(Some(::pyver::ids::PreHeader::#variant(#n)))
+        }
+        None => quote!(None),
+    };
+
+    let post = match captures.name("post") {
+        Some(_) => {
+            let post_head = match captures.name("post_l") {
+                Some(label) if label.as_str().eq_ignore_ascii_case("post") => {
+                    quote!(Some(::pyver::ids::PostHead::Post))
+                }
+                Some(_) => quote!(Some(::pyver::ids::PostHead::Rev)),
+                None => quote!(None),
+            };
+            let post_num = match number(&captures, "post_n1")? {
+                Some(n) => option(Some(n)),
+                None => option(number(&captures, "post_n2")?),
+            };
+            quote!(Some(::pyver::ids::PostHeader {
+                post_head: #post_head,
+                post_num: #post_num,
+            }))
+        }
+        None => quote!(None),
+    };
+
+    let dev = match captures.name("dev") {
+        Some(_) => {
+            let dev_num = option(number(&captures, "dev_n")?);
+            quote!(Some(::pyver::ids::DevHead { dev_num: #dev_num }))
+        }
+        None => quote!(None),
+    };
+
+    let local = match captures.name("local") {
+        Some(local) => {
+            let local = local.as_str();
+            quote!(Some(#local))
+        }
+        None => quote!(None),
+    };
+
+    Ok(quote! {
+        ::pyver::PackageVersion::__from_literal(
+            #version,
+            #epoch,
+            &[#(#release),*],
+            #pre,
+            #post,
+            #dev,
+            #local,
+        )
+    })
+}
+
+/// The number in an optional regex group
+fn number(captures: &Captures, group: &str) -> Result<Option<u32>, String> {
+    captures.name(group).map(|n| parse(n.as_str())).transpose()
+}
+
+fn parse<T: std::str::FromStr<Err = std::num::ParseIntError>>(
+    n: &str,
+) -> Result<T, String> {
+    n.parse::<T>()
+        .map_err(|e| format!("Invalid version number {}: {}", n, e))
+}
+
+fn option(n: Option<u32>) -> TokenStream2 {
+    match n {
+        Some(n) => quote!(Some(#n)),
+        None => quote!(None),
+    }
+}
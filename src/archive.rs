@@ -0,0 +1,291 @@
+use crate::ids::{
+    ArchivedDevHead, ArchivedPostHead, ArchivedPostHeader, ArchivedPreHeader, DevHead,
+    PostHead, PostHeader, PreHeader,
+};
+use crate::version::{cmp_local, ArchivedPackageVersion};
+use crate::PackageVersion;
+use core::cmp::Ordering;
+use rkyv::option::ArchivedOption;
+use rkyv::Archived;
+
+/// Accessors of an archived version, mirroring [`PackageVersion`]
+///
+/// Archived versions are ordered like versions, so a sorted table can
+/// be searched right where it's memory-mapped
+///
+/// ```
+/// use pyver::{ArchivedPackageVersion, PackageVersion};
+/// use rkyv::rancor::Error;
+/// use rkyv::vec::ArchivedVec;
+///
+/// let versions: Vec<PackageVersion> = ["1.0", "1.1rc1", "1.1", "2.0.post1"]
+///     .iter()
+///     .map(|v| PackageVersion::new(v).unwrap())
+///     .collect();
+/// let bytes = rkyv::to_bytes::<Error>(&versions).unwrap();
+///
+/// let archived =
+///     rkyv::access::<ArchivedVec<ArchivedPackageVersion>, Error>(&bytes).unwrap();
+/// let wanted = PackageVersion::new("1.1.0").unwrap();
+/// assert_eq!(archived.binary_search_by(|v| v.partial_cmp(&wanted).unwrap()), Ok(2));
+/// assert!(archived[1] < archived[2] && archived[1].pre().is_some());
+/// ```
+impl ArchivedPackageVersion {
+    /// The epoch, `0` if the version has none
+    pub fn epoch(&self) -> u32 {
+        native(&self.epoch).unwrap_or(0)
+    }
+
+    /// The release segments (`1`, `2` and `3` for `1.2.3`)
+    pub fn release_segments(&self) -> impl Iterator<Item = u64> + '_ {
+        self.release
+            .segments
+            .iter()
+            .map(|segment| segment.to_native())
+    }
+
+    pub fn pre(&self) -> Option<PreHeader> {
+        self.pre.as_ref().map(|pre| match pre {
+            ArchivedPreHeader::Beta(n) => PreHeader::Beta(native(n)),
+            ArchivedPreHeader::Alpha(n) => PreHeader::Alpha(native(n)),
+            ArchivedPreHeader::Preview(n) => PreHeader::Preview(native(n)),
+            ArchivedPreHeader::ReleaseCandidate(n) => {
+                PreHeader::ReleaseCandidate(native(n))
+            }
+        })
+    }
+
+    pub fn post(&self) -> Option<PostHeader> {
+        self.post.as_ref().map(
+            |ArchivedPostHeader {
+                 post_head,
+                 post_num,
+             }| PostHeader {
+                post_head: post_head.as_ref().map(|head| match head {
+                    ArchivedPostHead::Post => PostHead::Post,
+                    ArchivedPostHead::Rev => PostHead::Rev,
+                }),
+                post_num: native(post_num),
+            },
+        )
+    }
+
+    pub fn dev(&self) -> Option<DevHead> {
+        self.dev
+            .as_ref()
+            .map(|ArchivedDevHead { dev_num }| DevHead {
+                dev_num: native(dev_num),
+            })
+    }
+
+    /// The local version label as written, without the `+`
+    pub fn local(&self) -> Option<&str> {
+        self.local.as_ref().map(|local| &**local)
+    }
+
+    /// Whether this is the archived [`PackageVersion::MAX`]
+    pub fn is_max(&self) -> bool {
+        self.release.segments.is_empty() && native(&self.epoch) == Some(u32::MAX)
+    }
+}
+
+fn native(number: &ArchivedOption<Archived<u32>>) -> Option<u32> {
+    number.as_ref().map(|number| number.to_native())
+}
+
+/// The components versions are ordered by, borrowed from
+/// either an owned or an archived version
+struct SortParts<'a> {
+    max: bool,
+    epoch: u32,
+    release: Release<'a>,
+    /// A version of only the pre, post and dev segments,
+    /// to reuse their sort keys
+    suffixes: PackageVersion,
+    local: Option<&'a str>,
+}
+
+enum Release<'a> {
+    Owned(&'a [u64]),
+    Archived(&'a [Archived<u64>]),
+}
+
+impl Release<'_> {
+    fn len(&self) -> usize {
+        match self {
+            Release::Owned(segments) => segments.len(),
+            Release::Archived(segments) => segments.len(),
+        }
+    }
+
+    fn segment(&self, index: usize) -> u64 {
+        match self {
+            Release::Owned(segments) => segments.get(index).copied(),
+            Release::Archived(segments) => segments.get(index).map(|s| s.to_native()),
+        }
+        .unwrap_or(0)
+    }
+}
+
+impl<'a> SortParts<'a> {
+    fn owned(version: &'a PackageVersion) -> Self {
+        Self {
+            max: version.is_max(),
+            epoch: version.epoch(),
+            release: Release::Owned(&version.release.segments),
+            suffixes: PackageVersion::suffixes(version.pre, version.post, version.dev),
+            local: version.local(),
+        }
+    }
+
+    fn archived(version: &'a ArchivedPackageVersion) -> Self {
+        Self {
+            max: version.is_max(),
+            epoch: version.epoch(),
+            release: Release::Archived(&version.release.segments),
+            suffixes: PackageVersion::suffixes(
+                version.pre(),
+                version.post(),
+                version.dev(),
+            ),
+            local: version.local(),
+        }
+    }
+
+    /// The same order as [`PackageVersion::cmp`], without allocating
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self.max, other.max) {
+            (true, true) => return Ordering::Equal,
+            (true, false) => return Ordering::Greater,
+            (false, true) => return Ordering::Less,
+            (false, false) => {}
+        }
+        // Trailing zeros are insignificant, so the shorter release is padded
+        let release_len = self.release.len().max(other.release.len());
+        let release = (0..release_len)
+            .map(|i| self.release.segment(i).cmp(&other.release.segment(i)))
+            .find(|ordering| ordering.is_ne())
+            .unwrap_or(Ordering::Equal);
+
+        self.epoch
+            .cmp(&other.epoch)
+            .then(release)
+            .then_with(|| self.suffixes.pre_key().cmp(&other.suffixes.pre_key()))
+            .then_with(|| self.suffixes.post_key().cmp(&other.suffixes.post_key()))
+            .then_with(|| self.suffixes.dev_key().cmp(&other.suffixes.dev_key()))
+            .then_with(|| cmp_local(self.local, other.local))
+    }
+}
+
+impl PartialEq for ArchivedPackageVersion {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other).is_eq()
+    }
+}
+
+impl Eq for ArchivedPackageVersion {}
+
+impl PartialOrd for ArchivedPackageVersion {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for ArchivedPackageVersion {
+    fn cmp(&self, other: &Self) -> Ordering {
+        SortParts::archived(self).cmp(&SortParts::archived(other))
+    }
+}
+
+impl PartialEq<PackageVersion> for ArchivedPackageVersion {
+    fn eq(&self, other: &PackageVersion) -> bool {
+        self.partial_cmp(other) == Some(Ordering::Equal)
+    }
+}
+
+impl PartialOrd<PackageVersion> for ArchivedPackageVersion {
+    fn partial_cmp(&self, other: &PackageVersion) -> Option<Ordering> {
+        Some(SortParts::archived(self).cmp(&SortParts::owned(other)))
+    }
+}
+
+impl PartialEq<ArchivedPackageVersion> for PackageVersion {
+    fn eq(&self, other: &ArchivedPackageVersion) -> bool {
+        other == self
+    }
+}
+
+impl PartialOrd<ArchivedPackageVersion> for PackageVersion {
+    fn partial_cmp(&self, other: &ArchivedPackageVersion) -> Option<Ordering> {
+        other.partial_cmp(self).map(Ordering::reverse)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{ArchivedPackageVersion, PackageVersion};
+    use anyhow::Result;
+    use rkyv::rancor::Error;
+    use rkyv::vec::ArchivedVec;
+
+    #[test]
+    fn test_archived_ordering() -> Result<()> {
+        let versions = [
+            "1.0.dev0",
+            "1.0a1",
+            "1.0a1.post1.dev1",
+            "1.0rc1",
+            "v1.0",
+            "1.0.0",
+            "1.0+abc.5",
+            "1.0+ABC-10",
+            "1.0+abc.099999999999999999999",
+            "1.0+abc.x",
+            "1.0.post1.dev2",
+            "1.0.post1",
+            "1.0.1",
+            "1!0.1",
+        ];
+        let mut owned: Vec<PackageVersion> = versions
+            .iter()
+            .map(|v| PackageVersion::new(v))
+            .collect::<Result<_>>()?;
+        owned.push(PackageVersion::MAX);
+        owned.push(PackageVersion::MIN);
+
+        let bytes = rkyv::to_bytes::<Error>(&owned)?;
+        let archived =
+            rkyv::access::<ArchivedVec<ArchivedPackageVersion>, Error>(&bytes)?;
+
+        for (a, archived_a) in owned.iter().zip(archived.iter()) {
+            for (b, archived_b) in owned.iter().zip(archived.iter()) {
+                assert_eq!(archived_a.cmp(archived_b), a.cmp(b), "{} {}", a, b);
+                assert_eq!(archived_a.partial_cmp(b), Some(a.cmp(b)), "{} {}", a, b);
+                assert_eq!(a.partial_cmp(archived_b), Some(a.cmp(b)), "{} {}", a, b);
+            }
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_archived_roundtrip() -> Result<()> {
+        let version = PackageVersion::new("v2!1.2.3.4rc1.post2.dev3+Local.7")?;
+        let bytes = rkyv::to_bytes::<Error>(&version)?;
+        let archived = rkyv::access::<ArchivedPackageVersion, Error>(&bytes)?;
+
+        assert_eq!(archived.epoch(), 2);
+        assert_eq!(
+            archived.release_segments().collect::<Vec<_>>(),
+            [1, 2, 3, 4]
+        );
+        assert_eq!(archived.pre(), version.pre().copied());
+        assert_eq!(archived.post(), version.post().copied());
+        assert_eq!(archived.dev(), version.dev().copied());
+        assert_eq!(archived.local(), Some("Local.7"));
+
+        let deserialized: PackageVersion = rkyv::deserialize::<_, Error>(archived)?;
+        assert_eq!(deserialized, version);
+        assert_eq!(deserialized.to_string(), version.to_string());
+        Ok(())
+    }
+}
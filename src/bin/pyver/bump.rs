@@ -0,0 +1,173 @@
+//! `pyver bump`, the next version for a release script
+
+use crate::output::{write_one, Format, VersionRecord};
+use anyhow::{Context, Result};
+use clap::{Args, ValueEnum};
+use pyver::pyproject::PyProject;
+use pyver::PackageVersion;
+use serde::Serialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Args)]
+pub struct BumpArgs {
+    /// The part of the version to increment
+    part: Part,
+    /// The version to bump, the one of the `pyproject.toml` with
+    /// `--pyproject` if it's left out
+    #[arg(required_unless_present = "pyproject")]
+    version: Option<String>,
+    /// Write the new version to `[project] version` of this
+    /// `pyproject.toml`, keeping the rest of the file as it is
+    #[arg(long, value_name = "PATH")]
+    pyproject: Option<PathBuf>,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize, ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum Part {
+    /// `1.4.2` to `2.0.0`
+    Major,
+    /// `1.4.2` to `1.5.0`
+    Minor,
+    /// `1.4.2` to `1.4.3`
+    Micro,
+    /// `1.0rc1` to `1.0rc2`
+    Pre,
+    /// `1.0` to `1.0.post0`, `1.0.post0` to `1.0.post1`
+    Post,
+    /// `1.1.dev0` to `1.1.dev1`, `1.1` to `1.1.dev0`
+    Dev,
+    /// `1.0rc2` to `1.0`
+    Final,
+}
+
+/// The version before and after the bump
+#[derive(Debug, Serialize)]
+struct BumpRecord<'a> {
+    version: VersionRecord<'a>,
+    part: Part,
+    bumped: VersionRecord<'a>,
+}
+
+pub fn run(args: BumpArgs, format: Format) -> Result<()> {
+    let version = match &args.version {
+        Some(version) => Some(PackageVersion::new(version)?),
+        None => None,
+    };
+    let (version, bumped) = match &args.pyproject {
+        Some(path) => bump_pyproject(path, version, args.part)?,
+        None => match version {
+            Some(version) => {
+                let bumped = bump(&version, args.part)?;
+                (version, bumped)
+            }
+            None => anyhow::bail!("There is no version to bump"),
+        },
+    };
+
+    let (input, text) = (version.to_string(), bumped.to_string());
+    let record = BumpRecord {
+        version: VersionRecord::valid(&input, &version),
+        part: args.part,
+        bumped: VersionRecord::valid(&text, &bumped),
+    };
+    write_one(format, &text, &record)
+}
+
+fn bump(version: &PackageVersion, part: Part) -> Result<PackageVersion> {
+    match part {
+        Part::Major => version.bump_major(),
+        Part::Minor => version.bump_minor(),
+        Part::Micro => version.bump_micro(),
+        Part::Pre => version.bump_pre(),
+        Part::Post => version.bump_post(),
+        Part::Dev => version.bump_dev(),
+        Part::Final => Ok(version.finalize()),
+    }
+}
+
+/// Bumps the version, or the one of the file if there is none, and writes
+/// it to the file, returns the version before and after the bump
+fn bump_pyproject(
+    path: &Path,
+    version: Option<PackageVersion>,
+    part: Part,
+) -> Result<(PackageVersion, PackageVersion)> {
+    let content = fs::read_to_string(path)
+        .with_context(|| format!("Can't read {}", path.display()))?;
+    let mut pyproject = PyProject::new(&content)
+        .with_context(|| format!("Can't parse {}", path.display()))?;
+
+    let version = match version {
+        Some(version) => version,
+        None => match pyproject.version()? {
+            Some(version) => version,
+            None => anyhow::bail!("{} has no project.version", path.display()),
+        },
+    };
+    let bumped = bump(&version, part)?;
+    pyproject
+        .set_version(&bumped)
+        .with_context(|| format!("Can't update {}", path.display()))?;
+    fs::write(path, pyproject.to_string())
+        .with_context(|| format!("Can't write {}", path.display()))?;
+    Ok((version, bumped))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{bump, bump_pyproject, Part};
+    use anyhow::Result;
+    use pyver::PackageVersion;
+    use std::fs;
+
+    #[test]
+    fn test_bump() {
+        let bumps = [
+            ("1.4.2", Part::Major, "2.0.0"),
+            ("1.4.2", Part::Minor, "1.5.0"),
+            ("1.4", Part::Micro, "1.4.1"),
+            ("1.0rc1", Part::Pre, "1.0rc2"),
+            ("1.0.post0", Part::Post, "1.0.post1"),
+            ("1.1", Part::Dev, "1.1.dev0"),
+            ("1.0rc2.dev1+local", Part::Final, "1.0"),
+        ];
+        for (version, part, expected) in bumps {
+            let version = PackageVersion::new(version).unwrap();
+            assert_eq!(bump(&version, part).unwrap().to_string(), expected);
+        }
+        assert!(bump(&PackageVersion::new("1.0").unwrap(), Part::Pre).is_err());
+    }
+
+    #[test]
+    fn test_bump_pyproject() -> Result<()> {
+        let path = std::env::temp_dir()
+            .join(format!("pyver-bump-{}.toml", std::process::id()));
+        fs::write(
+            &path,
+            "[project]\nname = \"spam\"\nversion = \"1.4.2\"  # Release\n",
+        )?;
+
+        assert_eq!(
+            bump_pyproject(&path, None, Part::Minor)?.1.to_string(),
+            "1.5.0"
+        );
+        let version = PackageVersion::new("2.0rc1")?;
+        assert_eq!(
+            bump_pyproject(&path, Some(version), Part::Pre)?
+                .1
+                .to_string(),
+            "2.0rc2"
+        );
+        assert_eq!(
+            fs::read_to_string(&path)?,
+            "[project]\nname = \"spam\"\nversion = \"2.0rc2\"  # Release\n"
+        );
+
+        fs::write(&path, "[project]\ndynamic = [\"version\"]\n")?;
+        assert!(bump_pyproject(&path, None, Part::Minor).is_err());
+        fs::remove_file(path)?;
+        Ok(())
+    }
+}
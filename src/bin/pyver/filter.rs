@@ -0,0 +1,157 @@
+//! `pyver filter`, the versions matching specifiers
+
+use crate::input::{read_lines, Line};
+use crate::output::{Format, Output, VersionRecord};
+use anyhow::Result;
+use clap::Args;
+use pyver::specifiers::SpecifierSet;
+use pyver::PackageVersion;
+use std::path::PathBuf;
+use std::process::ExitCode;
+use std::ptr;
+
+#[derive(Debug, Args)]
+pub struct FilterArgs {
+    /// The specifiers every printed version matches (`>=1.0,<2.0,!=1.5.*`)
+    specifiers: SpecifierSet,
+    /// Files with one version per line, stdin is read if there are none
+    /// or for `-`
+    files: Vec<PathBuf>,
+    /// Print only the newest matching version
+    #[arg(long)]
+    latest: bool,
+    /// Match pre-releases and developmental releases even if final
+    /// releases match
+    #[arg(long)]
+    pre: bool,
+    /// Leave out lines that aren't versions instead of stopping at the
+    /// first one
+    #[arg(long)]
+    skip_invalid: bool,
+}
+
+pub fn run(args: FilterArgs, format: Format) -> Result<ExitCode> {
+    let matching = filter(read_lines(&args.files)?, &args)?;
+    let mut output = Output::new(format);
+    for (line, version) in &matching {
+        let record = VersionRecord::valid(&line.text, version).at(line);
+        output.write(Some(&line.text), &record)?;
+    }
+    output.finish()?;
+    // Like grep, so scripts can tell that nothing matched
+    Ok(if matching.is_empty() {
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
+    })
+}
+
+/// The lines with matching versions, in the order they were read
+///
+/// Pre-releases only match if the specifiers mention one, `--pre` is given
+/// or no final release matches, like pip picks them.
+fn filter(lines: Vec<Line>, args: &FilterArgs) -> Result<Vec<(Line, PackageVersion)>> {
+    let mut versions = Vec::new();
+    for line in lines {
+        match line.parse() {
+            Ok(version) => versions.push((version, line)),
+            Err(_) if args.skip_invalid => {}
+            Err(e) => return Err(e),
+        }
+    }
+
+    let specifiers = if args.pre {
+        args.specifiers.clone().with_prereleases(Some(true))
+    } else {
+        args.specifiers.clone()
+    };
+    // The matching versions are yielded in the order they were read
+    let mut matching = specifiers
+        .filter(versions.iter().map(|(version, _)| version))
+        .peekable();
+    let mut matched = Vec::new();
+    for (version, line) in &versions {
+        if matching.next_if(|m| ptr::eq(*m, version)).is_some() {
+            matched.push((line.clone(), version.clone()));
+        }
+    }
+
+    if args.latest {
+        // The first of equal versions, like the others are printed
+        let latest =
+            matched
+                .into_iter()
+                .reduce(|latest, next| if next.1 > latest.1 { next } else { latest });
+        return Ok(latest.into_iter().collect());
+    }
+    Ok(matched)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{filter, FilterArgs};
+    use crate::input::Line;
+    use pyver::PackageVersion;
+
+    fn lines(texts: &[&str]) -> Vec<Line> {
+        texts
+            .iter()
+            .enumerate()
+            .map(|(index, text)| Line {
+                source: "-".to_string(),
+                number: index + 1,
+                text: text.to_string(),
+            })
+            .collect()
+    }
+
+    fn texts(matching: Vec<(Line, PackageVersion)>) -> Vec<String> {
+        matching.into_iter().map(|(line, _)| line.text).collect()
+    }
+
+    fn args(
+        specifiers: &str,
+        latest: bool,
+        pre: bool,
+        skip_invalid: bool,
+    ) -> FilterArgs {
+        FilterArgs {
+            specifiers: specifiers.parse().unwrap(),
+            files: Vec::new(),
+            latest,
+            pre,
+            skip_invalid,
+        }
+    }
+
+    #[test]
+    fn test_filter() {
+        let input = lines(&["2.0", "1.5.2", "v1.4", "1.6rc1", "1.4.0", "0.9"]);
+        let cases = [
+            (
+                args(">=1.0,<2.0,!=1.5.*", false, false, false),
+                vec!["v1.4", "1.4.0"],
+            ),
+            (args(">=1.0,<2.0,!=1.5.*", true, false, false), vec!["v1.4"]),
+            (args(">=1.0,<2.0", true, true, false), vec!["1.6rc1"]),
+            (args(">=1.6rc1", false, false, false), vec!["2.0", "1.6rc1"]),
+            (args(">1.5.2,<2.0", false, false, false), vec!["1.6rc1"]),
+            (args(">=3", true, false, false), vec![]),
+        ];
+        for (args, expected) in cases {
+            assert_eq!(
+                texts(filter(input.clone(), &args).unwrap()),
+                expected,
+                "{:?}",
+                args
+            );
+        }
+
+        let input = lines(&["1.0", "latest", "1.1"]);
+        assert!(filter(input.clone(), &args(">=1.0", false, false, false)).is_err());
+        assert_eq!(
+            texts(filter(input, &args(">=1.0", false, false, true)).unwrap()),
+            vec!["1.0", "1.1"]
+        );
+    }
+}
@@ -0,0 +1,68 @@
+//! One version per line, read from files or stdin
+
+use anyhow::{Context, Result};
+use pyver::PackageVersion;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader};
+use std::path::PathBuf;
+
+/// A line that isn't blank, without surrounding whitespace
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Line {
+    /// The file the line was read from, `-` for stdin
+    pub source: String,
+    /// The line number, counting from 1
+    pub number: usize,
+    pub text: String,
+}
+
+impl Line {
+    /// Parses the line as a version, errors tell where the line was read
+    pub fn parse(&self) -> Result<PackageVersion> {
+        PackageVersion::new(&self.text)
+            .with_context(|| format!("{}:{}", self.source, self.number))
+    }
+}
+
+/// Calls `f` with every line of the files as soon as it's read, stdin is
+/// read if there are no files or for `-`
+pub fn for_each_line(
+    files: &[PathBuf],
+    mut f: impl FnMut(Line) -> Result<()>,
+) -> Result<()> {
+    let stdin = [PathBuf::from("-")];
+    let files = if files.is_empty() { &stdin[..] } else { files };
+
+    for file in files {
+        let source = file.display().to_string();
+        let reader: Box<dyn BufRead> = if source == "-" {
+            Box::new(io::stdin().lock())
+        } else {
+            let opened =
+                File::open(file).with_context(|| format!("Can't read {}", source))?;
+            Box::new(BufReader::new(opened))
+        };
+        for (index, text) in reader.lines().enumerate() {
+            let text = text.with_context(|| format!("Can't read {}", source))?;
+            if text.trim().is_empty() {
+                continue;
+            }
+            f(Line {
+                source: source.clone(),
+                number: index + 1,
+                text: text.trim().to_string(),
+            })?;
+        }
+    }
+    Ok(())
+}
+
+/// Every line of the files, see [`for_each_line`]
+pub fn read_lines(files: &[PathBuf]) -> Result<Vec<Line>> {
+    let mut lines = Vec::new();
+    for_each_line(files, |line| {
+        lines.push(line);
+        Ok(())
+    })?;
+    Ok(lines)
+}
@@ -0,0 +1,240 @@
+//! # `pyver`
+//! `PEP-440` versions for shell scripts, validated and compared like pip
+//! does it
+//!
+//! ```sh
+//! pyver validate 1.0rc1 && echo valid
+//! pyver compare 1.0 1.0.post1  # prints <
+//! git tag | pyver sort --invalid skip --reverse
+//! pyver normalize --check "$VERSION"
+//! pyver bump minor --pyproject pyproject.toml
+//! git tag | pyver filter --skip-invalid --latest '>=1.0,<2.0,!=1.5.*'
+//! pyver validate --json 1.0rc1 "1.0 junk"
+//! ```
+//!
+//! With `--json` the subcommands print JSON records instead of text, one
+//! array of them or one record per line with `--json-lines`.
+
+mod bump;
+mod filter;
+mod input;
+mod normalize;
+mod output;
+mod sort;
+
+use anyhow::Result;
+use bump::BumpArgs;
+use clap::{Parser, Subcommand};
+use filter::FilterArgs;
+use normalize::NormalizeArgs;
+use output::{write_one, Format, Output, VersionRecord};
+use pyver::PackageVersion;
+use serde::Serialize;
+use sort::SortArgs;
+use std::cmp::Ordering;
+use std::io;
+use std::process::ExitCode;
+
+/// Validate and compare Python package versions (PEP 440)
+#[derive(Debug, Parser)]
+#[command(name = "pyver", version)]
+struct Cli {
+    /// Print an array of JSON records instead of text
+    #[arg(long, global = true, conflicts_with = "json_lines")]
+    json: bool,
+    /// Print one JSON record per line instead of text
+    #[arg(long, global = true)]
+    json_lines: bool,
+    #[command(subcommand)]
+    command: Command,
+}
+
+impl Cli {
+    fn format(&self) -> Format {
+        match (self.json, self.json_lines) {
+            (true, _) => Format::Json,
+            (_, true) => Format::JsonLines,
+            _ => Format::Text,
+        }
+    }
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// Exit with 0 if every version is valid and with 1 if one isn't
+    Validate {
+        #[arg(required = true)]
+        versions: Vec<String>,
+    },
+    /// Print `<`, `=` or `>` as the first version sorts before, the same as
+    /// or after the second
+    ///
+    /// Exits with 0 for `=`, 11 for `<` and 12 for `>`, like
+    /// `systemd-analyze compare-versions`, and with 2 for invalid versions.
+    Compare { first: String, second: String },
+    /// Sort one version per line, oldest first
+    Sort(SortArgs),
+    /// Print the versions in their PEP 440 normal form (`1.0rc1` for
+    /// `v1.0-RC1`)
+    Normalize(NormalizeArgs),
+    /// Print the next version (`pyver bump minor 1.4.2` prints `1.5.0`)
+    Bump(BumpArgs),
+    /// Print the versions matching the specifiers, one per line
+    ///
+    /// Exits with 1 if no version matches.
+    #[command(visible_alias = "match")]
+    Filter(FilterArgs),
+}
+
+/// Invalid arguments, like clap exits for usage errors
+const EXIT_INVALID: u8 = 2;
+
+/// Both versions of `pyver compare` and how they compare
+#[derive(Debug, Serialize)]
+struct CompareRecord<'a> {
+    first: VersionRecord<'a>,
+    second: VersionRecord<'a>,
+    /// `<`, `=` or `>`, `None` if a version is invalid
+    ordering: Option<&'static str>,
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+    let format = cli.format();
+    match run(cli.command, format) {
+        Ok(code) => code,
+        // Like other filters, stop quietly when the reader has seen enough
+        Err(e) if is_broken_pipe(&e) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("pyver: {:#}", e);
+            ExitCode::from(EXIT_INVALID)
+        }
+    }
+}
+
+fn run(command: Command, format: Format) -> Result<ExitCode> {
+    match command {
+        Command::Validate { versions } => {
+            let mut output = Output::new(format);
+            let mut valid = true;
+            for version in &versions {
+                let parsed = PackageVersion::new(version);
+                if let Err(e) = &parsed {
+                    if format == Format::Text {
+                        eprintln!("pyver: {}", e);
+                    }
+                    valid = false;
+                }
+                output.write(None, &VersionRecord::new(version, &parsed))?;
+            }
+            output.finish()?;
+            Ok(if valid {
+                ExitCode::SUCCESS
+            } else {
+                ExitCode::FAILURE
+            })
+        }
+        Command::Compare { first, second } => {
+            let (a, b) = (PackageVersion::new(&first), PackageVersion::new(&second));
+            let (first, second) = (
+                VersionRecord::new(&first, &a),
+                VersionRecord::new(&second, &b),
+            );
+            // Fails with the error of the first invalid version
+            let ordering = a.and_then(|a| Ok(a.cmp(&b?)));
+            let record = CompareRecord {
+                first,
+                second,
+                ordering: ordering.as_ref().ok().map(|ordering| symbol(*ordering)),
+            };
+            match ordering {
+                Ok(ordering) => {
+                    write_one(format, symbol(ordering), &record)?;
+                    Ok(ExitCode::from(compare_exit_code(ordering)))
+                }
+                Err(e) => {
+                    if format != Format::Text {
+                        write_one(format, "", &record)?;
+                    }
+                    Err(e)
+                }
+            }
+        }
+        Command::Sort(args) => sort::run(args, format).map(|_| ExitCode::SUCCESS),
+        Command::Normalize(args) => normalize::run(args, format),
+        Command::Bump(args) => bump::run(args, format).map(|_| ExitCode::SUCCESS),
+        Command::Filter(args) => filter::run(args, format),
+    }
+}
+
+fn is_broken_pipe(e: &anyhow::Error) -> bool {
+    e.downcast_ref::<io::Error>()
+        .is_some_and(|e| e.kind() == io::ErrorKind::BrokenPipe)
+}
+
+fn symbol(ordering: Ordering) -> &'static str {
+    match ordering {
+        Ordering::Less => "<",
+        Ordering::Equal => "=",
+        Ordering::Greater => ">",
+    }
+}
+
+fn compare_exit_code(ordering: Ordering) -> u8 {
+    match ordering {
+        Ordering::Less => 11,
+        Ordering::Equal => 0,
+        Ordering::Greater => 12,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{compare_exit_code, symbol, Cli, Command};
+    use crate::output::Format;
+    use clap::{CommandFactory, Parser};
+    use pyver::PackageVersion;
+
+    #[test]
+    fn test_cli() {
+        Cli::command().debug_assert();
+
+        let cli = Cli::parse_from(["pyver", "compare", "1.0", "1.0.post1"]);
+        assert!(matches!(cli.command, Command::Compare { .. }));
+        assert!(Cli::try_parse_from(["pyver", "validate"]).is_err());
+
+        let cli = Cli::parse_from(["pyver", "normalize", "--check", "v1.0"]);
+        assert!(matches!(cli.command, Command::Normalize(_)));
+        assert!(Cli::try_parse_from(["pyver", "bump", "minor"]).is_err());
+        let cli = Cli::parse_from(["pyver", "match", ">=1.0, <2.0", "versions.txt"]);
+        assert!(matches!(cli.command, Command::Filter(_)));
+        assert!(Cli::try_parse_from(["pyver", "filter", ">=1.0,"]).is_err());
+        assert!(
+            Cli::try_parse_from(["pyver", "bump", "minor", "--pyproject", "a"]).is_ok()
+        );
+
+        let cli = Cli::parse_from(["pyver", "sort", "--json-lines"]);
+        assert_eq!(cli.format(), Format::JsonLines);
+        let cli = Cli::parse_from(["pyver", "--json", "validate", "1.0"]);
+        assert_eq!(cli.format(), Format::Json);
+        assert!(
+            Cli::try_parse_from(["pyver", "sort", "--json", "--json-lines"]).is_err()
+        );
+    }
+
+    #[test]
+    fn test_compare() {
+        let pairs = [
+            ("1.0", "1.0.post1", "<", 11),
+            ("v1.0", "1.0.0", "=", 0),
+            ("1.0", "1.0rc1", ">", 12),
+        ];
+        for (a, b, printed, code) in pairs {
+            let ordering = PackageVersion::new(a)
+                .unwrap()
+                .cmp(&PackageVersion::new(b).unwrap());
+            assert_eq!(symbol(ordering), printed);
+            assert_eq!(compare_exit_code(ordering), code);
+        }
+    }
+}
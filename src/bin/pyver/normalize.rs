@@ -0,0 +1,68 @@
+//! `pyver normalize`, versions in their `PEP-440` normal form
+
+use crate::input::{for_each_line, Line};
+use crate::output::{Format, Output, VersionRecord};
+use anyhow::Result;
+use clap::Args;
+use pyver::PackageVersion;
+use std::process::ExitCode;
+
+#[derive(Debug, Args)]
+pub struct NormalizeArgs {
+    /// The versions, stdin is read one version per line if there are none
+    versions: Vec<String>,
+    /// Print nothing and exit with 1 if a version isn't in normal form
+    #[arg(long)]
+    check: bool,
+}
+
+pub fn run(args: NormalizeArgs, format: Format) -> Result<ExitCode> {
+    let mut output = Output::new(format);
+    let mut normal = true;
+    let mut normalize = |text: &str,
+                         line: Option<&Line>,
+                         parsed: Result<PackageVersion>| {
+        let mut record = VersionRecord::new(text, &parsed);
+        if let Some(line) = line {
+            record = record.at(line);
+        }
+        let version = match parsed {
+            Ok(version) => version,
+            Err(e) => {
+                output.write(None, &record)?;
+                return Err(e);
+            }
+        };
+
+        let normalized = version.to_normalized_string();
+        if normalized != text {
+            normal = false;
+            if args.check && format == Format::Text {
+                eprintln!("pyver: {} isn't in normal form, {} is", text, normalized);
+            }
+        }
+        let text = if args.check {
+            None
+        } else {
+            Some(normalized.as_str())
+        };
+        output.write(text, &record)
+    };
+
+    let normalized = if args.versions.is_empty() {
+        for_each_line(&[], |line| normalize(&line.text, Some(&line), line.parse()))
+    } else {
+        args.versions.iter().try_for_each(|version| {
+            normalize(version, None, PackageVersion::new(version))
+        })
+    };
+    // Close the JSON array of the versions before an invalid one as well
+    output.finish()?;
+    normalized?;
+
+    Ok(if normal || !args.check {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    })
+}
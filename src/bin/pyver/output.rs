@@ -0,0 +1,257 @@
+//! Text, JSON and JSON Lines output of the subcommands
+
+use crate::input::Line;
+use anyhow::Result;
+use pyver::PackageVersion;
+use serde::Serialize;
+use std::io::{self, BufWriter, StdoutLock, Write};
+use std::ops::Range;
+
+/// How the subcommands print their results
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Format {
+    /// One line of text per result
+    Text,
+    /// One JSON array of all records
+    Json,
+    /// One JSON record per line
+    JsonLines,
+}
+
+/// Prints one result after the other, as text lines or JSON records
+pub struct Output {
+    format: Format,
+    out: BufWriter<StdoutLock<'static>>,
+    records: usize,
+}
+
+impl Output {
+    pub fn new(format: Format) -> Self {
+        Self {
+            format,
+            out: BufWriter::new(io::stdout().lock()),
+            records: 0,
+        }
+    }
+
+    /// Prints the record, or the text if there is some for text output
+    pub fn write(&mut self, text: Option<&str>, record: &impl Serialize) -> Result<()> {
+        match self.format {
+            Format::Text => {
+                if let Some(text) = text {
+                    writeln!(self.out, "{}", text)?;
+                }
+            }
+            Format::Json => {
+                let separator = if self.records == 0 { "[" } else { "," };
+                self.out.write_all(separator.as_bytes())?;
+                serde_json::to_writer(&mut self.out, record)
+                    .map_err(io::Error::from)?;
+            }
+            Format::JsonLines => {
+                serde_json::to_writer(&mut self.out, record)
+                    .map_err(io::Error::from)?;
+                writeln!(self.out)?;
+            }
+        }
+        self.records += 1;
+        Ok(())
+    }
+
+    /// Closes the JSON array and flushes the output
+    pub fn finish(mut self) -> Result<()> {
+        if self.format == Format::Json {
+            let start = if self.records == 0 { "[" } else { "" };
+            writeln!(self.out, "{}]", start)?;
+        }
+        self.out.flush()?;
+        Ok(())
+    }
+}
+
+/// Prints the single result of a subcommand, a JSON record is printed
+/// without an array around it
+pub fn write_one(format: Format, text: &str, record: &impl Serialize) -> Result<()> {
+    let mut out = io::stdout().lock();
+    match format {
+        Format::Text => writeln!(out, "{}", text)?,
+        Format::Json | Format::JsonLines => {
+            serde_json::to_writer(&mut out, record).map_err(io::Error::from)?;
+            writeln!(out)?;
+        }
+    }
+    Ok(())
+}
+
+/// # Version record
+/// A version as it was given, its components and normal form if it's
+/// valid and why it isn't otherwise
+#[derive(Debug, Serialize)]
+pub struct VersionRecord<'a> {
+    pub input: &'a str,
+    /// The file the version was read from, `-` for stdin
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub file: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub line: Option<usize>,
+    pub valid: bool,
+    pub normalized: Option<String>,
+    pub components: Option<Components>,
+    pub error: Option<ErrorRecord>,
+}
+
+impl<'a> VersionRecord<'a> {
+    pub fn new(input: &'a str, parsed: &Result<PackageVersion>) -> Self {
+        match parsed {
+            Ok(version) => Self::valid(input, version),
+            Err(e) => Self::invalid(input, e),
+        }
+    }
+
+    pub fn valid(input: &'a str, version: &PackageVersion) -> Self {
+        Self {
+            input,
+            file: None,
+            line: None,
+            valid: true,
+            normalized: Some(version.to_normalized_string()),
+            components: Some(Components::new(version)),
+            error: None,
+        }
+    }
+
+    pub fn invalid(input: &'a str, error: &anyhow::Error) -> Self {
+        Self {
+            input,
+            file: None,
+            line: None,
+            valid: false,
+            normalized: None,
+            components: None,
+            error: Some(ErrorRecord {
+                // Without the file and line, which are fields of their own
+                message: error.root_cause().to_string(),
+                span: invalid_span(input),
+            }),
+        }
+    }
+
+    /// The record of the version on the line
+    pub fn at(mut self, line: &'a Line) -> Self {
+        self.file = Some(&line.source);
+        self.line = Some(line.number);
+        self
+    }
+}
+
+/// The parsed parts of a version, numbers left out are `0`
+#[derive(Debug, Serialize)]
+pub struct Components {
+    pub epoch: u32,
+    pub release: Vec<u64>,
+    pub pre: Option<PreRecord>,
+    pub post: Option<u32>,
+    pub dev: Option<u32>,
+    pub local: Option<String>,
+}
+
+impl Components {
+    fn new(version: &PackageVersion) -> Self {
+        Self {
+            epoch: version.epoch(),
+            release: version.release().segments().to_vec(),
+            pre: version.pre().map(|pre| PreRecord {
+                phase: pre.phase(),
+                number: pre.number().unwrap_or(0),
+            }),
+            post: version.post().map(|post| post.post_num.unwrap_or(0)),
+            dev: version.dev().map(|dev| dev.dev_num.unwrap_or(0)),
+            local: version.local().map(str::to_string),
+        }
+    }
+}
+
+/// The phase (`a`, `b` or `rc`) and number of a pre-release
+#[derive(Debug, Serialize)]
+pub struct PreRecord {
+    pub phase: &'static str,
+    pub number: u32,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ErrorRecord {
+    pub message: String,
+    /// The byte offsets of the part of the input that isn't valid
+    pub span: Range<usize>,
+}
+
+/// Where the input stops being a version, after its longest valid prefix
+///
+/// A number that is cut short to be valid (one too large for its segment)
+/// is invalid as a whole.
+fn invalid_span(input: &str) -> Range<usize> {
+    let end = input.trim_end().len();
+    let mut start = (1..=input.len())
+        .rev()
+        .filter(|end| input.is_char_boundary(*end))
+        .find(|end| PackageVersion::new(&input[..*end]).is_ok())
+        .unwrap_or(0);
+
+    let bytes = input.as_bytes();
+    while start > 0 && start < end && bytes[start].is_ascii_digit() {
+        if !bytes[start - 1].is_ascii_digit() {
+            break;
+        }
+        start -= 1;
+    }
+    start.min(end)..end
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{invalid_span, VersionRecord};
+    use pyver::PackageVersion;
+
+    #[test]
+    fn test_invalid_span() {
+        let spans = [
+            ("junk", 0..4),
+            ("1.0 junk", 4..8),
+            ("1.0junk ", 3..7),
+            ("1.0.post1x", 9..10),
+            ("1.0+", 3..4),
+            ("1.99999999999999999999999", 2..25),
+            ("", 0..0),
+        ];
+        for (input, span) in spans {
+            assert_eq!(invalid_span(input), span, "{}", input);
+        }
+    }
+
+    #[test]
+    fn test_version_record() {
+        let parsed = PackageVersion::new("v1!2.0-RC1.post2.dev3+Local");
+        let record = VersionRecord::new("v1!2.0-RC1.post2.dev3+Local", &parsed);
+        assert_eq!(
+            serde_json::to_string(&record).unwrap(),
+            concat!(
+                r#"{"input":"v1!2.0-RC1.post2.dev3+Local","valid":true,"#,
+                r#""normalized":"1!2.0rc1.post2.dev3+local","#,
+                r#""components":{"epoch":1,"release":[2,0],"#,
+                r#""pre":{"phase":"rc","number":1},"post":2,"dev":3,"local":"Local"},"#,
+                r#""error":null}"#
+            )
+        );
+
+        let parsed = PackageVersion::new("1.0 junk");
+        let record = VersionRecord::new("1.0 junk", &parsed);
+        assert_eq!(
+            serde_json::to_string(&record).unwrap(),
+            concat!(
+                r#"{"input":"1.0 junk","valid":false,"normalized":null,"#,
+                r#""components":null,"error":{"message":"Failed to decode version "#,
+                r#"1.0 junk","span":{"start":4,"end":8}}}"#
+            )
+        );
+    }
+}
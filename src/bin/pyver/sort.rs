@@ -0,0 +1,157 @@
+//! `pyver sort`, a `sort -V` that orders versions like pip
+
+use crate::input::{read_lines, Line};
+use crate::output::{Format, Output, VersionRecord};
+use anyhow::Result;
+use clap::{Args, ValueEnum};
+use pyver::PackageVersion;
+use std::path::PathBuf;
+
+#[derive(Debug, Args)]
+pub struct SortArgs {
+    /// Files with one version per line, stdin is read if there are none
+    /// or for `-`
+    files: Vec<PathBuf>,
+    /// Print the newest version first
+    #[arg(short, long)]
+    reverse: bool,
+    /// Print only the first of equal versions (`1.0` and `1.0.0`)
+    #[arg(short, long)]
+    unique: bool,
+    /// Leave out pre-releases and developmental releases
+    #[arg(long)]
+    stable_only: bool,
+    /// What to do with lines that aren't versions
+    #[arg(long, value_enum, default_value_t = Invalid::Fail)]
+    invalid: Invalid,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, ValueEnum)]
+pub enum Invalid {
+    /// Leave them out
+    Skip,
+    /// Stop with an error naming the first one
+    Fail,
+    /// Print them after the versions (before them with `--reverse`) in
+    /// the order they were read
+    Passthrough,
+}
+
+pub fn run(args: SortArgs, format: Format) -> Result<()> {
+    let mut output = Output::new(format);
+    for (line, parsed) in sort(read_lines(&args.files)?, &args)? {
+        let record = VersionRecord::new(&line.text, &parsed).at(&line);
+        output.write(Some(&line.text), &record)?;
+    }
+    output.finish()
+}
+
+/// The lines in the order of their versions and the lines that aren't
+/// versions if they are passed through
+///
+/// Equal versions keep the order they were read in.
+fn sort(
+    lines: Vec<Line>,
+    args: &SortArgs,
+) -> Result<Vec<(Line, Result<PackageVersion>)>> {
+    let mut versions = Vec::new();
+    let mut invalid = Vec::new();
+    for line in lines {
+        match line.parse() {
+            Ok(version) => versions.push((version, line)),
+            Err(e) => match args.invalid {
+                Invalid::Skip => {}
+                Invalid::Fail => return Err(e),
+                Invalid::Passthrough => invalid.push((line, Err(e))),
+            },
+        }
+    }
+
+    if args.stable_only {
+        versions.retain(|(version, _)| version.is_stable());
+    }
+    if args.reverse {
+        versions.sort_by(|(a, _), (b, _)| b.cmp(a));
+    } else {
+        versions.sort_by(|(a, _), (b, _)| a.cmp(b));
+    }
+    if args.unique {
+        versions.dedup_by(|(a, _), (b, _)| a == b);
+    }
+
+    let versions = versions
+        .into_iter()
+        .map(|(version, line)| (line, Ok(version)));
+    Ok(if args.reverse {
+        invalid.into_iter().chain(versions).collect()
+    } else {
+        versions.chain(invalid).collect()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{sort, Invalid, SortArgs};
+    use crate::input::Line;
+
+    fn lines(texts: &[&str]) -> Vec<Line> {
+        texts
+            .iter()
+            .enumerate()
+            .map(|(index, text)| Line {
+                source: "-".to_string(),
+                number: index + 1,
+                text: text.to_string(),
+            })
+            .collect()
+    }
+
+    fn args(
+        reverse: bool,
+        unique: bool,
+        stable_only: bool,
+        invalid: Invalid,
+    ) -> SortArgs {
+        SortArgs {
+            files: Vec::new(),
+            reverse,
+            unique,
+            stable_only,
+            invalid,
+        }
+    }
+
+    #[test]
+    fn test_sort() {
+        let input = lines(&["1.0.post1", "v1.0", "1.0rc1", "junk", "1.0.0", "0.9"]);
+        let cases = [
+            (
+                args(false, false, false, Invalid::Skip),
+                vec!["0.9", "1.0rc1", "v1.0", "1.0.0", "1.0.post1"],
+            ),
+            (
+                args(true, false, false, Invalid::Skip),
+                vec!["1.0.post1", "v1.0", "1.0.0", "1.0rc1", "0.9"],
+            ),
+            (
+                args(false, true, true, Invalid::Passthrough),
+                vec!["0.9", "v1.0", "1.0.post1", "junk"],
+            ),
+            (
+                args(true, true, false, Invalid::Passthrough),
+                vec!["junk", "1.0.post1", "v1.0", "1.0rc1", "0.9"],
+            ),
+        ];
+        for (args, expected) in cases {
+            let sorted: Vec<String> = sort(input.clone(), &args)
+                .unwrap()
+                .into_iter()
+                .map(|(line, _)| line.text)
+                .collect();
+            assert_eq!(sorted, expected, "{:?}", args);
+        }
+
+        let error = sort(input, &args(false, false, false, Invalid::Fail)).unwrap_err();
+        assert_eq!(format!("{:#}", error), "-:4: Failed to decode version junk");
+    }
+}
@@ -0,0 +1,262 @@
+//! [`BorshSerialize`] and [`BorshDeserialize`] implementations with a
+//! binary layout that stays the same across releases of this crate, for
+//! lockfiles and caches written by one release and read by another.
+//!
+//! Each value starts with a format byte, currently `1`. A later format
+//! gets a new byte and decoding keeps accepting the old ones, an unknown
+//! format is an [`ErrorKind::InvalidData`] error. Numbers are little
+//! endian, and options, strings and sequences use the borsh encoding
+//! (a `0` or `1` byte, or a `u32` length before the contents).
+//!
+//! A [`PackageVersion`] (format `1`) is its normal form, so decoding gives
+//! an equal version written as [`PackageVersion::to_normalized_string`]:
+//!
+//! | Field   | Type             | Contents                                        |
+//! |---------|------------------|-------------------------------------------------|
+//! | format  | `u8`             | `1`                                             |
+//! | epoch   | `u32`            | `0` without an epoch                            |
+//! | release | `Vec<u64>`       | empty only for [`PackageVersion::MAX`]          |
+//! | pre     | `u8`             | `0` none, `1` alpha, `2` beta, `3` rc (preview) |
+//! |         | `u32`            | the pre-release number, only if there is one    |
+//! | post    | `Option<u32>`    |                                                 |
+//! | dev     | `Option<u32>`    |                                                 |
+//! | local   | `Option<String>` | the normal form of the label (`ubuntu.1`)       |
+//!
+//! A [`SpecifierSet`] (format `1`) keeps its pre-release policies:
+//!
+//! | Field       | Type           | Contents                                  |
+//! |-------------|----------------|-------------------------------------------|
+//! | format      | `u8`           | `1`                                       |
+//! | prereleases | `Option<bool>` | the policy of the set                     |
+//! | specifiers  | `u32`          | the number of specifiers, each one is     |
+//! |             | `String`       | the specifier as written (`>=1.0`)        |
+//! |             | `Option<bool>` | the policy of the specifier               |
+//!
+//! ```
+//! use pyver::specifiers::SpecifierSet;
+//! use pyver::PackageVersion;
+//!
+//! let version = PackageVersion::new("v1.0-RC1").unwrap();
+//! let bytes = borsh::to_vec(&version).unwrap();
+//! assert_eq!(bytes[..5], [1, 0, 0, 0, 0]);
+//!
+//! let decoded: PackageVersion = borsh::from_slice(&bytes).unwrap();
+//! assert_eq!(decoded, version);
+//! assert_eq!(decoded.to_string(), "1.0rc1");
+//!
+//! let set = SpecifierSet::new(">=1.0, <2.0").unwrap();
+//! let decoded: SpecifierSet = borsh::from_slice(&borsh::to_vec(&set).unwrap()).unwrap();
+//! assert_eq!(decoded, set);
+//! ```
+
+use crate::ids::PreHeader;
+use crate::specifiers::{Specifier, SpecifierSet};
+use crate::PackageVersion;
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use borsh::io::{Error, ErrorKind, Read, Result, Write};
+use borsh::{BorshDeserialize, BorshSerialize};
+
+/// The current format byte of both types
+const FORMAT: u8 = 1;
+
+fn invalid(message: String) -> Error {
+    Error::new(ErrorKind::InvalidData, message)
+}
+
+fn read_format<R: Read>(reader: &mut R, name: &str) -> Result<()> {
+    match u8::deserialize_reader(reader)? {
+        FORMAT => Ok(()),
+        format => Err(invalid(format!("Unknown {} format {}", name, format))),
+    }
+}
+
+impl BorshSerialize for PackageVersion {
+    fn serialize<W: Write>(&self, writer: &mut W) -> Result<()> {
+        FORMAT.serialize(writer)?;
+        self.epoch().serialize(writer)?;
+        // `MIN` has no release segments but compares equal to the release `0`
+        match self.release.segments.as_slice() {
+            [] if !self.is_max() => [0u64].as_slice().serialize(writer)?,
+            segments => segments.serialize(writer)?,
+        }
+
+        let pre = match self.pre {
+            None => 0u8,
+            Some(PreHeader::Alpha(_)) => 1,
+            Some(PreHeader::Beta(_)) => 2,
+            Some(PreHeader::ReleaseCandidate(_)) | Some(PreHeader::Preview(_)) => 3,
+        };
+        pre.serialize(writer)?;
+        if let Some(pre) = self.pre {
+            pre.number().unwrap_or(0).serialize(writer)?;
+        }
+
+        self.post_key().serialize(writer)?;
+        self.dev
+            .map(|dev| dev.dev_num.unwrap_or(0))
+            .serialize(writer)?;
+        self.normalized_local().serialize(writer)
+    }
+}
+
+impl BorshDeserialize for PackageVersion {
+    fn deserialize_reader<R: Read>(reader: &mut R) -> Result<Self> {
+        read_format(reader, "version")?;
+        let epoch = u32::deserialize_reader(reader)?;
+        let release = Vec::<u64>::deserialize_reader(reader)?;
+
+        let pre = match u8::deserialize_reader(reader)? {
+            0 => None,
+            1 => Some(PreHeader::Alpha(Some(u32::deserialize_reader(reader)?))),
+            2 => Some(PreHeader::Beta(Some(u32::deserialize_reader(reader)?))),
+            3 => Some(PreHeader::ReleaseCandidate(Some(u32::deserialize_reader(
+                reader,
+            )?))),
+            phase => {
+                return Err(invalid(format!("Unknown pre-release phase {}", phase)))
+            }
+        };
+        let post = Option::<u32>::deserialize_reader(reader)?;
+        let dev = Option::<u32>::deserialize_reader(reader)?;
+        let local = Option::<String>::deserialize_reader(reader)?;
+
+        if release.is_empty() && epoch == u32::MAX {
+            return Ok(Self::MAX);
+        }
+        Self::from_parts(epoch, release, pre, post, dev, local.as_deref())
+            .map_err(|e| invalid(e.to_string()))
+    }
+}
+
+impl BorshSerialize for SpecifierSet {
+    fn serialize<W: Write>(&self, writer: &mut W) -> Result<()> {
+        FORMAT.serialize(writer)?;
+        self.prereleases.serialize(writer)?;
+        u32::try_from(self.specifiers.len())
+            .map_err(|_| invalid(format!("{} specifiers", self.specifiers.len())))?
+            .serialize(writer)?;
+        for specifier in &self.specifiers {
+            specifier.to_string().serialize(writer)?;
+            specifier.prereleases.serialize(writer)?;
+        }
+        Ok(())
+    }
+}
+
+impl BorshDeserialize for SpecifierSet {
+    fn deserialize_reader<R: Read>(reader: &mut R) -> Result<Self> {
+        read_format(reader, "specifier set")?;
+        let prereleases = Option::<bool>::deserialize_reader(reader)?;
+        let len = u32::deserialize_reader(reader)?;
+
+        let specifiers = (0..len)
+            .map(|_| {
+                let specifier = String::deserialize_reader(reader)?;
+                let specifier =
+                    Specifier::new(&specifier).map_err(|e| invalid(e.to_string()))?;
+                Ok(specifier.with_prereleases(Option::deserialize_reader(reader)?))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        Ok(Self {
+            specifiers,
+            prereleases,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::specifiers::SpecifierSet;
+    use crate::PackageVersion;
+    use anyhow::Result;
+
+    #[test]
+    fn test_version_encoding() -> Result<()> {
+        // The layout is fixed, these bytes have to decode in every release
+        let version = PackageVersion::new("V2!1.2PREVIEW3-4.dev+Ubuntu-1")?;
+        let bytes = borsh::to_vec(&version)?;
+        let expected: Vec<u8> = [
+            &[1][..],
+            &[2, 0, 0, 0],
+            &[2, 0, 0, 0],
+            &[1, 0, 0, 0, 0, 0, 0, 0],
+            &[2, 0, 0, 0, 0, 0, 0, 0],
+            &[3, 3, 0, 0, 0],
+            &[1, 4, 0, 0, 0],
+            &[1, 0, 0, 0, 0],
+            &[1, 8, 0, 0, 0],
+            b"ubuntu.1",
+        ]
+        .concat();
+        assert_eq!(bytes, expected);
+
+        let decoded: PackageVersion = borsh::from_slice(&bytes)?;
+        assert_eq!(decoded, version);
+        assert_eq!(decoded.to_string(), "2!1.2rc3.post4.dev0+ubuntu.1");
+
+        let versions = ["1.0", "0!1.0.0a", "1.0.post1", "1.0rc0", "2024.1.1+abc"];
+        for version in versions {
+            let version = PackageVersion::new(version)?;
+            let decoded: PackageVersion = borsh::from_slice(&borsh::to_vec(&version)?)?;
+            assert_eq!(decoded, version);
+            assert_eq!(decoded.to_string(), version.to_normalized_string());
+        }
+        for version in [PackageVersion::MAX, PackageVersion::MIN] {
+            let decoded: PackageVersion = borsh::from_slice(&borsh::to_vec(&version)?)?;
+            assert_eq!(decoded, version);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_version_encoding_negative() -> Result<()> {
+        let valid = borsh::to_vec(&PackageVersion::new("1.0+abc")?)?;
+        let mut unknown_format = valid.clone();
+        unknown_format[0] = 2;
+        let mut unknown_phase = valid.clone();
+        unknown_phase[25] = 4;
+        let mut invalid_local = valid.clone();
+        invalid_local[33] = b'-';
+
+        let encodings = vec![
+            unknown_format,
+            unknown_phase,
+            invalid_local,
+            valid[..valid.len() - 1].to_vec(),
+            // No release segments
+            vec![1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0],
+        ];
+        for bytes in encodings {
+            match borsh::from_slice::<PackageVersion>(&bytes) {
+                Ok(v) => panic!("Oh no {}", v),
+                Err(_e) => continue,
+            }
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_specifier_set_encoding() -> Result<()> {
+        let set = SpecifierSet::new(">=1.0, !=1.3.*, ===foobar")?
+            .with_prereleases(Some(true));
+        let bytes = borsh::to_vec(&set)?;
+        assert_eq!(bytes[..6], [1, 1, 1, 3, 0, 0]);
+
+        let decoded: SpecifierSet = borsh::from_slice(&bytes)?;
+        assert_eq!(decoded, set);
+        assert_eq!(decoded.to_string(), ">=1.0,!=1.3.*,===foobar");
+
+        let empty: SpecifierSet =
+            borsh::from_slice(&borsh::to_vec(&SpecifierSet::default())?)?;
+        assert!(empty.is_empty());
+
+        match borsh::from_slice::<SpecifierSet>(&[
+            1, 0, 1, 0, 0, 0, 3, 0, 0, 0, b'1', b'.', b'0', 0,
+        ]) {
+            Ok(v) => panic!("Oh no {}", v),
+            Err(_e) => Ok(()),
+        }
+    }
+}
@@ -0,0 +1,282 @@
+use crate::ids::PreHeader;
+use crate::PackageVersion;
+use anyhow::Result;
+
+impl PackageVersion {
+    /// The next major release, incrementing the first release segment
+    /// and resetting the later ones to zero
+    ///
+    /// Like the other bumps this keeps the epoch, drops any pre, post, dev
+    /// and local segments and writes the new version in normal form
+    ///
+    /// ```
+    ///# use pyver::PackageVersion;
+    /// let version = PackageVersion::new("v1.4.2rc1+local").unwrap();
+    /// assert_eq!(version.bump_major().unwrap().to_string(), "2.0.0");
+    /// assert_eq!(version.bump_minor().unwrap().to_string(), "1.5.0");
+    /// assert_eq!(version.bump_micro().unwrap().to_string(), "1.4.3");
+    /// ```
+    pub fn bump_major(&self) -> Result<Self> {
+        self.bump_release(0)
+    }
+
+    /// The next minor release, incrementing the second release segment
+    /// (`1.4.2` to `1.5.0`, `1` to `1.1`)
+    pub fn bump_minor(&self) -> Result<Self> {
+        self.bump_release(1)
+    }
+
+    /// The next micro release, incrementing the third release segment
+    /// (`1.4.2` to `1.4.3`, `1.4` to `1.4.1`)
+    pub fn bump_micro(&self) -> Result<Self> {
+        self.bump_release(2)
+    }
+
+    /// The next pre-release in the same phase (`1.0rc1` to `1.0rc2`,
+    /// `1.0a` to `1.0a1`), dropping any post, dev and local segments
+    ///
+    /// Fails if the version isn't a pre-release since there's no phase
+    /// to keep, see [`PackageVersion::with_pre`] to start one
+    ///
+    /// ```
+    ///# use pyver::PackageVersion;
+    /// let version = PackageVersion::new("1.0b1.dev2").unwrap();
+    /// assert_eq!(version.bump_pre().unwrap().to_string(), "1.0b2");
+    /// assert!(PackageVersion::new("1.0").unwrap().bump_pre().is_err());
+    /// ```
+    pub fn bump_pre(&self) -> Result<Self> {
+        let pre = match self.pre {
+            Some(pre) => pre,
+            None => anyhow::bail!("{} has no pre-release phase to increment", self),
+        };
+        let number = next_number(Some(pre.number().unwrap_or(0)))?;
+        let pre = PreHeader::new(pre.phase(), Some(number))?;
+        Ok(self
+            .without_local()
+            .with_post(None)
+            .with_dev(None)
+            .with_pre(Some(pre)))
+    }
+
+    /// The next post-release, dropping any dev and local segments
+    ///
+    /// A version without a post-release gets `.post0`, every other
+    /// one the following number (`1.0.post` and `1.0.post0` give `1.0.post1`)
+    ///
+    /// ```
+    ///# use pyver::PackageVersion;
+    /// let version = PackageVersion::new("1.0").unwrap();
+    /// let hotfix = version.bump_post().unwrap();
+    /// assert_eq!(hotfix.to_string(), "1.0.post0");
+    /// assert_eq!(hotfix.bump_post().unwrap().to_string(), "1.0.post1");
+    /// ```
+    pub fn bump_post(&self) -> Result<Self> {
+        let number = next_number(self.post_key())?;
+        Ok(self.without_local().with_dev(None).with_post(Some(number)))
+    }
+
+    /// The next developmental release, dropping the local segment
+    ///
+    /// A version without a dev segment gets `.dev0`. That sorts before the
+    /// version itself (`1.1.dev0 < 1.1`), so nightlies usually bump the
+    /// release first (`1.0` to `1.1.dev0`)
+    ///
+    /// ```
+    ///# use pyver::PackageVersion;
+    /// let version = PackageVersion::new("1.0").unwrap();
+    /// let nightly = version.bump_minor().unwrap().bump_dev().unwrap();
+    /// assert_eq!(nightly.to_string(), "1.1.dev0");
+    /// assert_eq!(nightly.bump_dev().unwrap().to_string(), "1.1.dev1");
+    /// ```
+    pub fn bump_dev(&self) -> Result<Self> {
+        let current = self.dev.map(|dev| dev.dev_num.unwrap_or(0));
+        let number = next_number(current)?;
+        Ok(self.without_local().with_dev(Some(number)))
+    }
+
+    /// The final release of a pre, dev or local version
+    /// (`1.2.0rc3.dev2` to `1.2.0`)
+    ///
+    /// The post-release of a final release is kept (`1.0.post1.dev2`
+    /// to `1.0.post1`), the one of a pre-release belongs to the
+    /// pre-release and is dropped along with it
+    ///
+    /// ```
+    ///# use pyver::PackageVersion;
+    /// let version = PackageVersion::new("1.2.0rc3.dev2+local").unwrap();
+    /// assert_eq!(version.finalize().to_string(), "1.2.0");
+    /// ```
+    pub fn finalize(&self) -> Self {
+        let version = self.without_local().with_dev(None);
+        match self.pre {
+            Some(_) => version.with_pre(None).with_post(None),
+            None => version,
+        }
+    }
+
+    /// The first release of the next phase in the release train,
+    /// from alpha to beta to release candidate to the final release
+    ///
+    /// A dev release without a pre-release phase is promoted to the
+    /// first alpha. Post, dev and local segments are dropped and
+    /// the new phase starts at `0`, fails for final releases
+    ///
+    /// ```
+    ///# use pyver::PackageVersion;
+    /// let alpha = PackageVersion::new("1.0a3").unwrap();
+    /// let beta = alpha.promote_pre().unwrap();
+    /// let rc = beta.promote_pre().unwrap();
+    /// assert_eq!(beta.to_string(), "1.0b0");
+    /// assert_eq!(rc.to_string(), "1.0rc0");
+    /// assert_eq!(rc.promote_pre().unwrap().to_string(), "1.0");
+    /// ```
+    pub fn promote_pre(&self) -> Result<Self> {
+        let next = match (self.pre, &self.dev) {
+            (Some(PreHeader::Alpha(_)), _) => Some(PreHeader::Beta(Some(0))),
+            (Some(PreHeader::Beta(_)), _) => Some(PreHeader::ReleaseCandidate(Some(0))),
+            (Some(PreHeader::ReleaseCandidate(_) | PreHeader::Preview(_)), _) => None,
+            (None, Some(_)) if self.post.is_none() => Some(PreHeader::Alpha(Some(0))),
+            (None, _) => anyhow::bail!("{} is not a pre-release", self),
+        };
+        Ok(self
+            .without_local()
+            .with_post(None)
+            .with_dev(None)
+            .with_pre(next))
+    }
+
+    /// Increments the release segment at the index, padding the release
+    /// with zeros if it's shorter and zeroing the segments after it
+    pub(crate) fn bump_release(&self, index: usize) -> Result<Self> {
+        if self.is_max() {
+            anyhow::bail!("There is no release after {}", self);
+        }
+
+        let mut segments = self.release.segments.to_vec();
+        if segments.len() <= index {
+            segments.resize(index + 1, 0);
+        }
+        segments[index] = match segments[index].checked_add(1) {
+            Some(segment) => segment,
+            None => anyhow::bail!("Release segment {} of {} overflows", index, self),
+        };
+        for segment in &mut segments[index + 1..] {
+            *segment = 0;
+        }
+        Self::from_parts(self.epoch(), segments, None, None, None, None)
+    }
+}
+
+/// The number after an optional segment's number, `0` for a missing segment
+fn next_number(current: Option<u32>) -> Result<u32> {
+    match current {
+        None => Ok(0),
+        Some(n) => match n.checked_add(1) {
+            Some(n) => Ok(n),
+            None => anyhow::bail!("Version number {} overflows", n),
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::PackageVersion;
+    use anyhow::Result;
+
+    #[test]
+    fn test_bump_release() -> Result<()> {
+        // (version, major, minor, micro)
+        let versions = vec![
+            ("1.4.2", "2.0.0", "1.5.0", "1.4.3"),
+            ("1", "2", "1.1", "1.0.1"),
+            ("1.4", "2.0", "1.5", "1.4.1"),
+            ("1.4.2.7", "2.0.0.0", "1.5.0.0", "1.4.3.0"),
+            ("2!1.0.dev3", "2!2.0", "2!1.1", "2!1.0.1"),
+            ("1.0.post1+ubuntu.1", "2.0", "1.1", "1.0.1"),
+        ];
+        for (version, major, minor, micro) in versions {
+            let version = PackageVersion::new(version)?;
+            assert_eq!(version.bump_major()?.to_string(), major);
+            assert_eq!(version.bump_minor()?.to_string(), minor);
+            assert_eq!(version.bump_micro()?.to_string(), micro);
+        }
+
+        assert!(PackageVersion::MAX.bump_major().is_err());
+        let overflow = PackageVersion::new(&format!("1.{}", u64::MAX))?;
+        assert!(overflow.bump_minor().is_err());
+        assert_eq!(overflow.bump_major()?.to_string(), "2.0");
+        Ok(())
+    }
+
+    #[test]
+    fn test_bump_suffixes() -> Result<()> {
+        // (version, pre, post, dev), `None` where there is no pre-release
+        let versions = vec![
+            ("1.0rc1", Some("1.0rc2"), "1.0rc1.post0", "1.0rc1.dev0"),
+            ("1.0a", Some("1.0a1"), "1.0a0.post0", "1.0a0.dev0"),
+            ("1.0preview2", Some("1.0rc3"), "1.0rc2.post0", "1.0rc2.dev0"),
+            (
+                "1.0b1.post2.dev3+local",
+                Some("1.0b2"),
+                "1.0b1.post3",
+                "1.0b1.post2.dev4",
+            ),
+            ("1.0", None, "1.0.post0", "1.0.dev0"),
+            ("1.0.post", None, "1.0.post1", "1.0.post0.dev0"),
+            ("1!1.0.dev", None, "1!1.0.post0", "1!1.0.dev1"),
+        ];
+        for (version, pre, post, dev) in versions {
+            let version = PackageVersion::new(version)?;
+            match pre {
+                Some(pre) => assert_eq!(version.bump_pre()?.to_string(), pre),
+                None => assert!(version.bump_pre().is_err()),
+            }
+            assert_eq!(version.bump_post()?.to_string(), post);
+            assert_eq!(version.bump_dev()?.to_string(), dev);
+        }
+
+        let overflow = PackageVersion::new(&format!("1.0.dev{}", u32::MAX))?;
+        assert!(overflow.bump_dev().is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_finalize() -> Result<()> {
+        let versions = vec![
+            ("1.2.0rc3.dev2", "1.2.0"),
+            ("1.0a1.post2+local", "1.0"),
+            ("1.0.post1.dev2", "1.0.post1"),
+            ("1!2.0.dev1", "1!2.0"),
+            ("v1.0", "1.0"),
+        ];
+        for (version, final_release) in versions {
+            let version = PackageVersion::new(version)?;
+            assert_eq!(version.finalize().to_string(), final_release);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_promote_pre() -> Result<()> {
+        let versions = vec![
+            ("1.0.dev3", "1.0a0"),
+            ("1.0a1.dev2", "1.0b0"),
+            ("1.0b2.post1+local", "1.0rc0"),
+            ("1.0rc4", "1.0"),
+            ("1.0preview1", "1.0"),
+        ];
+        for (version, promoted) in versions {
+            let version = PackageVersion::new(version)?;
+            assert_eq!(version.promote_pre()?.to_string(), promoted);
+        }
+
+        let finals = vec!["1.0", "1.0.post1", "1.0.post1.dev1"];
+        for version in finals {
+            match PackageVersion::new(version)?.promote_pre() {
+                Ok(v) => panic!("Oh no {}", v),
+                Err(_e) => continue,
+            }
+        }
+        Ok(())
+    }
+}
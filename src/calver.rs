@@ -0,0 +1,386 @@
+//! # Calendar versioning
+//! Calendar versions (`2024.1`, `24.1.15.2`) following a declared scheme
+//! such as `YYYY.MM` or `YY.0M.0D.MICRO`
+//!
+//! Read more at <https://calver.org/>
+//!
+//! ## Example Usage
+//! ```
+//! use pyver::calver::{CalverDate, CalverScheme};
+//! use pyver::PackageVersion;
+//!
+//! let scheme = CalverScheme::new("YYYY.0M.MICRO").unwrap();
+//! let date = CalverDate::new(2024, 1, 15).unwrap();
+//!
+//! let first = scheme.next(None, date).unwrap();
+//! assert_eq!(first.to_string(), "2024.01.0");
+//!
+//! let second = scheme.next(Some(&first), date).unwrap();
+//! assert_eq!(second.to_string(), "2024.01.1");
+//!
+//! assert!(scheme.validate(&PackageVersion::new("2024.13.0").unwrap()).is_err());
+//! ```
+
+use crate::scm::civil_from_days;
+use crate::PackageVersion;
+use anyhow::Result;
+use std::fmt;
+use std::str::FromStr;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// A calendar date (proleptic Gregorian)
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub struct CalverDate {
+    year: u32,
+    month: u32,
+    day: u32,
+}
+
+impl CalverDate {
+    /// Fails for days which don't exist (`2023-02-29`)
+    pub fn new(year: u32, month: u32, day: u32) -> Result<Self> {
+        if !(1..=12).contains(&month)
+            || day == 0
+            || u64::from(day) > days_in_month(year.into(), month.into())
+        {
+            anyhow::bail!("Invalid date {:04}-{:02}-{:02}", year, month, day);
+        }
+        Ok(Self { year, month, day })
+    }
+
+    /// Today's date in UTC
+    pub fn today() -> Result<Self> {
+        let seconds = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs();
+        let (year, month, day) = civil_from_days((seconds / 86400) as i64);
+        Self::new(u32::try_from(year)?, month, day)
+    }
+
+    pub fn year(&self) -> u32 {
+        self.year
+    }
+
+    pub fn month(&self) -> u32 {
+        self.month
+    }
+
+    pub fn day(&self) -> u32 {
+        self.day
+    }
+}
+
+/// One dot separated part of a calendar versioning scheme
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub enum CalverPart {
+    /// `YYYY`, the full year (`2024`)
+    FullYear,
+    /// `YY`, the year since 2000 (`24`, `106`)
+    ShortYear,
+    /// `0Y`, the year since 2000 padded to two digits (`06`)
+    ZeroPaddedYear,
+    /// `MM`, the month (`1`, `12`)
+    Month,
+    /// `0M`, the month padded to two digits (`01`)
+    ZeroPaddedMonth,
+    /// `DD`, the day of the month (`1`, `31`)
+    Day,
+    /// `0D`, the day padded to two digits (`01`)
+    ZeroPaddedDay,
+    /// `MICRO`, a counter for releases sharing the date parts
+    Micro,
+}
+
+impl CalverPart {
+    /// The part as written in a scheme
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CalverPart::FullYear => "YYYY",
+            CalverPart::ShortYear => "YY",
+            CalverPart::ZeroPaddedYear => "0Y",
+            CalverPart::Month => "MM",
+            CalverPart::ZeroPaddedMonth => "0M",
+            CalverPart::Day => "DD",
+            CalverPart::ZeroPaddedDay => "0D",
+            CalverPart::Micro => "MICRO",
+        }
+    }
+
+    /// The segment of a release on the date, `None` for `MICRO`
+    fn segment(&self, date: &CalverDate) -> Option<u64> {
+        match self {
+            CalverPart::FullYear => Some(u64::from(date.year)),
+            CalverPart::ShortYear | CalverPart::ZeroPaddedYear => {
+                Some(u64::from(date.year.saturating_sub(2000)))
+            }
+            CalverPart::Month | CalverPart::ZeroPaddedMonth => {
+                Some(u64::from(date.month))
+            }
+            CalverPart::Day | CalverPart::ZeroPaddedDay => Some(u64::from(date.day)),
+            CalverPart::Micro => None,
+        }
+    }
+
+    /// Writes a segment the way the part is written in versions
+    fn write(&self, segment: u64) -> String {
+        match self {
+            CalverPart::ZeroPaddedYear
+            | CalverPart::ZeroPaddedMonth
+            | CalverPart::ZeroPaddedDay => format!("{:02}", segment),
+            _ => segment.to_string(),
+        }
+    }
+}
+
+/// # Calendar versioning scheme
+/// The parts of a calendar version's release, such as `YYYY.MM.DD.MICRO`
+///
+/// Padding is only a matter of writing the versions, `PEP-440` ignores
+/// leading zeros so `2024.1` is valid for `YYYY.0M` as well.
+/// Pre, post and dev segments are allowed after the release.
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub struct CalverScheme {
+    parts: Vec<CalverPart>,
+}
+
+impl CalverScheme {
+    /// Parses a scheme like `YYYY.0M.MICRO`, which has to start with a year
+    /// and can only end with `MICRO`
+    pub fn new(scheme: &str) -> Result<Self> {
+        let parts = scheme
+            .trim()
+            .split('.')
+            .map(|part| match part {
+                "YYYY" => Ok(CalverPart::FullYear),
+                "YY" => Ok(CalverPart::ShortYear),
+                "0Y" => Ok(CalverPart::ZeroPaddedYear),
+                "MM" => Ok(CalverPart::Month),
+                "0M" => Ok(CalverPart::ZeroPaddedMonth),
+                "DD" => Ok(CalverPart::Day),
+                "0D" => Ok(CalverPart::ZeroPaddedDay),
+                "MICRO" => Ok(CalverPart::Micro),
+                _ => Err(anyhow::anyhow!("Unknown calendar version part {}", part)),
+            })
+            .collect::<Result<Vec<CalverPart>>>()?;
+
+        let is_year = |part: &CalverPart| {
+            matches!(
+                part,
+                CalverPart::FullYear
+                    | CalverPart::ShortYear
+                    | CalverPart::ZeroPaddedYear
+            )
+        };
+        if !is_year(&parts[0]) || parts[1..].iter().any(is_year) {
+            anyhow::bail!("Scheme {} has to start with its only year", scheme);
+        }
+        if parts[..parts.len() - 1].contains(&CalverPart::Micro) {
+            anyhow::bail!("Scheme {} can only end with MICRO", scheme);
+        }
+        Ok(Self { parts })
+    }
+
+    pub fn parts(&self) -> &[CalverPart] {
+        &self.parts
+    }
+
+    /// Whether the release of a version matches the scheme,
+    /// with one segment per part and an existing date
+    pub fn validate(&self, version: &PackageVersion) -> Result<()> {
+        let segments = version.release().segments();
+        if segments.len() != self.parts.len() {
+            anyhow::bail!(
+                "Version {} needs {} release segments for scheme {}",
+                version,
+                self.parts.len(),
+                self
+            );
+        }
+
+        let (mut year, mut month, mut day) = (0, None, None);
+        for (part, segment) in self.parts.iter().zip(segments) {
+            match part {
+                CalverPart::FullYear => year = *segment,
+                CalverPart::ShortYear | CalverPart::ZeroPaddedYear => {
+                    year = segment.saturating_add(2000)
+                }
+                CalverPart::Month | CalverPart::ZeroPaddedMonth => {
+                    month = Some(*segment)
+                }
+                CalverPart::Day | CalverPart::ZeroPaddedDay => day = Some(*segment),
+                CalverPart::Micro => {}
+            }
+        }
+
+        if let Some(month) = month.filter(|month| !(1..=12).contains(month)) {
+            anyhow::bail!("Version {} has an invalid month {}", version, month);
+        }
+        // Without a month any day a month can have is fine
+        let max_day = month.map_or(31, |month| days_in_month(year, month));
+        if let Some(day) = day.filter(|day| *day == 0 || *day > max_day) {
+            anyhow::bail!("Version {} has an invalid day {}", version, day);
+        }
+        Ok(())
+    }
+
+    /// Parses and validates a calendar version
+    pub fn parse(&self, version: &str) -> Result<PackageVersion> {
+        let version = PackageVersion::new(version)?;
+        self.validate(&version)?;
+        Ok(version)
+    }
+
+    /// The version for a date and `MICRO` counter,
+    /// the counter is ignored if the scheme has none
+    pub fn format(&self, date: CalverDate, micro: u64) -> Result<PackageVersion> {
+        let release = self
+            .parts
+            .iter()
+            .map(|part| part.write(part.segment(&date).unwrap_or(micro)))
+            .collect::<Vec<String>>()
+            .join(".");
+        PackageVersion::new(&release)
+    }
+
+    /// The first version on a date after the latest release
+    ///
+    /// Releases on the same date increment `MICRO`, which fails for
+    /// schemes without one. The latest release has to match the scheme.
+    pub fn next(
+        &self,
+        latest: Option<&PackageVersion>,
+        date: CalverDate,
+    ) -> Result<PackageVersion> {
+        let latest = match latest {
+            Some(latest) => latest,
+            None => return self.format(date, 0),
+        };
+        self.validate(latest)?;
+
+        let segments = latest.release().segments();
+        let same_date =
+            self.parts.iter().zip(segments).all(|(part, segment)| {
+                part.segment(&date).is_none_or(|s| s == *segment)
+            });
+        if !same_date {
+            return self.format(date, 0);
+        }
+
+        match self.parts.last() {
+            Some(CalverPart::Micro) => {
+                match segments[segments.len() - 1].checked_add(1) {
+                    Some(micro) => self.format(date, micro),
+                    None => anyhow::bail!("MICRO of {} overflows", latest),
+                }
+            }
+            _ => anyhow::bail!("{} was already released for scheme {}", latest, self),
+        }
+    }
+}
+
+fn days_in_month(year: u64, month: u64) -> u64 {
+    match month {
+        2 if year.is_multiple_of(4)
+            && (!year.is_multiple_of(100) || year.is_multiple_of(400)) =>
+        {
+            29
+        }
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+impl FromStr for CalverScheme {
+    type Err = anyhow::Error;
+
+    fn from_str(scheme: &str) -> Result<Self> {
+        Self::new(scheme)
+    }
+}
+
+impl fmt::Display for CalverScheme {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let parts: Vec<&str> = self.parts.iter().map(CalverPart::as_str).collect();
+        write!(f, "{}", parts.join("."))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{CalverDate, CalverScheme};
+    use crate::PackageVersion;
+    use anyhow::Result;
+
+    #[test]
+    fn test_calver_scheme_parsing() -> Result<()> {
+        let scheme: CalverScheme = " YYYY.0M.0D.MICRO ".parse()?;
+        assert_eq!(scheme.parts().len(), 4);
+        assert_eq!(scheme.to_string(), "YYYY.0M.0D.MICRO");
+
+        let schemes = vec!["", "MM.YYYY", "YYYY.YY", "YYYY.MICRO.MM", "YYYY.WW"];
+        for scheme in schemes {
+            match CalverScheme::new(scheme) {
+                Ok(v) => panic!("Oh no {}", v),
+                Err(_e) => continue,
+            }
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_calver_validate() -> Result<()> {
+        // (scheme, version, valid)
+        let versions = vec![
+            ("YYYY.MM", "2024.1", true),
+            ("YYYY.0M", "2024.01", true),
+            ("YYYY.0M", "2024.1rc1", true),
+            ("YYYY.MM", "2024.13", false),
+            ("YYYY.MM", "2024.0", false),
+            ("YYYY.MM", "2024.1.0", false),
+            ("YY.MM.DD", "24.2.29", true),
+            ("YY.MM.DD", "23.2.29", false),
+            ("YYYY.MM.DD.MICRO", "2024.4.31.0", false),
+            ("YYYY.DD", "2024.31", true),
+            ("YYYY.DD", "2024.32", false),
+        ];
+        for (scheme, version, valid) in versions {
+            let scheme = CalverScheme::new(scheme)?;
+            assert_eq!(
+                scheme.parse(version).is_ok(),
+                valid,
+                "{} {}",
+                scheme,
+                version
+            );
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_calver_next() -> Result<()> {
+        let date = CalverDate::new(2024, 3, 5)?;
+        let scheme = CalverScheme::new("0Y.0M.0D.MICRO")?;
+        assert_eq!(scheme.format(date, 7)?.to_string(), "24.03.05.7");
+
+        let latest = PackageVersion::new("24.3.5.1")?;
+        assert_eq!(scheme.next(Some(&latest), date)?.to_string(), "24.03.05.2");
+        let latest = PackageVersion::new("24.3.4.1")?;
+        assert_eq!(scheme.next(Some(&latest), date)?.to_string(), "24.03.05.0");
+
+        let scheme = CalverScheme::new("YYYY.MM")?;
+        let latest = PackageVersion::new("2024.3")?;
+        assert!(scheme.next(Some(&latest), date).is_err());
+        let date = CalverDate::new(2024, 4, 1)?;
+        assert_eq!(scheme.next(Some(&latest), date)?.to_string(), "2024.4");
+        Ok(())
+    }
+
+    #[test]
+    fn test_calver_date() -> Result<()> {
+        assert!(CalverDate::new(2023, 2, 29).is_err());
+        assert!(CalverDate::new(2000, 2, 29).is_ok());
+        assert!(CalverDate::new(1900, 2, 29).is_err());
+        assert!(CalverDate::new(2024, 0, 1).is_err());
+        assert!(CalverDate::today()?.year() >= 2024);
+        Ok(())
+    }
+}
@@ -0,0 +1,462 @@
+//! # Version lists
+//! Sorting, deduplicating and picking from lists of versions,
+//! given as `PackageVersion`s or as strings to parse
+//!
+//! Strings which aren't valid versions don't stop the others from
+//! being processed, they are reported next to the result.
+//!
+//! ## Example Usage
+//! ```
+//! use pyver::collections::sort_versions;
+//!
+//! let sorted = sort_versions(["1.10", "1.9", "junk", "1.10.0rc1"]);
+//! let sorted: Vec<String> = sorted.value.iter().map(|v| v.to_string()).collect();
+//!
+//! assert_eq!(sorted, vec!["1.9", "1.10.0rc1", "1.10"]);
+//! ```
+
+use crate::version::SORT_KEY_LEN;
+use crate::PackageVersion;
+use std::cmp::Ordering;
+use std::collections::HashSet;
+use std::fmt;
+
+/// An item of a list which isn't a valid version
+#[derive(Debug)]
+pub struct InvalidVersion {
+    /// Position of the item in the list
+    pub index: usize,
+    pub error: anyhow::Error,
+}
+
+/// The result for the valid versions of a list and the invalid items
+#[derive(Debug)]
+pub struct Parsed<T> {
+    pub value: T,
+    pub invalid: Vec<InvalidVersion>,
+}
+
+/// Parses every item, keeping the valid versions in order
+///
+/// ```
+/// use pyver::collections::parse_versions;
+///
+/// let parsed = parse_versions(vec!["1.0", "not a version", "v2"]);
+/// assert_eq!(parsed.value.len(), 2);
+/// assert_eq!(parsed.invalid[0].index, 1);
+/// ```
+pub fn parse_versions<V>(
+    versions: impl IntoIterator<Item = V>,
+) -> Parsed<Vec<PackageVersion>>
+where
+    V: TryInto<PackageVersion>,
+    V::Error: Into<anyhow::Error>,
+{
+    let mut parsed = Parsed {
+        value: Vec::new(),
+        invalid: Vec::new(),
+    };
+    for (index, version) in versions.into_iter().enumerate() {
+        match version.try_into() {
+            Ok(version) => parsed.value.push(version),
+            Err(error) => parsed.invalid.push(InvalidVersion {
+                index,
+                error: error.into(),
+            }),
+        }
+    }
+    parsed
+}
+
+/// The valid versions in `PEP-440` order, equal versions
+/// keep their order in the list
+pub fn sort_versions<V>(
+    versions: impl IntoIterator<Item = V>,
+) -> Parsed<Vec<PackageVersion>>
+where
+    V: TryInto<PackageVersion>,
+    V::Error: Into<anyhow::Error>,
+{
+    let mut parsed = parse_versions(versions);
+    parsed.value.sort();
+    parsed
+}
+
+/// Parses every item on all cores, keeping the valid versions in order
+///
+/// ```
+/// use pyver::collections::parse_all_par;
+///
+/// let parsed = parse_all_par(&["1.0", "not a version", "v2"]);
+/// assert_eq!(parsed.value.len(), 2);
+/// assert_eq!(parsed.invalid[0].index, 1);
+/// ```
+#[cfg(feature = "rayon")]
+pub fn parse_all_par<S: AsRef<str> + Sync>(
+    versions: &[S],
+) -> Parsed<Vec<PackageVersion>> {
+    use rayon::prelude::*;
+
+    let results: Vec<anyhow::Result<PackageVersion>> = versions
+        .par_iter()
+        .map(|version| PackageVersion::new(version.as_ref()))
+        .collect();
+
+    let mut parsed = Parsed {
+        value: Vec::with_capacity(results.len()),
+        invalid: Vec::new(),
+    };
+    for (index, result) in results.into_iter().enumerate() {
+        match result {
+            Ok(version) => parsed.value.push(version),
+            Err(error) => parsed.invalid.push(InvalidVersion { index, error }),
+        }
+    }
+    parsed
+}
+
+/// The valid versions in `PEP-440` order, parsed and sorted on all cores,
+/// equal versions keep their order in the list
+#[cfg(feature = "rayon")]
+pub fn sort_par<S: AsRef<str> + Sync>(versions: &[S]) -> Parsed<Vec<PackageVersion>> {
+    use rayon::prelude::*;
+
+    let mut parsed = parse_all_par(versions);
+    parsed.value.par_sort();
+    parsed
+}
+
+/// Where pre-releases (including developmental releases) are sorted
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Hash)]
+pub enum PrereleaseOrder {
+    /// `PEP-440` order, right before their final release
+    #[default]
+    Pep440,
+    /// Before every final release
+    First,
+    /// After every final release
+    Last,
+}
+
+/// How [`sort_with`] orders versions, the default is `PEP-440` order
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub struct SortOptions {
+    /// Where pre-releases are sorted
+    pub prereleases: PrereleaseOrder,
+    /// Whether local version labels are compared,
+    /// otherwise `1.0+local` and `1.0` are equal
+    pub local: bool,
+    /// Whether equal versions are ordered by the strings they were written as,
+    /// otherwise they keep their order in the list
+    pub original: bool,
+    /// Whether the highest version comes first
+    pub descending: bool,
+}
+
+impl Default for SortOptions {
+    fn default() -> Self {
+        Self {
+            prereleases: PrereleaseOrder::Pep440,
+            local: true,
+            original: false,
+            descending: false,
+        }
+    }
+}
+
+/// The valid versions sorted with the given options
+///
+/// ```
+/// use pyver::collections::{sort_with, PrereleaseOrder, SortOptions};
+///
+/// let options = SortOptions {
+///     prereleases: PrereleaseOrder::Last,
+///     descending: true,
+///     ..Default::default()
+/// };
+/// let sorted = sort_with(["1.0", "2.0rc1", "1.1"], options).value;
+/// let sorted: Vec<String> = sorted.iter().map(|v| v.to_string()).collect();
+///
+/// assert_eq!(sorted, vec!["1.1", "1.0", "2.0rc1"]);
+/// ```
+pub fn sort_with<V>(
+    versions: impl IntoIterator<Item = V>,
+    options: SortOptions,
+) -> Parsed<Vec<PackageVersion>>
+where
+    V: TryInto<PackageVersion>,
+    V::Error: Into<anyhow::Error>,
+{
+    let group = |version: &PackageVersion| match options.prereleases {
+        PrereleaseOrder::Pep440 => 0,
+        PrereleaseOrder::First => u8::from(!version.is_prerelease()),
+        PrereleaseOrder::Last => u8::from(version.is_prerelease()),
+    };
+    let depth = match options.local {
+        true => SORT_KEY_LEN,
+        false => SORT_KEY_LEN - 1,
+    };
+
+    let mut parsed = parse_versions(versions);
+    parsed.value.sort_by(|a, b| {
+        let ordering = a.cmp_prefix(b, depth);
+        let ordering = match options.descending {
+            true => ordering.reverse(),
+            false => ordering,
+        };
+        let original = match options.original {
+            true => a.written().cmp(&b.written()),
+            false => Ordering::Equal,
+        };
+        // Descending order only reverses the versions, a group is still
+        // first or last
+        group(a).cmp(&group(b)).then(ordering).then(original)
+    });
+    parsed
+}
+
+/// The valid versions without later duplicates, keeping the first
+/// of equal versions as written (`1.0` and not `1.0.0`)
+///
+/// ```
+/// use pyver::collections::dedup;
+///
+/// let unique = dedup(["1.0", "v1.0.0", "1.1", "1.0"]).value;
+/// assert_eq!(unique.len(), 2);
+/// assert_eq!(unique[0].to_string(), "1.0");
+/// ```
+pub fn dedup<V>(versions: impl IntoIterator<Item = V>) -> Parsed<Vec<PackageVersion>>
+where
+    V: TryInto<PackageVersion>,
+    V::Error: Into<anyhow::Error>,
+{
+    let mut parsed = parse_versions(versions);
+    let mut seen = HashSet::new();
+    parsed
+        .value
+        .retain(|version| seen.insert(version.cmp_key()));
+    parsed
+}
+
+/// The highest valid version, the first one of equal versions
+pub fn max_version<V>(
+    versions: impl IntoIterator<Item = V>,
+) -> Parsed<Option<PackageVersion>>
+where
+    V: TryInto<PackageVersion>,
+    V::Error: Into<anyhow::Error>,
+{
+    let parsed = parse_versions(versions);
+    Parsed {
+        value: highest(parsed.value),
+        invalid: parsed.invalid,
+    }
+}
+
+/// The lowest valid version, the first one of equal versions
+pub fn min_version<V>(
+    versions: impl IntoIterator<Item = V>,
+) -> Parsed<Option<PackageVersion>>
+where
+    V: TryInto<PackageVersion>,
+    V::Error: Into<anyhow::Error>,
+{
+    let parsed = parse_versions(versions);
+    Parsed {
+        value: parsed.value.into_iter().reduce(|min, version| {
+            if version < min {
+                version
+            } else {
+                min
+            }
+        }),
+        invalid: parsed.invalid,
+    }
+}
+
+/// The highest stable version, or the highest pre-release (including
+/// developmental releases) if there is no stable one, like pip does
+///
+/// ```
+/// use pyver::collections::latest_stable;
+///
+/// let latest = latest_stable(["1.0", "1.1rc1", "0.9"]).value.unwrap();
+/// assert_eq!(latest.to_string(), "1.0");
+///
+/// let latest = latest_stable(["1.0b1", "1.0rc1.dev2"]).value.unwrap();
+/// assert_eq!(latest.to_string(), "1.0rc1.dev2");
+/// ```
+pub fn latest_stable<V>(
+    versions: impl IntoIterator<Item = V>,
+) -> Parsed<Option<PackageVersion>>
+where
+    V: TryInto<PackageVersion>,
+    V::Error: Into<anyhow::Error>,
+{
+    let parsed = parse_versions(versions);
+    let (stable, pre): (Vec<PackageVersion>, Vec<PackageVersion>) = parsed
+        .value
+        .into_iter()
+        .partition(|version| !version.is_prerelease());
+    let candidates = if stable.is_empty() { pre } else { stable };
+    Parsed {
+        value: highest(candidates),
+        invalid: parsed.invalid,
+    }
+}
+
+/// The highest version, the first one of equal versions
+fn highest(versions: Vec<PackageVersion>) -> Option<PackageVersion> {
+    versions
+        .into_iter()
+        .reduce(|max, version| if version > max { version } else { max })
+}
+
+/// Writes the position and the error (`item 2: Failed to decode version junk`)
+impl fmt::Display for InvalidVersion {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "item {}: {}", self.index, self.error)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        dedup, latest_stable, max_version, min_version, parse_versions, sort_versions,
+        sort_with, PrereleaseOrder, SortOptions,
+    };
+    use crate::PackageVersion;
+    use anyhow::Result;
+
+    #[test]
+    fn test_parse_versions() -> Result<()> {
+        let parsed = parse_versions(vec!["1.0".to_string(), "1.0 junk".to_string()]);
+        assert_eq!(parsed.value, vec![PackageVersion::new("1.0")?]);
+        assert_eq!(parsed.invalid.len(), 1);
+        assert_eq!(
+            parsed.invalid[0].to_string(),
+            "item 1: Failed to decode version 1.0 junk"
+        );
+
+        let versions = vec![PackageVersion::new("2.0")?, PackageVersion::new("1.0")?];
+        let parsed = parse_versions(versions.clone());
+        assert_eq!(parsed.value, versions);
+        assert!(parsed.invalid.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn test_sort_and_dedup() -> Result<()> {
+        let versions = vec!["1.0.0", "2.0", "1.0", "1.0rc1", "?", "1.0.post1", "v1"];
+
+        let sorted: Vec<String> = sort_versions(versions.clone())
+            .value
+            .iter()
+            .map(|v| v.to_string())
+            .collect();
+        assert_eq!(
+            sorted,
+            vec!["1.0rc1", "1.0.0", "1.0", "v1", "1.0.post1", "2.0"]
+        );
+
+        let unique = dedup(versions);
+        let unique_strs: Vec<String> =
+            unique.value.iter().map(|v| v.to_string()).collect();
+        assert_eq!(unique_strs, vec!["1.0.0", "2.0", "1.0rc1", "1.0.post1"]);
+        assert_eq!(unique.invalid[0].index, 4);
+        Ok(())
+    }
+
+    #[test]
+    fn test_min_and_max_version() -> Result<()> {
+        let versions = vec!["1.0", "2.0", "junk", "2.0.0", "0.9"];
+        let max = max_version(versions.clone());
+        assert_eq!(max.value.map(|v| v.to_string()), Some("2.0".to_string()));
+        assert_eq!(max.invalid.len(), 1);
+        let min = min_version(versions);
+        assert_eq!(min.value.map(|v| v.to_string()), Some("0.9".to_string()));
+
+        let empty: Vec<&str> = Vec::new();
+        assert!(max_version(empty).value.is_none());
+        assert!(min_version(vec!["junk"]).value.is_none());
+        Ok(())
+    }
+
+    #[test]
+    fn test_latest_stable() -> Result<()> {
+        let lists = vec![
+            (vec!["1.0", "2.0rc1", "1.1.dev1", "0.9"], Some("1.0")),
+            (vec!["1.0+local", "1.0.post1", "0.9"], Some("1.0.post1")),
+            (
+                vec!["2.0a1", "junk", "2.0b1.dev1", "1.0a1"],
+                Some("2.0b1.dev1"),
+            ),
+            (vec!["1.0.dev1"], Some("1.0.dev1")),
+            (vec!["junk"], None),
+        ];
+        for (versions, latest) in lists {
+            let found = latest_stable(versions).value.map(|v| v.to_string());
+            assert_eq!(found.as_deref(), latest);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_sort_with() -> Result<()> {
+        let versions = vec!["1.0+b", "1.0rc1", "v1.0", "1.1.dev1", "1.0+a", "0.9"];
+        let sorted = |options: SortOptions| -> Vec<String> {
+            sort_with(versions.clone(), options)
+                .value
+                .iter()
+                .map(|v| v.to_string())
+                .collect()
+        };
+
+        assert_eq!(
+            sorted(SortOptions::default()),
+            vec!["0.9", "1.0rc1", "v1.0", "1.0+a", "1.0+b", "1.1.dev1"]
+        );
+        let options = SortOptions {
+            prereleases: PrereleaseOrder::First,
+            local: false,
+            ..Default::default()
+        };
+        assert_eq!(
+            sorted(options),
+            vec!["1.0rc1", "1.1.dev1", "0.9", "1.0+b", "v1.0", "1.0+a"]
+        );
+        let options = SortOptions {
+            prereleases: PrereleaseOrder::Last,
+            local: false,
+            original: true,
+            descending: true,
+        };
+        assert_eq!(
+            sorted(options),
+            vec!["1.0+a", "1.0+b", "v1.0", "0.9", "1.1.dev1", "1.0rc1"]
+        );
+        Ok(())
+    }
+
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn test_parallel() -> Result<()> {
+        let versions: Vec<String> = (0..1000)
+            .rev()
+            .map(|i| match i % 100 {
+                0 => format!("{} junk", i),
+                _ => format!("{}.{}", i / 10, i % 10),
+            })
+            .collect();
+
+        let parsed = super::parse_all_par(&versions);
+        assert_eq!(parsed.value, parse_versions(versions.clone()).value);
+        assert_eq!(parsed.invalid.len(), 10);
+        assert_eq!(parsed.invalid[0].index, 99);
+
+        let sorted = super::sort_par(&versions);
+        assert_eq!(sorted.value, sort_versions(versions.clone()).value);
+        assert_eq!(sorted.value[0].to_string(), "0.1");
+        Ok(())
+    }
+}
@@ -0,0 +1,280 @@
+use crate::PackageVersion;
+use core::cmp::Ordering;
+use core::fmt;
+
+/// # Comparison explanation
+/// How two versions compare and which component decided it,
+/// see [`PackageVersion::explain_cmp`]
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub struct Comparison {
+    /// How the first version compares to the second
+    pub ordering: Ordering,
+    /// The first component the versions differ in
+    pub decided_by: ComparedBy,
+}
+
+/// The component of two versions deciding their order
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub enum ComparedBy {
+    /// No component differs, the versions are equal
+    Equal,
+    /// One of them is the synthetic [`PackageVersion::MAX`]
+    Max,
+    /// The epochs (`1!1.0` against `2.0`)
+    Epoch,
+    /// The release segment at the index, missing segments are zeros
+    /// (`1.2` against `1.10` is decided by segment 1)
+    Release(usize),
+    /// Developmental, alpha, beta and release candidate phases
+    /// or the final release (`1.0a1` against `1.0rc1`)
+    PrePhase,
+    /// The number within the same pre-release phase (`1.0a1` against `1.0a2`)
+    PreNumber,
+    /// The post-release numbers, or whether there is one at all
+    Post,
+    /// The developmental release numbers, or whether there is one at all
+    Dev,
+    /// The local version labels
+    Local,
+}
+
+/// # Version difference
+/// The most significant change between two versions,
+/// see [`PackageVersion::diff`]
+///
+/// The classification doesn't depend on the direction,
+/// going from `2.0` to `1.0` is a `MajorBump` as well
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub enum VersionDiff {
+    /// The versions are equal
+    Equal,
+    /// The epochs differ
+    EpochChange,
+    /// The first release segment differs (`1.4` to `2.0`)
+    MajorBump,
+    /// The second release segment differs (`1.4` to `1.5`)
+    MinorBump,
+    /// A later release segment differs (`1.4.1` to `1.4.2`, `1.4.1.1` to `1.4.1.2`)
+    MicroBump,
+    /// Only pre-release or developmental segments differ (`2.0a1` to `2.0`)
+    PreReleaseChange,
+    /// Only the post-release differs (`2.0` to `2.0.post1`)
+    PostOnly,
+    /// Only the local version label differs (`2.0+ubuntu1` to `2.0+ubuntu2`)
+    LocalOnly,
+}
+
+impl PackageVersion {
+    /// Compares the versions and tells which component decided their order
+    ///
+    /// ```
+    /// use pyver::{ComparedBy, PackageVersion};
+    /// use std::cmp::Ordering;
+    ///
+    /// let a = PackageVersion::new("1.2.9").unwrap();
+    /// let b = PackageVersion::new("1.10").unwrap();
+    ///
+    /// let comparison = a.explain_cmp(&b);
+    /// assert_eq!(comparison.ordering, Ordering::Less);
+    /// assert_eq!(comparison.decided_by, ComparedBy::Release(1));
+    /// assert_eq!(comparison.to_string(), "less because of release segment 1");
+    /// ```
+    pub fn explain_cmp(&self, other: &Self) -> Comparison {
+        let (a, b) = (self, other);
+        let release_len = a.release.len().max(b.release.len());
+
+        let candidates = [
+            (a.is_max().cmp(&b.is_max()), ComparedBy::Max),
+            (a.epoch().cmp(&b.epoch()), ComparedBy::Epoch),
+        ]
+        .into_iter()
+        .chain((0..release_len).map(|i| {
+            let ordering = a.release.segment(i).cmp(&b.release.segment(i));
+            (ordering, ComparedBy::Release(i))
+        }))
+        .chain([
+            (
+                (a.pre_key().0, a.pre_key().1).cmp(&(b.pre_key().0, b.pre_key().1)),
+                ComparedBy::PrePhase,
+            ),
+            (a.pre_key().2.cmp(&b.pre_key().2), ComparedBy::PreNumber),
+            (a.post_key().cmp(&b.post_key()), ComparedBy::Post),
+            (a.dev_key().cmp(&b.dev_key()), ComparedBy::Dev),
+            (
+                a.local_segments().cmp(&b.local_segments()),
+                ComparedBy::Local,
+            ),
+        ]);
+
+        for (ordering, decided_by) in candidates {
+            if ordering.is_ne() {
+                return Comparison {
+                    ordering,
+                    decided_by,
+                };
+            }
+        }
+        Comparison {
+            ordering: Ordering::Equal,
+            decided_by: ComparedBy::Equal,
+        }
+    }
+
+    /// Classifies the most significant change between the versions
+    ///
+    /// ```
+    /// use pyver::{PackageVersion, VersionDiff};
+    ///
+    /// let diff = |a: &str, b: &str| {
+    ///     PackageVersion::new(a).unwrap().diff(&PackageVersion::new(b).unwrap())
+    /// };
+    /// assert_eq!(diff("1.4.2", "1.5.0"), VersionDiff::MinorBump);
+    /// assert_eq!(diff("2.0rc1", "2.0"), VersionDiff::PreReleaseChange);
+    /// assert_eq!(diff("2.0", "2.0.0+local"), VersionDiff::LocalOnly);
+    /// ```
+    pub fn diff(&self, other: &Self) -> VersionDiff {
+        match self.explain_cmp(other).decided_by {
+            ComparedBy::Equal => VersionDiff::Equal,
+            ComparedBy::Max | ComparedBy::Epoch => VersionDiff::EpochChange,
+            ComparedBy::Release(0) => VersionDiff::MajorBump,
+            ComparedBy::Release(1) => VersionDiff::MinorBump,
+            ComparedBy::Release(_) => VersionDiff::MicroBump,
+            ComparedBy::PrePhase | ComparedBy::PreNumber | ComparedBy::Dev => {
+                VersionDiff::PreReleaseChange
+            }
+            ComparedBy::Post => VersionDiff::PostOnly,
+            ComparedBy::Local => VersionDiff::LocalOnly,
+        }
+    }
+
+    /// Whether upgrading from `self` to `other` stays within the compatible
+    /// release range of `self`, keeping the first `precision` release segments
+    ///
+    /// A precision of 2 for `1.4.2` is `~=1.4.2` (`>=1.4.2, ==1.4.*`),
+    /// a precision of 1 is `>=1.4.2, ==1.*`. Like other ranges
+    /// this only looks at the order of the versions, pre-releases in range
+    /// are compatible.
+    ///
+    /// ```
+    /// use pyver::PackageVersion;
+    ///
+    /// let current = PackageVersion::new("1.4.2").unwrap();
+    /// let minor = PackageVersion::new("1.5.0").unwrap();
+    ///
+    /// assert!(current.compatible_with(&minor, 1));
+    /// assert!(!current.compatible_with(&minor, 2));
+    /// assert!(!minor.compatible_with(&current, 1));
+    /// ```
+    pub fn compatible_with(&self, other: &Self, precision: usize) -> bool {
+        other >= self
+            && other.epoch() == self.epoch()
+            && (0..precision)
+                .all(|i| other.release.segment(i) == self.release.segment(i))
+    }
+}
+
+impl fmt::Display for ComparedBy {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ComparedBy::Equal => write!(f, "no difference"),
+            ComparedBy::Max => write!(f, "the synthetic maximum version"),
+            ComparedBy::Epoch => write!(f, "the epoch"),
+            ComparedBy::Release(i) => write!(f, "release segment {}", i),
+            ComparedBy::PrePhase => write!(f, "the pre-release phase"),
+            ComparedBy::PreNumber => write!(f, "the pre-release number"),
+            ComparedBy::Post => write!(f, "the post-release"),
+            ComparedBy::Dev => write!(f, "the developmental release"),
+            ComparedBy::Local => write!(f, "the local version"),
+        }
+    }
+}
+
+/// Writes the ordering and the reason (`less because of the epoch`)
+impl fmt::Display for Comparison {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let ordering = match self.ordering {
+            Ordering::Less => "less",
+            Ordering::Equal => "equal",
+            Ordering::Greater => "greater",
+        };
+        write!(f, "{} because of {}", ordering, self.decided_by)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ComparedBy, VersionDiff};
+    use crate::PackageVersion;
+    use anyhow::Result;
+
+    #[test]
+    fn test_explain_cmp() -> Result<()> {
+        let pairs = vec![
+            ("1!1.0", "2.0", ComparedBy::Epoch),
+            ("1.0", "1.0.0", ComparedBy::Equal),
+            ("1.0.1", "1.0", ComparedBy::Release(2)),
+            ("1.0.dev1", "1.0a1", ComparedBy::PrePhase),
+            ("1.0b1", "1.0rc1", ComparedBy::PrePhase),
+            ("1.0a1", "1.0", ComparedBy::PrePhase),
+            ("1.0rc1", "1.0rc2", ComparedBy::PreNumber),
+            ("1.0.post1", "1.0", ComparedBy::Post),
+            ("1.0.post1.dev1", "1.0.post1", ComparedBy::Dev),
+            ("1.0+abc", "1.0+5", ComparedBy::Local),
+        ];
+        for (a, b, decided_by) in pairs {
+            let (a, b) = (PackageVersion::new(a)?, PackageVersion::new(b)?);
+            let comparison = a.explain_cmp(&b);
+            assert_eq!(comparison.decided_by, decided_by, "{} {}", a, b);
+            assert_eq!(comparison.ordering, a.cmp(&b), "{} {}", a, b);
+            assert_eq!(b.explain_cmp(&a).ordering, b.cmp(&a), "{} {}", b, a);
+        }
+
+        let max = PackageVersion::MAX.explain_cmp(&PackageVersion::new("1.0")?);
+        assert_eq!(max.decided_by, ComparedBy::Max);
+        Ok(())
+    }
+
+    #[test]
+    fn test_diff() -> Result<()> {
+        let pairs = vec![
+            ("1.0", "1.0.0", VersionDiff::Equal),
+            ("1.0", "1!1.0", VersionDiff::EpochChange),
+            ("1.9", "2.0", VersionDiff::MajorBump),
+            ("2.0", "1.9", VersionDiff::MajorBump),
+            ("1.4", "1.5a1", VersionDiff::MinorBump),
+            ("1.4.1", "1.4.2", VersionDiff::MicroBump),
+            ("1.4.1.1", "1.4.1.2", VersionDiff::MicroBump),
+            ("1.4.dev3", "1.4a1", VersionDiff::PreReleaseChange),
+            ("1.4.post1.dev1", "1.4.post1", VersionDiff::PreReleaseChange),
+            ("1.4", "1.4.post1", VersionDiff::PostOnly),
+            ("1.4+a", "1.4+b", VersionDiff::LocalOnly),
+        ];
+        for (a, b, diff) in pairs {
+            let (a, b) = (PackageVersion::new(a)?, PackageVersion::new(b)?);
+            assert_eq!(a.diff(&b), diff, "{} {}", a, b);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_compatible_with() -> Result<()> {
+        let current = PackageVersion::new("2.3.1")?;
+        // (version, precision 1, precision 2, precision 3)
+        let versions = vec![
+            ("2.3.1", true, true, true),
+            ("2.3.1.post1", true, true, true),
+            ("2.3.5", true, true, false),
+            ("2.4a1", true, false, false),
+            ("3.0", false, false, false),
+            ("2.3.0", false, false, false),
+            ("1!2.3.1", false, false, false),
+        ];
+        for (version, major, minor, micro) in versions {
+            let version = PackageVersion::new(version)?;
+            assert_eq!(current.compatible_with(&version, 1), major, "{}", version);
+            assert_eq!(current.compatible_with(&version, 2), minor, "{}", version);
+            assert_eq!(current.compatible_with(&version, 3), micro, "{}", version);
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,719 @@
+//! # Conda versions
+//! Versions and version constraints of conda packages, ordered and matched
+//! like conda's `VersionOrder` and `VersionSpec`
+//!
+//! Conda versions look like `PEP-440` ones but are split into components at
+//! `.` and `_` and those into runs of digits and letters, so any letters are
+//! allowed and sort before numbers (`1.1.parallel`, `1.0.1_`). Only `dev`
+//! and `post` are special, `dev` sorts before other letters and `post`
+//! after every number. Missing components count as `0`, so `1.1` and `1.1.0`
+//! are equal, and versions of the same `PEP-440` release can sort
+//! differently (`1.0.dev1` comes after `1.0a1` in conda).
+//!
+//! Read more at <https://docs.conda.io/projects/conda-build/en/stable/resources/package-spec.html>
+//!
+//! ## Example Usage
+//! ```
+//! use pyver::conda::{CondaVersion, CondaVersionSpec};
+//!
+//! let version = CondaVersion::new("1.2.3_ALPHA").unwrap();
+//! assert!(version < CondaVersion::new("1.2.3").unwrap());
+//! assert_eq!(version.to_string(), "1.2.3_alpha");
+//!
+//! let spec = CondaVersionSpec::new("1.2.*|>=2,<3").unwrap();
+//! assert!(spec.contains(&CondaVersion::new("1.2.7").unwrap()));
+//! assert!(spec.contains(&CondaVersion::new("2.5").unwrap()));
+//! assert!(!spec.contains(&CondaVersion::new("1.3").unwrap()));
+//! ```
+
+use crate::PackageVersion;
+use alloc::string::{String, ToString};
+use alloc::vec;
+use alloc::vec::Vec;
+use anyhow::Result;
+use core::cmp::Ordering;
+use core::fmt;
+use core::str::FromStr;
+
+/// A run of digits, letters or `*` in a component
+#[derive(Clone, Debug, Eq, PartialEq)]
+enum Part {
+    /// The digits without leading zeros, empty for zero
+    Number(String),
+    /// `post`, after every number
+    Post,
+    /// Letters or `*`, with `dev` as `DEV` so it sorts first
+    Text(String),
+}
+
+/// The value of missing components and parts, like `0` of `1.1.0` in `1.1`
+const ZERO: Part = Part::Number(String::new());
+
+/// Strings sort before numbers and numbers before `post`
+fn cmp_part(a: &Part, b: &Part) -> Ordering {
+    match (a, b) {
+        (Part::Text(a), Part::Text(b)) => a.cmp(b),
+        (Part::Text(_), _) => Ordering::Less,
+        (_, Part::Text(_)) => Ordering::Greater,
+        (Part::Post, Part::Post) => Ordering::Equal,
+        (Part::Post, _) => Ordering::Greater,
+        (_, Part::Post) => Ordering::Less,
+        (Part::Number(a), Part::Number(b)) => (a.len(), a).cmp(&(b.len(), b)),
+    }
+}
+
+/// Compares the parts of a component pairwise, filling in missing ones
+/// with zeros
+fn cmp_parts(a: &[Part], b: &[Part]) -> Ordering {
+    for i in 0..a.len().max(b.len()) {
+        let ordering = cmp_part(a.get(i).unwrap_or(&ZERO), b.get(i).unwrap_or(&ZERO));
+        if ordering != Ordering::Equal {
+            return ordering;
+        }
+    }
+    Ordering::Equal
+}
+
+/// Compares components pairwise, filling in missing ones with zeros
+fn cmp_components(a: &[Vec<Part>], b: &[Vec<Part>]) -> Ordering {
+    for i in 0..a.len().max(b.len()) {
+        let one = a.get(i).map_or(&[][..], Vec::as_slice);
+        let two = b.get(i).map_or(&[][..], Vec::as_slice);
+        let ordering = cmp_parts(one, two);
+        if ordering != Ordering::Equal {
+            return ordering;
+        }
+    }
+    Ordering::Equal
+}
+
+/// Splits a component into runs of digits, `*` and other characters,
+/// starting it with a zero if it doesn't start with a number
+fn split_component(component: &str) -> Result<Vec<Part>> {
+    if component.is_empty() {
+        anyhow::bail!("Empty version component");
+    }
+    let mut parts = Vec::new();
+    if !component.starts_with(|c: char| c.is_ascii_digit()) {
+        parts.push(ZERO);
+    }
+    let kind = |c: char| match c {
+        '0'..='9' => 0,
+        '*' => 1,
+        _ => 2,
+    };
+    let mut rest = component;
+    while let Some(first) = rest.chars().next() {
+        let end = rest
+            .find(|c: char| kind(c) != kind(first))
+            .unwrap_or(rest.len());
+        let (run, tail) = rest.split_at(end);
+        parts.push(match run {
+            _ if kind(first) == 0 => {
+                Part::Number(run.trim_start_matches('0').to_string())
+            }
+            "post" => Part::Post,
+            "dev" => Part::Text("DEV".to_string()),
+            _ => Part::Text(run.to_string()),
+        });
+        rest = tail;
+    }
+    Ok(parts)
+}
+
+/// # Conda version
+/// A version of a conda package, ordered like conda orders them
+///
+/// ```
+/// use pyver::conda::CondaVersion;
+///
+/// let versions = [
+///     "0.4.1.rc", "0.4.1", "1.1dev1", "1.1_", "1.1a1", "1.1", "1.1.post1",
+/// ];
+/// let versions: Vec<CondaVersion> = versions
+///     .iter()
+///     .map(|v| CondaVersion::new(v).unwrap())
+///     .collect();
+/// assert!(versions.windows(2).all(|pair| pair[0] < pair[1]));
+/// assert_eq!(versions[5], CondaVersion::new("1.1.0").unwrap());
+/// ```
+#[derive(Clone, Debug)]
+pub struct CondaVersion {
+    normalized: String,
+    version: Vec<Vec<Part>>,
+    local: Vec<Vec<Part>>,
+}
+
+impl CondaVersion {
+    /// Parses a version of letters, digits and `.`, `_`, `*`, an optional
+    /// epoch (`1!`) and local version (`+local`), ignoring case
+    ///
+    /// Dashes are read as underscores unless the version has both.
+    pub fn new(version: &str) -> Result<Self> {
+        let is_valid = |version: &str| {
+            version.chars().all(
+                |c| matches!(c, '*' | '.' | '+' | '!' | '_' | '0'..='9' | 'a'..='z'),
+            )
+        };
+        let mut normalized = version.trim().to_ascii_lowercase();
+        if normalized.is_empty() {
+            anyhow::bail!("Empty conda version");
+        }
+        if !is_valid(&normalized)
+            && normalized.contains('-')
+            && !normalized.contains('_')
+        {
+            normalized = normalized.replace('-', "_");
+        }
+        if !is_valid(&normalized) {
+            anyhow::bail!("Invalid characters in the conda version {}", version);
+        }
+
+        let (epoch, rest) = match normalized.split_once('!') {
+            Some((epoch, _))
+                if epoch.is_empty() || !epoch.bytes().all(|c| c.is_ascii_digit()) =>
+            {
+                anyhow::bail!(
+                    "The epoch of the conda version {} isn't a number",
+                    version
+                )
+            }
+            Some((_, rest)) if rest.contains('!') => {
+                anyhow::bail!("More than one epoch in the conda version {}", version)
+            }
+            Some((epoch, rest)) => (epoch, rest),
+            None => ("0", normalized.as_str()),
+        };
+        let (public, local) = match rest.split_once('+') {
+            Some((_, local)) if local.contains('+') => {
+                anyhow::bail!(
+                    "More than one local version in the conda version {}",
+                    version
+                )
+            }
+            Some((public, local)) => (public, Some(local)),
+            None => (rest, None),
+        };
+        if public.is_empty() {
+            anyhow::bail!("No version before the local version in {}", version);
+        }
+
+        let mut components = vec![epoch.to_string()];
+        match public.strip_suffix('_') {
+            // A trailing underscore stays on the last component, so
+            // `1.0.1_` sorts before `1.0.1a` like openssl versions
+            Some(public) => {
+                components
+                    .extend(public.replace('_', ".").split('.').map(str::to_string));
+                if let Some(last) = components.last_mut() {
+                    last.push('_');
+                }
+            }
+            None => components
+                .extend(public.replace('_', ".").split('.').map(str::to_string)),
+        }
+        let local: Vec<String> = match local {
+            Some(local) => local
+                .replace('_', ".")
+                .split('.')
+                .map(str::to_string)
+                .collect(),
+            None => Vec::new(),
+        };
+
+        let split = |components: &[String]| -> Result<Vec<Vec<Part>>> {
+            components
+                .iter()
+                .map(|component| split_component(component))
+                .collect::<Result<_>>()
+                .map_err(|e| anyhow::anyhow!("{} in the conda version {}", e, version))
+        };
+        Ok(Self {
+            version: split(&components)?,
+            local: split(&local)?,
+            normalized,
+        })
+    }
+
+    /// Whether the version starts with all components of `prefix`, the last
+    /// one as a prefix if it ends in letters (`1.2.3` starts with `1.2` but
+    /// not with `1.23`, `1.2rc1` with `1.2r`)
+    ///
+    /// ```
+    /// use pyver::conda::CondaVersion;
+    ///
+    /// let version = CondaVersion::new("1.2.3").unwrap();
+    /// assert!(version.starts_with(&CondaVersion::new("1.2").unwrap()));
+    /// assert!(!version.starts_with(&CondaVersion::new("1.23").unwrap()));
+    /// ```
+    pub fn starts_with(&self, prefix: &CondaVersion) -> bool {
+        let (own, theirs) = if prefix.local.is_empty() {
+            (&self.version, &prefix.version)
+        } else {
+            if cmp_components(&self.version, &prefix.version) != Ordering::Equal {
+                return false;
+            }
+            (&self.local, &prefix.local)
+        };
+
+        let last = theirs.len() - 1;
+        if cmp_components(&own[..last.min(own.len())], &theirs[..last])
+            != Ordering::Equal
+        {
+            return false;
+        }
+        let own = own.get(last).map_or(&[][..], Vec::as_slice);
+        let theirs = &theirs[last];
+        let last = theirs.len() - 1;
+        if cmp_parts(&own[..last.min(own.len())], &theirs[..last]) != Ordering::Equal {
+            return false;
+        }
+        match (own.get(last).unwrap_or(&ZERO), &theirs[last]) {
+            (Part::Text(own), Part::Text(theirs)) => own.starts_with(theirs.as_str()),
+            (_, Part::Text(_)) => false,
+            (own, theirs) => own == theirs,
+        }
+    }
+}
+
+/// Writes the version lowercased, with dashes read as underscores
+impl fmt::Display for CondaVersion {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.normalized)
+    }
+}
+
+impl FromStr for CondaVersion {
+    type Err = anyhow::Error;
+
+    fn from_str(version: &str) -> Result<Self> {
+        Self::new(version)
+    }
+}
+
+/// The conda version of the normal form (`1.0rc1.post2`), which conda
+/// orders differently than `PEP-440` for some versions
+impl TryFrom<&PackageVersion> for CondaVersion {
+    type Error = anyhow::Error;
+
+    fn try_from(version: &PackageVersion) -> Result<Self> {
+        Self::new(&version.to_normalized_string())
+    }
+}
+
+impl Ord for CondaVersion {
+    fn cmp(&self, other: &Self) -> Ordering {
+        cmp_components(&self.version, &other.version)
+            .then_with(|| cmp_components(&self.local, &other.local))
+    }
+}
+
+impl PartialOrd for CondaVersion {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Equal when conda sees the same version (`1.1` and `1.1.0`)
+impl PartialEq for CondaVersion {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for CondaVersion {}
+
+/// How a single constraint compares versions
+#[derive(Clone, Debug)]
+enum Constraint {
+    /// `*`
+    Any,
+    /// `1.*.3`, matching the written version with `*` as any text
+    Glob(String),
+    Equal(CondaVersion),
+    NotEqual(CondaVersion),
+    Less(CondaVersion),
+    LessEqual(CondaVersion),
+    Greater(CondaVersion),
+    GreaterEqual(CondaVersion),
+    /// `1.2.*`, `1.2*` and `=1.2`
+    StartsWith(CondaVersion),
+    /// `!=1.2.*`
+    NotStartsWith(CondaVersion),
+    /// `~=1.2.3`, at least the version and starting with `1.2`
+    Compatible(CondaVersion, CondaVersion),
+    /// Constraints joined by `,`
+    All(Vec<Constraint>),
+    /// Constraints joined by `|`
+    AnyOf(Vec<Constraint>),
+}
+
+impl Constraint {
+    fn contains(&self, version: &CondaVersion) -> bool {
+        match self {
+            Constraint::Any => true,
+            Constraint::Glob(glob) => {
+                glob_match(glob.as_bytes(), version.normalized.as_bytes())
+            }
+            Constraint::Equal(other) => version == other,
+            Constraint::NotEqual(other) => version != other,
+            Constraint::Less(other) => version < other,
+            Constraint::LessEqual(other) => version <= other,
+            Constraint::Greater(other) => version > other,
+            Constraint::GreaterEqual(other) => version >= other,
+            Constraint::StartsWith(prefix) => version.starts_with(prefix),
+            Constraint::NotStartsWith(prefix) => !version.starts_with(prefix),
+            Constraint::Compatible(other, prefix) => {
+                version >= other && version.starts_with(prefix)
+            }
+            Constraint::All(constraints) => {
+                constraints.iter().all(|c| c.contains(version))
+            }
+            Constraint::AnyOf(constraints) => {
+                constraints.iter().any(|c| c.contains(version))
+            }
+        }
+    }
+}
+
+/// Whether `text` matches `glob`, where `*` stands for any characters
+fn glob_match(glob: &[u8], text: &[u8]) -> bool {
+    match glob.split_first() {
+        None => text.is_empty(),
+        Some((b'*', rest)) => {
+            (0..=text.len()).any(|skip| glob_match(rest, &text[skip..]))
+        }
+        Some((c, rest)) => text.first() == Some(c) && glob_match(rest, &text[1..]),
+    }
+}
+
+/// # Conda version spec
+/// A constraint on conda versions like `>=1.2,<2|3.*`, where `,` binds
+/// tighter than `|` and parentheses group constraints
+///
+/// Each constraint is an operator (`==`, `!=`, `<`, `<=`, `>`, `>=`, `~=`
+/// or `=`, which matches versions starting with the version) and a version,
+/// a version ending in `*` (`1.2.*`), `*` for any version, a version with
+/// `*` in the middle matched against the written version (`1.*.3`) or a
+/// version only equal versions match. Regular expression constraints
+/// (`^1\.2$`) aren't supported.
+///
+/// ```
+/// use pyver::conda::{CondaVersion, CondaVersionSpec};
+///
+/// let spec = CondaVersionSpec::new("(1.7|1.8.*),!=1.8.2").unwrap();
+/// let matches = |v: &str| spec.contains(&CondaVersion::new(v).unwrap());
+/// assert!(matches("1.7") && matches("1.8.1") && !matches("1.8.2"));
+/// assert!(!matches("1.7.1"));
+/// ```
+#[derive(Clone, Debug)]
+pub struct CondaVersionSpec {
+    spec: String,
+    constraint: Constraint,
+}
+
+impl CondaVersionSpec {
+    pub fn new(spec: &str) -> Result<Self> {
+        let mut parser = SpecParser {
+            spec,
+            rest: spec.trim(),
+        };
+        let constraint = parser.any_of()?;
+        if !parser.rest.is_empty() {
+            anyhow::bail!(
+                "Unexpected {} in the conda version spec {}",
+                parser.rest,
+                spec
+            );
+        }
+        Ok(Self {
+            spec: spec.trim().to_string(),
+            constraint,
+        })
+    }
+
+    /// Whether the version meets the constraints
+    pub fn contains(&self, version: &CondaVersion) -> bool {
+        self.constraint.contains(version)
+    }
+}
+
+/// Writes the spec as it was given
+impl fmt::Display for CondaVersionSpec {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.spec)
+    }
+}
+
+impl FromStr for CondaVersionSpec {
+    type Err = anyhow::Error;
+
+    fn from_str(spec: &str) -> Result<Self> {
+        Self::new(spec)
+    }
+}
+
+/// Reads `|` separated groups of `,` separated constraints
+struct SpecParser<'a> {
+    spec: &'a str,
+    rest: &'a str,
+}
+
+impl SpecParser<'_> {
+    fn eat(&mut self, token: char) -> bool {
+        match self.rest.strip_prefix(token) {
+            Some(rest) => {
+                self.rest = rest.trim_start();
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn any_of(&mut self) -> Result<Constraint> {
+        let mut constraints = vec![self.all()?];
+        while self.eat('|') {
+            constraints.push(self.all()?);
+        }
+        Ok(match constraints.len() {
+            1 => constraints.remove(0),
+            _ => Constraint::AnyOf(constraints),
+        })
+    }
+
+    fn all(&mut self) -> Result<Constraint> {
+        let mut constraints = vec![self.single()?];
+        while self.eat(',') {
+            constraints.push(self.single()?);
+        }
+        Ok(match constraints.len() {
+            1 => constraints.remove(0),
+            _ => Constraint::All(constraints),
+        })
+    }
+
+    fn single(&mut self) -> Result<Constraint> {
+        if self.eat('(') {
+            let constraint = self.any_of()?;
+            if !self.eat(')') {
+                anyhow::bail!(
+                    "Unclosed parenthesis in the conda version spec {}",
+                    self.spec
+                );
+            }
+            return Ok(constraint);
+        }
+        let end = self
+            .rest
+            .find(['(', ')', '|', ','])
+            .unwrap_or(self.rest.len());
+        let (term, rest) = self.rest.split_at(end);
+        self.rest = rest;
+        parse_constraint(term.trim()).map_err(|e| {
+            anyhow::anyhow!("{} in the conda version spec {}", e, self.spec)
+        })
+    }
+}
+
+/// Reads one constraint without `,`, `|` or parentheses
+fn parse_constraint(term: &str) -> Result<Constraint> {
+    if term.is_empty() {
+        anyhow::bail!("Empty constraint");
+    }
+    if term.starts_with('^') || term.ends_with('$') {
+        anyhow::bail!("Unsupported regular expression {}", term);
+    }
+
+    const OPERATORS: [&str; 8] = ["==", "!=", "<=", ">=", "~=", "=", "<", ">"];
+    if let Some(operator) = OPERATORS.iter().find(|op| term.starts_with(*op)) {
+        let version = &term[operator.len()..];
+        if version.is_empty()
+            || version.starts_with(['=', '<', '>', '!', '~'])
+            || version.contains(char::is_whitespace)
+        {
+            anyhow::bail!("Invalid operator in {}", term);
+        }
+        let (version, wildcard) = match version.strip_suffix(".*") {
+            Some(version) => (version, true),
+            None => (version, false),
+        };
+        let parsed = CondaVersion::new(version)?;
+        return Ok(match (*operator, wildcard) {
+            ("=", _) => Constraint::StartsWith(parsed),
+            ("!=", true) => Constraint::NotStartsWith(parsed),
+            ("~=", true) => anyhow::bail!("Invalid operator with .* in {}", term),
+            ("~=", false) => {
+                let prefix = match parsed.normalized.rsplit_once('.') {
+                    Some((prefix, _)) => CondaVersion::new(prefix)?,
+                    None => anyhow::bail!("Nothing to keep for ~= in {}", term),
+                };
+                Constraint::Compatible(parsed, prefix)
+            }
+            // The wildcard is ignored with the other operators
+            ("==", _) => Constraint::Equal(parsed),
+            ("!=", _) => Constraint::NotEqual(parsed),
+            ("<=", _) => Constraint::LessEqual(parsed),
+            (">=", _) => Constraint::GreaterEqual(parsed),
+            ("<", _) => Constraint::Less(parsed),
+            _ => Constraint::Greater(parsed),
+        });
+    }
+
+    if term == "*" {
+        Ok(Constraint::Any)
+    } else if term.trim_end_matches('*').contains('*') {
+        Ok(Constraint::Glob(term.to_string()))
+    } else if let Some(prefix) = term.strip_suffix('*') {
+        let prefix = prefix.trim_end_matches('*').trim_end_matches('.');
+        Ok(Constraint::StartsWith(CondaVersion::new(prefix)?))
+    } else {
+        Ok(Constraint::Equal(CondaVersion::new(term)?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{CondaVersion, CondaVersionSpec};
+    use crate::PackageVersion;
+
+    #[test]
+    fn test_conda_version_order() {
+        // The order in the documentation of conda's `VersionOrder`
+        let versions = [
+            "0.4",
+            "0.4.1.rc",
+            "0.4.1",
+            "0.5a1",
+            "0.5b3",
+            "0.5C1",
+            "0.5",
+            "0.9.6",
+            "0.960923",
+            "1.0",
+            "1.1dev1",
+            "1.1_",
+            "1.1a1",
+            "1.1.0dev1",
+            "1.1.a1",
+            "1.1.0rc1",
+            "1.1.0",
+            "1.1.0post1",
+            "1.1post1",
+            "1996.07.12",
+            "1!0.4.1",
+            "1!3.1.1.6",
+            "2!0.4.1",
+        ];
+        let parsed: Vec<CondaVersion> = versions
+            .iter()
+            .map(|v| CondaVersion::new(v).unwrap())
+            .collect();
+        for pair in parsed.windows(2) {
+            assert!(pair[0] < pair[1], "{} and {}", pair[0], pair[1]);
+        }
+
+        let equal = [
+            ("0.4", "0.4.0"),
+            ("0.4.1.rc", "0.4.1.RC"),
+            ("1.1.0dev1", "1.1.dev1"),
+            ("1.1", "1.1.0"),
+            ("1.1.0post1", "1.1.post1"),
+            ("1.1.a1", "1.1.0a1"),
+            ("1.0-2", "1.0_2"),
+            ("007", "7"),
+        ];
+        for (a, b) in equal {
+            assert_eq!(CondaVersion::new(a).unwrap(), CondaVersion::new(b).unwrap());
+        }
+        assert!(
+            CondaVersion::new("1.0+1").unwrap() > CondaVersion::new("1.0+a").unwrap()
+        );
+        assert!(
+            CondaVersion::new("1.1+2").unwrap() < CondaVersion::new("1.2").unwrap()
+        );
+    }
+
+    #[test]
+    fn test_conda_version_from_package_version() {
+        let version = PackageVersion::new("v1!1.0RC1.post2").unwrap();
+        let conda = CondaVersion::try_from(&version).unwrap();
+        assert_eq!(conda.to_string(), "1!1.0rc1.post2");
+
+        // Conda doesn't sort dev releases first
+        let dev = CondaVersion::new("1.0.dev1").unwrap();
+        assert!(dev > CondaVersion::new("1.0a1").unwrap());
+    }
+
+    #[test]
+    fn test_conda_version_negative() {
+        for version in [
+            "", "1.0 2", "1..0", "1.0.", "1!2!3", "a!1.0", "+1.0", "1.0+", "1+2+3",
+            "1.0-2_3", "1.0#",
+        ] {
+            match CondaVersion::new(version) {
+                Ok(v) => panic!("Oh no {}", v),
+                Err(_e) => continue,
+            }
+        }
+    }
+
+    #[test]
+    fn test_conda_version_spec() {
+        let cases = [
+            ("1.2.*", "1.2.3", true),
+            ("1.2.*", "1.20", false),
+            ("1.2*", "1.2.3", true),
+            ("=1.2", "1.2.0.1", true),
+            ("=1.2", "1.3", false),
+            ("1.2", "1.2.0", true),
+            ("1.2", "1.2.1", false),
+            ("==1.2", "1.2", true),
+            ("!=1.2.*", "1.2.5", false),
+            ("!=1.2.*", "1.3", true),
+            (">=1.2.*", "1.3", true),
+            ("<2.0.*", "1.9", true),
+            ("~=1.2.3", "1.2.5", true),
+            ("~=1.2.3", "1.3", false),
+            ("~=1.2.3", "1.2.2", false),
+            ("*", "7", true),
+            ("1.*.3", "1.2.3", true),
+            ("1.*.3", "1.2.4", false),
+            (">=1.2,<2|3.*", "1.5", true),
+            (">=1.2,<2|3.*", "2.5", false),
+            (">=1.2,<2|3.*", "3.1", true),
+            ("(1.5|(1.6|1.7),1.8,1.9|2.0)|2.1", "2.1", true),
+            ("1.5|(1.6|1.7),1.8,1.9|2.0", "1.6", false),
+            ("1.2rc", "1.2rc0", true),
+            ("1.2r*", "1.2rc1", true),
+        ];
+        for (spec, version, expected) in cases {
+            let parsed = CondaVersionSpec::new(spec).unwrap();
+            let matches = parsed.contains(&CondaVersion::new(version).unwrap());
+            assert_eq!(matches, expected, "{} and {}", spec, version);
+        }
+        let spec = CondaVersionSpec::new(" >=1.2,<2 ").unwrap();
+        assert_eq!(spec.to_string(), ">=1.2,<2");
+    }
+
+    #[test]
+    fn test_conda_version_spec_negative() {
+        for spec in [
+            "",
+            "<= 1.2",
+            "> 1.0 , < 2",
+            "<>1.2",
+            "~=1.2.*",
+            "~=1",
+            ">=1.2,",
+            "(1.2",
+            "1.2)",
+            "^1\\.2$",
+            "||",
+        ] {
+            match CondaVersionSpec::new(spec) {
+                Ok(spec) => panic!("Oh no {}", spec),
+                Err(_e) => continue,
+            }
+        }
+    }
+}
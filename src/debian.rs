@@ -0,0 +1,171 @@
+use crate::PackageVersion;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use anyhow::Result;
+use core::fmt::Write;
+
+impl PackageVersion {
+    /// The upstream part of a Debian package version, ordered by dpkg like
+    /// the versions are by `PEP-440`
+    ///
+    /// The epoch is kept as the Debian epoch (`1:`), pre-releases and
+    /// developmental releases follow a `~` so they sort before the release
+    /// (`1.0~rc1`, `1.0~~dev2`), post-releases a `+` (`1.0+post1`) and the
+    /// local version label is kept after a `+` as well (`1.0+ubuntu.1`).
+    ///
+    /// dpkg compares local labels with `post` by their letters, so a local
+    /// version is only ordered right against the release and other local
+    /// versions of it. Release segments are written as they are, dpkg sorts
+    /// `1.0` before `1.0.0` although they are the same version.
+    ///
+    /// ```
+    ///# use pyver::PackageVersion;
+    /// let debian = |v: &str| PackageVersion::new(v).unwrap().to_debian_version();
+    /// assert_eq!(debian("1.0rc1"), "1.0~rc1");
+    /// assert_eq!(debian("1.0.dev2"), "1.0~~dev2");
+    /// assert_eq!(debian("1.0b1.dev2"), "1.0~b1~dev2");
+    /// assert_eq!(debian("1!2.0.post1+Local"), "1:2.0+post1+local");
+    /// ```
+    pub fn to_debian_version(&self) -> String {
+        let mut debian = String::new();
+        if self.epoch() != 0 {
+            let _ = write!(debian, "{}:", self.epoch());
+        }
+        let segments: Vec<String> = self
+            .release()
+            .segments()
+            .iter()
+            .map(|segment| segment.to_string())
+            .collect();
+        debian.push_str(&segments.join("."));
+
+        if let Some(pre) = self.pre() {
+            let _ = write!(debian, "~{}{}", pre.phase(), pre.number().unwrap_or(0));
+        }
+        if let Some(post) = self.post() {
+            let _ = write!(debian, "+post{}", post.post_num.unwrap_or(0));
+        }
+        if let Some(dev) = self.dev() {
+            // A second tilde sorts the dev releases of a release before
+            // its pre-releases
+            let tilde = if self.pre().is_none() && self.post().is_none() {
+                "~~"
+            } else {
+                "~"
+            };
+            let _ = write!(debian, "{}dev{}", tilde, dev.dev_num.unwrap_or(0));
+        }
+        if let Some(local) = self.normalized_local() {
+            debian.push('+');
+            debian.push_str(&local);
+        }
+        debian
+    }
+
+    /// Reads a Debian package version written by
+    /// [`PackageVersion::to_debian_version`] or by hand in the usual style
+    ///
+    /// The Debian revision (`-1ubuntu2`) is dropped, the epoch kept and the
+    /// `~` and `+` suffixes read back as pre, post, dev and local segments.
+    /// Other `+` suffixes, like those of repacked sources (`+dfsg`), become
+    /// the local version label.
+    ///
+    /// ```
+    ///# use pyver::PackageVersion;
+    /// let version = PackageVersion::from_debian_version("1:2.0~rc1-3").unwrap();
+    /// assert_eq!(version.to_string(), "1!2.0rc1");
+    ///
+    /// let version = PackageVersion::from_debian_version("1.4+dfsg-1").unwrap();
+    /// assert_eq!(version.to_string(), "1.4+dfsg");
+    /// assert!(PackageVersion::from_debian_version("1.0~git20240101").is_err());
+    /// ```
+    pub fn from_debian_version(debian: &str) -> Result<Self> {
+        let (epoch, rest) = match debian.split_once(':') {
+            Some((epoch, rest)) => (Some(epoch), rest),
+            None => (None, debian),
+        };
+        let upstream = rest.rsplit_once('-').map_or(rest, |(upstream, _)| upstream);
+
+        let mut parts = upstream.split('+');
+        let mut version = String::new();
+        if let Some(epoch) = epoch {
+            let _ = write!(version, "{}!", epoch);
+        }
+        version.push_str(&parts.next().unwrap_or_default().replace('~', ""));
+        let mut local = Vec::new();
+        for part in parts {
+            if part.starts_with("post") {
+                version.push('.');
+                version.push_str(&part.replace('~', ""));
+            } else {
+                local.push(part);
+            }
+        }
+        if !local.is_empty() {
+            version.push('+');
+            version.push_str(&local.join("."));
+        }
+
+        match Self::new(&version) {
+            Ok(version) => Ok(version.canonicalize()),
+            Err(_) => {
+                anyhow::bail!("The Debian version {} has no PEP 440 equivalent", debian)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::PackageVersion;
+
+    #[test]
+    fn test_to_debian_version() {
+        let versions = [
+            ("1.0", "1.0"),
+            ("v1.0.0", "1.0.0"),
+            ("1.0a", "1.0~a0"),
+            ("1.0-preview-2", "1.0~rc2"),
+            ("1.0.dev0", "1.0~~dev0"),
+            ("1.0rc1.dev2", "1.0~rc1~dev2"),
+            ("1.0.post1.dev2", "1.0+post1~dev2"),
+            ("1.0rc1.post1", "1.0~rc1+post1"),
+            ("1.0+Ubuntu-1", "1.0+ubuntu.1"),
+            ("2!1.0.post3+local", "2:1.0+post3+local"),
+        ];
+        for (version, debian) in versions {
+            let version = PackageVersion::new(version).unwrap();
+            assert_eq!(version.to_debian_version(), debian);
+            assert_eq!(
+                PackageVersion::from_debian_version(debian).unwrap(),
+                version
+            );
+        }
+    }
+
+    #[test]
+    fn test_from_debian_version() {
+        let versions = [
+            ("1.0-1", "1.0"),
+            ("1.0~b2-0ubuntu1", "1.0b2"),
+            ("3:1.0+post1-2", "3!1.0.post1"),
+            ("1.0+ds-1", "1.0+ds"),
+            ("1.0+dfsg+ds1", "1.0+dfsg.ds1"),
+            ("1.0+post1+dfsg-1", "1.0.post1+dfsg"),
+        ];
+        for (debian, expected) in versions {
+            let version = PackageVersion::from_debian_version(debian).unwrap();
+            assert_eq!(version.to_string(), expected);
+        }
+    }
+
+    #[test]
+    fn test_from_debian_version_negative() {
+        for debian in ["", "a:1.0", "1.0~git20240101", "1.0+"] {
+            match PackageVersion::from_debian_version(debian) {
+                Ok(v) => panic!("Oh no {}", v),
+                Err(_e) => continue,
+            }
+        }
+    }
+}
@@ -0,0 +1,72 @@
+use super::{SdistFilename, WheelFilename};
+use crate::{PackageName, PackageVersion};
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+
+/// # Distribution filename
+/// Either a wheel or a source distribution filename,
+/// told apart by the `.whl` extension
+///
+/// ## Example Usage
+/// ```
+/// use pyver::dist::DistFilename;
+///
+/// let wheel = DistFilename::new("spam-1.0-py3-none-any.whl").unwrap();
+/// let sdist = DistFilename::new("spam-1.0.tar.gz").unwrap();
+///
+/// assert!(matches!(wheel, DistFilename::Wheel(_)));
+/// assert_eq!(wheel.version(), sdist.version());
+/// ```
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq, Hash)]
+pub enum DistFilename {
+    Wheel(WheelFilename),
+    Sdist(SdistFilename),
+}
+
+impl DistFilename {
+    pub fn new(filename: &str) -> Result<Self> {
+        match filename.ends_with(".whl") {
+            true => Ok(Self::Wheel(WheelFilename::new(filename)?)),
+            false => Ok(Self::Sdist(SdistFilename::new(filename)?)),
+        }
+    }
+
+    /// Parses a filename belonging to `project`, failing if the
+    /// filename names another project, see [`SdistFilename::new_for_project`]
+    ///
+    /// ```
+    /// use pyver::dist::DistFilename;
+    ///
+    /// let wheel = "foo_bar-1.0-py3-none-any.whl";
+    /// assert!(DistFilename::new_for_project(wheel, "Foo.Bar").is_ok());
+    /// assert!(DistFilename::new_for_project(wheel, "foo").is_err());
+    /// ```
+    pub fn new_for_project(filename: &str, project: &str) -> Result<Self> {
+        if !filename.ends_with(".whl") {
+            let sdist = SdistFilename::new_for_project(filename, project)?;
+            return Ok(Self::Sdist(sdist));
+        }
+
+        let wheel = WheelFilename::new(filename)?;
+        if wheel.name != PackageName::new(project)? {
+            anyhow::bail!("Wheel filename {} does not match {}", filename, project);
+        }
+        Ok(Self::Wheel(wheel))
+    }
+
+    /// `PEP-503` normalized project name
+    pub fn name(&self) -> &PackageName {
+        match self {
+            Self::Wheel(wheel) => &wheel.name,
+            Self::Sdist(sdist) => &sdist.name,
+        }
+    }
+
+    /// Version of the distribution
+    pub fn version(&self) -> &PackageVersion {
+        match self {
+            Self::Wheel(wheel) => &wheel.version,
+            Self::Sdist(sdist) => &sdist.version,
+        }
+    }
+}
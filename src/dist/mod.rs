@@ -0,0 +1,17 @@
+//! # Distributions
+//! Parsing of distribution filenames (i.e. the files uploaded to an index)
+//!
+//! Importing Example
+//! ```
+//! use pyver::dist::{
+//!     select_best_wheel, DistFilename, SdistExtension, SdistFilename, WheelFilename,
+//! };
+//! ```
+
+mod filename;
+mod sdist;
+mod wheel;
+
+pub use filename::*;
+pub use sdist::*;
+pub use wheel::*;
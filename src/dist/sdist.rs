@@ -0,0 +1,200 @@
+use crate::name::normalize_name;
+use crate::{PackageName, PackageVersion};
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// # Source distribution archive format
+/// The file extensions accepted for source distributions
+#[derive(Hash, Copy, Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+pub enum SdistExtension {
+    /// `.tar.gz` archives (the only format allowed by `PEP-625`)
+    TarGz,
+    /// Legacy `.zip` archives
+    Zip,
+}
+
+impl SdistExtension {
+    /// The extension as it appears at the end of a filename
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SdistExtension::TarGz => ".tar.gz",
+            SdistExtension::Zip => ".zip",
+        }
+    }
+}
+
+/// # Source distribution filename
+/// Holds the components of a filename like `pkg-1.0.tar.gz`
+///
+/// Project names may themselves contain dashes and digits
+/// (`foo-2-1.0.tar.gz`), which makes the split point ambiguous.
+/// [`SdistFilename::new`] splits on the last dash, while
+/// [`SdistFilename::new_for_project`] uses the known project name to
+/// find the separator, the same way pip does when scanning an index page.
+///
+/// ## Example Usage
+/// ```
+/// use pyver::dist::SdistFilename;
+/// use pyver::PackageVersion;
+///
+/// let sdist = SdistFilename::new("Foo_Bar-1.0.tar.gz").unwrap();
+///
+/// assert_eq!(sdist.name, "foo-bar");
+/// assert_eq!(sdist.version, PackageVersion::new("1.0").unwrap());
+/// ```
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq, Hash)]
+pub struct SdistFilename {
+    /// `PEP-503` normalized project name
+    pub name: PackageName,
+    /// Version of the distribution
+    pub version: PackageVersion,
+    /// Archive format of the distribution
+    pub extension: SdistExtension,
+}
+
+impl SdistFilename {
+    /// Parses a source distribution filename, splitting the project name
+    /// from the version on the last dash
+    pub fn new(filename: &str) -> Result<Self> {
+        let (stem, extension) = split_extension(filename)?;
+
+        let (name, version) = match stem.rsplit_once('-') {
+            Some(v) => v,
+            None => anyhow::bail!("Sdist filename {} has no version", filename),
+        };
+
+        Self::from_parts(filename, name, version, extension)
+    }
+
+    /// Parses a source distribution filename belonging to `project`,
+    /// using the project name to locate the name/version separator
+    ///
+    /// ```
+    /// use pyver::dist::SdistFilename;
+    ///
+    /// let sdist = SdistFilename::new_for_project("foo-2-1.0.zip", "Foo-2").unwrap();
+    /// assert_eq!(sdist.version.to_string(), "1.0");
+    ///
+    /// assert!(SdistFilename::new_for_project("bar-1.0.zip", "foo").is_err());
+    /// ```
+    pub fn new_for_project(filename: &str, project: &str) -> Result<Self> {
+        let (stem, extension) = split_extension(filename)?;
+        let canonical = PackageName::new(project)?;
+
+        // Try every dash in turn and take the first prefix that
+        // normalizes to the requested project name
+        let separator = stem
+            .match_indices('-')
+            .map(|(i, _)| i)
+            .find(|&i| canonical == normalize_name(&stem[..i]).as_str());
+
+        match separator {
+            Some(i) => {
+                Self::from_parts(filename, &stem[..i], &stem[i + 1..], extension)
+            }
+            None => {
+                anyhow::bail!("Sdist filename {} does not match {}", filename, project)
+            }
+        }
+    }
+
+    fn from_parts(
+        filename: &str,
+        name: &str,
+        version: &str,
+        extension: SdistExtension,
+    ) -> Result<Self> {
+        if name.is_empty() {
+            anyhow::bail!("Sdist filename {} has no project name", filename);
+        }
+
+        Ok(Self {
+            name: PackageName::new(name)?,
+            version: PackageVersion::new(version)?,
+            extension,
+        })
+    }
+}
+
+/// Splits a filename into its stem and a known sdist extension
+fn split_extension(filename: &str) -> Result<(&str, SdistExtension)> {
+    for extension in [SdistExtension::TarGz, SdistExtension::Zip] {
+        let suffix = extension.as_str();
+        // Compare the extension case-insensitively like pip does
+        if filename.len() > suffix.len()
+            && filename.is_char_boundary(filename.len() - suffix.len())
+            && filename[filename.len() - suffix.len()..].eq_ignore_ascii_case(suffix)
+        {
+            return Ok((&filename[..filename.len() - suffix.len()], extension));
+        }
+    }
+    anyhow::bail!("{} is not a source distribution filename", filename)
+}
+
+impl fmt::Display for SdistFilename {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{}-{}{}",
+            self.name,
+            self.version,
+            self.extension.as_str()
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{SdistExtension, SdistFilename};
+    use crate::PackageVersion;
+    use anyhow::Result;
+
+    #[test]
+    fn test_sdist_parsing() -> Result<()> {
+        let sdist = SdistFilename::new("requests-2.28.1.tar.gz")?;
+        assert_eq!(sdist.name, "requests");
+        assert_eq!(sdist.version, PackageVersion::new("2.28.1")?);
+        assert_eq!(sdist.extension, SdistExtension::TarGz);
+
+        let sdist = SdistFilename::new("Zope.Interface-5.0.ZIP")?;
+        assert_eq!(sdist.name, "zope-interface");
+        assert_eq!(sdist.extension, SdistExtension::Zip);
+
+        // Without a project name the last dash is the separator
+        let sdist = SdistFilename::new("foo-bar-2-1.0rc1.tar.gz")?;
+        assert_eq!(sdist.name, "foo-bar-2");
+        assert_eq!(sdist.version, PackageVersion::new("1.0rc1")?);
+        Ok(())
+    }
+
+    #[test]
+    fn test_sdist_ambiguous_names() -> Result<()> {
+        let sdist = SdistFilename::new_for_project("foo-2-1.0.tar.gz", "FOO_2")?;
+        assert_eq!(sdist.name, "foo-2");
+        assert_eq!(sdist.version.to_string(), "1.0");
+
+        let sdist = SdistFilename::new_for_project("foo.2-1.0rc1.tar.gz", "foo-2")?;
+        assert_eq!(sdist.name, "foo-2");
+        assert_eq!(sdist.version.to_string(), "1.0rc1");
+
+        assert!(SdistFilename::new_for_project("foo-2-1.0.tar.gz", "bar").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_sdist_negative() {
+        let filenames = vec![
+            "requests-2.28.1-py3-none-any.whl",
+            "requests.tar.gz",
+            "-1.0.tar.gz",
+            ".tar.gz",
+        ];
+
+        for filename in filenames {
+            if let Ok(v) = SdistFilename::new(filename) {
+                panic!("Oh no {}", v);
+            }
+        }
+    }
+}
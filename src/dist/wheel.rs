@@ -0,0 +1,277 @@
+use crate::tags::{parse_tag_set, Tag, TagEnvironment};
+use crate::{PackageName, PackageVersion};
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// # Wheel build tag
+/// Optional tie-breaker in wheel filenames (`pkg-1.0-1a-py3-none-any.whl`),
+/// consisting of a leading number and an arbitrary suffix
+#[derive(
+    Hash, Ord, Clone, Debug, Serialize, Deserialize, Eq, PartialEq, PartialOrd,
+)]
+pub struct BuildTag {
+    pub number: u32,
+    pub suffix: String,
+}
+
+impl fmt::Display for BuildTag {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}{}", self.number, self.suffix)
+    }
+}
+
+/// # Wheel filename
+/// Holds the components of a filename like
+/// `pkg-1.0-cp311-cp311-manylinux_2_17_x86_64.whl`
+///
+/// Compressed tag sets are expanded, so `tags` holds every
+/// tag the wheel is compatible with.
+///
+/// ## Example Usage
+/// ```
+/// use pyver::dist::WheelFilename;
+/// use pyver::tags::Tag;
+///
+/// let wheel = WheelFilename::new("Foo_Bar-1.0-py2.py3-none-any.whl").unwrap();
+///
+/// assert_eq!(wheel.name, "foo-bar");
+/// assert_eq!(wheel.tags.len(), 2);
+/// assert!(wheel.tags.contains(&Tag::new("py3", "none", "any")));
+/// ```
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq, Hash)]
+pub struct WheelFilename {
+    /// `PEP-503` normalized project name
+    pub name: PackageName,
+    /// Version of the distribution
+    pub version: PackageVersion,
+    /// Optional build tag
+    pub build: Option<BuildTag>,
+    /// Every compatibility tag described by the filename
+    pub tags: Vec<Tag>,
+}
+
+impl WheelFilename {
+    pub fn new(filename: &str) -> Result<Self> {
+        let stem = match filename.strip_suffix(".whl") {
+            Some(v) => v,
+            None => anyhow::bail!("{} is not a wheel filename", filename),
+        };
+
+        let parts: Vec<&str> = stem.split('-').collect();
+        let (name, version, build, tag) = match parts.as_slice() {
+            [name, version, python, abi, platform] => (
+                name,
+                version,
+                None,
+                format!("{}-{}-{}", python, abi, platform),
+            ),
+            [name, version, build, python, abi, platform] => (
+                name,
+                version,
+                Some(parse_build_tag(build)?),
+                format!("{}-{}-{}", python, abi, platform),
+            ),
+            _ => anyhow::bail!(
+                "Wheel filename {} has an invalid number of parts",
+                filename
+            ),
+        };
+
+        if name.is_empty() {
+            anyhow::bail!("Wheel filename {} has no project name", filename);
+        }
+
+        Ok(Self {
+            name: PackageName::new(name)?,
+            version: PackageVersion::new(version)?,
+            build,
+            tags: parse_tag_set(&tag)?,
+        })
+    }
+
+    /// Whether any of the wheel's tags is supported by the environment
+    pub fn is_compatible(&self, env: &TagEnvironment) -> bool {
+        self.tags.iter().any(|tag| env.is_compatible(tag))
+    }
+
+    /// Best priority of the wheel's tags in the environment (lower is better)
+    pub fn priority(&self, env: &TagEnvironment) -> Option<usize> {
+        env.best_priority(&self.tags)
+    }
+}
+
+/// Parses a build tag, which has to start with a digit
+fn parse_build_tag(build: &str) -> Result<BuildTag> {
+    let split = build
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(build.len());
+    if split == 0 {
+        anyhow::bail!("Build tag {} must start with a digit", build);
+    }
+
+    Ok(BuildTag {
+        number: build[..split].parse::<u32>()?,
+        suffix: build[split..].to_string(),
+    })
+}
+
+impl fmt::Display for WheelFilename {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{}-{}",
+            self.name.as_str().replace('-', "_"),
+            self.version
+        )?;
+        if let Some(build) = &self.build {
+            write!(f, "-{}", build)?;
+        }
+
+        // Re-compress the tags component by component
+        let mut pythons: Vec<&str> = Vec::new();
+        let mut abis: Vec<&str> = Vec::new();
+        let mut platforms: Vec<&str> = Vec::new();
+        for tag in &self.tags {
+            for (values, value) in [
+                (&mut pythons, &tag.python),
+                (&mut abis, &tag.abi),
+                (&mut platforms, &tag.platform),
+            ] {
+                if !values.contains(&value.as_str()) {
+                    values.push(value);
+                }
+            }
+        }
+        write!(
+            f,
+            "-{}-{}-{}.whl",
+            pythons.join("."),
+            abis.join("."),
+            platforms.join(".")
+        )
+    }
+}
+
+/// Picks the wheel to install for an environment
+///
+/// Incompatible wheels are skipped. Among the rest the highest version wins,
+/// ties are broken by the most specific tag (the best priority in `env`)
+/// and then by the highest build tag.
+///
+/// ```
+/// use pyver::dist::{select_best_wheel, WheelFilename};
+/// use pyver::tags::TagEnvironment;
+///
+/// let env = TagEnvironment::cpython(3, 11, &["manylinux_2_17_x86_64"]);
+/// let candidates = vec![
+///     WheelFilename::new("pkg-1.0-py3-none-any.whl").unwrap(),
+///     WheelFilename::new("pkg-1.0-cp311-cp311-manylinux_2_17_x86_64.whl").unwrap(),
+///     WheelFilename::new("pkg-2.0-cp311-cp311-win_amd64.whl").unwrap(),
+/// ];
+///
+/// let best = select_best_wheel(&env, &candidates).unwrap();
+/// assert_eq!(best, &candidates[1]);
+/// ```
+pub fn select_best_wheel<'a>(
+    env: &TagEnvironment,
+    candidates: &'a [WheelFilename],
+) -> Option<&'a WheelFilename> {
+    candidates
+        .iter()
+        .enumerate()
+        .filter_map(|(index, wheel)| {
+            wheel.priority(env).map(|priority| (index, wheel, priority))
+        })
+        .max_by(|(a_index, a, a_priority), (b_index, b, b_priority)| {
+            a.version
+                .cmp(&b.version)
+                // A lower priority is a more specific tag
+                .then_with(|| b_priority.cmp(a_priority))
+                .then_with(|| a.build.cmp(&b.build))
+                // Prefer the earliest candidate on a full tie
+                .then_with(|| b_index.cmp(a_index))
+        })
+        .map(|(_, wheel, _)| wheel)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{select_best_wheel, BuildTag, WheelFilename};
+    use crate::tags::{Tag, TagEnvironment};
+    use crate::PackageVersion;
+    use anyhow::Result;
+
+    #[test]
+    fn test_wheel_parsing() -> Result<()> {
+        let wheel =
+            WheelFilename::new("numpy-1.24.0-cp311-cp311-manylinux_2_17_x86_64.whl")?;
+        assert_eq!(wheel.name, "numpy");
+        assert_eq!(wheel.version, PackageVersion::new("1.24.0")?);
+        assert_eq!(wheel.build, None);
+        assert_eq!(
+            wheel.tags,
+            vec![Tag::new("cp311", "cp311", "manylinux_2_17_x86_64")]
+        );
+
+        let wheel = WheelFilename::new("pkg-1.0-12b-py2.py3-none-any.whl")?;
+        assert_eq!(
+            wheel.build,
+            Some(BuildTag {
+                number: 12,
+                suffix: "b".to_string()
+            })
+        );
+        assert_eq!(wheel.to_string(), "pkg-1.0-12b-py2.py3-none-any.whl");
+        Ok(())
+    }
+
+    #[test]
+    fn test_wheel_negative() {
+        let filenames = vec![
+            "pkg-1.0.tar.gz",
+            "pkg-1.0-none-any.whl",
+            "pkg-1.0-b1-py3-none-any.whl",
+            "-1.0-py3-none-any.whl",
+            "pkg-1.0-1-2-py3-none-any.whl",
+        ];
+
+        for filename in filenames {
+            if let Ok(v) = WheelFilename::new(filename) {
+                panic!("Oh no {}", v);
+            }
+        }
+    }
+
+    #[test]
+    fn test_select_best_wheel() -> Result<()> {
+        let env =
+            TagEnvironment::cpython(3, 10, &["manylinux_2_17_x86_64", "linux_x86_64"]);
+        let candidates = vec![
+            WheelFilename::new("pkg-1.0-py3-none-any.whl")?,
+            WheelFilename::new("pkg-1.1-py3-none-any.whl")?,
+            WheelFilename::new("pkg-1.1-cp39-abi3-manylinux_2_17_x86_64.whl")?,
+            WheelFilename::new("pkg-1.1-1-cp39-abi3-manylinux_2_17_x86_64.whl")?,
+            WheelFilename::new("pkg-1.2-cp311-cp311-manylinux_2_17_x86_64.whl")?,
+        ];
+
+        // 1.2 isn't installable, the abi3 wheel beats the pure python one
+        // and the build tag breaks the remaining tie
+        assert_eq!(select_best_wheel(&env, &candidates), Some(&candidates[3]));
+
+        let env = TagEnvironment::cpython(3, 10, &["win_amd64"]);
+        assert_eq!(select_best_wheel(&env, &candidates), Some(&candidates[1]));
+
+        assert_eq!(select_best_wheel(&env, &candidates[2..]), None);
+
+        // On a full tie the first candidate wins, whichever it is
+        let mut tied = vec![
+            WheelFilename::new("pkg-1.0-py3-none-any.whl")?,
+            WheelFilename::new("pkg-1.0.0-py2.py3-none-any.whl")?,
+        ];
+        assert_eq!(select_best_wheel(&env, &tied), Some(&tied[0]));
+        tied.reverse();
+        assert_eq!(select_best_wheel(&env, &tied), Some(&tied[0]));
+        Ok(())
+    }
+}
@@ -0,0 +1,169 @@
+//! A C ABI for parsing, comparing and matching versions from C and C++
+//!
+//! The functions are declared in `include/pyver.h`, which is generated with
+//! [cbindgen](https://github.com/mozilla/cbindgen) from this module:
+//!
+//! ```sh
+//! cbindgen --config cbindgen.toml --output include/pyver.h
+//! cargo rustc --release --features ffi --crate-type cdylib
+//! ```
+//!
+//! ```c
+//! #include "pyver.h"
+//!
+//! PyverVersion *a = pyver_parse("1.0rc1");
+//! PyverVersion *b = pyver_parse("v1.0");
+//! assert(pyver_compare(a, b) < 0);
+//! assert(pyver_satisfies(b, ">=1.0, <2.0") == 1);
+//!
+//! char *normalized = pyver_normalize(b); // "1.0"
+//! pyver_string_free(normalized);
+//! pyver_version_free(a);
+//! pyver_version_free(b);
+//! ```
+//!
+//! Strings are NUL-terminated UTF-8. Versions and strings returned by the
+//! library are owned by the caller and freed with [`pyver_version_free`]
+//! and [`pyver_string_free`], never with `free`.
+
+use crate::specifiers::SpecifierSet;
+use crate::PackageVersion;
+use core::cmp::Ordering;
+use std::ffi::{c_char, c_int, CStr, CString};
+use std::ptr;
+
+/// # C version
+/// A parsed version, only handled through pointers from [`pyver_parse`]
+pub struct PyverVersion(PackageVersion);
+
+/// The string behind `string`, `None` for null pointers and invalid UTF-8
+///
+/// # Safety
+/// `string` is null or points to a NUL-terminated string
+unsafe fn to_str<'a>(string: *const c_char) -> Option<&'a str> {
+    if string.is_null() {
+        return None;
+    }
+    CStr::from_ptr(string).to_str().ok()
+}
+
+/// Parses a version, returning null if it isn't valid
+///
+/// # Safety
+/// `version` is null or points to a NUL-terminated string
+#[no_mangle]
+pub unsafe extern "C" fn pyver_parse(version: *const c_char) -> *mut PyverVersion {
+    match to_str(version).and_then(|version| PackageVersion::new(version).ok()) {
+        Some(version) => Box::into_raw(Box::new(PyverVersion(version))),
+        None => ptr::null_mut(),
+    }
+}
+
+/// Frees a version from [`pyver_parse`], null is ignored
+///
+/// # Safety
+/// `version` is null or came from [`pyver_parse`] and wasn't freed yet
+#[no_mangle]
+pub unsafe extern "C" fn pyver_version_free(version: *mut PyverVersion) {
+    if !version.is_null() {
+        drop(Box::from_raw(version));
+    }
+}
+
+/// `-1`, `0` or `1` as `a` sorts before, the same as or after `b`
+///
+/// # Safety
+/// `a` and `b` are versions from [`pyver_parse`]
+#[no_mangle]
+pub unsafe extern "C" fn pyver_compare(
+    a: *const PyverVersion,
+    b: *const PyverVersion,
+) -> c_int {
+    match (*a).0.cmp(&(*b).0) {
+        Ordering::Less => -1,
+        Ordering::Equal => 0,
+        Ordering::Greater => 1,
+    }
+}
+
+/// `1` if the version matches every specifier (`>=1.0, <2.0`), `0` if it
+/// doesn't and `-1` if the specifiers aren't valid
+///
+/// # Safety
+/// `version` is a version from [`pyver_parse`] and `specifiers` is null or
+/// points to a NUL-terminated string
+#[no_mangle]
+pub unsafe extern "C" fn pyver_satisfies(
+    version: *const PyverVersion,
+    specifiers: *const c_char,
+) -> c_int {
+    match to_str(specifiers).and_then(|specifiers| SpecifierSet::new(specifiers).ok()) {
+        Some(set) => c_int::from(set.contains(&(*version).0)),
+        None => -1,
+    }
+}
+
+/// The version in normal form (`1.0rc1` for `v1.0-RC1`), freed with
+/// [`pyver_string_free`]
+///
+/// # Safety
+/// `version` is a version from [`pyver_parse`]
+#[no_mangle]
+pub unsafe extern "C" fn pyver_normalize(version: *const PyverVersion) -> *mut c_char {
+    // Versions never contain NUL, they only hold ASCII letters, digits and `!+-._`
+    CString::new((*version).0.to_normalized_string())
+        .map_or(ptr::null_mut(), CString::into_raw)
+}
+
+/// Frees a string returned by the library, null is ignored
+///
+/// # Safety
+/// `string` is null or came from [`pyver_normalize`] and wasn't freed yet
+#[no_mangle]
+pub unsafe extern "C" fn pyver_string_free(string: *mut c_char) {
+    if !string.is_null() {
+        drop(CString::from_raw(string));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{pyver_compare, pyver_normalize, pyver_parse, pyver_satisfies};
+    use super::{pyver_string_free, pyver_version_free};
+    use std::ffi::CStr;
+    use std::ptr;
+
+    #[test]
+    fn test_ffi() {
+        unsafe {
+            let a = pyver_parse(c"1.0rc1".as_ptr());
+            let b = pyver_parse(c"v1.0".as_ptr());
+            assert!(!a.is_null() && !b.is_null());
+            assert_eq!(pyver_compare(a, b), -1);
+            assert_eq!(pyver_compare(b, a), 1);
+            assert_eq!(pyver_compare(b, b), 0);
+
+            assert_eq!(pyver_satisfies(b, c">=1.0, <2.0".as_ptr()), 1);
+            assert_eq!(pyver_satisfies(a, c">=1.0, <2.0".as_ptr()), 0);
+            assert_eq!(pyver_satisfies(b, c">=1.0,".as_ptr()), -1);
+            assert_eq!(pyver_satisfies(b, ptr::null()), -1);
+
+            let normalized = pyver_normalize(a);
+            assert_eq!(CStr::from_ptr(normalized).to_str().unwrap(), "1.0rc1");
+            pyver_string_free(normalized);
+
+            pyver_version_free(a);
+            pyver_version_free(b);
+            pyver_version_free(ptr::null_mut());
+            pyver_string_free(ptr::null_mut());
+        }
+    }
+
+    #[test]
+    fn test_ffi_negative() {
+        for version in [c"1.0 junk", c"", c"\xff"] {
+            assert!(unsafe { pyver_parse(version.as_ptr()) }.is_null());
+        }
+        assert!(unsafe { pyver_parse(ptr::null()) }.is_null());
+    }
+}
@@ -0,0 +1,265 @@
+use crate::ids::PreHeader;
+use crate::PackageVersion;
+use arbitrary::unstructured::Int;
+use arbitrary::{Arbitrary, Result, Unstructured};
+use core::ops::RangeInclusive;
+
+/// Characters the parser treats specially or that look like they could be
+/// part of a version, used to break otherwise valid strings
+const NEAR_MISSES: &[char] = &[
+    '.', '-', '_', '+', '!', '*', ' ', '\t', '0', '9', 'a', 'v', 'ſ', 'é', '١',
+];
+
+const LOCAL_CHARS: &[u8] = b"abcdefghijklmnopqrstuvwxyz0123456789";
+
+/// Structurally valid versions, most with small release segments and numbers
+/// so that generated versions are likely to compare equal or share a prefix
+///
+/// ```
+/// use arbitrary::{Arbitrary, Unstructured};
+/// use pyver::PackageVersion;
+///
+/// let mut input = Unstructured::new(&[7, 1, 42, 3, 9, 200, 5, 17]);
+/// let version = PackageVersion::arbitrary(&mut input).unwrap();
+/// assert_eq!(PackageVersion::new(&version.to_string()).unwrap(), version);
+/// ```
+impl<'a> Arbitrary<'a> for PackageVersion {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        let epoch = if u.ratio(1, 8)? { u.arbitrary()? } else { 0 };
+        let release = (0..u.int_in_range(1..=5)?)
+            .map(|_| small_or_any(u, 0..=20))
+            .collect::<Result<Vec<u64>>>()?;
+
+        let pre = if u.ratio(1, 3)? {
+            let number = optional(u, |u| small_or_any(u, 0..=10))?;
+            let phase = u.choose(&["a", "b", "rc", "pre"])?;
+            // Safe to unwrap since these are phases `PEP-440` spells
+            Some(PreHeader::new(phase, number).unwrap())
+        } else {
+            None
+        };
+        let post = optional(u, |u| small_or_any(u, 0..=10))?;
+        let dev = optional(u, |u| small_or_any(u, 0..=10))?;
+
+        let local = if u.ratio(1, 4)? {
+            let segments = (0..u.int_in_range(1..=3)?)
+                .map(|_| {
+                    (0..u.int_in_range(1..=6)?)
+                        .map(|_| u.choose(LOCAL_CHARS).map(|c| *c as char))
+                        .collect::<Result<String>>()
+                })
+                .collect::<Result<Vec<String>>>()?;
+            Some(segments.join("."))
+        } else {
+            None
+        };
+
+        // Safe to unwrap since there's a release and the local label is valid
+        Ok(Self::from_parts(epoch, release, pre, post, dev, local.as_deref()).unwrap())
+    }
+}
+
+/// # Version string
+/// A version string as it might be written by hand, in any of the spellings
+/// `PEP-440` allows, and broken by a stray character a quarter of the time
+///
+/// Meant as fuzzing input for code that parses versions, which should turn
+/// valid strings into the same version as their normal form and reject the
+/// rest without panicking.
+///
+/// ```
+/// use arbitrary::{Arbitrary, Unstructured};
+/// use pyver::fuzzing::VersionString;
+/// use pyver::PackageVersion;
+///
+/// let mut input = Unstructured::new(&[3, 1, 4, 1, 5, 9, 2, 6, 5, 3, 5, 8, 9, 7]);
+/// let VersionString(written) = VersionString::arbitrary(&mut input).unwrap();
+/// if let Ok(version) = PackageVersion::new(&written) {
+///     assert_eq!(PackageVersion::new(&format!("{:#}", version)).unwrap(), version);
+/// }
+/// ```
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct VersionString(pub String);
+
+impl<'a> Arbitrary<'a> for VersionString {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        let version = PackageVersion::arbitrary(u)?;
+        let mut written: Vec<char> = spell(&version, u)?.chars().collect();
+
+        if u.ratio(1, 4)? {
+            let index = u.choose_index(written.len() + 1)?;
+            let c = *u.choose(NEAR_MISSES)?;
+            match u.int_in_range(0..=2)? {
+                0 => written.insert(index, c),
+                _ if index == written.len() => written.push(c),
+                1 => written[index] = c,
+                _ => {
+                    written.remove(index);
+                }
+            }
+        }
+        Ok(Self(written.into_iter().collect()))
+    }
+}
+
+/// A number in the small range most of the time, any number otherwise
+fn small_or_any<'a, T>(u: &mut Unstructured<'a>, small: RangeInclusive<T>) -> Result<T>
+where
+    T: Arbitrary<'a> + Int,
+{
+    if u.ratio(1, 16)? {
+        u.arbitrary()
+    } else {
+        u.int_in_range(small)
+    }
+}
+
+fn optional<T>(
+    u: &mut Unstructured,
+    value: impl FnOnce(&mut Unstructured) -> Result<T>,
+) -> Result<Option<T>> {
+    if u.ratio(1, 3)? {
+        value(u).map(Some)
+    } else {
+        Ok(None)
+    }
+}
+
+/// Writes a valid version in a random spelling of the same version
+fn spell(version: &PackageVersion, u: &mut Unstructured) -> Result<String> {
+    let separator = |u: &mut Unstructured| u.choose(&["", ".", "-", "_"]).copied();
+    let mut written = String::new();
+
+    if u.ratio(1, 8)? {
+        written.push_str(u.choose(&[" ", "\t", "\n "])?);
+    }
+    if u.ratio(1, 4)? {
+        written.push('v');
+    }
+    if version.epoch() != 0 || u.ratio(1, 16)? {
+        written.push_str(&format!("{}!", version.epoch()));
+    }
+    for (i, segment) in version.release().segments().iter().enumerate() {
+        if i > 0 {
+            written.push('.');
+        }
+        if u.ratio(1, 16)? {
+            written.push('0');
+        }
+        written.push_str(&segment.to_string());
+    }
+
+    if let Some(pre) = version.pre() {
+        let spellings: &[&str] = match pre {
+            PreHeader::Alpha(_) => &["a", "alpha"],
+            PreHeader::Beta(_) => &["b", "beta"],
+            PreHeader::Preview(_) => &["pre", "preview"],
+            _ => &["rc", "c"],
+        };
+        written.push_str(separator(u)?);
+        written.push_str(u.choose(spellings)?);
+        match pre.number() {
+            Some(0) if u.ratio(1, 2)? => {}
+            number => {
+                written.push_str(separator(u)?);
+                written.push_str(&number.unwrap_or(0).to_string());
+            }
+        }
+    }
+
+    if let Some(post) = version.post().and_then(|post| post.post_num) {
+        if u.ratio(1, 4)? {
+            written.push_str(&format!("-{}", post));
+        } else {
+            written.push_str(separator(u)?);
+            written.push_str(u.choose(&["post", "rev", "r"])?);
+            written.push_str(separator(u)?);
+            written.push_str(&post.to_string());
+        }
+    }
+
+    if let Some(dev) = version.dev().and_then(|dev| dev.dev_num) {
+        written.push_str(separator(u)?);
+        written.push_str("dev");
+        if dev != 0 || u.ratio(1, 2)? {
+            written.push_str(separator(u)?);
+            written.push_str(&dev.to_string());
+        }
+    }
+
+    if let Some(local) = version.local() {
+        written.push('+');
+        for (i, segment) in local.split('.').enumerate() {
+            if i > 0 {
+                written.push_str(u.choose(&[".", "-", "_"])?);
+            }
+            written.push_str(segment);
+        }
+    }
+
+    // Letters are case-insensitive, mix them up
+    let mut mixed = String::with_capacity(written.len());
+    for c in written.chars() {
+        if u.ratio(1, 4)? {
+            mixed.push(c.to_ascii_uppercase());
+        } else {
+            mixed.push(c);
+        }
+    }
+    Ok(mixed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{spell, VersionString};
+    use crate::PackageVersion;
+    use arbitrary::{Arbitrary, Unstructured};
+
+    /// Pseudo-random fuzzing input, the same on every run
+    fn inputs() -> impl Iterator<Item = Vec<u8>> {
+        let mut state: u64 = 0x2545_f491_4f6c_dd1d;
+        (0..500).map(move |_| {
+            (0..64)
+                .map(|_| {
+                    state ^= state << 13;
+                    state ^= state >> 7;
+                    state ^= state << 17;
+                    state as u8
+                })
+                .collect()
+        })
+    }
+
+    #[test]
+    fn test_arbitrary_versions() {
+        for input in inputs() {
+            let mut u = Unstructured::new(&input);
+            let version = PackageVersion::arbitrary(&mut u).unwrap();
+            assert!(version.is_canonical(), "{:?}", version);
+            assert_eq!(PackageVersion::new(&version.to_string()).unwrap(), version);
+
+            let written = spell(&version, &mut u).unwrap();
+            let parsed = PackageVersion::new(&written).unwrap();
+            assert_eq!(parsed, version, "{}", written);
+            assert_eq!(parsed.to_normalized_string(), version.to_string());
+        }
+    }
+
+    #[test]
+    fn test_arbitrary_strings() {
+        let mut invalid = 0;
+        for input in inputs() {
+            let VersionString(written) =
+                VersionString::arbitrary(&mut Unstructured::new(&input)).unwrap();
+            match PackageVersion::new(&written) {
+                Ok(version) => {
+                    let normalized = version.to_normalized_string();
+                    assert_eq!(PackageVersion::new(&normalized).unwrap(), version);
+                }
+                Err(_e) => invalid += 1,
+            }
+        }
+        // Some strings are broken, but most are still versions
+        assert!(invalid > 0 && invalid < 250, "{}", invalid);
+    }
+}
@@ -1,3 +1,7 @@
+use super::fragment_version;
+use anyhow::Result;
+use core::fmt;
+use core::str::FromStr;
 use serde::{Deserialize, Serialize};
 
 /// # `PEP-440` Developmental release identifier
@@ -18,12 +22,36 @@ use serde::{Deserialize, Serialize};
 /// );
 /// ```
 #[derive(
-    Hash, Ord, Clone, Debug, Serialize, Deserialize, Eq, PartialEq, PartialOrd,
+    Hash, Ord, Clone, Copy, Debug, Serialize, Deserialize, Eq, PartialEq, PartialOrd,
+)]
+#[cfg_attr(
+    feature = "rkyv",
+    derive(rkyv::Archive, rkyv::Serialize, rkyv::Deserialize)
 )]
 pub struct DevHead {
     pub dev_num: Option<u32>,
 }
 
+/// Writes the normal form, `.dev` and the number (`.dev0`)
+impl fmt::Display for DevHead {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, ".dev{}", self.dev_num.unwrap_or(0))
+    }
+}
+
+/// Parses a developmental release fragment (`dev`, `.dev2`)
+impl FromStr for DevHead {
+    type Err = anyhow::Error;
+
+    fn from_str(fragment: &str) -> Result<Self> {
+        let version = fragment_version(fragment)?;
+        match version.dev {
+            Some(dev) if version.pre.is_none() && version.post.is_none() => Ok(dev),
+            _ => anyhow::bail!("Invalid developmental release {}", fragment),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::DevHead;
@@ -33,4 +61,25 @@ mod tests {
         assert!(DevHead { dev_num: Some(0) } > DevHead { dev_num: None });
         assert!(DevHead { dev_num: Some(1) } > DevHead { dev_num: Some(0) });
     }
+
+    #[test]
+    fn test_dev_display() {
+        assert_eq!(DevHead { dev_num: None }.to_string(), ".dev0");
+        assert_eq!(DevHead { dev_num: Some(7) }.to_string(), ".dev7");
+    }
+
+    #[test]
+    fn test_dev_from_str() -> anyhow::Result<()> {
+        assert_eq!("dev".parse::<DevHead>()?, DevHead { dev_num: None });
+        assert_eq!(".dev2".parse::<DevHead>()?, DevHead { dev_num: Some(2) });
+
+        let fragments = vec!["", "2", "rc1.dev2", "dev2+local"];
+        for fragment in fragments {
+            match fragment.parse::<DevHead>() {
+                Ok(v) => panic!("Oh no {}", v),
+                Err(_e) => continue,
+            }
+        }
+        Ok(())
+    }
 }
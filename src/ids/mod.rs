@@ -13,3 +13,12 @@ pub use dev_id::*;
 pub use post_id::*;
 pub use pre_id::*;
 pub use release_id::*;
+
+/// Parses a version fragment (`rc1`, `.post2`) as part of the version `0`
+fn fragment_version(fragment: &str) -> anyhow::Result<crate::PackageVersion> {
+    let version = crate::PackageVersion::new(&alloc::format!("0{}", fragment))?;
+    if fragment.trim() != fragment || version.local.is_some() {
+        anyhow::bail!("Invalid version fragment {}", fragment);
+    }
+    Ok(version)
+}
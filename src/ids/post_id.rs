@@ -1,5 +1,9 @@
+use super::fragment_version;
+use anyhow::Result;
+use core::cmp::Ordering;
+use core::fmt;
+use core::str::FromStr;
 use serde::{Deserialize, Serialize};
-use std::cmp::Ordering;
 
 /// # `PEP-440` Post-Release identifier
 /// This identifier is used to mark a Post Release/Revision Version
@@ -23,7 +27,11 @@ use std::cmp::Ordering;
 ///     }
 /// );
 /// ```
-#[derive(Hash, Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+#[derive(Hash, Clone, Copy, Debug, Serialize, Deserialize, Eq, PartialEq)]
+#[cfg_attr(
+    feature = "rkyv",
+    derive(rkyv::Archive, rkyv::Serialize, rkyv::Deserialize)
+)]
 pub struct PostHeader {
     pub post_head: Option<PostHead>,
     pub post_num: Option<u32>,
@@ -36,7 +44,17 @@ pub struct PostHeader {
 /// Examples of versions that use this enum:
 /// - `1.0.post456`
 /// - `1.0rev`
-#[derive(Hash, Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+///
+/// Both spellings mean the same in a version, they are
+/// only ordered to tell them apart
+#[derive(
+    Hash, Ord, Clone, Copy, Debug, Serialize, Deserialize, Eq, PartialEq, PartialOrd,
+)]
+#[cfg_attr(
+    feature = "rkyv",
+    derive(rkyv::Archive, rkyv::Serialize, rkyv::Deserialize)
+)]
+#[non_exhaustive]
 pub enum PostHead {
     /// ```
     /// use pyver::ids::PostHead;
@@ -52,15 +70,30 @@ pub enum PostHead {
     Rev,
 }
 
-impl Ord for PostHead {
-    fn cmp(&self, _other: &Self) -> Ordering {
-        Ordering::Equal
+impl PostHead {
+    /// The keyword in any spelling `PEP-440` allows (`post`, `rev`, `r`)
+    ///
+    /// ```
+    /// use pyver::ids::PostHead;
+    ///
+    /// assert_eq!(PostHead::new("r").unwrap(), PostHead::Rev);
+    /// assert_eq!(PostHead::new("POST").unwrap().as_str(), "post");
+    /// assert!(PostHead::new("patch").is_err());
+    /// ```
+    pub fn new(keyword: &str) -> Result<Self> {
+        match keyword.to_ascii_lowercase().as_str() {
+            "post" => Ok(PostHead::Post),
+            "rev" | "r" => Ok(PostHead::Rev),
+            _ => anyhow::bail!("Unknown post-release keyword {}", keyword),
+        }
     }
-}
 
-impl PartialOrd for PostHead {
-    fn partial_cmp(&self, _other: &Self) -> Option<Ordering> {
-        Some(Ordering::Equal)
+    /// The keyword as `PEP-440` spells it out (`post` or `rev`)
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PostHead::Post => "post",
+            PostHead::Rev => "rev",
+        }
     }
 }
 
@@ -70,22 +103,33 @@ impl PartialOrd<Self> for PostHeader {
     }
 }
 
+/// Post-releases are ordered by their number (an implicit number first),
+/// the spelling only breaks ties
 impl Ord for PostHeader {
     fn cmp(&self, other: &Self) -> Ordering {
-        if self.post_num == other.post_num {
-            return Ordering::Equal;
-        }
+        self.post_num
+            .cmp(&other.post_num)
+            .then_with(|| self.post_head.cmp(&other.post_head))
+    }
+}
 
-        if self.post_num.is_none() && other.post_num.is_some() {
-            return Ordering::Less;
-        } else if self.post_num.is_some() && other.post_num.is_none() {
-            return Ordering::Greater;
-        }
+/// Writes the normal form, `.post` and the number (`.post2`)
+impl fmt::Display for PostHeader {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, ".post{}", self.post_num.unwrap_or(0))
+    }
+}
 
-        if self.post_num < other.post_num {
-            Ordering::Less
-        } else {
-            Ordering::Greater
+/// Parses a post-release fragment in any spelling `PEP-440` allows
+/// (`post3`, `.rev`, `-3`)
+impl FromStr for PostHeader {
+    type Err = anyhow::Error;
+
+    fn from_str(fragment: &str) -> Result<Self> {
+        let version = fragment_version(fragment)?;
+        match version.post {
+            Some(post) if version.pre.is_none() && version.dev.is_none() => Ok(post),
+            _ => anyhow::bail!("Invalid post-release {}", fragment),
         }
     }
 }
@@ -94,6 +138,7 @@ impl Ord for PostHeader {
 mod tests {
     use super::PostHead;
     use super::PostHeader;
+    use std::collections::{BTreeSet, HashSet};
 
     #[test]
     fn test_post_ordering() {
@@ -116,4 +161,55 @@ mod tests {
             }
         );
     }
+
+    #[test]
+    fn test_post_in_sets() {
+        let post = PostHeader {
+            post_head: Some(PostHead::Post),
+            post_num: Some(1),
+        };
+        let rev = PostHeader {
+            post_head: Some(PostHead::Rev),
+            ..post
+        };
+
+        let sorted: BTreeSet<PostHeader> = [post, rev, post].into_iter().collect();
+        let hashed: HashSet<PostHeader> = [post, rev, post].into_iter().collect();
+        assert_eq!(sorted.len(), 2);
+        assert_eq!(hashed.len(), 2);
+    }
+
+    #[test]
+    fn test_post_display() {
+        let post = PostHeader {
+            post_head: Some(PostHead::Rev),
+            post_num: Some(2),
+        };
+        assert_eq!(post.to_string(), ".post2");
+        let implicit = PostHeader {
+            post_head: None,
+            post_num: None,
+        };
+        assert_eq!(implicit.to_string(), ".post0");
+    }
+
+    #[test]
+    fn test_post_from_str() -> anyhow::Result<()> {
+        let post = "post3".parse::<PostHeader>()?;
+        assert_eq!(post.post_head, Some(PostHead::Post));
+        assert_eq!(post.post_num, Some(3));
+        let implicit = "-3".parse::<PostHeader>()?;
+        assert_eq!(implicit.post_head, None);
+        assert_eq!(implicit.post_num, Some(3));
+        assert_eq!(".rev".parse::<PostHeader>()?.post_head, Some(PostHead::Rev));
+
+        let fragments = vec!["", "3", "post3.dev1", "rc1"];
+        for fragment in fragments {
+            match fragment.parse::<PostHeader>() {
+                Ok(v) => panic!("Oh no {}", v),
+                Err(_e) => continue,
+            }
+        }
+        Ok(())
+    }
 }
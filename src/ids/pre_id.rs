@@ -1,10 +1,21 @@
+use super::fragment_version;
+use anyhow::Result;
+use core::cmp::Ordering;
+use core::fmt;
+use core::str::FromStr;
 use serde::{Deserialize, Serialize};
 
 /// # `PEP-440` Pre-Release identifier
 /// This identifier is used to mark a Pre-Release version
-#[derive(
-    Hash, Ord, Clone, Debug, Serialize, Deserialize, Eq, PartialEq, PartialOrd,
+///
+/// More phases may be added, use [`PreHeader::phase`] and
+/// [`PreHeader::number`] to inspect one without matching
+#[derive(Hash, Clone, Copy, Debug, Serialize, Deserialize, Eq, PartialEq)]
+#[cfg_attr(
+    feature = "rkyv",
+    derive(rkyv::Archive, rkyv::Serialize, rkyv::Deserialize)
 )]
+#[non_exhaustive]
 pub enum PreHeader {
     /// Present in versions like 1.1beta1 or 1.0b1 both are represented the same way
     /// ```
@@ -36,15 +47,106 @@ pub enum PreHeader {
     ReleaseCandidate(Option<u32>),
 }
 
+impl PreHeader {
+    /// A pre-release from its phase in any spelling `PEP-440` allows
+    /// (`a`, `alpha`, `b`, `beta`, `c`, `rc`, `pre`, `preview`)
+    ///
+    /// ```
+    /// use pyver::ids::PreHeader;
+    ///
+    /// let pre = PreHeader::new("Alpha", Some(2)).unwrap();
+    /// assert_eq!(pre, PreHeader::Alpha(Some(2)));
+    /// assert_eq!(pre.phase(), "a");
+    /// assert_eq!(pre.number(), Some(2));
+    /// assert!(PreHeader::new("gamma", None).is_err());
+    /// ```
+    pub fn new(phase: &str, number: Option<u32>) -> Result<Self> {
+        match phase.to_ascii_lowercase().as_str() {
+            "a" | "alpha" => Ok(PreHeader::Alpha(number)),
+            "b" | "beta" => Ok(PreHeader::Beta(number)),
+            "c" | "rc" => Ok(PreHeader::ReleaseCandidate(number)),
+            "pre" | "preview" => Ok(PreHeader::Preview(number)),
+            _ => anyhow::bail!("Unknown pre-release phase {}", phase),
+        }
+    }
+
+    /// The phase in normal form (`a`, `b` or `rc`)
+    pub fn phase(&self) -> &'static str {
+        match self {
+            PreHeader::Alpha(_) => "a",
+            PreHeader::Beta(_) => "b",
+            PreHeader::Preview(_) | PreHeader::ReleaseCandidate(_) => "rc",
+        }
+    }
+
+    /// The number as written, `None` when it was left out
+    pub fn number(&self) -> Option<u32> {
+        match self {
+            PreHeader::Alpha(n)
+            | PreHeader::Beta(n)
+            | PreHeader::Preview(n)
+            | PreHeader::ReleaseCandidate(n) => *n,
+        }
+    }
+}
+
+impl PartialOrd<Self> for PreHeader {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Pre-releases are ordered by their phase (`a`, `b`, then `rc` which
+/// `pre` is a spelling of) and then their number (an implicit number
+/// first), the spelling only breaks ties
+impl Ord for PreHeader {
+    fn cmp(&self, other: &Self) -> Ordering {
+        let rank = |pre: &Self| match pre {
+            PreHeader::Alpha(_) => 0,
+            PreHeader::Beta(_) => 1,
+            PreHeader::Preview(_) | PreHeader::ReleaseCandidate(_) => 2,
+        };
+        let is_rc = |pre: &Self| matches!(pre, PreHeader::ReleaseCandidate(_));
+        rank(self)
+            .cmp(&rank(other))
+            .then_with(|| self.number().cmp(&other.number()))
+            .then_with(|| is_rc(self).cmp(&is_rc(other)))
+    }
+}
+
+/// Writes the normal form, the short phase and the number (`a0`, `rc1`)
+impl fmt::Display for PreHeader {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}{}", self.phase(), self.number().unwrap_or(0))
+    }
+}
+
+/// Parses a pre-release fragment in any spelling `PEP-440` allows
+/// (`rc1`, `-alpha.2`, `b`)
+impl FromStr for PreHeader {
+    type Err = anyhow::Error;
+
+    fn from_str(fragment: &str) -> Result<Self> {
+        let version = fragment_version(fragment)?;
+        match version.pre {
+            Some(pre) if version.post.is_none() && version.dev.is_none() => Ok(pre),
+            _ => anyhow::bail!("Invalid pre-release {}", fragment),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::PreHeader;
 
     #[test]
     fn test_pre_ordering() {
+        assert!(PreHeader::Alpha(Some(1)) < PreHeader::Beta(Some(1)));
+        assert!(PreHeader::Beta(Some(1)) < PreHeader::ReleaseCandidate(Some(1)));
         assert!(PreHeader::ReleaseCandidate(None) > PreHeader::Preview(None));
-        assert!(PreHeader::Preview(None) > PreHeader::Alpha(None));
-        assert!(PreHeader::Alpha(None) > PreHeader::Beta(None));
+        assert!(PreHeader::Preview(Some(1)) < PreHeader::ReleaseCandidate(Some(2)));
+        assert!(PreHeader::Preview(None) > PreHeader::Beta(None));
+        assert!(PreHeader::Beta(None) > PreHeader::Alpha(None));
 
         assert!(
             PreHeader::ReleaseCandidate(Some(2)) > PreHeader::ReleaseCandidate(Some(1))
@@ -55,4 +157,31 @@ mod tests {
 
         assert!(PreHeader::ReleaseCandidate(Some(1)) > PreHeader::Beta(Some(45067885)));
     }
+
+    #[test]
+    fn test_pre_display() {
+        assert_eq!(PreHeader::Alpha(None).to_string(), "a0");
+        assert_eq!(PreHeader::Beta(Some(2)).to_string(), "b2");
+        assert_eq!(PreHeader::Preview(Some(3)).to_string(), "rc3");
+        assert_eq!(PreHeader::ReleaseCandidate(Some(1)).to_string(), "rc1");
+    }
+
+    #[test]
+    fn test_pre_from_str() -> anyhow::Result<()> {
+        assert_eq!(
+            "rc1".parse::<PreHeader>()?,
+            PreHeader::ReleaseCandidate(Some(1))
+        );
+        assert_eq!("-alpha.2".parse::<PreHeader>()?, PreHeader::Alpha(Some(2)));
+        assert_eq!("B".parse::<PreHeader>()?, PreHeader::Beta(None));
+
+        let fragments = vec!["", "1", "rc1.post1", "rc1+local", " rc1", "post1"];
+        for fragment in fragments {
+            match fragment.parse::<PreHeader>() {
+                Ok(v) => panic!("Oh no {}", v),
+                Err(_e) => continue,
+            }
+        }
+        Ok(())
+    }
 }
@@ -1,14 +1,148 @@
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::cmp::Ordering;
+use core::fmt;
+use core::hash::{Hash, Hasher};
+use core::ops::Index;
 use serde::{Deserialize, Serialize};
+use smallvec::SmallVec;
 
 /// `PEP-440` Release numbers
-#[derive(
-    Hash, Ord, Clone, Debug, Serialize, Deserialize, Eq, PartialEq, PartialOrd,
+///
+/// Holds every dot separated release segment (`1.0.15` is `[1, 0, 15]`),
+/// only releases with more than three segments allocate.
+/// Trailing zeros are insignificant, so `1.0` and `1.0.0` compare
+/// and hash the same
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[cfg_attr(
+    feature = "rkyv",
+    derive(rkyv::Archive, rkyv::Serialize, rkyv::Deserialize)
 )]
 pub struct ReleaseHeader {
-    /// Major release such as 1.0 or breaking changes
-    pub major: u32,
-    /// Minor release Such as new functionality
-    pub minor: u32,
+    /// Release segments, starting with the major release
+    pub segments: SmallVec<[u64; 3]>,
+}
+
+impl ReleaseHeader {
+    /// The first segment
+    ///
+    /// ```
+    /// use pyver::ids::ReleaseHeader;
+    ///
+    /// let release = ReleaseHeader { segments: vec![3, 12].into() };
+    /// assert_eq!((release.major(), release.minor(), release.micro()), (3, 12, 0));
+    /// assert_eq!(release[1], 12);
+    /// assert_eq!(release.len(), 2);
+    /// assert_eq!(release.iter().sum::<u64>(), 15);
+    /// ```
+    pub fn major(&self) -> u64 {
+        self.segment(0)
+    }
+
+    /// The second segment, `0` if the release has none
+    pub fn minor(&self) -> u64 {
+        self.segment(1)
+    }
+
+    /// The third segment, `0` if the release has none
+    pub fn micro(&self) -> u64 {
+        self.segment(2)
+    }
+
+    /// The segment at `index`, `0` past the written segments
+    /// as missing segments are implicit zeros
+    pub fn segment(&self, index: usize) -> u64 {
+        self.segments.get(index).copied().unwrap_or(0)
+    }
+
+    /// The segments as written, starting with the major release
+    pub fn segments(&self) -> &[u64] {
+        &self.segments
+    }
+
+    /// Number of written segments (`1.0` has two)
+    pub fn len(&self) -> usize {
+        self.segments.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.segments.is_empty()
+    }
+
+    pub fn iter(&self) -> core::slice::Iter<'_, u64> {
+        self.segments.iter()
+    }
+
+    /// Segments without insignificant trailing zeros
+    pub(crate) fn significant(&self) -> &[u64] {
+        let len = self
+            .segments
+            .iter()
+            .rposition(|segment| *segment != 0)
+            .map_or(0, |i| i + 1);
+        &self.segments[..len]
+    }
+}
+
+impl PartialEq for ReleaseHeader {
+    fn eq(&self, other: &Self) -> bool {
+        self.significant() == other.significant()
+    }
+}
+
+impl Eq for ReleaseHeader {}
+
+impl PartialOrd for ReleaseHeader {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for ReleaseHeader {
+    fn cmp(&self, other: &Self) -> Ordering {
+        // Comparing the significant segments is the same as
+        // padding the shorter release with zeros
+        self.significant().cmp(other.significant())
+    }
+}
+
+impl Hash for ReleaseHeader {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.significant().hash(state);
+    }
+}
+
+impl Index<usize> for ReleaseHeader {
+    type Output = u64;
+
+    fn index(&self, index: usize) -> &u64 {
+        &self.segments[index]
+    }
+}
+
+impl<'a> IntoIterator for &'a ReleaseHeader {
+    type Item = &'a u64;
+    type IntoIter = core::slice::Iter<'a, u64>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.segments.iter()
+    }
+}
+
+/// Writes the segments separated by dots (`1.0.15`)
+impl fmt::Display for ReleaseHeader {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let segments: Vec<String> = self
+            .segments
+            .iter()
+            .map(|segment| segment.to_string())
+            .collect();
+        if segments.is_empty() {
+            write!(f, "0")
+        } else {
+            write!(f, "{}", segments.join("."))
+        }
+    }
 }
 
 #[cfg(test)]
@@ -18,17 +152,78 @@ mod test {
     #[test]
     fn test_release_ordering() {
         assert!(
-            ReleaseHeader { major: 1, minor: 0 } > ReleaseHeader { major: 0, minor: 0 }
+            ReleaseHeader {
+                segments: vec![1, 0].into()
+            } > ReleaseHeader {
+                segments: vec![0, 0].into()
+            }
         );
         assert!(
-            ReleaseHeader { major: 1, minor: 1 } > ReleaseHeader { major: 1, minor: 0 }
+            ReleaseHeader {
+                segments: vec![1, 1].into()
+            } > ReleaseHeader {
+                segments: vec![1, 0].into()
+            }
         );
         assert!(
-            ReleaseHeader { major: 2, minor: 1 }
-                > ReleaseHeader {
-                    major: 1,
-                    minor: 52,
-                }
+            ReleaseHeader {
+                segments: vec![2, 1].into()
+            } > ReleaseHeader {
+                segments: vec![1, 52].into()
+            }
         );
+        assert!(
+            ReleaseHeader {
+                segments: vec![1, 0, 15].into()
+            } > ReleaseHeader {
+                segments: vec![1, 0].into()
+            }
+        );
+    }
+
+    #[test]
+    fn test_release_padding() {
+        assert_eq!(
+            ReleaseHeader {
+                segments: vec![1, 0, 0].into()
+            },
+            ReleaseHeader {
+                segments: vec![1].into()
+            }
+        );
+        assert!(
+            ReleaseHeader {
+                segments: vec![1, 0, 0, 1].into()
+            } > ReleaseHeader {
+                segments: vec![1].into()
+            }
+        );
+    }
+
+    #[test]
+    fn test_release_display() {
+        let release = ReleaseHeader {
+            segments: vec![1, 0, 15].into(),
+        };
+        assert_eq!(release.to_string(), "1.0.15");
+    }
+
+    #[test]
+    fn test_release_accessors() {
+        let release = ReleaseHeader {
+            segments: vec![2023, 10, 4, 20231004123456].into(),
+        };
+        assert_eq!(release.major(), 2023);
+        assert_eq!(release.minor(), 10);
+        assert_eq!(release.micro(), 4);
+        assert_eq!(release[3], 20231004123456);
+        assert_eq!(release.segment(7), 0);
+        assert_eq!(release.segments(), &[2023, 10, 4, 20231004123456]);
+
+        let short = ReleaseHeader {
+            segments: vec![1].into(),
+        };
+        assert_eq!((short.minor(), short.micro()), (0, 0));
+        assert_eq!((&short).into_iter().count(), 1);
     }
 }
@@ -0,0 +1,443 @@
+use crate::dist::DistFilename;
+use crate::specifiers::{
+    parse_requires_python, requires_python_matches, Operator, SpecifierSet,
+};
+use crate::PackageVersion;
+use anyhow::Result;
+
+/// # `PEP-503` Simple index project page
+/// The distribution links of a project page such as
+/// `https://pypi.org/simple/requests/`
+///
+/// Besides the filename each link carries the `PEP-592` yank status and the
+/// `PEP-345` `data-requires-python` attribute, HTML entities in attribute
+/// values are decoded. Relative URLs are kept as written.
+///
+/// Read more at <https://peps.python.org/pep-0503/>
+///
+/// ## Example Usage
+/// ```
+/// use pyver::index::ProjectPage;
+/// use pyver::PackageVersion;
+///
+/// let page = ProjectPage::new(
+///     r#"<!DOCTYPE html>
+/// <html><body>
+///   <a href="../../packages/spam-1.0.tar.gz#sha256=0a1b">spam-1.0.tar.gz</a><br/>
+///   <a href="../../packages/spam-2.0-py3-none-any.whl" data-requires-python="&gt;=3.8">spam-2.0-py3-none-any.whl</a><br/>
+///   <a href="../../packages/spam-2.1.tar.gz" data-yanked="Broken build">spam-2.1.tar.gz</a><br/>
+/// </body></html>"#,
+///     "spam",
+/// )
+/// .unwrap();
+///
+/// assert_eq!(page.links.len(), 3);
+/// assert_eq!(page.links[0].hash, Some(("sha256".to_string(), "0a1b".to_string())));
+/// assert_eq!(page.links[2].yanked.as_deref(), Some("Broken build"));
+///
+/// let python = PackageVersion::new("3.7").unwrap();
+/// assert_eq!(page.installable(&python).count(), 1);
+/// ```
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ProjectPage {
+    pub links: Vec<IndexLink>,
+}
+
+/// A distribution link of a project page
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct IndexLink {
+    /// The filename, taken from the URL path
+    pub filename: String,
+    /// The URL without its fragment
+    pub url: String,
+    /// Hash algorithm and digest from the `#sha256=...` fragment
+    pub hash: Option<(String, String)>,
+    /// `data-requires-python`, `None` if it's missing or invalid
+    pub requires_python: Option<SpecifierSet>,
+    /// `data-yanked`, holding the reason (empty if none was given)
+    pub yanked: Option<String>,
+    /// The parsed filename, `None` for files that aren't distributions
+    pub dist: Option<DistFilename>,
+}
+
+impl ProjectPage {
+    /// Parses the project page of `project`,
+    /// which is needed to split ambiguous sdist filenames
+    pub fn new(html: &str, project: &str) -> Result<Self> {
+        let mut links = Vec::new();
+        let mut rest = html;
+
+        while let Some(start) = find_anchor(rest) {
+            let after = &rest[start + 2..];
+            let end = match after.find('>') {
+                Some(end) => end,
+                None => anyhow::bail!("Unclosed <a> tag"),
+            };
+            let attributes = parse_attributes(&after[..end]);
+            rest = &after[end + 1..];
+
+            let href = match attributes.iter().find(|(name, _)| name == "href") {
+                Some((_, Some(href))) => href,
+                _ => continue,
+            };
+            let attribute = |key: &str| {
+                attributes
+                    .iter()
+                    .find(|(name, _)| name == key)
+                    .map(|(_, value)| value.clone().unwrap_or_default())
+            };
+
+            let (url, fragment) = match href.split_once('#') {
+                Some((url, fragment)) => (url, Some(fragment)),
+                None => (href.as_str(), None),
+            };
+            let hash = fragment
+                .and_then(|fragment| fragment.split_once('='))
+                .map(|(name, digest)| (name.to_string(), digest.to_string()));
+            let filename = url.rsplit('/').next().unwrap_or(url).to_string();
+
+            links.push(IndexLink {
+                dist: DistFilename::new_for_project(&filename, project).ok(),
+                requires_python: attribute("data-requires-python")
+                    .and_then(|value| parse_requires_python(&value).ok()),
+                yanked: attribute("data-yanked"),
+                filename,
+                url: url.to_string(),
+                hash,
+            });
+        }
+        Ok(Self { links })
+    }
+
+    /// The distribution links that aren't yanked
+    /// and support the Python version
+    pub fn installable<'a>(
+        &'a self,
+        python: &'a PackageVersion,
+    ) -> impl Iterator<Item = &'a IndexLink> {
+        self.links.iter().filter(move |link| {
+            link.dist.is_some()
+                && link.yanked.is_none()
+                && link
+                    .requires_python
+                    .as_ref()
+                    .is_none_or(|spec| requires_python_matches(spec, python))
+        })
+    }
+}
+
+/// # Release candidate
+/// A version offered by an index, with its `PEP-592` yank status
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Candidate {
+    pub version: PackageVersion,
+    /// Why the release was yanked (empty if no reason was given),
+    /// `None` if it wasn't
+    pub yanked: Option<String>,
+    /// Python versions supported by the release, `None` for any
+    pub requires_python: Option<SpecifierSet>,
+}
+
+impl Candidate {
+    pub fn new(version: PackageVersion) -> Self {
+        Self {
+            version,
+            yanked: None,
+            requires_python: None,
+        }
+    }
+
+    pub fn is_yanked(&self) -> bool {
+        self.yanked.is_some()
+    }
+}
+
+impl TryFrom<&IndexLink> for Candidate {
+    type Error = anyhow::Error;
+
+    fn try_from(link: &IndexLink) -> Result<Self> {
+        match &link.dist {
+            Some(dist) => Ok(Self {
+                version: dist.version().clone(),
+                yanked: link.yanked.clone(),
+                requires_python: link.requires_python.clone(),
+            }),
+            None => anyhow::bail!("{} is not a distribution", link.filename),
+        }
+    }
+}
+
+/// The candidates matching the specifiers, following pip's handling
+/// of yanked releases
+///
+/// Yanked releases are skipped, unless the specifiers pin an exact version
+/// (`==1.0` or `===1.0`) and only yanked releases match it.
+/// Pre-releases follow the policy of the set, see [`SpecifierSet::filter`].
+///
+/// Read more at <https://peps.python.org/pep-0592/#installers>
+///
+/// ```
+/// use pyver::index::{select_candidates, Candidate};
+/// use pyver::specifiers::SpecifierSet;
+/// use pyver::PackageVersion;
+///
+/// let candidates: Vec<Candidate> = [("1.0", false), ("1.1", true)]
+///     .iter()
+///     .map(|(version, yanked)| Candidate {
+///         yanked: yanked.then(String::new),
+///         ..Candidate::new(PackageVersion::new(version).unwrap())
+///     })
+///     .collect();
+///
+/// let range = SpecifierSet::new(">=1.0").unwrap();
+/// assert_eq!(select_candidates(&candidates, &range).len(), 1);
+///
+/// let pin = SpecifierSet::new("==1.1").unwrap();
+/// assert!(select_candidates(&candidates, &pin)[0].is_yanked());
+/// ```
+pub fn select_candidates<'a>(
+    candidates: &'a [Candidate],
+    spec: &SpecifierSet,
+) -> Vec<&'a Candidate> {
+    let selected = matching(candidates.iter().filter(|c| !c.is_yanked()), spec);
+    if !selected.is_empty() || !is_pinned(spec) {
+        return selected;
+    }
+    matching(candidates.iter(), spec)
+}
+
+/// The highest version out of [`select_candidates`]
+pub fn best_candidate<'a>(
+    candidates: &'a [Candidate],
+    spec: &SpecifierSet,
+) -> Option<&'a Candidate> {
+    select_candidates(candidates, spec)
+        .into_iter()
+        .max_by(|a, b| a.version.cmp(&b.version))
+}
+
+/// The candidates whose versions the set yields
+fn matching<'a>(
+    candidates: impl Iterator<Item = &'a Candidate> + Clone,
+    spec: &SpecifierSet,
+) -> Vec<&'a Candidate> {
+    let versions: Vec<&PackageVersion> = spec
+        .filter(candidates.clone().map(|c| &c.version))
+        .collect();
+    candidates
+        .filter(|c| versions.iter().any(|v| std::ptr::eq(*v, &c.version)))
+        .collect()
+}
+
+/// Whether the set pins an exact version
+fn is_pinned(spec: &SpecifierSet) -> bool {
+    spec.iter().any(|specifier| match specifier.operator() {
+        Operator::Equal => !specifier.is_wildcard(),
+        Operator::ArbitraryEqual => true,
+        _ => false,
+    })
+}
+
+/// Position of the next `<a` tag, ignoring case
+fn find_anchor(html: &str) -> Option<usize> {
+    let bytes = html.as_bytes();
+    (0..bytes.len().saturating_sub(2)).find(|&i| {
+        bytes[i] == b'<'
+            && bytes[i + 1].eq_ignore_ascii_case(&b'a')
+            && bytes[i + 2].is_ascii_whitespace()
+    })
+}
+
+/// Attributes of a tag as lowercased names and decoded values,
+/// `None` for attributes without a value
+fn parse_attributes(tag: &str) -> Vec<(String, Option<String>)> {
+    let mut attributes = Vec::new();
+    let mut rest = tag.trim_end_matches('/');
+
+    loop {
+        rest = rest.trim_start();
+        let name_end = rest
+            .find(|c: char| c.is_whitespace() || c == '=')
+            .unwrap_or(rest.len());
+        if name_end == 0 {
+            break;
+        }
+        let name = rest[..name_end].to_ascii_lowercase();
+        rest = rest[name_end..].trim_start();
+
+        let value = match rest.strip_prefix('=') {
+            Some(after) => {
+                let after = after.trim_start();
+                let (value, remaining) = match after.chars().next() {
+                    Some(quote @ ('"' | '\'')) => match after[1..].find(quote) {
+                        Some(end) => (&after[1..end + 1], &after[end + 2..]),
+                        None => (&after[1..], ""),
+                    },
+                    _ => {
+                        let end =
+                            after.find(char::is_whitespace).unwrap_or(after.len());
+                        (&after[..end], &after[end..])
+                    }
+                };
+                rest = remaining;
+                Some(unescape(value))
+            }
+            None => None,
+        };
+        attributes.push((name, value));
+    }
+    attributes
+}
+
+/// Decodes the named entities used in index pages and numeric entities
+fn unescape(value: &str) -> String {
+    let mut unescaped = String::new();
+    let mut rest = value;
+
+    while let Some(start) = rest.find('&') {
+        unescaped.push_str(&rest[..start]);
+        rest = &rest[start..];
+
+        let entity = rest.find(';').map(|end| &rest[1..end]);
+        let decoded = entity.and_then(|entity| match entity {
+            "lt" => Some('<'),
+            "gt" => Some('>'),
+            "amp" => Some('&'),
+            "quot" => Some('"'),
+            "apos" => Some('\''),
+            _ => {
+                let number = entity.strip_prefix('#')?;
+                let code = match number.strip_prefix(['x', 'X']) {
+                    Some(hex) => u32::from_str_radix(hex, 16).ok()?,
+                    None => number.parse().ok()?,
+                };
+                char::from_u32(code)
+            }
+        });
+
+        match (entity, decoded) {
+            (Some(entity), Some(decoded)) => {
+                unescaped.push(decoded);
+                rest = &rest[entity.len() + 2..];
+            }
+            _ => {
+                unescaped.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    unescaped.push_str(rest);
+    unescaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{best_candidate, select_candidates, unescape, Candidate, ProjectPage};
+    use crate::dist::DistFilename;
+    use crate::specifiers::SpecifierSet;
+    use anyhow::Result;
+
+    #[test]
+    fn test_project_page() -> Result<()> {
+        let html = r#"<html><head><title>Links for foo-2</title></head><body>
+<h1>Links for foo-2</h1>
+<A HREF="https://files.example.com/foo-2-1.0.zip#md5=abc" data-requires-python='&gt;=2.7,!=3.0.*'>foo-2-1.0.zip</A>
+<a data-yanked href=/files/foo_2-1.1-1-py3-none-any.whl>foo_2-1.1-1-py3-none-any.whl</a>
+<a href="/files/foo-2-1.2.tar.gz" data-requires-python="&gt;=3.x">foo-2-1.2.tar.gz</a>
+<a href="/files/foo-2-1.2.tar.gz.asc">foo-2-1.2.tar.gz.asc</a>
+<a name="no-href">anchor</a>
+<abbr title="not a link">abbr</abbr>
+</body></html>"#;
+        let page = ProjectPage::new(html, "foo-2")?;
+        assert_eq!(page.links.len(), 4);
+
+        let zip = &page.links[0];
+        assert_eq!(zip.filename, "foo-2-1.0.zip");
+        assert_eq!(zip.url, "https://files.example.com/foo-2-1.0.zip");
+        assert_eq!(zip.hash, Some(("md5".to_string(), "abc".to_string())));
+        assert_eq!(
+            zip.requires_python
+                .as_ref()
+                .map(|v| v.to_string())
+                .as_deref(),
+            Some(">=2.7,!=3.0.*")
+        );
+        assert_eq!(
+            zip.dist
+                .as_ref()
+                .map(|d| d.version().to_string())
+                .as_deref(),
+            Some("1.0")
+        );
+
+        let wheel = &page.links[1];
+        assert_eq!(wheel.yanked.as_deref(), Some(""));
+        assert!(matches!(wheel.dist, Some(DistFilename::Wheel(_))));
+
+        // Invalid metadata is ignored the way pip does
+        assert_eq!(page.links[2].requires_python, None);
+        assert_eq!(page.links[3].dist, None);
+        Ok(())
+    }
+
+    #[test]
+    fn test_candidate_selection() -> Result<()> {
+        let html = r#"
+<a href="spam-1.0.tar.gz">spam-1.0.tar.gz</a>
+<a href="spam-1.1.tar.gz" data-yanked="">spam-1.1.tar.gz</a>
+<a href="spam-1.2rc1.tar.gz">spam-1.2rc1.tar.gz</a>
+<a href="spam-1.2.tar.gz" data-yanked="CVE">spam-1.2.tar.gz</a>
+<a href="spam-1.2.tar.gz.asc">spam-1.2.tar.gz.asc</a>
+"#;
+        let page = ProjectPage::new(html, "spam")?;
+        let candidates: Vec<Candidate> = page
+            .links
+            .iter()
+            .filter_map(|link| Candidate::try_from(link).ok())
+            .collect();
+        assert_eq!(candidates.len(), 4);
+
+        let cases = vec![
+            ("", vec!["1.0"]),
+            (">1.0", vec!["1.2rc1"]),
+            ("==1.1", vec!["1.1"]),
+            ("===1.2", vec!["1.2"]),
+            ("==1.1.*", vec![]),
+            ("==1.2, >=1.0", vec!["1.2"]),
+            (">=1.1, <1.2", vec![]),
+            ("==1.0", vec!["1.0"]),
+        ];
+
+        for (spec, expected) in cases {
+            let spec = SpecifierSet::new(spec)?;
+            let selected: Vec<String> = select_candidates(&candidates, &spec)
+                .iter()
+                .map(|c| c.version.to_string())
+                .collect();
+            assert_eq!(selected, expected, "{}", spec);
+        }
+
+        let best = best_candidate(&candidates, &SpecifierSet::new(">=1.0")?);
+        assert_eq!(best.map(|c| c.version.to_string()).as_deref(), Some("1.0"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_unescape() {
+        assert_eq!(
+            unescape("&gt;=3.6&#44;&lt;4&#x2C; &amp;&unknown; &"),
+            ">=3.6,<4, &&unknown; &"
+        );
+    }
+
+    #[test]
+    fn test_project_page_negative() {
+        let pages = vec!["<a href=\"spam-1.0.tar.gz\""];
+
+        for html in pages {
+            if let Ok(v) = ProjectPage::new(html, "spam") {
+                panic!("Oh no {:?}", v);
+            }
+        }
+    }
+}
@@ -0,0 +1,218 @@
+use crate::{PackageVersion, VersionKey};
+use anyhow::Result;
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::ops::Deref;
+use std::sync::Arc;
+
+/// # Version interner
+/// Parses each distinct version string once and hands out shared
+/// [`InternedVersion`]s for it
+///
+/// Meant for dependency graphs and index mirrors where the same version
+/// (e.g. `2.28.1`) is seen thousands of times. Strings are deduplicated
+/// as written, so `1.0` and `v1.0` are interned separately even though
+/// they are equal versions.
+///
+/// ## Example Usage
+/// ```
+/// use pyver::VersionInterner;
+///
+/// let mut interner = VersionInterner::new();
+/// let a = interner.intern("2.28.1").unwrap();
+/// let b = interner.intern("2.28.1").unwrap();
+/// let c = interner.intern("2.28.0").unwrap();
+///
+/// assert!(a.ptr_eq(&b));
+/// assert!(c < a);
+/// assert_eq!(interner.len(), 2);
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct VersionInterner {
+    versions: HashMap<Box<str>, InternedVersion>,
+}
+
+impl VersionInterner {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The shared version for a string, parsing it the first time it's seen
+    ///
+    /// Invalid strings fail every time and aren't stored
+    pub fn intern(&mut self, version: &str) -> Result<InternedVersion> {
+        if let Some(interned) = self.versions.get(version) {
+            return Ok(interned.clone());
+        }
+
+        let interned = InternedVersion::new(PackageVersion::new(version)?);
+        self.versions.insert(version.into(), interned.clone());
+        Ok(interned)
+    }
+
+    /// The shared version for a string if it was interned before
+    pub fn get(&self, version: &str) -> Option<InternedVersion> {
+        self.versions.get(version).cloned()
+    }
+
+    /// Number of distinct version strings
+    pub fn len(&self) -> usize {
+        self.versions.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.versions.is_empty()
+    }
+}
+
+/// # Interned version
+/// A cheaply cloned handle to a version shared through a [`VersionInterner`]
+///
+/// The sort key is computed once when interning, so handles compare,
+/// order and hash without looking at the version's segments again.
+/// Handles dereference to the [`PackageVersion`] itself.
+#[derive(Clone)]
+pub struct InternedVersion(Arc<Interned>);
+
+struct Interned {
+    version: PackageVersion,
+    key: VersionKey,
+}
+
+impl InternedVersion {
+    /// Shares a version outside of an interner, computing its key
+    pub fn new(version: PackageVersion) -> Self {
+        let key = version.cmp_key();
+        Self(Arc::new(Interned { version, key }))
+    }
+
+    pub fn version(&self) -> &PackageVersion {
+        &self.0.version
+    }
+
+    /// The precomputed sort key, see [`PackageVersion::cmp_key`]
+    pub fn key(&self) -> &VersionKey {
+        &self.0.key
+    }
+
+    /// Whether both handles point to the same interned version
+    pub fn ptr_eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl Deref for InternedVersion {
+    type Target = PackageVersion;
+
+    fn deref(&self) -> &PackageVersion {
+        &self.0.version
+    }
+}
+
+impl From<PackageVersion> for InternedVersion {
+    fn from(version: PackageVersion) -> Self {
+        Self::new(version)
+    }
+}
+
+impl PartialEq for InternedVersion {
+    fn eq(&self, other: &Self) -> bool {
+        self.ptr_eq(other) || self.key() == other.key()
+    }
+}
+
+impl Eq for InternedVersion {}
+
+impl PartialOrd for InternedVersion {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for InternedVersion {
+    fn cmp(&self, other: &Self) -> Ordering {
+        if self.ptr_eq(other) {
+            return Ordering::Equal;
+        }
+        self.key().cmp(other.key())
+    }
+}
+
+impl Hash for InternedVersion {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.key().hash(state);
+    }
+}
+
+impl fmt::Debug for InternedVersion {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Debug::fmt(&self.0.version, f)
+    }
+}
+
+impl fmt::Display for InternedVersion {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Display::fmt(&self.0.version, f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{InternedVersion, VersionInterner};
+    use crate::PackageVersion;
+    use anyhow::Result;
+    use std::collections::HashSet;
+
+    #[test]
+    fn test_interner() -> Result<()> {
+        let mut interner = VersionInterner::new();
+        let versions = ["1.0", "2.0rc1", "1.0", "v1.0", "1.0.0", "2.0rc1"];
+        let interned = versions
+            .iter()
+            .map(|v| interner.intern(v))
+            .collect::<Result<Vec<_>>>()?;
+
+        assert_eq!(interner.len(), 4);
+        assert!(interned[0].ptr_eq(&interned[2]));
+        assert!(!interned[0].ptr_eq(&interned[3]));
+        assert_eq!(interned[0], interned[3]);
+        assert_eq!(interned[3].to_string(), "v1.0");
+        assert!(interned[1] > interned[4]);
+
+        let unique: HashSet<&InternedVersion> = interned.iter().collect();
+        assert_eq!(unique.len(), 2);
+        assert!(interner
+            .get("1.0.0")
+            .is_some_and(|v| v.ptr_eq(&interned[4])));
+        assert!(interner.get("3.0").is_none());
+        Ok(())
+    }
+
+    #[test]
+    fn test_interned_ordering() -> Result<()> {
+        let versions = ["1.0.dev0", "1.0a1", "1.0", "1.0+local", "1.0.post1"];
+        for pair in versions.windows(2) {
+            let a = InternedVersion::new(PackageVersion::new(pair[0])?);
+            let b = InternedVersion::from(PackageVersion::new(pair[1])?);
+            assert!(a < b, "{} < {}", a, b);
+            assert_eq!(a.cmp(&b), a.version().cmp(b.version()));
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_interner_negative() {
+        let mut interner = VersionInterner::new();
+        let versions = vec!["", "1.0 junk", "junk"];
+
+        for version in versions {
+            match interner.intern(version) {
+                Ok(v) => panic!("Oh no {}", v),
+                Err(_e) => continue,
+            }
+        }
+        assert!(interner.is_empty());
+    }
+}
@@ -0,0 +1,167 @@
+//! Versions in configuration files are often written without quotes
+//! (`version: 1.0` in YAML, `version = 2024` in TOML), which makes them
+//! numbers. These helpers accept numbers as well as strings:
+//!
+//! * Integers are written out in full, `2024` becomes the version `2024`
+//! * Floats are written in their shortest form that reads back as the
+//!   same float, keeping one fractional digit for whole numbers, so
+//!   `1.0` becomes `1.0` and `2.5` becomes `2.5`
+//!
+//! A float only holds the value and not how it was written, so trailing
+//! zeros are lost (`3.10` is read as `3.1`) and versions like `1.2.3`
+//! can't be numbers at all. Negative numbers and floats too large to be
+//! written without an exponent are rejected, quote those versions.
+//!
+//! ```
+//! use pyver::PackageVersion;
+//! use serde::Deserialize;
+//!
+//! #[derive(Deserialize)]
+//! struct Config {
+//!     #[serde(deserialize_with = "pyver::lenient::deserialize")]
+//!     version: PackageVersion,
+//!     #[serde(default, deserialize_with = "pyver::lenient::option::deserialize")]
+//!     minimum: Option<PackageVersion>,
+//! }
+//!
+//! let config: Config = serde_json::from_str(r#"{"version": 1.0}"#).unwrap();
+//! assert_eq!(config.version.to_string(), "1.0");
+//! assert!(config.minimum.is_none());
+//! ```
+
+use crate::PackageVersion;
+use alloc::format;
+use alloc::string::ToString;
+use core::fmt;
+use serde::de::{self, Deserialize, Deserializer, Visitor};
+
+/// Deserializes a version from a string or a number
+pub fn deserialize<'de, D>(deserializer: D) -> Result<PackageVersion, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    Lenient::deserialize(deserializer).map(|version| version.0)
+}
+
+/// Helpers for optional versions
+pub mod option {
+    use super::Lenient;
+    use crate::PackageVersion;
+    use serde::{Deserialize, Deserializer};
+
+    /// Deserializes an optional version from a string or a number,
+    /// use with `#[serde(default)]` to allow missing fields
+    pub fn deserialize<'de, D>(
+        deserializer: D,
+    ) -> Result<Option<PackageVersion>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        Option::<Lenient>::deserialize(deserializer)
+            .map(|version| version.map(|version| version.0))
+    }
+}
+
+struct Lenient(PackageVersion);
+
+impl<'de> Deserialize<'de> for Lenient {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_any(LenientVisitor).map(Lenient)
+    }
+}
+
+struct LenientVisitor;
+
+impl LenientVisitor {
+    fn parse<E: de::Error>(version: &str) -> Result<PackageVersion, E> {
+        PackageVersion::new(version).map_err(E::custom)
+    }
+}
+
+impl Visitor<'_> for LenientVisitor {
+    type Value = PackageVersion;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "a version string or a non-negative number")
+    }
+
+    fn visit_str<E: de::Error>(self, version: &str) -> Result<PackageVersion, E> {
+        Self::parse(version)
+    }
+
+    fn visit_u64<E: de::Error>(self, version: u64) -> Result<PackageVersion, E> {
+        Self::parse(&version.to_string())
+    }
+
+    fn visit_i64<E: de::Error>(self, version: i64) -> Result<PackageVersion, E> {
+        match u64::try_from(version) {
+            Ok(version) => self.visit_u64(version),
+            Err(_) => Err(E::custom(format!("Negative version {}", version))),
+        }
+    }
+
+    fn visit_f64<E: de::Error>(self, version: f64) -> Result<PackageVersion, E> {
+        if !version.is_finite() || version.is_sign_negative() {
+            return Err(E::custom(format!("Invalid version {}", version)));
+        }
+        // `Debug` is the shortest round-trip form and keeps the `.0` of
+        // whole numbers, large floats get an exponent and fail to parse
+        Self::parse(&format!("{:?}", version))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::PackageVersion;
+    use anyhow::Result;
+    use serde::Deserialize;
+
+    #[derive(Deserialize)]
+    struct Config {
+        #[serde(deserialize_with = "super::deserialize")]
+        version: PackageVersion,
+        #[serde(default, deserialize_with = "super::option::deserialize")]
+        minimum: Option<PackageVersion>,
+    }
+
+    #[test]
+    fn test_lenient() -> Result<()> {
+        // (json value, version)
+        let versions = vec![
+            ("1.0", "1.0"),
+            ("2024", "2024"),
+            ("2.5", "2.5"),
+            ("3.10", "3.1"),
+            ("0.1", "0.1"),
+            ("1e3", "1000.0"),
+            (r#""v1.2.3rc1""#, "v1.2.3rc1"),
+        ];
+        for (value, version) in versions {
+            let json = format!(r#"{{"version": {}, "minimum": {}}}"#, value, value);
+            let config: Config = serde_json::from_str(&json)?;
+            assert_eq!(config.version.to_string(), version);
+            assert_eq!(config.minimum.map(|v| v.to_string()), Some(version.into()));
+        }
+
+        let config: Config =
+            serde_json::from_str(r#"{"version": 1, "minimum": null}"#)?;
+        assert!(config.minimum.is_none());
+        Ok(())
+    }
+
+    #[test]
+    fn test_lenient_negative() {
+        let values = vec!["-1", "-1.5", "1e300", r#""1.0 junk""#, "true", "[1]"];
+
+        for value in values {
+            let json = format!(r#"{{"version": {}}}"#, value);
+            match serde_json::from_str::<Config>(&json) {
+                Ok(v) => panic!("Oh no {}", v.version),
+                Err(_e) => continue,
+            }
+        }
+    }
+}
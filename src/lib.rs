@@ -25,25 +25,207 @@ assert!(a < b);
 
 If you want to verify single version strings do
 ```
-use pyver::validate_440_version;
+use pyver::Validator;
 
 assert!(
-    validate_440_version("1.0").is_ok()
+    Validator::new().validate("1.0").is_ok()
 );
 ```
+The groups matched by the `PEP-440` regex are available from
+`validate_440_version` with the `regex-parser` or `regex-lite` feature.
+
+# `no_std`
+Without the default `std` feature the crate only needs `alloc`, leaving
+versions, their identifiers, names and specifiers. Modules reading files,
+running processes or hashing lists of versions need `std`.
+```toml
+[dependencies]
+pyver = { version = "1.0", default-features = false }
+```
 */
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
 
-#[macro_use]
-extern crate derivative;
+extern crate alloc;
 
+mod parser;
 mod validator;
-// Expose validate_440_version function
+// Expose validate_440_version function and the batch Validator
+#[cfg(any(test, feature = "regex-parser", feature = "regex-lite"))]
 pub use validator::validate_440_version;
+pub use validator::Validator;
 
 /// Identifiers (i.e. the components of a version string)
 // Expose Ids Module
 pub mod ids;
 
+/// Distribution filenames (sdists and wheels)
+#[cfg(feature = "std")]
+pub mod dist;
+
+/// Version specifiers (i.e. `>=1.0`)
+pub mod specifiers;
+
+/// Platform compatibility tags
+#[cfg(feature = "std")]
+pub mod tags;
+
+/// Environment markers (i.e. `python_version < "3.11"`)
+#[cfg(feature = "std")]
+pub mod markers;
+
+/// pip requirements files (i.e. `requirements.txt`)
+#[cfg(feature = "std")]
+pub mod requirements;
+
+/// Simple repository index pages
+#[cfg(feature = "std")]
+pub mod index;
+
+/// Core metadata files (i.e. `METADATA` and `PKG-INFO`)
+#[cfg(feature = "std")]
+pub mod metadata;
+
+/// Versions assigned in Python source code (i.e. `__version__ = "1.0"`)
+#[cfg(feature = "std")]
+pub mod source;
+
+/// Versions derived from version control (i.e. `1.4.3.dev3+g1a2b3c4`)
+#[cfg(feature = "std")]
+pub mod scm;
+
+/// Calendar versioning (i.e. `2024.01.15`)
+#[cfg(feature = "std")]
+pub mod calver;
+
+/// Release version policies (i.e. no epochs, at most three segments)
+#[cfg(feature = "std")]
+pub mod policy;
+
+/// Sorting and picking from lists of versions
+#[cfg(feature = "std")]
+pub mod collections;
+
+/// `pyproject.toml` project metadata
+#[cfg(feature = "toml")]
+pub mod pyproject;
+
+/// PyPI JSON API responses
+#[cfg(feature = "json")]
+pub mod pypi;
+
+// JSON schemas of the types serialized as strings
+#[cfg(feature = "schemars")]
+mod schema;
+
+/// Fuzzing input (i.e. arbitrary versions and version strings)
+#[cfg(feature = "arbitrary")]
+pub mod fuzzing;
+
+// Stable binary encoding of versions and specifier sets
+#[cfg(feature = "borsh")]
+mod binary;
+
+// Versions as text in Postgres queries
+#[cfg(feature = "postgres")]
+mod postgres;
+
+// Versions and specifier sets as text with sqlx and Diesel
+#[cfg(any(feature = "sqlx", feature = "diesel"))]
+mod sql;
+
+/// Python bindings (i.e. `Version`, `Specifier` and `SpecifierSet` classes)
+#[cfg(feature = "python")]
+pub mod python;
+
+/// JavaScript bindings (i.e. `compare`, `satisfies` and a `Version` class)
+#[cfg(feature = "wasm")]
+pub mod wasm;
+
+/// Swift and Kotlin bindings (i.e. `compareVersions` and a `Version` class)
+#[cfg(feature = "uniffi")]
+pub mod uniffi;
+#[cfg(feature = "uniffi")]
+::uniffi::setup_scaffolding!();
+
+/// C bindings (i.e. `pyver_parse`, `pyver_compare` and `pyver_satisfies`)
+#[cfg(feature = "ffi")]
+pub mod ffi;
+
+/// RPM package versions (i.e. `1.0~rc1`, `1.0^post1`)
+pub mod rpm;
+
+/// Conda package versions and constraints (i.e. `1.1.0post1`, `>=1.2,<2|3.*`)
+pub mod conda;
+
+/// Deserializing versions written as numbers (i.e. `version: 1.0`)
+pub mod lenient;
+
+mod name;
+// Expose PackageName and Extra Structs
+pub use name::{Extra, PackageName};
+
 mod version;
-// Expose PackageVersion Struct
-pub use version::PackageVersion;
+// Expose PackageVersion Structs
+#[cfg(feature = "rkyv")]
+pub use version::ArchivedPackageVersion;
+pub use version::{PackageVersion, PackageVersionBuilder, VersionKey};
+
+// Ordering and accessors of archived versions
+#[cfg(feature = "rkyv")]
+mod archive;
+
+mod version_ref;
+// Expose the borrowed PackageVersionRef Struct
+pub use version_ref::PackageVersionRef;
+
+#[cfg(feature = "std")]
+mod interner;
+// Expose VersionInterner and its shared InternedVersion handles
+#[cfg(feature = "std")]
+pub use interner::{InternedVersion, VersionInterner};
+
+/// Compile-time checked version literals (i.e. `pyver!("1.0a2")`)
+///
+/// ```
+/// use pyver::{pyver, PackageVersion};
+///
+/// let version = pyver!("v1.0a2.post1");
+/// assert_eq!(version, PackageVersion::new("1.0a2.post1").unwrap());
+/// assert_eq!(version.to_string(), "v1.0a2.post1");
+/// ```
+///
+/// Invalid versions don't compile
+/// ```compile_fail
+/// let version = pyver::pyver!("1.0 junk");
+/// ```
+#[cfg(feature = "macros")]
+pub use pyver_macros::pyver;
+
+mod compare;
+// Expose Comparison explanations and differences
+pub use compare::{ComparedBy, Comparison, VersionDiff};
+
+// Release bumping methods of PackageVersion
+mod bump;
+
+// Byte keys of PackageVersion ordered like versions
+mod sort_key;
+
+// Debian package versions of PackageVersion
+mod debian;
+
+#[cfg(feature = "semver")]
+mod semver_compat;
+// Expose the warnings of lossy conversions to semver versions
+#[cfg(feature = "semver")]
+pub use semver_compat::SemverWarning;
+
+mod series;
+// Expose ReleaseSeries and grouping by series
+pub use series::{group_by_series, ReleaseSeries};
+
+#[cfg(feature = "std")]
+mod requirement;
+// Expose Requirement Structs
+#[cfg(feature = "std")]
+pub use requirement::{Requirement, RequirementUrl};
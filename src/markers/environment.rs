@@ -0,0 +1,303 @@
+use super::MarkerVariable;
+#[cfg(feature = "current")]
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "current")]
+use std::ffi::OsStr;
+#[cfg(feature = "current")]
+use std::process::Command;
+
+/// # `PEP-508` Marker environment
+/// The values of the marker variables for one Python interpreter,
+/// markers are evaluated against this
+///
+/// Every field holds the value Python reports for it,
+/// e.g. `python_version` is `platform.python_version()[:3]`.
+/// Environments for other machines can be put together with
+/// [`MarkerEnvironment::builder`], the environment of the local
+/// interpreter is available through `MarkerEnvironment::current`
+/// with the `current` feature enabled.
+#[derive(Hash, Clone, Debug, Default, Serialize, Deserialize, Eq, PartialEq)]
+pub struct MarkerEnvironment {
+    /// `sys.implementation.name` (`cpython`)
+    pub implementation_name: String,
+    /// Version of the implementation (`3.11.4`)
+    pub implementation_version: String,
+    /// `os.name` (`posix`)
+    pub os_name: String,
+    /// `platform.machine()` (`x86_64`)
+    pub platform_machine: String,
+    /// `platform.python_implementation()` (`CPython`)
+    pub platform_python_implementation: String,
+    /// `platform.release()` (`6.1.0-13-amd64`)
+    pub platform_release: String,
+    /// `platform.system()` (`Linux`)
+    pub platform_system: String,
+    /// `platform.version()`
+    pub platform_version: String,
+    /// `platform.python_version()` (`3.11.4`)
+    pub python_full_version: String,
+    /// Major and minor Python version (`3.11`)
+    pub python_version: String,
+    /// `sys.platform` (`linux`)
+    pub sys_platform: String,
+}
+
+impl MarkerEnvironment {
+    /// Starts building an environment, e.g. to evaluate markers
+    /// for a platform other than the current one
+    ///
+    /// ```
+    /// use pyver::markers::{Marker, MarkerEnvironment};
+    ///
+    /// let windows = MarkerEnvironment::builder()
+    ///     .implementation_name("cpython")
+    ///     .python_full_version("3.12.1")
+    ///     .os_name("nt")
+    ///     .sys_platform("win32")
+    ///     .platform_system("Windows")
+    ///     .platform_machine("AMD64")
+    ///     .build();
+    ///
+    /// assert_eq!(windows.python_version, "3.12");
+    /// assert_eq!(windows.platform_python_implementation, "CPython");
+    ///
+    /// let marker = Marker::new("sys_platform == 'win32' and python_version >= '3.8'");
+    /// assert!(marker.unwrap().evaluate(&windows));
+    /// ```
+    pub fn builder() -> MarkerEnvironmentBuilder {
+        MarkerEnvironmentBuilder::default()
+    }
+
+    /// The value of a marker variable, `None` for `extra`
+    /// which depends on the requested extras instead
+    pub fn get(&self, variable: MarkerVariable) -> Option<&str> {
+        let value = match variable {
+            MarkerVariable::ImplementationName => &self.implementation_name,
+            MarkerVariable::ImplementationVersion => &self.implementation_version,
+            MarkerVariable::OsName => &self.os_name,
+            MarkerVariable::PlatformMachine => &self.platform_machine,
+            MarkerVariable::PlatformPythonImplementation => {
+                &self.platform_python_implementation
+            }
+            MarkerVariable::PlatformRelease => &self.platform_release,
+            MarkerVariable::PlatformSystem => &self.platform_system,
+            MarkerVariable::PlatformVersion => &self.platform_version,
+            MarkerVariable::PythonFullVersion => &self.python_full_version,
+            MarkerVariable::PythonVersion => &self.python_version,
+            MarkerVariable::SysPlatform => &self.sys_platform,
+            MarkerVariable::Extra => return None,
+        };
+        Some(value)
+    }
+}
+
+/// Prints every marker variable on its own line,
+/// in the order of the `MarkerEnvironment` fields
+#[cfg(feature = "current")]
+const ENVIRONMENT_SCRIPT: &str = r#"
+import os, platform, sys
+
+def format_version(info):
+    version = "{0.major}.{0.minor}.{0.micro}".format(info)
+    if info.releaselevel != "final":
+        version += info.releaselevel[0] + str(info.serial)
+    return version
+
+print(sys.implementation.name)
+print(format_version(sys.implementation.version))
+print(os.name)
+print(platform.machine())
+print(platform.python_implementation())
+print(platform.release())
+print(platform.system())
+print(platform.version())
+print(platform.python_version())
+print(".".join(platform.python_version_tuple()[:2]))
+print(sys.platform)
+"#;
+
+#[cfg(feature = "current")]
+impl MarkerEnvironment {
+    /// The environment of the `python3` interpreter on the `PATH`
+    /// (`python` on Windows)
+    pub fn current() -> Result<Self> {
+        let interpreter = if cfg!(windows) { "python" } else { "python3" };
+        Self::from_interpreter(interpreter)
+    }
+
+    /// The environment of a Python interpreter, which is run once
+    /// to report its values
+    pub fn from_interpreter(interpreter: impl AsRef<OsStr>) -> Result<Self> {
+        let interpreter = interpreter.as_ref();
+        let output = Command::new(interpreter)
+            .args(["-c", ENVIRONMENT_SCRIPT])
+            .output()?;
+        if !output.status.success() {
+            anyhow::bail!(
+                "Failed to query {}: {}",
+                interpreter.to_string_lossy(),
+                String::from_utf8_lossy(&output.stderr)
+            );
+        }
+
+        let stdout = String::from_utf8(output.stdout)?;
+        let mut values = stdout.lines().map(|line| line.trim().to_string());
+        let mut next = || match values.next() {
+            Some(value) => Ok(value),
+            None => anyhow::bail!(
+                "Unexpected output from {}",
+                interpreter.to_string_lossy()
+            ),
+        };
+
+        Ok(Self {
+            implementation_name: next()?,
+            implementation_version: next()?,
+            os_name: next()?,
+            platform_machine: next()?,
+            platform_python_implementation: next()?,
+            platform_release: next()?,
+            platform_system: next()?,
+            platform_version: next()?,
+            python_full_version: next()?,
+            python_version: next()?,
+            sys_platform: next()?,
+        })
+    }
+}
+
+/// # Marker environment builder
+/// Sets the values of a [`MarkerEnvironment`] one by one,
+/// values derived from others are filled in by [`MarkerEnvironmentBuilder::build`]
+/// unless they were set explicitly
+#[derive(Clone, Debug, Default)]
+pub struct MarkerEnvironmentBuilder {
+    environment: MarkerEnvironment,
+}
+
+impl MarkerEnvironmentBuilder {
+    /// `sys.implementation.name` (`cpython`, `pypy`)
+    pub fn implementation_name(mut self, value: &str) -> Self {
+        self.environment.implementation_name = value.to_string();
+        self
+    }
+
+    /// Defaults to the `python_full_version`
+    pub fn implementation_version(mut self, value: &str) -> Self {
+        self.environment.implementation_version = value.to_string();
+        self
+    }
+
+    pub fn os_name(mut self, value: &str) -> Self {
+        self.environment.os_name = value.to_string();
+        self
+    }
+
+    pub fn platform_machine(mut self, value: &str) -> Self {
+        self.environment.platform_machine = value.to_string();
+        self
+    }
+
+    /// Defaults to the spelling Python uses for the `implementation_name`
+    pub fn platform_python_implementation(mut self, value: &str) -> Self {
+        self.environment.platform_python_implementation = value.to_string();
+        self
+    }
+
+    pub fn platform_release(mut self, value: &str) -> Self {
+        self.environment.platform_release = value.to_string();
+        self
+    }
+
+    pub fn platform_system(mut self, value: &str) -> Self {
+        self.environment.platform_system = value.to_string();
+        self
+    }
+
+    pub fn platform_version(mut self, value: &str) -> Self {
+        self.environment.platform_version = value.to_string();
+        self
+    }
+
+    /// The full Python version (`3.11.4`)
+    pub fn python_full_version(mut self, value: &str) -> Self {
+        self.environment.python_full_version = value.to_string();
+        self
+    }
+
+    /// Defaults to the major and minor version of the `python_full_version`
+    pub fn python_version(mut self, value: &str) -> Self {
+        self.environment.python_version = value.to_string();
+        self
+    }
+
+    pub fn sys_platform(mut self, value: &str) -> Self {
+        self.environment.sys_platform = value.to_string();
+        self
+    }
+
+    /// Fills in the derived values that weren't set
+    pub fn build(self) -> MarkerEnvironment {
+        let mut environment = self.environment;
+
+        if environment.python_version.is_empty() {
+            let parts: Vec<&str> = environment.python_full_version.split('.').collect();
+            if parts.len() >= 2 {
+                environment.python_version = format!("{}.{}", parts[0], parts[1]);
+            }
+        }
+        if environment.implementation_version.is_empty() {
+            environment.implementation_version =
+                environment.python_full_version.clone();
+        }
+        if environment.platform_python_implementation.is_empty() {
+            environment.platform_python_implementation =
+                match environment.implementation_name.as_str() {
+                    "cpython" => "CPython".to_string(),
+                    "pypy" => "PyPy".to_string(),
+                    "ironpython" => "IronPython".to_string(),
+                    "jython" => "Jython".to_string(),
+                    name => name.to_string(),
+                };
+        }
+        environment
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::MarkerEnvironment;
+
+    #[test]
+    fn test_environment_builder() {
+        let environment = MarkerEnvironment::builder()
+            .implementation_name("pypy")
+            .implementation_version("7.3.13")
+            .python_full_version("3.10.13")
+            .build();
+        assert_eq!(environment.python_version, "3.10");
+        assert_eq!(environment.implementation_version, "7.3.13");
+        assert_eq!(environment.platform_python_implementation, "PyPy");
+
+        let environment = MarkerEnvironment::builder()
+            .python_full_version("3.13.0rc1")
+            .python_version("3.13")
+            .sys_platform("darwin")
+            .build();
+        assert_eq!(environment.implementation_version, "3.13.0rc1");
+        assert_eq!(environment.sys_platform, "darwin");
+        assert_eq!(environment.os_name, "");
+    }
+
+    #[cfg(feature = "current")]
+    #[test]
+    fn test_current_environment() -> anyhow::Result<()> {
+        let environment = MarkerEnvironment::current()?;
+        assert!(environment
+            .python_full_version
+            .starts_with(&environment.python_version));
+        assert!(!environment.sys_platform.is_empty());
+        Ok(())
+    }
+}
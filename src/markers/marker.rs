@@ -0,0 +1,647 @@
+use super::MarkerEnvironment;
+use crate::name::normalize_name;
+use crate::specifiers::{Operator, Specifier};
+use crate::Extra;
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+/// # `PEP-508` Marker variable
+/// The environment values a marker can refer to
+#[derive(Hash, Copy, Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+pub enum MarkerVariable {
+    ImplementationName,
+    ImplementationVersion,
+    OsName,
+    PlatformMachine,
+    PlatformPythonImplementation,
+    PlatformRelease,
+    PlatformSystem,
+    PlatformVersion,
+    PythonFullVersion,
+    PythonVersion,
+    SysPlatform,
+    /// The extra a requirement is evaluated for
+    Extra,
+}
+
+impl MarkerVariable {
+    /// The variable name as written in a marker
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            MarkerVariable::ImplementationName => "implementation_name",
+            MarkerVariable::ImplementationVersion => "implementation_version",
+            MarkerVariable::OsName => "os_name",
+            MarkerVariable::PlatformMachine => "platform_machine",
+            MarkerVariable::PlatformPythonImplementation => {
+                "platform_python_implementation"
+            }
+            MarkerVariable::PlatformRelease => "platform_release",
+            MarkerVariable::PlatformSystem => "platform_system",
+            MarkerVariable::PlatformVersion => "platform_version",
+            MarkerVariable::PythonFullVersion => "python_full_version",
+            MarkerVariable::PythonVersion => "python_version",
+            MarkerVariable::SysPlatform => "sys_platform",
+            MarkerVariable::Extra => "extra",
+        }
+    }
+}
+
+impl FromStr for MarkerVariable {
+    type Err = anyhow::Error;
+
+    fn from_str(variable: &str) -> Result<Self> {
+        Ok(match variable {
+            "implementation_name" => MarkerVariable::ImplementationName,
+            "implementation_version" => MarkerVariable::ImplementationVersion,
+            "os_name" | "os.name" => MarkerVariable::OsName,
+            "platform_machine" | "platform.machine" => MarkerVariable::PlatformMachine,
+            // `python_implementation` and the dotted names are from `PEP-345`
+            "platform_python_implementation"
+            | "platform.python_implementation"
+            | "python_implementation" => MarkerVariable::PlatformPythonImplementation,
+            "platform_release" => MarkerVariable::PlatformRelease,
+            "platform_system" => MarkerVariable::PlatformSystem,
+            "platform_version" | "platform.version" => MarkerVariable::PlatformVersion,
+            "python_full_version" => MarkerVariable::PythonFullVersion,
+            "python_version" => MarkerVariable::PythonVersion,
+            "sys_platform" | "sys.platform" => MarkerVariable::SysPlatform,
+            "extra" => MarkerVariable::Extra,
+            _ => anyhow::bail!("Unknown marker variable {}", variable),
+        })
+    }
+}
+
+impl fmt::Display for MarkerVariable {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+/// # Marker operand
+/// Either side of a marker comparison
+#[derive(Hash, Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+pub enum MarkerValue {
+    /// An environment variable (`python_version`)
+    Variable(MarkerVariable),
+    /// A quoted string (`"3.11"`)
+    String(String),
+}
+
+impl fmt::Display for MarkerValue {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            MarkerValue::Variable(variable) => write!(f, "{}", variable),
+            // Strings can't contain the quote they are wrapped in
+            MarkerValue::String(value) if value.contains('"') => {
+                write!(f, "'{}'", value)
+            }
+            MarkerValue::String(value) => write!(f, "\"{}\"", value),
+        }
+    }
+}
+
+/// # Marker comparison operator
+#[derive(Hash, Copy, Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+pub enum MarkerOperator {
+    /// A version comparison (`<`, `~=`, ...), falling back to comparing
+    /// strings if either side isn't a version
+    Comparison(Operator),
+    /// `in`, whether the left string is part of the right one
+    In,
+    /// `not in`
+    NotIn,
+}
+
+impl fmt::Display for MarkerOperator {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            MarkerOperator::Comparison(operator) => write!(f, "{}", operator),
+            MarkerOperator::In => write!(f, "in"),
+            MarkerOperator::NotIn => write!(f, "not in"),
+        }
+    }
+}
+
+/// # Marker comparison
+/// A single comparison such as `python_version < "3.11"`
+#[derive(Hash, Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+pub struct MarkerExpression {
+    pub left: MarkerValue,
+    pub operator: MarkerOperator,
+    pub right: MarkerValue,
+}
+
+impl fmt::Display for MarkerExpression {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} {} {}", self.left, self.operator, self.right)
+    }
+}
+
+/// # `PEP-508` Environment marker
+/// A boolean expression of comparisons deciding whether a requirement
+/// applies to an environment, e.g.
+/// `python_version < "3.11" and (sys_platform == "linux" or extra == "test")`
+///
+/// Comparisons use `PEP-440` version ordering when both sides are versions
+/// (so `"3.9" < "3.10"`) and compare strings otherwise.
+///
+/// ## Example Usage
+/// ```
+/// use pyver::markers::{Marker, MarkerEnvironment};
+///
+/// let marker = Marker::new(r#"python_version < "3.11" and os_name == 'posix'"#).unwrap();
+/// let environment = MarkerEnvironment {
+///     python_version: "3.9".to_string(),
+///     os_name: "posix".to_string(),
+///     ..Default::default()
+/// };
+///
+/// assert!(marker.evaluate(&environment));
+/// assert_eq!(
+///     marker.to_string(),
+///     r#"python_version < "3.11" and os_name == "posix""#
+/// );
+/// ```
+#[derive(Hash, Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+#[serde(try_from = "String", into = "String")]
+pub enum Marker {
+    Expression(MarkerExpression),
+    /// Every marker has to be true
+    And(Vec<Marker>),
+    /// Any marker has to be true
+    Or(Vec<Marker>),
+}
+
+impl Marker {
+    pub fn new(marker: &str) -> Result<Self> {
+        let tokens = tokenize(marker)?;
+        let mut parser = Parser {
+            tokens: &tokens,
+            position: 0,
+        };
+        let parsed = parser.parse_or()?;
+        if parser.position != tokens.len() {
+            anyhow::bail!("Marker {} has trailing input", marker);
+        }
+        Ok(parsed)
+    }
+
+    /// Whether the marker is true for the environment
+    /// when no extras are requested
+    pub fn evaluate(&self, environment: &MarkerEnvironment) -> bool {
+        self.evaluate_for_extra(environment, "")
+    }
+
+    /// Whether the marker is true for the environment and any of the
+    /// requested extras, the same way pip evaluates them once per extra
+    ///
+    /// ```
+    /// use pyver::markers::{Marker, MarkerEnvironment};
+    /// use pyver::Extra;
+    ///
+    /// let marker = Marker::new("extra == 'Socks'").unwrap();
+    /// let environment = MarkerEnvironment::default();
+    ///
+    /// assert!(!marker.evaluate(&environment));
+    /// assert!(marker.evaluate_with_extras(&environment, &[Extra::new("socks").unwrap()]));
+    /// ```
+    pub fn evaluate_with_extras(
+        &self,
+        environment: &MarkerEnvironment,
+        extras: &[Extra],
+    ) -> bool {
+        if extras.is_empty() {
+            return self.evaluate(environment);
+        }
+        extras
+            .iter()
+            .any(|extra| self.evaluate_for_extra(environment, extra.as_str()))
+    }
+
+    fn evaluate_for_extra(&self, environment: &MarkerEnvironment, extra: &str) -> bool {
+        match self {
+            Marker::Expression(expression) => {
+                evaluate_expression(expression, environment, extra)
+            }
+            Marker::And(markers) => markers
+                .iter()
+                .all(|marker| marker.evaluate_for_extra(environment, extra)),
+            Marker::Or(markers) => markers
+                .iter()
+                .any(|marker| marker.evaluate_for_extra(environment, extra)),
+        }
+    }
+}
+
+fn evaluate_expression(
+    expression: &MarkerExpression,
+    environment: &MarkerEnvironment,
+    extra: &str,
+) -> bool {
+    let resolve = |value: &MarkerValue| -> String {
+        match value {
+            MarkerValue::Variable(MarkerVariable::Extra) => extra.to_string(),
+            MarkerValue::Variable(variable) => {
+                environment.get(*variable).unwrap_or_default().to_string()
+            }
+            MarkerValue::String(value) => value.clone(),
+        }
+    };
+    let (mut left, mut right) = (resolve(&expression.left), resolve(&expression.right));
+
+    // Extras are compared by their normalized names
+    let is_extra =
+        |value: &MarkerValue| *value == MarkerValue::Variable(MarkerVariable::Extra);
+    if is_extra(&expression.left) || is_extra(&expression.right) {
+        left = normalize_name(&left);
+        right = normalize_name(&right);
+    }
+
+    match expression.operator {
+        MarkerOperator::In => right.contains(&left),
+        MarkerOperator::NotIn => !right.contains(&left),
+        MarkerOperator::Comparison(operator) => {
+            // Versions are compared as versions (pre-releases included),
+            // anything else as plain strings
+            if let Ok(specifier) = Specifier::new(&format!("{}{}", operator, right)) {
+                return specifier.with_prereleases(Some(true)).contains_str(&left);
+            }
+            match operator {
+                Operator::Equal => left == right,
+                Operator::NotEqual => left != right,
+                Operator::LessThan => left < right,
+                Operator::LessThanEqual => left <= right,
+                Operator::GreaterThan => left > right,
+                Operator::GreaterThanEqual => left >= right,
+                // `~=` has no meaning for strings
+                Operator::Compatible | Operator::ArbitraryEqual => false,
+            }
+        }
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+enum Token {
+    Variable(String),
+    String(String),
+    Operator(Operator),
+    And,
+    Or,
+    In,
+    Not,
+    OpenParen,
+    CloseParen,
+}
+
+fn tokenize(marker: &str) -> Result<Vec<Token>> {
+    let mut tokens = Vec::new();
+    let mut chars = marker.char_indices().peekable();
+
+    while let Some((start, c)) = chars.next() {
+        match c {
+            c if c.is_whitespace() => continue,
+            '(' => tokens.push(Token::OpenParen),
+            ')' => tokens.push(Token::CloseParen),
+            '"' | '\'' => {
+                let rest = &marker[start + 1..];
+                let end = match rest.find(c) {
+                    Some(end) => end,
+                    None => {
+                        anyhow::bail!("Marker {} has an unterminated string", marker)
+                    }
+                };
+                tokens.push(Token::String(rest[..end].to_string()));
+                // Skip to the closing quote
+                for (i, _) in chars.by_ref() {
+                    if i == start + 1 + end {
+                        break;
+                    }
+                }
+            }
+            '<' | '>' | '=' | '!' | '~' => {
+                let rest = &marker[start..];
+                // Longer operators first, `==` is a prefix of `===`
+                let operators = [
+                    Operator::ArbitraryEqual,
+                    Operator::Equal,
+                    Operator::NotEqual,
+                    Operator::LessThanEqual,
+                    Operator::GreaterThanEqual,
+                    Operator::Compatible,
+                    Operator::LessThan,
+                    Operator::GreaterThan,
+                ];
+                let operator = match operators
+                    .iter()
+                    .find(|operator| rest.starts_with(operator.as_str()))
+                {
+                    Some(operator) => *operator,
+                    None => anyhow::bail!("Marker {} has an invalid operator", marker),
+                };
+                for _ in 1..operator.as_str().len() {
+                    chars.next();
+                }
+                tokens.push(Token::Operator(operator));
+            }
+            c if c.is_ascii_alphanumeric() || c == '_' => {
+                let mut end = start + c.len_utf8();
+                while let Some((i, c)) = chars.peek() {
+                    if !(c.is_ascii_alphanumeric() || matches!(c, '_' | '.')) {
+                        break;
+                    }
+                    end = i + c.len_utf8();
+                    chars.next();
+                }
+                tokens.push(match &marker[start..end] {
+                    "and" => Token::And,
+                    "or" => Token::Or,
+                    "in" => Token::In,
+                    "not" => Token::Not,
+                    word => Token::Variable(word.to_string()),
+                });
+            }
+            _ => anyhow::bail!("Marker {} has an unexpected {}", marker, c),
+        }
+    }
+    Ok(tokens)
+}
+
+/// Recursive descent parser for the `PEP-508` marker grammar,
+/// `and` binds tighter than `or`
+struct Parser<'t> {
+    tokens: &'t [Token],
+    position: usize,
+}
+
+impl<'t> Parser<'t> {
+    fn next(&mut self) -> Option<&'t Token> {
+        let token = self.tokens.get(self.position);
+        self.position += 1;
+        token
+    }
+
+    fn peek(&self) -> Option<&'t Token> {
+        self.tokens.get(self.position)
+    }
+
+    fn parse_or(&mut self) -> Result<Marker> {
+        let mut markers = vec![self.parse_and()?];
+        while self.peek() == Some(&Token::Or) {
+            self.position += 1;
+            markers.push(self.parse_and()?);
+        }
+        Ok(match markers.len() {
+            1 => markers.remove(0),
+            _ => Marker::Or(markers),
+        })
+    }
+
+    fn parse_and(&mut self) -> Result<Marker> {
+        let mut markers = vec![self.parse_atom()?];
+        while self.peek() == Some(&Token::And) {
+            self.position += 1;
+            markers.push(self.parse_atom()?);
+        }
+        Ok(match markers.len() {
+            1 => markers.remove(0),
+            _ => Marker::And(markers),
+        })
+    }
+
+    fn parse_atom(&mut self) -> Result<Marker> {
+        if self.peek() == Some(&Token::OpenParen) {
+            self.position += 1;
+            let marker = self.parse_or()?;
+            if self.next() != Some(&Token::CloseParen) {
+                anyhow::bail!("Marker has unclosed parentheses");
+            }
+            return Ok(marker);
+        }
+
+        let left = self.parse_value()?;
+        let operator = match self.next() {
+            Some(Token::Operator(operator)) => MarkerOperator::Comparison(*operator),
+            Some(Token::In) => MarkerOperator::In,
+            Some(Token::Not) if self.next() == Some(&Token::In) => {
+                MarkerOperator::NotIn
+            }
+            _ => anyhow::bail!("Marker is missing an operator after {}", left),
+        };
+        let right = self.parse_value()?;
+
+        Ok(Marker::Expression(MarkerExpression {
+            left,
+            operator,
+            right,
+        }))
+    }
+
+    fn parse_value(&mut self) -> Result<MarkerValue> {
+        match self.next() {
+            Some(Token::Variable(variable)) => {
+                Ok(MarkerValue::Variable(variable.parse()?))
+            }
+            Some(Token::String(value)) => Ok(MarkerValue::String(value.clone())),
+            _ => anyhow::bail!("Marker is missing a variable or string"),
+        }
+    }
+}
+
+impl FromStr for Marker {
+    type Err = anyhow::Error;
+
+    fn from_str(marker: &str) -> Result<Self> {
+        Self::new(marker)
+    }
+}
+
+impl TryFrom<String> for Marker {
+    type Error = anyhow::Error;
+
+    fn try_from(marker: String) -> Result<Self> {
+        Self::new(&marker)
+    }
+}
+
+impl From<Marker> for String {
+    fn from(marker: Marker) -> Self {
+        marker.to_string()
+    }
+}
+
+impl fmt::Display for Marker {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let (markers, separator) = match self {
+            Marker::Expression(expression) => return write!(f, "{}", expression),
+            Marker::And(markers) => (markers, " and "),
+            Marker::Or(markers) => (markers, " or "),
+        };
+
+        for (i, marker) in markers.iter().enumerate() {
+            if i > 0 {
+                write!(f, "{}", separator)?;
+            }
+            // Only `and` inside `or` can go without parentheses
+            match (self, marker) {
+                (_, Marker::Expression(_)) | (Marker::Or(_), Marker::And(_)) => {
+                    write!(f, "{}", marker)?
+                }
+                _ => write!(f, "({})", marker)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Marker, MarkerOperator, MarkerValue, MarkerVariable};
+    use crate::markers::MarkerEnvironment;
+    use crate::specifiers::Operator;
+    use crate::Extra;
+    use anyhow::Result;
+
+    fn environment() -> MarkerEnvironment {
+        MarkerEnvironment {
+            implementation_name: "cpython".to_string(),
+            implementation_version: "3.10.12".to_string(),
+            os_name: "posix".to_string(),
+            platform_machine: "x86_64".to_string(),
+            platform_python_implementation: "CPython".to_string(),
+            platform_release: "6.1.0-13-amd64".to_string(),
+            platform_system: "Linux".to_string(),
+            platform_version: "#1 SMP PREEMPT_DYNAMIC Debian 6.1.55-1".to_string(),
+            python_full_version: "3.10.12".to_string(),
+            python_version: "3.10".to_string(),
+            sys_platform: "linux".to_string(),
+        }
+    }
+
+    fn evaluate(marker: &str) -> bool {
+        Marker::new(marker).unwrap().evaluate(&environment())
+    }
+
+    #[test]
+    fn test_marker_parsing() -> Result<()> {
+        let marker = Marker::new("python_version>='3.8'")?;
+        match marker {
+            Marker::Expression(expression) => {
+                assert_eq!(
+                    expression.left,
+                    MarkerValue::Variable(MarkerVariable::PythonVersion)
+                );
+                assert_eq!(
+                    expression.operator,
+                    MarkerOperator::Comparison(Operator::GreaterThanEqual)
+                );
+                assert_eq!(expression.right, MarkerValue::String("3.8".to_string()));
+            }
+            _ => panic!("Oh no {}", marker),
+        }
+
+        let marker = Marker::new("os_name == 'a' or os_name == 'b' and extra == 'c'")?;
+        assert!(matches!(&marker, Marker::Or(markers) if markers.len() == 2));
+
+        let marker = Marker::new("'linux' not in sys.platform")?;
+        assert_eq!(marker.to_string(), r#""linux" not in sys_platform"#);
+        Ok(())
+    }
+
+    #[test]
+    fn test_marker_round_trip() -> Result<()> {
+        let markers = vec![
+            (r#"python_version < "3.11""#, r#"python_version < "3.11""#),
+            (
+                "os_name=='nt'and(extra=='a' or extra=='b')",
+                r#"os_name == "nt" and (extra == "a" or extra == "b")"#,
+            ),
+            ("((os_name=='nt'))", r#"os_name == "nt""#),
+            (
+                "os_name=='nt' and os_name=='posix' or sys_platform=='linux'",
+                r#"os_name == "nt" and os_name == "posix" or sys_platform == "linux""#,
+            ),
+            (
+                "os_name=='a' and (os_name=='b' and os_name=='c')",
+                r#"os_name == "a" and (os_name == "b" and os_name == "c")"#,
+            ),
+            (
+                r#"platform_version == 'with "quotes"'"#,
+                r#"platform_version == 'with "quotes"'"#,
+            ),
+        ];
+
+        for (marker, canonical) in markers {
+            let parsed = Marker::new(marker)?;
+            assert_eq!(parsed.to_string(), canonical);
+            assert_eq!(Marker::new(canonical)?, parsed);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_marker_negative() {
+        let markers = vec![
+            "",
+            "python_version",
+            "python_version <",
+            "python_version < '3.8",
+            "unknown_var == 'a'",
+            "python_version =< '3.8'",
+            "(python_version < '3.8'",
+            "python_version < '3.8')",
+            "python_version < '3.8' and",
+            "os_name not 'a'",
+        ];
+
+        for marker in markers {
+            if let Ok(v) = Marker::new(marker) {
+                panic!("Oh no {}", v);
+            }
+        }
+    }
+
+    #[test]
+    fn test_marker_evaluation() {
+        assert!(evaluate("python_version >= '3.8'"));
+        // Versions compare as versions, not strings
+        assert!(evaluate("python_version > '3.9'"));
+        assert!(!evaluate("python_version < '3.9'"));
+        assert!(evaluate("python_full_version == '3.10.*'"));
+        assert!(evaluate("python_version ~= '3.1'"));
+        assert!(evaluate("'3.9' < python_version"));
+
+        assert!(evaluate("sys_platform == 'linux'"));
+        assert!(evaluate("sys_platform != 'win32'"));
+        assert!(evaluate("'linux' in sys_platform"));
+        assert!(evaluate("'win' not in sys_platform"));
+        assert!(evaluate("platform_machine >= 'x86'"));
+        assert!(!evaluate("os_name ~= 'posix'"));
+        // `platform_release` isn't a valid version, so never matches a version
+        assert!(!evaluate("platform_release >= '5.0'"));
+
+        assert!(evaluate("os_name == 'nt' or python_version >= '3.8'"));
+        assert!(!evaluate("os_name == 'nt' and python_version >= '3.8'"));
+        assert!(evaluate(
+            "(os_name == 'nt' or os_name == 'posix') and sys_platform == 'linux'"
+        ));
+    }
+
+    #[test]
+    fn test_marker_extras() -> Result<()> {
+        let marker = Marker::new("extra == 'Test_Utils' and os_name == 'posix'")?;
+        let environment = environment();
+
+        assert!(!marker.evaluate(&environment));
+        assert!(marker.evaluate_with_extras(&environment, &[Extra::new("test-utils")?]));
+        assert!(marker.evaluate_with_extras(
+            &environment,
+            &[Extra::new("docs")?, Extra::new("test.utils")?]
+        ));
+        assert!(!marker.evaluate_with_extras(&environment, &[Extra::new("docs")?]));
+
+        assert!(Marker::new("extra != 'docs'")?.evaluate(&environment));
+        Ok(())
+    }
+}
@@ -0,0 +1,16 @@
+//! # Environment Markers
+//! Handling of `PEP-508` environment markers such as
+//! `python_version < "3.11" and sys_platform == "linux"`
+//!
+//! Read more at <https://peps.python.org/pep-0508/#environment-markers>
+//!
+//! Importing Example
+//! ```
+//! use pyver::markers::{Marker, MarkerEnvironment};
+//! ```
+
+mod environment;
+mod marker;
+
+pub use environment::*;
+pub use marker::*;
@@ -0,0 +1,182 @@
+use crate::specifiers::{parse_requires_python, SpecifierSet};
+use crate::{Extra, PackageName, PackageVersion, Requirement};
+use anyhow::{Context, Result};
+use std::str::FromStr;
+
+/// # Core metadata
+/// The version related fields of a `METADATA` (wheel) or `PKG-INFO` (sdist) file
+///
+/// The file consists of email style `Key: value` headers, where lines
+/// starting with whitespace continue the previous header and the
+/// first empty line starts the description, which is ignored.
+///
+/// Read more at <https://packaging.python.org/en/latest/specifications/core-metadata/>
+///
+/// ## Example Usage
+/// ```
+/// use pyver::metadata::CoreMetadata;
+///
+/// let metadata = CoreMetadata::new(
+///     "Metadata-Version: 2.1\n\
+///      Name: requests\n\
+///      Version: 2.31.0\n\
+///      Requires-Python: >=3.7\n\
+///      Requires-Dist: charset-normalizer (<4,>=2)\n\
+///      Requires-Dist: PySocks (!=1.5.7,>=1.5.6) ; extra == 'socks'\n\
+///      Provides-Extra: socks\n\
+///      \n\
+///      Requests: HTTP for Humans\n",
+/// )
+/// .unwrap();
+///
+/// assert_eq!(metadata.name, "requests");
+/// assert_eq!(metadata.version.to_string(), "2.31.0");
+/// assert_eq!(metadata.requires_dist.len(), 2);
+/// assert_eq!(metadata.provides_extra[0], "socks");
+/// ```
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CoreMetadata {
+    /// `Metadata-Version`, `None` if it's missing
+    pub metadata_version: Option<String>,
+    pub name: PackageName,
+    pub version: PackageVersion,
+    /// `Requires-Python`, read leniently since old uploads
+    /// often contain values like `>=3.6.*`
+    pub requires_python: Option<SpecifierSet>,
+    pub requires_dist: Vec<Requirement>,
+    pub provides_extra: Vec<Extra>,
+}
+
+impl CoreMetadata {
+    pub fn new(metadata: &str) -> Result<Self> {
+        let mut metadata_version = None;
+        let mut name = None;
+        let mut version = None;
+        let mut requires_python = None;
+        let mut requires_dist = Vec::new();
+        let mut provides_extra = Vec::new();
+
+        for (line, key, value) in headers(metadata)? {
+            let context = || format!("Line {}: {}", line, key);
+            match key.to_ascii_lowercase().as_str() {
+                "metadata-version" => metadata_version = Some(value),
+                "name" => name = Some(PackageName::new(&value).with_context(context)?),
+                "version" => {
+                    version = Some(PackageVersion::new(&value).with_context(context)?)
+                }
+                "requires-python" => {
+                    requires_python =
+                        Some(parse_requires_python(&value).with_context(context)?)
+                }
+                "requires-dist" => {
+                    requires_dist.push(Requirement::new(&value).with_context(context)?)
+                }
+                "provides-extra" => {
+                    provides_extra.push(Extra::new(&value).with_context(context)?)
+                }
+                _ => {}
+            }
+        }
+
+        Ok(Self {
+            metadata_version,
+            name: name.context("Metadata has no Name")?,
+            version: version.context("Metadata has no Version")?,
+            requires_python,
+            requires_dist,
+            provides_extra,
+        })
+    }
+}
+
+/// The headers with the line they start on,
+/// continuation lines are joined with a single space
+fn headers(metadata: &str) -> Result<Vec<(usize, String, String)>> {
+    let mut headers: Vec<(usize, String, String)> = Vec::new();
+
+    for (index, line) in metadata.lines().enumerate() {
+        if line.trim().is_empty() {
+            break;
+        }
+
+        if line.starts_with([' ', '\t']) {
+            match headers.last_mut() {
+                Some((_, _, value)) => {
+                    value.push(' ');
+                    value.push_str(line.trim());
+                }
+                None => anyhow::bail!("Line {} continues no header", index + 1),
+            }
+            continue;
+        }
+
+        match line.split_once(':') {
+            Some((key, value)) => headers.push((
+                index + 1,
+                key.trim().to_string(),
+                value.trim().to_string(),
+            )),
+            None => anyhow::bail!("Line {} is not a header", index + 1),
+        }
+    }
+    Ok(headers)
+}
+
+impl FromStr for CoreMetadata {
+    type Err = anyhow::Error;
+
+    fn from_str(metadata: &str) -> Result<Self> {
+        Self::new(metadata)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::CoreMetadata;
+    use crate::PackageVersion;
+    use anyhow::Result;
+
+    #[test]
+    fn test_metadata_parsing() -> Result<()> {
+        let metadata = CoreMetadata::new(
+            "Metadata-Version: 1.0\r\n\
+             name: Old.Project\r\n\
+             VERSION: 1.0-r1\r\n\
+             Requires-Python: >=2.7,\r\n\
+             \x20 !=3.0.*\r\n\
+             Classifier: Programming Language :: Python\r\n\
+             \r\n\
+             Requires-Dist: not a header anymore\r\n",
+        )?;
+        assert_eq!(metadata.metadata_version.as_deref(), Some("1.0"));
+        assert_eq!(metadata.name, "old-project");
+        assert_eq!(metadata.version, PackageVersion::new("1.0.post1")?);
+        assert_eq!(
+            metadata.requires_python.map(|v| v.to_string()).as_deref(),
+            Some(">=2.7,!=3.0.*")
+        );
+        assert!(metadata.requires_dist.is_empty());
+        assert!(metadata.provides_extra.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn test_metadata_negative() {
+        let files = vec![
+            "",
+            "Name: spam",
+            "Version: 1.0",
+            " Name: spam\nVersion: 1.0",
+            "Name: spam\nVersion: 1.0\nNot a header",
+            "Name: spam\nVersion: one",
+            "Name: spam\nVersion: 1.0\nRequires-Dist: eggs >=1 <2",
+            "Name: spam\nVersion: 1.0\nRequires-Python: >=3.x",
+        ];
+
+        for metadata in files {
+            if let Ok(v) = CoreMetadata::new(metadata) {
+                panic!("Oh no {:?}", v);
+            }
+        }
+    }
+}
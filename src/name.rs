@@ -0,0 +1,205 @@
+use alloc::string::String;
+use anyhow::Result;
+use core::fmt;
+use core::str::FromStr;
+use serde::{Deserialize, Serialize};
+
+/// Defines a normalized name type along with its constructor,
+/// conversions, comparisons against `&str` and serde support
+macro_rules! normalized_name {
+    ($(#[$meta:meta])* $name:ident, $kind:literal) => {
+        $(#[$meta])*
+        #[derive(
+            Hash, Ord, Clone, Debug, Serialize, Deserialize, Eq, PartialEq, PartialOrd,
+        )]
+        #[serde(try_from = "String", into = "String")]
+        pub struct $name(String);
+
+        impl $name {
+            #[doc = concat!("Validates ", $kind, " and normalizes it")]
+            pub fn new(name: &str) -> Result<Self> {
+                if !is_valid_name(name) {
+                    anyhow::bail!(concat!("Invalid ", $kind, " {}"), name);
+                }
+
+                Ok(Self(normalize_name(name)))
+            }
+
+            /// The normalized name
+            pub fn as_str(&self) -> &str {
+                &self.0
+            }
+        }
+
+        impl FromStr for $name {
+            type Err = anyhow::Error;
+
+            fn from_str(name: &str) -> Result<Self> {
+                Self::new(name)
+            }
+        }
+
+        impl TryFrom<String> for $name {
+            type Error = anyhow::Error;
+
+            fn try_from(name: String) -> Result<Self> {
+                Self::new(&name)
+            }
+        }
+
+        impl From<$name> for String {
+            fn from(name: $name) -> Self {
+                name.0
+            }
+        }
+
+        impl AsRef<str> for $name {
+            fn as_ref(&self) -> &str {
+                &self.0
+            }
+        }
+
+        impl fmt::Display for $name {
+            fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                write!(f, "{}", self.0)
+            }
+        }
+
+        /// Compares against an already normalized name
+        impl PartialEq<str> for $name {
+            fn eq(&self, other: &str) -> bool {
+                self.0 == other
+            }
+        }
+
+        impl PartialEq<&str> for $name {
+            fn eq(&self, other: &&str) -> bool {
+                self.0 == *other
+            }
+        }
+    };
+}
+
+normalized_name!(
+    /// `PEP-503` Normalized project name
+    ///
+    /// Runs of `-`, `_` and `.` are collapsed into a single `-` and
+    /// the name is lowercased, so `Foo._-Bar` and `foo-bar` are the same project.
+    /// Lookups against indexes should always go through this type.
+    ///
+    /// Read more at <https://peps.python.org/pep-0503/#normalized-names>
+    ///
+    /// # Example Usage
+    /// ```
+    /// use pyver::PackageName;
+    ///
+    /// let name = PackageName::new("Foo._-Bar").unwrap();
+    ///
+    /// assert_eq!(name, "foo-bar");
+    /// assert_eq!(name, PackageName::new("FOO_BAR").unwrap());
+    /// assert!(PackageName::new("-foo").is_err());
+    /// ```
+    PackageName,
+    "a `PEP-508` project name"
+);
+
+normalized_name!(
+    /// `PEP-685` Normalized extra name
+    ///
+    /// Extras follow the same normalization as project names
+    /// (lowercased, runs of `-`, `_` and `.` collapsed to `-`),
+    /// so `Security_Extras` requested by one tool matches `security-extras`
+    /// declared in another tool's metadata.
+    ///
+    /// Read more at <https://peps.python.org/pep-0685/>
+    ///
+    /// # Example Usage
+    /// ```
+    /// use pyver::Extra;
+    ///
+    /// assert_eq!(
+    ///     Extra::new("Security_Extras").unwrap(),
+    ///     Extra::new("security.extras").unwrap()
+    /// );
+    /// ```
+    Extra,
+    "an extra name"
+);
+
+/// Names have to start and end with a letter or digit
+/// and may contain `-`, `_` and `.` in between
+fn is_valid_name(name: &str) -> bool {
+    name.starts_with(|c: char| c.is_ascii_alphanumeric())
+        && name.ends_with(|c: char| c.is_ascii_alphanumeric())
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+}
+
+/// Normalizes a name as described in `PEP-503`
+pub(crate) fn normalize_name(name: &str) -> String {
+    let mut normalized = String::with_capacity(name.len());
+    let mut last_was_separator = false;
+
+    for c in name.chars() {
+        if matches!(c, '-' | '_' | '.') {
+            if !last_was_separator {
+                normalized.push('-');
+            }
+            last_was_separator = true;
+        } else {
+            normalized.push(c.to_ascii_lowercase());
+            last_was_separator = false;
+        }
+    }
+    normalized
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Extra, PackageName};
+    use anyhow::Result;
+    use std::collections::HashSet;
+
+    #[test]
+    fn test_name_normalization() -> Result<()> {
+        assert_eq!(PackageName::new("Foo._-Bar")?.as_str(), "foo-bar");
+        assert_eq!(
+            PackageName::new("zope.interface")?.as_str(),
+            "zope-interface"
+        );
+        assert_eq!(PackageName::new("A")?.as_str(), "a");
+        assert_eq!(PackageName::new("Django")?.to_string(), "django");
+
+        let names: HashSet<PackageName> =
+            ["requests", "Requests", "REQUESTS", "re_quests"]
+                .iter()
+                .map(|v| PackageName::new(v))
+                .collect::<Result<_>>()?;
+        assert_eq!(names.len(), 2);
+        Ok(())
+    }
+
+    #[test]
+    fn test_name_negative() {
+        let names = vec!["", "-foo", "foo_", "foo bar", "föö", "foo!", "foo,bar"];
+
+        for name in names {
+            if let Ok(v) = PackageName::new(name) {
+                panic!("Oh no {}", v);
+            }
+            if let Ok(v) = Extra::new(name) {
+                panic!("Oh no {}", v);
+            }
+        }
+    }
+
+    #[test]
+    fn test_extra_normalization() -> Result<()> {
+        assert_eq!(Extra::new("Security")?, "security");
+        assert_eq!(Extra::new("foo__.-bar")?.as_str(), "foo-bar");
+        assert_eq!(Extra::new("Foo.Bar")?, Extra::new("foo_bar")?);
+        assert_ne!(Extra::new("foobar")?, Extra::new("foo-bar")?);
+        Ok(())
+    }
+}
@@ -0,0 +1,385 @@
+use crate::ids::{DevHead, PostHead, PostHeader, PreHeader};
+#[cfg(any(test, feature = "regex-parser"))]
+use crate::validator::Captures;
+use anyhow::Result;
+use core::num::ParseIntError;
+
+/// The components of a version string as written, before their numbers
+/// are parsed (`pre` is `("RC", Some("1"))` for `1.0-RC1`)
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub(crate) struct VersionParts<'a> {
+    pub epoch: Option<&'a str>,
+    pub release: &'a str,
+    /// The label and number of the pre-release
+    pub pre: Option<(&'a str, Option<&'a str>)>,
+    /// The label and number of the post-release, `1.0-1` has no label
+    pub post: Option<(Option<&'a str>, Option<&'a str>)>,
+    /// The number of the developmental release
+    pub dev: Option<Option<&'a str>>,
+    pub local: Option<&'a str>,
+}
+
+impl<'a> VersionParts<'a> {
+    /// Splits a version string with a hand-written parser accepting
+    /// exactly what the `PEP-440` regex of [`crate::validate_440_version`] does
+    ///
+    /// The regex is written top to bottom without ever having to go back
+    /// further than the start of a component, so each optional component
+    /// is tried once and skipped if it doesn't match. Like the regex it
+    /// ignores surrounding whitespace and the case of ASCII letters. The
+    /// `regex` crate also folds `ſ` (U+017F) to `s`, so it accepts
+    /// `1.0.poſt1` and `1.0+ſ` which are rejected here (and by `regex-lite`).
+    pub fn parse(version: &'a str) -> Result<Self> {
+        match Cursor::new(version.trim()).version() {
+            Some(parts) => Ok(parts),
+            None => anyhow::bail!("Failed to decode version {}", version),
+        }
+    }
+
+    /// Splits a version string with the regex if the `regex-parser`
+    /// feature is enabled, otherwise with [`VersionParts::parse`]
+    pub fn new(version: &'a str) -> Result<Self> {
+        #[cfg(not(feature = "regex-parser"))]
+        return Self::parse(version);
+        #[cfg(feature = "regex-parser")]
+        return Self::from_captures(&crate::validate_440_version(version)?);
+    }
+
+    pub fn epoch_number(&self) -> Result<Option<u32>> {
+        number(self.epoch)
+    }
+
+    /// The release segments, failing for the ones that don't fit into a `u64`
+    pub fn release_segments(
+        &self,
+    ) -> impl Iterator<Item = Result<u64, ParseIntError>> + 'a {
+        self.release
+            .split('.')
+            .map(|segment| segment.parse::<u64>())
+    }
+
+    /// Checks that every release segment fits into a `u64`
+    /// without collecting them
+    pub fn check_release(&self) -> Result<()> {
+        for segment in self.release_segments() {
+            segment?;
+        }
+        Ok(())
+    }
+
+    /// Checks that every number fits into the type of its component, like
+    /// building a version from the parts would
+    pub fn check_numbers(&self) -> Result<()> {
+        self.check_release()?;
+        self.epoch_number()?;
+        self.pre_header()?;
+        self.post_header()?;
+        self.dev_head()?;
+        Ok(())
+    }
+
+    pub fn pre_header(&self) -> Result<Option<PreHeader>> {
+        Ok(match self.pre {
+            Some((pre_l, pre_n)) => Some(PreHeader::new(pre_l, number(pre_n)?)?),
+            None => None,
+        })
+    }
+
+    pub fn post_header(&self) -> Result<Option<PostHeader>> {
+        Ok(match self.post {
+            Some((post_l, post_n)) => {
+                let post_head: Option<PostHead> = match post_l {
+                    Some(v) => Some(PostHead::new(v)?),
+                    None => None,
+                };
+                Some(PostHeader {
+                    post_head,
+                    post_num: number(post_n)?,
+                })
+            }
+            None => None,
+        })
+    }
+
+    pub fn dev_head(&self) -> Result<Option<DevHead>> {
+        Ok(match self.dev {
+            Some(dev_n) => Some(DevHead {
+                dev_num: number(dev_n)?,
+            }),
+            None => None,
+        })
+    }
+
+    /// The components captured by the `PEP-440` regex
+    #[cfg(any(test, feature = "regex-parser"))]
+    pub fn from_captures(captures: &Captures<'a>) -> Result<Self> {
+        let group = |name: &str| captures.name(name).map(|group| group.as_str());
+        let release = match group("release") {
+            Some(release) => release,
+            // There always has to be at least a major version
+            None => anyhow::bail!("Failed to decode version {}", &captures[0]),
+        };
+        Ok(Self {
+            epoch: group("epoch"),
+            release,
+            pre: group("pre_l").map(|label| (label, group("pre_n"))),
+            post: group("post")
+                .map(|_| (group("post_l"), group("post_n1").or(group("post_n2")))),
+            dev: group("dev").map(|_| group("dev_n")),
+            local: group("local"),
+        })
+    }
+}
+
+/// The number of an epoch, pre, post or dev segment, which are `u32`
+/// unlike the `u64` release segments
+fn number(n: Option<&str>) -> Result<Option<u32>> {
+    Ok(match n {
+        Some(n) => Some(n.parse::<u32>()?),
+        None => None,
+    })
+}
+
+/// Position in the version string while parsing it
+struct Cursor<'a> {
+    input: &'a str,
+    position: usize,
+}
+
+impl<'a> Cursor<'a> {
+    fn new(input: &'a str) -> Self {
+        Self { input, position: 0 }
+    }
+
+    /// `v? (epoch !)? release pre? post? dev? (+ local)?` and nothing else
+    fn version(mut self) -> Option<VersionParts<'a>> {
+        self.byte(b'v');
+        let epoch = self.attempt(|cursor| {
+            let epoch = cursor.digits()?;
+            cursor.byte(b'!').then_some(epoch)
+        });
+        let release = self.release()?;
+        let pre = self.attempt(|cursor| {
+            cursor.separator();
+            let label = cursor
+                .keyword(&["preview", "alpha", "beta", "pre", "rc", "a", "b", "c"])?;
+            cursor.separator();
+            Some((label, cursor.digits()))
+        });
+        let post = self
+            .attempt(|cursor| {
+                cursor.byte(b'-').then_some(())?;
+                Some((None, Some(cursor.digits()?)))
+            })
+            .or_else(|| {
+                self.attempt(|cursor| {
+                    cursor.separator();
+                    let label = cursor.keyword(&["post", "rev", "r"])?;
+                    cursor.separator();
+                    Some((Some(label), cursor.digits()))
+                })
+            });
+        let dev = self.attempt(|cursor| {
+            cursor.separator();
+            cursor.keyword(&["dev"])?;
+            cursor.separator();
+            Some(cursor.digits())
+        });
+        let local = self.attempt(|cursor| {
+            cursor.byte(b'+').then_some(())?;
+            cursor.local()
+        });
+
+        (self.position == self.input.len()).then_some(VersionParts {
+            epoch,
+            release,
+            pre,
+            post,
+            dev,
+            local,
+        })
+    }
+
+    /// Runs `parse`, going back to where it started if it fails
+    fn attempt<T>(&mut self, parse: impl FnOnce(&mut Self) -> Option<T>) -> Option<T> {
+        let start = self.position;
+        let parsed = parse(self);
+        if parsed.is_none() {
+            self.position = start;
+        }
+        parsed
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.input.as_bytes().get(self.position).copied()
+    }
+
+    /// Skips the byte (ignoring the case of letters) if it's next
+    fn byte(&mut self, byte: u8) -> bool {
+        match self.peek() {
+            Some(next) if next.eq_ignore_ascii_case(&byte) => {
+                self.position += 1;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Skips an optional `-`, `_` or `.`
+    fn separator(&mut self) {
+        if matches!(self.peek(), Some(b'-' | b'_' | b'.')) {
+            self.position += 1;
+        }
+    }
+
+    /// The first of the keywords coming next, ignoring case
+    fn keyword(&mut self, keywords: &[&str]) -> Option<&'a str> {
+        let rest = &self.input.as_bytes()[self.position..];
+        let keyword = keywords.iter().find(|keyword| {
+            rest.len() >= keyword.len()
+                && rest[..keyword.len()].eq_ignore_ascii_case(keyword.as_bytes())
+        })?;
+        Some(self.take(keyword.len()))
+    }
+
+    /// `[0-9]+`
+    fn digits(&mut self) -> Option<&'a str> {
+        self.span(|byte| byte.is_ascii_digit())
+    }
+
+    /// `[0-9]+ (. [0-9]+)*`
+    fn release(&mut self) -> Option<&'a str> {
+        let start = self.position;
+        self.digits()?;
+        while self
+            .attempt(|cursor| {
+                cursor.byte(b'.').then_some(())?;
+                cursor.digits()
+            })
+            .is_some()
+        {}
+        Some(&self.input[start..self.position])
+    }
+
+    /// `[a-z0-9]+ ([-_.] [a-z0-9]+)*`
+    fn local(&mut self) -> Option<&'a str> {
+        let start = self.position;
+        self.span(|byte| byte.is_ascii_alphanumeric())?;
+        while self
+            .attempt(|cursor| {
+                matches!(cursor.peek(), Some(b'-' | b'_' | b'.')).then_some(())?;
+                cursor.position += 1;
+                cursor.span(|byte| byte.is_ascii_alphanumeric())
+            })
+            .is_some()
+        {}
+        Some(&self.input[start..self.position])
+    }
+
+    /// The longest non-empty run of bytes matching `accept`
+    fn span(&mut self, accept: impl Fn(u8) -> bool) -> Option<&'a str> {
+        let length = self.input.as_bytes()[self.position..]
+            .iter()
+            .take_while(|byte| accept(**byte))
+            .count();
+        (length > 0).then(|| self.take(length))
+    }
+
+    /// The next `length` bytes, which are all ASCII
+    fn take(&mut self, length: usize) -> &'a str {
+        let taken = &self.input[self.position..self.position + length];
+        self.position += length;
+        taken
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::VersionParts;
+    use crate::validate_440_version;
+
+    /// Both parsers have to agree on every combination of these components
+    #[test]
+    fn test_parser_matches_regex() {
+        let prefixes = vec!["", "v", "V", "1!", "v01!", "!", "1!!"];
+        let releases = vec!["1", "1.0", "01.2.30", "1.", ".1", "1..2", "a"];
+        let pres = vec![
+            "", "a", "-alpha.1", "RC", "_pre_2", "c-", "preview", "b.", "pre-view",
+        ];
+        let posts = vec!["", "-1", ".post", "post2", "-rev-3", "r", "-", "_R.", "--1"];
+        let devs = vec!["", ".dev", "dev1", "-DEV_2", ".", "dev-", "de"];
+        let locals = vec![
+            "", "+abc", "+a.b-C_1", "+", "+a.", "+a..b", "+\u{e9}", "+1+2",
+        ];
+
+        let mut count = 0;
+        for prefix in &prefixes {
+            for release in &releases {
+                for pre in &pres {
+                    for post in &posts {
+                        for dev in &devs {
+                            for local in &locals {
+                                let version = format!(
+                                    "{}{}{}{}{}{}",
+                                    prefix, release, pre, post, dev, local
+                                );
+                                let expected = validate_440_version(&version).and_then(
+                                    |captures| VersionParts::from_captures(&captures),
+                                );
+                                let parsed = VersionParts::parse(&version);
+                                match (expected, parsed) {
+                                    (Ok(expected), Ok(parsed)) => {
+                                        assert_eq!(parsed, expected, "{}", version);
+                                        count += 1;
+                                    }
+                                    (Err(_), Err(_)) => {}
+                                    (expected, parsed) => {
+                                        panic!(
+                                            "{}: {:?} {:?}",
+                                            version, expected, parsed
+                                        )
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+        assert!(count > 10000);
+
+        let others = vec![
+            " 1.0 ",
+            "\t1.0rc1\n",
+            "1.0 rc1",
+            "",
+            " ",
+            "1.0\u{2003}",
+            "1.0-rc.1-2.dev-3+x",
+        ];
+        for version in others {
+            assert_eq!(
+                VersionParts::parse(version).ok(),
+                validate_440_version(version)
+                    .ok()
+                    .map(|captures| VersionParts::from_captures(&captures).unwrap()),
+                "{:?}",
+                version
+            );
+        }
+
+        // Unicode case folding of the regex, see `VersionParts::parse`
+        let folded = vec!["1.0.poſt1", "1.0rc1-POſT2", "1.0+ſ", "1.0+local.ſ"];
+        for version in folded {
+            assert_eq!(
+                validate_440_version(version).is_ok(),
+                !cfg!(feature = "regex-lite"),
+                "{}",
+                version
+            );
+            if let Ok(v) = VersionParts::parse(version) {
+                panic!("Oh no {:?}", v);
+            }
+        }
+    }
+}
@@ -0,0 +1,268 @@
+//! # Version policies
+//! Rules for the versions a project releases, checked by CI before tagging
+//!
+//! ## Example Usage
+//! ```
+//! use pyver::policy::{PolicyViolation, VersionPolicy};
+//! use pyver::PackageVersion;
+//!
+//! let policy = VersionPolicy {
+//!     forbid_epoch: true,
+//!     max_release_segments: Some(3),
+//!     ..Default::default()
+//! };
+//!
+//! let version = PackageVersion::new("1!1.0.0.1").unwrap();
+//! assert_eq!(
+//!     policy.check(&version),
+//!     vec![
+//!         PolicyViolation::Epoch,
+//!         PolicyViolation::TooManySegments { max: 3, found: 4 },
+//!     ]
+//! );
+//! ```
+
+use crate::PackageVersion;
+use std::fmt;
+
+/// Rules a release version has to follow, everything is allowed by default
+#[derive(Clone, Debug, Default, Eq, PartialEq, Hash)]
+pub struct VersionPolicy {
+    /// Forbid versions with an epoch other than `0`
+    pub forbid_epoch: bool,
+    /// Forbid pre-releases (`1.0rc1`)
+    pub forbid_pre: bool,
+    /// Forbid post-releases (`1.0.post1`)
+    pub forbid_post: bool,
+    /// Forbid developmental releases (`1.0.dev1`)
+    pub forbid_dev: bool,
+    /// Forbid local version labels (`1.0+ubuntu1`)
+    pub forbid_local: bool,
+    /// Require versions to be written in normal form (`1.0rc1`, not `v1.0-RC1`)
+    pub require_canonical: bool,
+    /// Require a release candidate of a final release to be released first,
+    /// only checked by [`VersionPolicy::check_release`]
+    pub require_rc: bool,
+    /// The fewest release segments allowed (`2` rejects `1`)
+    pub min_release_segments: Option<usize>,
+    /// The most release segments allowed (`3` rejects `1.0.0.1`)
+    pub max_release_segments: Option<usize>,
+}
+
+/// A rule of a [`VersionPolicy`] broken by a version
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub enum PolicyViolation {
+    /// The version has an epoch
+    Epoch,
+    /// The version is a pre-release
+    PreRelease,
+    /// The version is a post-release
+    PostRelease,
+    /// The version is a developmental release
+    DevRelease,
+    /// The version has a local version label
+    LocalVersion,
+    /// The version isn't written in normal form
+    NotCanonical,
+    /// No release candidate of the final release was released
+    MissingReleaseCandidate,
+    /// The release has fewer segments than allowed
+    TooFewSegments { min: usize, found: usize },
+    /// The release has more segments than allowed
+    TooManySegments { max: usize, found: usize },
+}
+
+impl VersionPolicy {
+    /// The rules the version breaks, in the order of the policy's fields
+    pub fn check(&self, version: &PackageVersion) -> Vec<PolicyViolation> {
+        let found = version.release().len();
+        let rules = [
+            (
+                self.forbid_epoch && version.epoch() != 0,
+                PolicyViolation::Epoch,
+            ),
+            (
+                self.forbid_pre && version.pre().is_some(),
+                PolicyViolation::PreRelease,
+            ),
+            (
+                self.forbid_post && version.is_postrelease(),
+                PolicyViolation::PostRelease,
+            ),
+            (
+                self.forbid_dev && version.is_devrelease(),
+                PolicyViolation::DevRelease,
+            ),
+            (
+                self.forbid_local && version.is_local(),
+                PolicyViolation::LocalVersion,
+            ),
+            (
+                self.require_canonical && !version.is_canonical(),
+                PolicyViolation::NotCanonical,
+            ),
+        ];
+        let mut violations: Vec<PolicyViolation> = rules
+            .into_iter()
+            .filter(|(broken, _)| *broken)
+            .map(|(_, violation)| violation)
+            .collect();
+
+        if let Some(min) = self.min_release_segments.filter(|min| found < *min) {
+            violations.push(PolicyViolation::TooFewSegments { min, found });
+        }
+        if let Some(max) = self.max_release_segments.filter(|max| found > *max) {
+            violations.push(PolicyViolation::TooManySegments { max, found });
+        }
+        violations
+    }
+
+    /// The rules a new release breaks given the versions released before,
+    /// which adds the `require_rc` rule to [`VersionPolicy::check`]
+    ///
+    /// ```
+    /// use pyver::policy::{PolicyViolation, VersionPolicy};
+    /// use pyver::PackageVersion;
+    ///
+    /// let policy = VersionPolicy {
+    ///     require_rc: true,
+    ///     ..Default::default()
+    /// };
+    /// let released = vec![PackageVersion::new("2.0rc1").unwrap()];
+    ///
+    /// let version = PackageVersion::new("2.0").unwrap();
+    /// assert!(policy.check_release(&version, &released).is_empty());
+    ///
+    /// let version = PackageVersion::new("2.1").unwrap();
+    /// assert_eq!(
+    ///     policy.check_release(&version, &released),
+    ///     vec![PolicyViolation::MissingReleaseCandidate]
+    /// );
+    /// ```
+    pub fn check_release(
+        &self,
+        version: &PackageVersion,
+        released: &[PackageVersion],
+    ) -> Vec<PolicyViolation> {
+        let mut violations = self.check(version);
+        // Post-releases follow a final release which needed a candidate itself
+        let is_final = version.pre().is_none()
+            && !version.is_postrelease()
+            && !version.is_devrelease();
+        if self.require_rc && is_final {
+            let has_rc = released.iter().any(|released| {
+                released.pre().is_some_and(|pre| pre.phase() == "rc")
+                    && released.finalize().without_local() == version.without_local()
+            });
+            if !has_rc {
+                violations.push(PolicyViolation::MissingReleaseCandidate);
+            }
+        }
+        violations
+    }
+}
+
+impl fmt::Display for PolicyViolation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PolicyViolation::Epoch => write!(f, "epochs are not allowed"),
+            PolicyViolation::PreRelease => write!(f, "pre-releases are not allowed"),
+            PolicyViolation::PostRelease => write!(f, "post-releases are not allowed"),
+            PolicyViolation::DevRelease => {
+                write!(f, "developmental releases are not allowed")
+            }
+            PolicyViolation::LocalVersion => {
+                write!(f, "local versions are not allowed")
+            }
+            PolicyViolation::NotCanonical => {
+                write!(f, "the version is not in normal form")
+            }
+            PolicyViolation::MissingReleaseCandidate => {
+                write!(f, "no release candidate was released first")
+            }
+            PolicyViolation::TooFewSegments { min, found } => write!(
+                f,
+                "{} release segments where at least {} are required",
+                found, min
+            ),
+            PolicyViolation::TooManySegments { max, found } => write!(
+                f,
+                "{} release segments where at most {} are allowed",
+                found, max
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{PolicyViolation, VersionPolicy};
+    use crate::PackageVersion;
+    use anyhow::Result;
+
+    #[test]
+    fn test_policy_check() -> Result<()> {
+        let strict = VersionPolicy {
+            forbid_epoch: true,
+            forbid_pre: true,
+            forbid_post: true,
+            forbid_dev: true,
+            forbid_local: true,
+            require_canonical: true,
+            require_rc: false,
+            min_release_segments: Some(2),
+            max_release_segments: Some(3),
+        };
+        let versions = vec![
+            ("1.0.0", vec![]),
+            (
+                "1",
+                vec![PolicyViolation::TooFewSegments { min: 2, found: 1 }],
+            ),
+            ("v1.0", vec![PolicyViolation::NotCanonical]),
+            (
+                "1.0rc1.post1.dev1+local",
+                vec![
+                    PolicyViolation::PreRelease,
+                    PolicyViolation::PostRelease,
+                    PolicyViolation::DevRelease,
+                    PolicyViolation::LocalVersion,
+                ],
+            ),
+        ];
+        for (version, violations) in versions {
+            let version = PackageVersion::new(version)?;
+            assert_eq!(strict.check(&version), violations, "{}", version);
+            assert!(VersionPolicy::default().check(&version).is_empty());
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_policy_require_rc() -> Result<()> {
+        let policy = VersionPolicy {
+            require_rc: true,
+            ..Default::default()
+        };
+        let released = vec![
+            PackageVersion::new("1.0b1")?,
+            PackageVersion::new("1.1rc2.dev1")?,
+            PackageVersion::new("1!2.0rc1")?,
+        ];
+        let versions = vec![
+            ("1.0", false),
+            ("1.1", true),
+            ("1.1.0+local", true),
+            ("2.0", false),
+            ("1!2.0", true),
+            ("1.2rc1", true),
+            ("1.0.post1", true),
+        ];
+        for (version, allowed) in versions {
+            let version = PackageVersion::new(version)?;
+            let violations = policy.check_release(&version, &released);
+            assert_eq!(violations.is_empty(), allowed, "{}", version);
+        }
+        Ok(())
+    }
+}
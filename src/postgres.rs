@@ -0,0 +1,75 @@
+//! [`ToSql`] and [`FromSql`] implementations storing versions as text
+//!
+//! Versions are written in normal form (`1.0rc1` for `v1.0-RC1`) and read
+//! by parsing, so they bind to any column or parameter a string does
+//! (`TEXT`, `VARCHAR`, ...). Text compares character by character in
+//! Postgres, sort by a [`PackageVersion::to_sort_key`] column instead.
+
+use crate::PackageVersion;
+use bytes::BytesMut;
+use postgres_types::{to_sql_checked, FromSql, IsNull, ToSql, Type};
+use std::error::Error;
+
+impl ToSql for PackageVersion {
+    fn to_sql(
+        &self,
+        ty: &Type,
+        out: &mut BytesMut,
+    ) -> Result<IsNull, Box<dyn Error + Sync + Send>> {
+        self.to_normalized_string().as_str().to_sql(ty, out)
+    }
+
+    fn accepts(ty: &Type) -> bool {
+        <&str as ToSql>::accepts(ty)
+    }
+
+    to_sql_checked!();
+}
+
+impl<'a> FromSql<'a> for PackageVersion {
+    fn from_sql(
+        ty: &Type,
+        raw: &'a [u8],
+    ) -> Result<Self, Box<dyn Error + Sync + Send>> {
+        let version = <&str as FromSql>::from_sql(ty, raw)?;
+        Ok(Self::new(version)?)
+    }
+
+    fn accepts(ty: &Type) -> bool {
+        <&str as FromSql>::accepts(ty)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::PackageVersion;
+    use bytes::BytesMut;
+    use postgres_types::{FromSql, IsNull, ToSql, Type};
+
+    #[test]
+    fn test_postgres_text() {
+        let version = PackageVersion::new("v1.0-RC1").unwrap();
+        let mut out = BytesMut::new();
+        let is_null = version.to_sql_checked(&Type::TEXT, &mut out).unwrap();
+        assert!(matches!(is_null, IsNull::No));
+        assert_eq!(&out[..], b"1.0rc1");
+
+        let read = PackageVersion::from_sql(&Type::VARCHAR, &out).unwrap();
+        assert_eq!(read, version);
+        assert_eq!(read.to_string(), "1.0rc1");
+
+        assert!(<PackageVersion as ToSql>::accepts(&Type::TEXT));
+        assert!(!<PackageVersion as ToSql>::accepts(&Type::INT4));
+        assert!(version.to_sql_checked(&Type::INT4, &mut out).is_err());
+    }
+
+    #[test]
+    fn test_postgres_text_negative() {
+        for raw in [&b"1.0 junk"[..], b"", b"\xff"] {
+            match PackageVersion::from_sql(&Type::TEXT, raw) {
+                Ok(v) => panic!("Oh no {}", v),
+                Err(_e) => continue,
+            }
+        }
+    }
+}
@@ -0,0 +1,227 @@
+use crate::specifiers::SpecifierSet;
+use crate::PackageVersion;
+use anyhow::Result;
+use serde::{Deserialize, Deserializer};
+use std::collections::{BTreeMap, HashMap};
+
+/// # PyPI JSON API project
+/// The response of `https://pypi.org/pypi/<project>/json`
+///
+/// Release versions are parsed into [`PackageVersion`]s, legacy versions
+/// which aren't valid `PEP-440` versions are left out.
+/// A release counts as yanked when all of its files are yanked.
+///
+/// Read more at <https://warehouse.pypa.io/api-reference/json.html>
+///
+/// ## Example Usage
+/// ```
+/// use pyver::pypi::Project;
+/// use pyver::specifiers::SpecifierSet;
+///
+/// let project = Project::from_json(
+///     r#"{
+///         "info": {"name": "spam", "version": "2.0", "requires_python": ">=3.8"},
+///         "releases": {
+///             "1.0": [{"filename": "spam-1.0.tar.gz", "url": "https://example.com/spam-1.0.tar.gz"}],
+///             "1.1": [{"filename": "spam-1.1.tar.gz", "url": "https://example.com/spam-1.1.tar.gz",
+///                      "yanked": true, "yanked_reason": "Broken"}],
+///             "2.0": [{"filename": "spam-2.0.tar.gz", "url": "https://example.com/spam-2.0.tar.gz"}],
+///             "2.1b1": [{"filename": "spam-2.1b1.tar.gz", "url": "https://example.com/spam-2.1b1.tar.gz"}]
+///         },
+///         "urls": []
+///     }"#,
+/// )
+/// .unwrap();
+///
+/// assert_eq!(project.latest_stable().unwrap().to_string(), "2.0");
+///
+/// let spec = SpecifierSet::new("<2").unwrap();
+/// let matching: Vec<String> = project
+///     .versions_matching(&spec)
+///     .iter()
+///     .map(|v| v.to_string())
+///     .collect();
+/// assert_eq!(matching, vec!["1.0"]);
+/// ```
+#[derive(Clone, Debug, Deserialize)]
+pub struct Project {
+    pub info: ProjectInfo,
+    /// Files of every release, ordered by version
+    #[serde(default, deserialize_with = "deserialize_releases")]
+    pub releases: BTreeMap<PackageVersion, Vec<ReleaseFile>>,
+    /// Files of the latest release
+    #[serde(default)]
+    pub urls: Vec<ReleaseFile>,
+}
+
+/// The `info` object, describing the latest release
+#[derive(Clone, Debug, Deserialize)]
+pub struct ProjectInfo {
+    pub name: String,
+    /// `None` if the latest version isn't a valid `PEP-440` version
+    #[serde(deserialize_with = "deserialize_version")]
+    pub version: Option<PackageVersion>,
+    #[serde(default)]
+    pub requires_python: Option<String>,
+    #[serde(default)]
+    pub summary: Option<String>,
+    #[serde(default)]
+    pub yanked: bool,
+    #[serde(default)]
+    pub yanked_reason: Option<String>,
+}
+
+/// A file uploaded for a release
+#[derive(Clone, Debug, Deserialize)]
+pub struct ReleaseFile {
+    pub filename: String,
+    pub url: String,
+    /// `sdist` or `bdist_wheel`
+    #[serde(default)]
+    pub packagetype: Option<String>,
+    #[serde(default)]
+    pub requires_python: Option<String>,
+    /// Hashes by algorithm (`sha256`)
+    #[serde(default)]
+    pub digests: HashMap<String, String>,
+    #[serde(default)]
+    pub size: Option<u64>,
+    #[serde(default)]
+    pub upload_time_iso_8601: Option<String>,
+    #[serde(default)]
+    pub yanked: bool,
+    #[serde(default)]
+    pub yanked_reason: Option<String>,
+}
+
+impl Project {
+    /// Parses a JSON API response
+    pub fn from_json(json: &str) -> Result<Self> {
+        Ok(serde_json::from_str(json)?)
+    }
+
+    /// Whether every file of the release is yanked,
+    /// releases without files count as yanked
+    pub fn is_yanked(&self, version: &PackageVersion) -> bool {
+        match self.releases.get(version) {
+            Some(files) => files.iter().all(|file| file.yanked),
+            None => true,
+        }
+    }
+
+    /// The releases which have files that aren't yanked, in ascending order
+    pub fn available_versions(&self) -> impl Iterator<Item = &PackageVersion> {
+        self.releases
+            .keys()
+            .filter(move |version| !self.is_yanked(version))
+    }
+
+    /// The highest release that isn't a pre-release, dev release or yanked
+    pub fn latest_stable(&self) -> Option<&PackageVersion> {
+        self.available_versions()
+            .filter(|version| version.is_stable())
+            .last()
+    }
+
+    /// The releases matching the specifiers (and their pre-release policy),
+    /// skipping yanked releases, in ascending order
+    pub fn versions_matching(&self, spec: &SpecifierSet) -> Vec<&PackageVersion> {
+        spec.filter(self.available_versions()).collect()
+    }
+}
+
+fn deserialize_version<'de, D>(
+    deserializer: D,
+) -> Result<Option<PackageVersion>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let version = String::deserialize(deserializer)?;
+    Ok(PackageVersion::new(&version).ok())
+}
+
+/// Parses the release versions, dropping the invalid ones
+/// and merging versions that are written differently but equal
+fn deserialize_releases<'de, D>(
+    deserializer: D,
+) -> Result<BTreeMap<PackageVersion, Vec<ReleaseFile>>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let releases = HashMap::<String, Vec<ReleaseFile>>::deserialize(deserializer)?;
+
+    let mut parsed: BTreeMap<PackageVersion, Vec<ReleaseFile>> = BTreeMap::new();
+    for (version, files) in releases {
+        if let Ok(version) = PackageVersion::new(&version) {
+            parsed.entry(version).or_default().extend(files);
+        }
+    }
+    Ok(parsed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Project;
+    use crate::specifiers::SpecifierSet;
+    use crate::PackageVersion;
+    use anyhow::Result;
+
+    fn file(name: &str, yanked: bool) -> String {
+        format!(
+            r#"{{"filename": "{0}", "url": "https://example.com/{0}", "yanked": {1}}}"#,
+            name, yanked
+        )
+    }
+
+    #[test]
+    fn test_pypi_project() -> Result<()> {
+        let json = format!(
+            r#"{{
+                "info": {{"name": "Spam", "version": "2004d", "summary": null}},
+                "releases": {{
+                    "2004d": [{}],
+                    "0.9": [],
+                    "1.0": [{}, {}],
+                    "1.0.0": [{}],
+                    "1.5rc1": [{}],
+                    "2.0": [{}, {}]
+                }}
+            }}"#,
+            file("spam-2004d.tar.gz", false),
+            file("spam-1.0.tar.gz", true),
+            file("spam-1.0-py3-none-any.whl", false),
+            file("spam-1.0.0.zip", true),
+            file("spam-1.5rc1.tar.gz", false),
+            file("spam-2.0.tar.gz", true),
+            file("spam-2.0-py3-none-any.whl", true),
+        );
+        let project = Project::from_json(&json)?;
+
+        assert_eq!(project.info.version, None);
+        assert_eq!(project.releases.len(), 4);
+        assert_eq!(project.releases[&PackageVersion::new("1")?].len(), 3);
+        assert!(project.is_yanked(&PackageVersion::new("0.9")?));
+        assert!(project.is_yanked(&PackageVersion::new("2.0")?));
+        assert_eq!(project.latest_stable(), Some(&PackageVersion::new("1.0")?));
+
+        let matching = project.versions_matching(&SpecifierSet::new(">1.0")?);
+        assert_eq!(matching, vec![&PackageVersion::new("1.5rc1")?]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_pypi_negative() {
+        let responses = vec![
+            "",
+            "{}",
+            r#"{"info": {"version": "1.0"}}"#,
+            r#"{"info": {"name": "spam", "version": "1.0"}, "releases": {"1.0": [{}]}}"#,
+        ];
+
+        for json in responses {
+            if let Ok(v) = Project::from_json(json) {
+                panic!("Oh no {:?}", v);
+            }
+        }
+    }
+}
@@ -0,0 +1,221 @@
+use crate::specifiers::SpecifierSet;
+use crate::{PackageVersion, Requirement};
+use anyhow::Result;
+use std::fmt;
+use std::str::FromStr;
+use toml_edit::{DocumentMut, Item, TableLike, Value};
+
+/// # `pyproject.toml` project metadata
+/// Reads the `PEP-621` `[project]` table of a `pyproject.toml` file
+/// and updates its version without touching the rest of the file
+///
+/// Read more at <https://packaging.python.org/en/latest/specifications/pyproject-toml/>
+///
+/// ## Example Usage
+/// ```
+/// use pyver::pyproject::PyProject;
+/// use pyver::PackageVersion;
+///
+/// let mut pyproject = PyProject::new(
+///     r#"
+/// [project]
+/// name = "spam"
+/// version = "1.0.0"  # Bumped by the release script
+/// requires-python = ">=3.8"
+/// dependencies = ["httpx>=0.25", "tomli; python_version < '3.11'"]
+/// "#,
+/// )
+/// .unwrap();
+///
+/// assert_eq!(pyproject.version().unwrap().unwrap(), PackageVersion::new("1.0").unwrap());
+/// assert_eq!(pyproject.requires_python().unwrap().unwrap().to_string(), ">=3.8");
+/// assert_eq!(pyproject.dependencies().unwrap()[0].name, "httpx");
+///
+/// pyproject
+///     .set_version(&PackageVersion::new("1.1.0").unwrap())
+///     .unwrap();
+/// assert!(pyproject
+///     .to_string()
+///     .contains(r#"version = "1.1.0"  # Bumped by the release script"#));
+/// ```
+#[derive(Clone, Debug)]
+pub struct PyProject {
+    document: DocumentMut,
+}
+
+impl PyProject {
+    pub fn new(content: &str) -> Result<Self> {
+        Ok(Self {
+            document: content.parse()?,
+        })
+    }
+
+    /// `[project] version`, `None` if it isn't set (i.e. `dynamic`)
+    pub fn version(&self) -> Result<Option<PackageVersion>> {
+        match self.string("version")? {
+            Some(version) => Ok(Some(PackageVersion::new(version)?)),
+            None => Ok(None),
+        }
+    }
+
+    /// `[project] requires-python`, `None` if it isn't set
+    pub fn requires_python(&self) -> Result<Option<SpecifierSet>> {
+        match self.string("requires-python")? {
+            Some(specifiers) => Ok(Some(SpecifierSet::new(specifiers)?)),
+            None => Ok(None),
+        }
+    }
+
+    /// `[project] dependencies`, empty if they aren't set
+    pub fn dependencies(&self) -> Result<Vec<Requirement>> {
+        let dependencies = match self.project().and_then(|p| p.get("dependencies")) {
+            Some(dependencies) => dependencies,
+            None => return Ok(Vec::new()),
+        };
+        let array = match dependencies.as_array() {
+            Some(array) => array,
+            None => anyhow::bail!("project.dependencies has to be an array"),
+        };
+
+        array
+            .iter()
+            .map(|dependency| match dependency.as_str() {
+                Some(dependency) => Requirement::new(dependency),
+                None => anyhow::bail!("project.dependencies has to contain strings"),
+            })
+            .collect()
+    }
+
+    /// Sets `[project] version`, keeping the formatting and comments
+    /// around the value
+    ///
+    /// Fails if the project has no `[project]` table
+    /// or declares its version as `dynamic`
+    pub fn set_version(&mut self, version: &PackageVersion) -> Result<()> {
+        let project = match self
+            .document
+            .get_mut("project")
+            .and_then(Item::as_table_like_mut)
+        {
+            Some(project) => project,
+            None => anyhow::bail!("pyproject.toml has no [project] table"),
+        };
+
+        let dynamic =
+            project
+                .get("dynamic")
+                .and_then(Item::as_array)
+                .is_some_and(|dynamic| {
+                    dynamic.iter().any(|v| v.as_str() == Some("version"))
+                });
+        if dynamic {
+            anyhow::bail!("project.version is dynamic");
+        }
+
+        match project.get_mut("version").and_then(Item::as_value_mut) {
+            Some(value) => {
+                let decor = value.decor().clone();
+                *value = Value::from(version.to_string());
+                *value.decor_mut() = decor;
+            }
+            None => {
+                project.insert("version", toml_edit::value(version.to_string()));
+            }
+        }
+        Ok(())
+    }
+
+    fn project(&self) -> Option<&dyn TableLike> {
+        self.document.get("project").and_then(Item::as_table_like)
+    }
+
+    /// A string value of the `[project]` table
+    fn string(&self, key: &str) -> Result<Option<&str>> {
+        match self.project().and_then(|project| project.get(key)) {
+            Some(item) => match item.as_str() {
+                Some(value) => Ok(Some(value)),
+                None => anyhow::bail!("project.{} has to be a string", key),
+            },
+            None => Ok(None),
+        }
+    }
+}
+
+impl FromStr for PyProject {
+    type Err = anyhow::Error;
+
+    fn from_str(content: &str) -> Result<Self> {
+        Self::new(content)
+    }
+}
+
+impl fmt::Display for PyProject {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.document)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::PyProject;
+    use crate::PackageVersion;
+    use anyhow::Result;
+
+    #[test]
+    fn test_pyproject_reading() -> Result<()> {
+        let pyproject =
+            PyProject::new("[project]\nname = \"spam\"\ndynamic = [\"version\"]")?;
+        assert_eq!(pyproject.version()?, None);
+        assert_eq!(pyproject.requires_python()?, None);
+        assert!(pyproject.dependencies()?.is_empty());
+
+        let pyproject = PyProject::new("project = { version = \"2!1.0rc1\" }")?;
+        assert_eq!(pyproject.version()?, Some(PackageVersion::new("2!1.0rc1")?));
+
+        let pyproject = PyProject::new("[tool.poetry]\nversion = \"1.0\"")?;
+        assert_eq!(pyproject.version()?, None);
+        Ok(())
+    }
+
+    #[test]
+    fn test_pyproject_set_version() -> Result<()> {
+        let mut pyproject =
+            PyProject::new("[project]\nname = \"spam\"\n\n[tool.other]\nkey = 1\n")?;
+        pyproject.set_version(&PackageVersion::new("0.1.0")?)?;
+        assert_eq!(
+            pyproject.to_string(),
+            "[project]\nname = \"spam\"\nversion = \"0.1.0\"\n\n[tool.other]\nkey = 1\n"
+        );
+
+        let mut pyproject = PyProject::new("[project]\ndynamic = [\"version\"]")?;
+        assert!(pyproject.set_version(&PackageVersion::new("1.0")?).is_err());
+
+        let mut pyproject = PyProject::new("[tool.poetry]\nversion = \"1.0\"")?;
+        assert!(pyproject.set_version(&PackageVersion::new("1.0")?).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_pyproject_negative() -> Result<()> {
+        let pyprojects = vec![
+            "[project]\nversion = 1",
+            "[project]\nversion = \"one\"",
+            "[project]\nrequires-python = \">=3.x\"",
+            "[project]\ndependencies = \"requests\"",
+            "[project]\ndependencies = [\"requests >=1 <2\"]",
+        ];
+
+        for content in pyprojects {
+            let pyproject = PyProject::new(content)?;
+            let result = pyproject
+                .version()
+                .and(pyproject.requires_python())
+                .and(pyproject.dependencies());
+            if let Ok(v) = result {
+                panic!("Oh no {:?}", v);
+            }
+        }
+        assert!(PyProject::new("[project").is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,453 @@
+//! Python classes wrapping versions and specifiers, shaped like
+//! `packaging.version.Version` and `packaging.specifiers` so the
+//! module can replace them in Python code
+//!
+//! [`pyver`] fills a Python module with the classes, build it into an
+//! extension module (e.g. with maturin) from a `cdylib` crate:
+//!
+//! ```no_run
+//! use pyo3::prelude::*;
+//!
+//! #[pymodule(name = "pyver")]
+//! fn pyver_module(m: &Bound<'_, PyModule>) -> PyResult<()> {
+//!     pyver::python::pyver(m)
+//! }
+//! ```
+//!
+//! Which is used like `packaging`:
+//!
+//! ```python
+//! from pyver import SpecifierSet, Version
+//!
+//! assert Version("1.0rc1") < Version("1.0")
+//! assert "1.5" in SpecifierSet(">=1.0, <2.0")
+//! ```
+
+use crate::specifiers::{Specifier, SpecifierSet};
+use crate::PackageVersion;
+use pyo3::basic::CompareOp;
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::types::{PyIterator, PyList, PyTuple};
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+
+pyo3::create_exception!(
+    pyver,
+    InvalidVersion,
+    PyValueError,
+    "A version that isn't valid according to PEP 440"
+);
+pyo3::create_exception!(
+    pyver,
+    InvalidSpecifier,
+    PyValueError,
+    "A specifier that isn't valid according to PEP 440"
+);
+
+/// Adds the `Version`, `Specifier` and `SpecifierSet` classes and the
+/// `InvalidVersion` and `InvalidSpecifier` exceptions to a module
+pub fn pyver(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_class::<PyVersion>()?;
+    m.add_class::<PySpecifier>()?;
+    m.add_class::<PySpecifierSet>()?;
+    m.add("InvalidVersion", m.py().get_type::<InvalidVersion>())?;
+    m.add("InvalidSpecifier", m.py().get_type::<InvalidSpecifier>())?;
+    Ok(())
+}
+
+/// # Python version
+/// A [`PackageVersion`] as the Python class `Version`, ordered, compared
+/// and hashed like the Rust version
+#[pyclass(name = "Version", module = "pyver", frozen, skip_from_py_object)]
+#[derive(Clone, Debug)]
+pub struct PyVersion(pub PackageVersion);
+
+#[pymethods]
+impl PyVersion {
+    #[new]
+    fn new(version: &str) -> PyResult<Self> {
+        PackageVersion::new(version)
+            .map(Self)
+            .map_err(|e| InvalidVersion::new_err(e.to_string()))
+    }
+
+    #[getter]
+    fn epoch(&self) -> u32 {
+        self.0.epoch()
+    }
+
+    #[getter]
+    fn release<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyTuple>> {
+        PyTuple::new(py, self.0.release().segments())
+    }
+
+    /// The phase and number, e.g. `("rc", 1)`
+    #[getter]
+    fn pre(&self) -> Option<(&'static str, u32)> {
+        self.0
+            .pre()
+            .map(|pre| (pre.phase(), pre.number().unwrap_or(0)))
+    }
+
+    #[getter]
+    fn post(&self) -> Option<u32> {
+        self.0.post().map(|post| post.post_num.unwrap_or(0))
+    }
+
+    #[getter]
+    fn dev(&self) -> Option<u32> {
+        self.0.dev().map(|dev| dev.dev_num.unwrap_or(0))
+    }
+
+    /// The local version label in normal form
+    #[getter]
+    fn local(&self) -> Option<String> {
+        let normalized = self.0.to_normalized_string();
+        normalized
+            .split_once('+')
+            .map(|(_, local)| local.to_string())
+    }
+
+    #[getter]
+    fn public(&self) -> String {
+        self.0.public()
+    }
+
+    #[getter]
+    fn base_version(&self) -> String {
+        self.0.base_version().to_normalized_string()
+    }
+
+    #[getter]
+    fn is_prerelease(&self) -> bool {
+        self.0.is_prerelease()
+    }
+
+    #[getter]
+    fn is_postrelease(&self) -> bool {
+        self.0.is_postrelease()
+    }
+
+    #[getter]
+    fn is_devrelease(&self) -> bool {
+        self.0.is_devrelease()
+    }
+
+    #[getter]
+    fn major(&self) -> u64 {
+        self.segment(0)
+    }
+
+    #[getter]
+    fn minor(&self) -> u64 {
+        self.segment(1)
+    }
+
+    #[getter]
+    fn micro(&self) -> u64 {
+        self.segment(2)
+    }
+
+    /// Only compares with other versions, not version strings
+    fn __richcmp__(&self, other: &Bound<'_, Self>, op: CompareOp) -> bool {
+        op.matches(self.0.cmp(&other.get().0))
+    }
+
+    fn __hash__(&self) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        self.0.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    fn __str__(&self) -> String {
+        self.0.to_normalized_string()
+    }
+
+    fn __repr__(&self) -> String {
+        format!("<Version('{}')>", self.0.to_normalized_string())
+    }
+}
+
+impl PyVersion {
+    fn segment(&self, index: usize) -> u64 {
+        self.0.release().segments().get(index).copied().unwrap_or(0)
+    }
+}
+
+/// # Python specifier
+/// A [`Specifier`] as the Python class `Specifier`
+#[pyclass(
+    name = "Specifier",
+    module = "pyver",
+    frozen,
+    eq,
+    hash,
+    skip_from_py_object
+)]
+#[derive(Clone, Debug, Hash, Eq, PartialEq)]
+pub struct PySpecifier(pub Specifier);
+
+#[pymethods]
+impl PySpecifier {
+    #[new]
+    #[pyo3(signature = (spec, prereleases = None))]
+    fn new(spec: &str, prereleases: Option<bool>) -> PyResult<Self> {
+        Specifier::new(spec)
+            .map(|specifier| Self(specifier.with_prereleases(prereleases)))
+            .map_err(|e| InvalidSpecifier::new_err(e.to_string()))
+    }
+
+    #[getter]
+    fn operator(&self) -> &'static str {
+        self.0.operator().as_str()
+    }
+
+    /// The version as written, with the `.*` of a prefix
+    #[getter]
+    fn version(&self) -> String {
+        let specifier = self.0.to_string();
+        specifier[self.operator().len()..].to_string()
+    }
+
+    #[getter]
+    fn prereleases(&self) -> bool {
+        self.0.prereleases()
+    }
+
+    /// Whether a `Version` or a version string matches, an invalid
+    /// version string never does
+    #[pyo3(signature = (item, prereleases = None))]
+    fn contains(&self, item: &Bound<'_, PyAny>, prereleases: Option<bool>) -> bool {
+        let specifier = self
+            .0
+            .clone()
+            .with_prereleases(prereleases.or(self.0.prereleases));
+        version_of(item).is_some_and(|version| specifier.contains(&version))
+    }
+
+    fn __contains__(&self, item: &Bound<'_, PyAny>) -> bool {
+        self.contains(item, None)
+    }
+
+    /// The matching items of an iterable of versions and version strings,
+    /// see [`SpecifierSet::filter`]
+    #[pyo3(signature = (iterable, prereleases = None))]
+    fn filter<'py>(
+        &self,
+        iterable: &Bound<'py, PyAny>,
+        prereleases: Option<bool>,
+    ) -> PyResult<Vec<Bound<'py, PyAny>>> {
+        filter(SpecifierSet::from(self.0.clone()), iterable, prereleases)
+    }
+
+    fn __str__(&self) -> String {
+        self.0.to_string()
+    }
+
+    fn __repr__(&self) -> String {
+        format!("<Specifier('{}')>", self.0)
+    }
+}
+
+/// # Python specifier set
+/// A [`SpecifierSet`] as the Python class `SpecifierSet`
+#[pyclass(
+    name = "SpecifierSet",
+    module = "pyver",
+    frozen,
+    eq,
+    hash,
+    skip_from_py_object
+)]
+#[derive(Clone, Debug, Hash, Eq, PartialEq)]
+pub struct PySpecifierSet(pub SpecifierSet);
+
+#[pymethods]
+impl PySpecifierSet {
+    #[new]
+    #[pyo3(signature = (specifiers = "", prereleases = None))]
+    fn new(specifiers: &str, prereleases: Option<bool>) -> PyResult<Self> {
+        SpecifierSet::new(specifiers)
+            .map(|set| Self(set.with_prereleases(prereleases)))
+            .map_err(|e| InvalidSpecifier::new_err(e.to_string()))
+    }
+
+    #[getter]
+    fn prereleases(&self) -> bool {
+        self.0.prereleases()
+    }
+
+    /// Whether a `Version` or a version string matches every specifier,
+    /// an invalid version string never does
+    #[pyo3(signature = (item, prereleases = None))]
+    fn contains(&self, item: &Bound<'_, PyAny>, prereleases: Option<bool>) -> bool {
+        let set = self
+            .0
+            .clone()
+            .with_prereleases(prereleases.or(self.0.prereleases));
+        version_of(item).is_some_and(|version| set.contains(&version))
+    }
+
+    fn __contains__(&self, item: &Bound<'_, PyAny>) -> bool {
+        self.contains(item, None)
+    }
+
+    /// The matching items of an iterable of versions and version strings,
+    /// see [`SpecifierSet::filter`]
+    #[pyo3(signature = (iterable, prereleases = None))]
+    fn filter<'py>(
+        &self,
+        iterable: &Bound<'py, PyAny>,
+        prereleases: Option<bool>,
+    ) -> PyResult<Vec<Bound<'py, PyAny>>> {
+        filter(self.0.clone(), iterable, prereleases)
+    }
+
+    fn __len__(&self) -> usize {
+        self.0.len()
+    }
+
+    fn __iter__<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyIterator>> {
+        let specifiers = self
+            .0
+            .iter()
+            .map(|specifier| PySpecifier(specifier.clone()));
+        PyList::new(py, specifiers)?.try_iter()
+    }
+
+    /// The intersection of both sets (`a & b`)
+    fn __and__(&self, other: &Bound<'_, Self>) -> Self {
+        Self(self.0.intersection(&other.get().0))
+    }
+
+    fn __str__(&self) -> String {
+        self.0.to_string()
+    }
+
+    fn __repr__(&self) -> String {
+        format!("<SpecifierSet('{}')>", self.0)
+    }
+}
+
+/// The version of a `Version` or a version string, `None` otherwise
+fn version_of(item: &Bound<'_, PyAny>) -> Option<PackageVersion> {
+    match item.cast::<PyVersion>() {
+        Ok(version) => Some(version.get().0.clone()),
+        Err(_) => PackageVersion::new(&item.extract::<String>().ok()?).ok(),
+    }
+}
+
+fn filter<'py>(
+    set: SpecifierSet,
+    iterable: &Bound<'py, PyAny>,
+    prereleases: Option<bool>,
+) -> PyResult<Vec<Bound<'py, PyAny>>> {
+    let policy = prereleases.or(set.prereleases);
+    let set = set.with_prereleases(policy);
+
+    let mut items = Vec::new();
+    let mut versions = Vec::new();
+    for item in iterable.try_iter()? {
+        let item = item?;
+        if let Some(version) = version_of(&item) {
+            versions.push(version);
+            items.push(item);
+        }
+    }
+
+    // The versions come out in order, so each is found after the last one
+    let mut matching = Vec::new();
+    let mut index = 0;
+    for version in set.filter(versions.iter()) {
+        while !core::ptr::eq(&versions[index], version) {
+            index += 1;
+        }
+        matching.push(items[index].clone());
+    }
+    Ok(matching)
+}
+
+#[cfg(test)]
+mod tests {
+    use pyo3::prelude::*;
+    use pyo3::types::PyModule;
+
+    fn run(code: &core::ffi::CStr) {
+        Python::initialize();
+        Python::attach(|py| {
+            let module = PyModule::new(py, "pyver").unwrap();
+            super::pyver(&module).unwrap();
+            if let Err(e) = py.run(code, Some(&module.dict()), None) {
+                panic!("Oh no {}", e);
+            }
+        });
+    }
+
+    #[test]
+    fn test_python_version() {
+        run(c"
+v = Version('v1!2.0-RC1.post3.dev4+Ubuntu-1')
+assert str(v) == '1!2.0rc1.post3.dev4+ubuntu.1'
+assert repr(Version('1.0')) == \"<Version('1.0')>\"
+assert (v.epoch, v.release, v.pre, v.post, v.dev) == (1, (2, 0), ('rc', 1), 3, 4)
+assert (v.local, v.public, v.base_version) == ('ubuntu.1', '1!2.0rc1.post3.dev4', '1!2.0')
+assert v.is_prerelease and v.is_postrelease and v.is_devrelease
+assert (v.major, v.minor, v.micro) == (2, 0, 0)
+
+assert Version('1.0.dev0') < Version('1.0a1') < Version('1.0') < Version('1.0.post1')
+assert Version('1.0') == Version('1.0.0') and Version('1.0') != Version('1.1')
+assert hash(Version('1.0')) == hash(Version('v1.0.0'))
+assert len({Version('1.0'), Version('1.0.0'), Version('1.1')}) == 2
+assert sorted(map(Version, ['1.1', '1.0rc1', '1.0']))[0] == Version('1.0rc1')
+assert Version('1.0') != '1.0'
+
+for invalid in ['1.0 junk', '']:
+    try:
+        Version(invalid)
+        assert False
+    except InvalidVersion:
+        pass
+try:
+    Version('1.0') < '1.0'
+    assert False
+except TypeError:
+    pass
+");
+    }
+
+    #[test]
+    fn test_python_specifiers() {
+        run(c"
+s = Specifier('>=1.0')
+assert (s.operator, s.version, s.prereleases) == ('>=', '1.0', False)
+assert Specifier('==1.*').version == '1.*'
+assert '1.5' in s and Version('1.5') in s and '0.9' not in s and 'junk' not in s
+assert '2.0rc1' not in s and s.contains('2.0rc1', prereleases=True)
+assert Specifier('>=1.0', prereleases=True).contains('2.0rc1')
+assert s == Specifier('>=1.0') and hash(s) == hash(Specifier('>=1.0'))
+assert repr(s) == \"<Specifier('>=1.0')>\"
+
+ss = SpecifierSet('>=1.0, !=1.3.4, <2.0')
+assert str(ss) == '>=1.0,!=1.3.4,<2.0' and len(ss) == 3
+assert [str(s) for s in ss] == ['>=1.0', '!=1.3.4', '<2.0']
+assert '1.5' in ss and '1.3.4' not in ss and Version('2.0') not in ss
+assert '1.5rc1' not in ss and ss.contains('1.5rc1', prereleases=True)
+assert '1.5' in SpecifierSet() and SpecifierSet() == SpecifierSet('')
+both = ss & SpecifierSet('>1.4')
+assert '1.5' in both and '1.2' not in both and '2.1' not in both
+
+versions = ['0.9', Version('1.0'), '1.1rc1', 'junk', '1.1']
+assert ss.filter(versions) == [versions[1], '1.1']
+assert SpecifierSet('>1.0, !=1.1').filter(versions) == ['1.1rc1']
+assert s.filter(versions, prereleases=True) == [versions[1], '1.1rc1', '1.1']
+
+for invalid in ['>=1.0,', '1.0', '>=1.0.*']:
+    try:
+        SpecifierSet(invalid)
+        assert False
+    except InvalidSpecifier:
+        pass
+");
+    }
+}
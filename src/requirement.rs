@@ -0,0 +1,505 @@
+use crate::markers::Marker;
+use crate::specifiers::SpecifierSet;
+use crate::{Extra, PackageName};
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+/// # `PEP-508` Dependency specification
+/// A requirement such as `requests[security]>=2.8.1; python_version < "3.11"`
+///
+/// The project name, extras and marker are normalized and the extras sorted,
+/// so the [`Display`](fmt::Display) output is the canonical form of the
+/// requirement and parses back into an equal requirement.
+///
+/// Read more at <https://peps.python.org/pep-0508/>
+///
+/// ## Example Usage
+/// ```
+/// use pyver::Requirement;
+/// use pyver::PackageVersion;
+///
+/// let requirement = Requirement::new(
+///     r#"Requests [security] >=2.8.1, ==2.8.* ; python_version < '3.11'"#,
+/// )
+/// .unwrap();
+///
+/// assert_eq!(requirement.name, "requests");
+/// assert_eq!(requirement.extras[0], "security");
+/// assert!(requirement
+///     .specifiers
+///     .contains(&PackageVersion::new("2.8.3").unwrap()));
+/// assert_eq!(
+///     requirement.to_string(),
+///     r#"requests[security]>=2.8.1,==2.8.*; python_version < "3.11""#
+/// );
+/// ```
+///
+/// Markers can be evaluated to check whether a requirement applies
+/// ```
+/// use pyver::markers::MarkerEnvironment;
+/// use pyver::Requirement;
+///
+/// let requirement = Requirement::new(r#"tomli; python_version < "3.11""#).unwrap();
+/// let environment = MarkerEnvironment {
+///     python_version: "3.12".to_string(),
+///     ..Default::default()
+/// };
+///
+/// assert!(!requirement.marker.unwrap().evaluate(&environment));
+/// ```
+#[derive(Hash, Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+#[serde(try_from = "String", into = "String")]
+pub struct Requirement {
+    /// `PEP-503` normalized project name
+    pub name: PackageName,
+    /// Requested extras, sorted and without duplicates
+    pub extras: Vec<Extra>,
+    /// Versions allowed for the project, empty to allow any version
+    pub specifiers: SpecifierSet,
+    /// Direct reference (`name @ https://...`), used instead of specifiers
+    pub url: Option<RequirementUrl>,
+    /// Environment marker deciding whether the requirement applies
+    pub marker: Option<Marker>,
+}
+
+impl Requirement {
+    pub fn new(requirement: &str) -> Result<Self> {
+        let requirement = requirement.trim();
+
+        // The name runs until the extras, the specifiers or whitespace
+        let name_end = requirement
+            .find(|c: char| {
+                !(c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+            })
+            .unwrap_or(requirement.len());
+        let name = PackageName::new(&requirement[..name_end])?;
+        let mut rest = requirement[name_end..].trim_start();
+
+        let mut extras = Vec::new();
+        if let Some(after) = rest.strip_prefix('[') {
+            let (list, after) = match after.split_once(']') {
+                Some(v) => v,
+                None => {
+                    anyhow::bail!("Requirement {} has unclosed extras", requirement)
+                }
+            };
+            if !list.trim().is_empty() {
+                extras = list
+                    .split(',')
+                    .map(|extra| Extra::new(extra.trim()))
+                    .collect::<Result<Vec<Extra>>>()?;
+            }
+            extras.sort();
+            extras.dedup();
+            rest = after.trim_start();
+        }
+
+        // URLs may contain `;`, so their marker has to be separated by whitespace
+        if let Some(after) = rest.strip_prefix('@') {
+            let after = after.trim_start();
+            let (url, after) =
+                after.split_once(char::is_whitespace).unwrap_or((after, ""));
+            let after = after.trim_start();
+            let marker = match after.strip_prefix(';') {
+                Some(marker) => Some(Marker::new(marker)?),
+                None if after.is_empty() => None,
+                None => anyhow::bail!(
+                    "Requirement {} has trailing characters after its URL",
+                    requirement
+                ),
+            };
+
+            return Ok(Self {
+                name,
+                extras,
+                specifiers: SpecifierSet::default(),
+                url: Some(RequirementUrl::new(url)?),
+                marker,
+            });
+        }
+
+        let (mut rest, marker) = match rest.split_once(';') {
+            Some((rest, marker)) => (rest.trim_end(), Some(Marker::new(marker)?)),
+            None => (rest, None),
+        };
+
+        // Specifiers may be wrapped in parentheses, `name (>=1.0)`
+        if let Some(inner) = rest.strip_prefix('(') {
+            rest = match inner.strip_suffix(')') {
+                Some(inner) => inner,
+                None => anyhow::bail!(
+                    "Requirement {} has unclosed parentheses",
+                    requirement
+                ),
+            };
+        }
+        let specifiers = SpecifierSet::new(rest)?;
+
+        Ok(Self {
+            name,
+            extras,
+            specifiers,
+            url: None,
+            marker,
+        })
+    }
+}
+
+impl FromStr for Requirement {
+    type Err = anyhow::Error;
+
+    fn from_str(requirement: &str) -> Result<Self> {
+        Self::new(requirement)
+    }
+}
+
+impl TryFrom<String> for Requirement {
+    type Error = anyhow::Error;
+
+    fn try_from(requirement: String) -> Result<Self> {
+        Self::new(&requirement)
+    }
+}
+
+impl From<Requirement> for String {
+    fn from(requirement: Requirement) -> Self {
+        requirement.to_string()
+    }
+}
+
+impl fmt::Display for Requirement {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.name)?;
+        if !self.extras.is_empty() {
+            let extras: Vec<&str> = self.extras.iter().map(Extra::as_str).collect();
+            write!(f, "[{}]", extras.join(","))?;
+        }
+        match &self.url {
+            Some(url) => {
+                write!(f, " @ {}", url)?;
+                if let Some(marker) = &self.marker {
+                    write!(f, " ; {}", marker)?;
+                }
+            }
+            None => {
+                write!(f, "{}", self.specifiers)?;
+                if let Some(marker) = &self.marker {
+                    write!(f, "; {}", marker)?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// # `PEP-508` URL reference
+/// The URL of a direct reference such as
+/// `pip @ git+https://github.com/pypa/pip.git@22.0#egg=pip`
+///
+/// Version control URLs (`vcs+scheme://`) are split into the VCS and revision,
+/// the fragment into the known `egg`, `subdirectory` and hash keys and the
+/// other pairs, which are written back after them.
+///
+/// ## Example Usage
+/// ```
+/// use pyver::RequirementUrl;
+///
+/// let url = RequirementUrl::new(
+///     "git+https://github.com/pypa/pip.git@22.0#egg=pip&subdirectory=src",
+/// )
+/// .unwrap();
+///
+/// assert_eq!(url.vcs.as_deref(), Some("git"));
+/// assert_eq!(url.revision.as_deref(), Some("22.0"));
+/// assert_eq!(url.egg.as_deref(), Some("pip"));
+/// assert_eq!(url.subdirectory.as_deref(), Some("src"));
+/// ```
+#[derive(Hash, Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+#[serde(try_from = "String", into = "String")]
+pub struct RequirementUrl {
+    /// The URL without its fragment
+    pub url: String,
+    /// Version control system of `vcs+scheme://` URLs (`git`)
+    pub vcs: Option<String>,
+    /// Tag, branch or commit after the `@` of version control URLs
+    pub revision: Option<String>,
+    /// Project name from `#egg=`
+    pub egg: Option<String>,
+    /// Hash algorithm and digest from `#sha256=...`
+    pub hash: Option<(String, String)>,
+    /// Directory of the project inside the repository or archive
+    pub subdirectory: Option<String>,
+    /// The other `key=value` pairs of the fragment, in the order they were
+    /// written
+    pub other_fragment: Vec<(String, String)>,
+}
+
+/// Hash algorithms accepted as URL fragment keys
+const HASH_ALGORITHMS: [&str; 6] =
+    ["md5", "sha1", "sha224", "sha256", "sha384", "sha512"];
+
+impl RequirementUrl {
+    pub fn new(url: &str) -> Result<Self> {
+        let (url, fragment) = match url.split_once('#') {
+            Some((url, fragment)) => (url, Some(fragment)),
+            None => (url, None),
+        };
+
+        let scheme = match url.split_once(':') {
+            Some((scheme, _)) => scheme,
+            None => anyhow::bail!("URL {} has no scheme", url),
+        };
+        if scheme.is_empty()
+            || !scheme.starts_with(|c: char| c.is_ascii_alphabetic())
+            || !scheme
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '.'))
+        {
+            anyhow::bail!("URL {} has an invalid scheme", url);
+        }
+        if url.chars().any(char::is_whitespace) {
+            anyhow::bail!("URL {} contains whitespace", url);
+        }
+
+        // The revision follows the last `@` of the path, the host may hold a user
+        let (vcs, revision) = match scheme.split_once('+') {
+            Some((vcs, _)) => {
+                let authority_start = url.find("://").map_or(0, |i| i + 3);
+                let path_start = url[authority_start..]
+                    .find('/')
+                    .map_or(url.len(), |i| authority_start + i);
+                let revision = url[path_start..]
+                    .rfind('@')
+                    .map(|i| url[path_start + i + 1..].to_string());
+                (Some(vcs.to_lowercase()), revision)
+            }
+            None => (None, None),
+        };
+        if revision.as_deref() == Some("") {
+            anyhow::bail!("URL {} has an empty revision", url);
+        }
+
+        let mut egg = None;
+        let mut hash = None;
+        let mut subdirectory = None;
+        let mut other_fragment = Vec::new();
+        for pair in fragment.unwrap_or("").split('&').filter(|p| !p.is_empty()) {
+            let (key, value) = match pair.split_once('=') {
+                Some(v) => v,
+                None => anyhow::bail!("URL fragment {} is not a key=value pair", pair),
+            };
+            match key {
+                "egg" => egg = Some(value.to_string()),
+                "subdirectory" => subdirectory = Some(value.to_string()),
+                key if HASH_ALGORITHMS.contains(&key) => {
+                    hash = Some((key.to_string(), value.to_string()))
+                }
+                _ => other_fragment.push((key.to_string(), value.to_string())),
+            }
+        }
+
+        Ok(Self {
+            url: url.to_string(),
+            vcs,
+            revision,
+            egg,
+            hash,
+            subdirectory,
+            other_fragment,
+        })
+    }
+
+    /// Whether the URL points to the local filesystem
+    pub fn is_local(&self) -> bool {
+        self.url.starts_with("file:")
+    }
+}
+
+impl FromStr for RequirementUrl {
+    type Err = anyhow::Error;
+
+    fn from_str(url: &str) -> Result<Self> {
+        Self::new(url)
+    }
+}
+
+impl TryFrom<String> for RequirementUrl {
+    type Error = anyhow::Error;
+
+    fn try_from(url: String) -> Result<Self> {
+        Self::new(&url)
+    }
+}
+
+impl From<RequirementUrl> for String {
+    fn from(url: RequirementUrl) -> Self {
+        url.to_string()
+    }
+}
+
+impl fmt::Display for RequirementUrl {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.url)?;
+
+        let mut fragment = Vec::new();
+        if let Some(egg) = &self.egg {
+            fragment.push(format!("egg={}", egg));
+        }
+        if let Some((algorithm, digest)) = &self.hash {
+            fragment.push(format!("{}={}", algorithm, digest));
+        }
+        if let Some(subdirectory) = &self.subdirectory {
+            fragment.push(format!("subdirectory={}", subdirectory));
+        }
+        for (key, value) in &self.other_fragment {
+            fragment.push(format!("{}={}", key, value));
+        }
+        if !fragment.is_empty() {
+            write!(f, "#{}", fragment.join("&"))?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Requirement, RequirementUrl};
+    use crate::PackageVersion;
+    use anyhow::Result;
+
+    #[test]
+    fn test_requirement_parsing() -> Result<()> {
+        let requirement = Requirement::new("name")?;
+        assert_eq!(requirement.name, "name");
+        assert!(requirement.extras.is_empty());
+        assert!(requirement.specifiers.is_empty());
+        assert_eq!(requirement.marker, None);
+
+        let requirement = Requirement::new("Zope.Interface [B, a,b] (>=3.0, <4)")?;
+        assert_eq!(requirement.name, "zope-interface");
+        assert_eq!(requirement.extras.len(), 2);
+        assert_eq!(requirement.extras[0], "a");
+        assert_eq!(requirement.specifiers.len(), 2);
+        assert!(requirement
+            .specifiers
+            .contains(&PackageVersion::new("3.5")?));
+
+        let requirement = Requirement::new("name[]; os_name=='a'")?;
+        assert!(requirement.extras.is_empty());
+        assert_eq!(requirement.marker, Some("os_name == 'a'".parse()?));
+        Ok(())
+    }
+
+    #[test]
+    fn test_requirement_round_trip() -> Result<()> {
+        let requirements = vec![
+            ("name", "name"),
+            ("Name_Two>=1.0", "name-two>=1.0"),
+            ("name [Foo,bar] ( >=1.0, <2 )", "name[bar,foo]>=1.0,<2"),
+            (
+                "name>=1.0 ; python_version<'3.8'",
+                r#"name>=1.0; python_version < "3.8""#,
+            ),
+            (
+                "name@https://example.com/name-1.0.zip#sha256=abc",
+                "name @ https://example.com/name-1.0.zip#sha256=abc",
+            ),
+            (
+                "name [foo] @ file:///tmp/name;x ; os_name=='nt'",
+                r#"name[foo] @ file:///tmp/name;x ; os_name == "nt""#,
+            ),
+        ];
+
+        for (requirement, canonical) in requirements {
+            let parsed = Requirement::new(requirement)?;
+            assert_eq!(parsed.to_string(), canonical);
+            assert_eq!(Requirement::new(canonical)?, parsed);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_requirement_negative() {
+        let requirements = vec![
+            "",
+            ">=1.0",
+            "name[foo",
+            "name[foo!]",
+            "name (>=1.0",
+            "name >=1.0 <2.0",
+            "name;",
+            "name>=1.0; ",
+            "name; os_name",
+            "name @",
+            "name @ example.com",
+            "name @ https://example.com/name.zip extra",
+            "name @ https://example.com/name.zip; os_name == 'nt'",
+        ];
+
+        for requirement in requirements {
+            if let Ok(v) = Requirement::new(requirement) {
+                panic!("Oh no {}", v);
+            }
+        }
+    }
+
+    #[test]
+    fn test_requirement_url() -> Result<()> {
+        let requirement = Requirement::new(
+            "pip @ git+ssh://git@github.com/pypa/pip.git@22.0#egg=pip",
+        )?;
+        assert!(requirement.specifiers.is_empty());
+        let url = requirement.url.unwrap();
+        assert_eq!(url.url, "git+ssh://git@github.com/pypa/pip.git@22.0");
+        assert_eq!(url.vcs.as_deref(), Some("git"));
+        assert_eq!(url.revision.as_deref(), Some("22.0"));
+        assert_eq!(url.egg.as_deref(), Some("pip"));
+        assert!(!url.is_local());
+
+        let url = RequirementUrl::new("git+https://github.com/pypa/pip.git")?;
+        assert_eq!(url.revision, None);
+
+        let url = RequirementUrl::new(
+            "file:///tmp/name-1.0.tar.gz#sha256=0123abc&subdirectory=sub/dir",
+        )?;
+        assert!(url.is_local());
+        assert_eq!(url.vcs, None);
+        assert_eq!(
+            url.hash,
+            Some(("sha256".to_string(), "0123abc".to_string()))
+        );
+        assert_eq!(url.subdirectory.as_deref(), Some("sub/dir"));
+        assert_eq!(
+            url.to_string(),
+            "file:///tmp/name-1.0.tar.gz#sha256=0123abc&subdirectory=sub/dir"
+        );
+
+        // Keys that aren't known are kept in order
+        let requirement = "foo @ https://example.com/foo.zip#egg=foo&custom=1&b=2";
+        let parsed = Requirement::new(requirement)?;
+        assert_eq!(
+            parsed.url.as_ref().unwrap().other_fragment,
+            vec![
+                ("custom".to_string(), "1".to_string()),
+                ("b".to_string(), "2".to_string())
+            ]
+        );
+        assert_eq!(parsed.to_string(), requirement);
+
+        let urls = vec![
+            "",
+            "no-scheme",
+            "1http://a",
+            "git+https://a/b@",
+            "http://a#egg",
+        ];
+        for url in urls {
+            if let Ok(v) = RequirementUrl::new(url) {
+                panic!("Oh no {}", v);
+            }
+        }
+        Ok(())
+    }
+}
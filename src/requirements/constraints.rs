@@ -0,0 +1,261 @@
+use super::{RequirementsFile, RequirementsItem};
+use crate::markers::MarkerEnvironment;
+use crate::{PackageName, Requirement};
+use anyhow::Result;
+use std::fmt;
+
+/// # pip constraints
+/// Constraints limit the versions of projects without requiring them,
+/// applying them to requirements narrows the specifiers of the
+/// requirements for the same project
+///
+/// Constraints with an environment marker only take effect once
+/// [`ConstraintSet::for_environment`] decided whether they apply.
+///
+/// Read more at <https://pip.pypa.io/en/stable/user_guide/#constraints-files>
+///
+/// ## Example Usage
+/// ```
+/// use pyver::requirements::{ConstraintSet, RequirementsFile};
+/// use pyver::Requirement;
+///
+/// let file = RequirementsFile::parse("Django<4.2\nrequests!=2.30.0", "constraints.txt");
+/// let constraints = ConstraintSet::from_requirements_file(&file.unwrap()).unwrap();
+///
+/// let requirements = vec![
+///     Requirement::new("django>=3.2").unwrap(),
+///     Requirement::new("flask").unwrap(),
+/// ];
+/// let constrained = constraints.apply(&requirements).unwrap();
+///
+/// assert_eq!(constrained[0].to_string(), "django>=3.2,<4.2");
+/// assert_eq!(constrained[1].to_string(), "flask");
+///
+/// let conflicting = vec![Requirement::new("django>=4.2").unwrap()];
+/// assert!(constraints.apply(&conflicting).is_err());
+/// ```
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct ConstraintSet {
+    constraints: Vec<Requirement>,
+}
+
+/// A constraint which leaves no version of a requirement allowed
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ConstraintConflict {
+    /// The requirement, narrowed by the constraints applied before the conflict
+    pub requirement: Requirement,
+    pub constraint: Requirement,
+}
+
+impl ConstraintSet {
+    pub fn new(constraints: Vec<Requirement>) -> Self {
+        Self { constraints }
+    }
+
+    /// The constraints of a constraints file, every requirement
+    /// and `-c` constraint in it is used as a constraint
+    ///
+    /// Like pip, unnamed and editable constraints are rejected
+    pub fn from_requirements_file(file: &RequirementsFile) -> Result<Self> {
+        let mut constraints = Vec::new();
+        for entry in file.requirements.iter().chain(&file.constraints) {
+            let location = format!("{}:{}", entry.path.display(), entry.line);
+            if entry.editable {
+                anyhow::bail!("{}: Editable constraints are not allowed", location);
+            }
+            match &entry.item {
+                RequirementsItem::Named(requirement) => {
+                    constraints.push(requirement.as_ref().clone())
+                }
+                RequirementsItem::Unnamed(item) => anyhow::bail!(
+                    "{}: Unnamed constraint {} is not allowed",
+                    location,
+                    item
+                ),
+            }
+        }
+        Ok(Self { constraints })
+    }
+
+    pub fn push(&mut self, constraint: Requirement) {
+        self.constraints.push(constraint);
+    }
+
+    /// Resolves the markers of the constraints, dropping those
+    /// which don't apply to the environment
+    pub fn for_environment(&self, environment: &MarkerEnvironment) -> Self {
+        let constraints = self
+            .constraints
+            .iter()
+            .filter(|constraint| match &constraint.marker {
+                Some(marker) => marker.evaluate(environment),
+                None => true,
+            })
+            .map(|constraint| Requirement {
+                marker: None,
+                ..constraint.clone()
+            })
+            .collect();
+        Self { constraints }
+    }
+
+    /// The constraints for a project, without those guarded by a marker
+    pub fn get<'a>(
+        &'a self,
+        name: &'a PackageName,
+    ) -> impl Iterator<Item = &'a Requirement> {
+        self.constraints.iter().filter(move |constraint| {
+            &constraint.name == name && constraint.marker.is_none()
+        })
+    }
+
+    /// Narrows the requirements by the constraints,
+    /// failing with every conflict if any constraint contradicts a requirement
+    pub fn apply(&self, requirements: &[Requirement]) -> Result<Vec<Requirement>> {
+        let (requirements, conflicts) = self.narrow(requirements);
+        if !conflicts.is_empty() {
+            let conflicts: Vec<String> =
+                conflicts.iter().map(|c| c.to_string()).collect();
+            anyhow::bail!("Conflicting constraints: {}", conflicts.join(", "));
+        }
+        Ok(requirements)
+    }
+
+    /// Every constraint contradicting a requirement
+    pub fn conflicts(&self, requirements: &[Requirement]) -> Vec<ConstraintConflict> {
+        self.narrow(requirements).1
+    }
+
+    fn narrow(
+        &self,
+        requirements: &[Requirement],
+    ) -> (Vec<Requirement>, Vec<ConstraintConflict>) {
+        let mut narrowed = Vec::new();
+        let mut conflicts = Vec::new();
+
+        for requirement in requirements {
+            let mut requirement = requirement.clone();
+            for constraint in self.get(&requirement.name) {
+                let conflict = match (&requirement.url, &constraint.url) {
+                    (Some(url), Some(constraint_url)) => url != constraint_url,
+                    // The version behind a URL is unknown, so specifiers can't conflict
+                    (Some(_), None) => false,
+                    (None, Some(_)) => {
+                        requirement.url = constraint.url.clone();
+                        requirement.specifiers = Default::default();
+                        false
+                    }
+                    (None, None) => {
+                        let specifiers =
+                            requirement.specifiers.intersection(&constraint.specifiers);
+                        let satisfiable = specifiers.is_satisfiable();
+                        if satisfiable {
+                            requirement.specifiers = specifiers;
+                        }
+                        !satisfiable
+                    }
+                };
+
+                if conflict {
+                    conflicts.push(ConstraintConflict {
+                        requirement: requirement.clone(),
+                        constraint: constraint.clone(),
+                    });
+                }
+            }
+            narrowed.push(requirement);
+        }
+        (narrowed, conflicts)
+    }
+}
+
+impl fmt::Display for ConstraintConflict {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{} conflicts with constraint {}",
+            self.requirement, self.constraint
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ConstraintSet;
+    use crate::markers::MarkerEnvironment;
+    use crate::requirements::RequirementsFile;
+    use crate::Requirement;
+    use anyhow::Result;
+
+    fn requirements(requirements: &[&str]) -> Result<Vec<Requirement>> {
+        requirements.iter().map(|r| Requirement::new(r)).collect()
+    }
+
+    #[test]
+    fn test_constraints_apply() -> Result<()> {
+        let constraints = ConstraintSet::new(requirements(&[
+            "numpy>=1.20",
+            "numpy<2",
+            "torch @ https://example.com/torch-2.0.whl",
+            "pandas<2; python_version < '3.9'",
+        ])?);
+
+        let applied = constraints.apply(&requirements(&[
+            "numpy[dev]>1.22; os_name == 'nt'",
+            "torch>=2.0",
+            "pandas",
+        ])?)?;
+        let applied: Vec<String> = applied.iter().map(|r| r.to_string()).collect();
+        assert_eq!(
+            applied,
+            vec![
+                r#"numpy[dev]>1.22,>=1.20,<2; os_name == "nt""#,
+                "torch @ https://example.com/torch-2.0.whl",
+                "pandas",
+            ]
+        );
+
+        let environment = MarkerEnvironment::builder()
+            .python_full_version("3.8.10")
+            .build();
+        let applied = constraints
+            .for_environment(&environment)
+            .apply(&requirements(&["pandas"])?)?;
+        assert_eq!(applied[0].to_string(), "pandas<2");
+        Ok(())
+    }
+
+    #[test]
+    fn test_constraints_conflicts() -> Result<()> {
+        let constraints = ConstraintSet::new(requirements(&[
+            "numpy<2",
+            "numpy!=1.26.4",
+            "torch @ https://example.com/torch-2.0.whl",
+        ])?);
+
+        let conflicts = constraints.conflicts(&requirements(&[
+            "numpy==1.26.4",
+            "torch @ https://example.com/torch-2.1.whl",
+            "requests==2.0",
+        ])?);
+        assert_eq!(conflicts.len(), 2);
+        assert_eq!(
+            conflicts[0].to_string(),
+            "numpy==1.26.4,<2 conflicts with constraint numpy!=1.26.4"
+        );
+        assert_eq!(conflicts[1].constraint.name, "torch");
+        Ok(())
+    }
+
+    #[test]
+    fn test_constraints_negative() {
+        let files = vec!["-e .", "./downloads/numpy.whl"];
+
+        for content in files {
+            let file = RequirementsFile::parse(content, "constraints.txt").unwrap();
+            if let Ok(v) = ConstraintSet::from_requirements_file(&file) {
+                panic!("Oh no {:?}", v);
+            }
+        }
+    }
+}
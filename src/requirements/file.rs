@@ -0,0 +1,472 @@
+use crate::Requirement;
+use anyhow::{Context, Result};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// # pip requirements file
+/// The requirements, constraints and options of a `requirements.txt` file,
+/// with the files it includes through `-r` and `-c` already resolved
+///
+/// Lines may be continued with a trailing `\`, comments start with a `#`
+/// at the beginning of a line or after whitespace, and `${VARIABLE}`
+/// is replaced with the value of the environment variable.
+///
+/// ## Example Usage
+/// ```
+/// use pyver::requirements::{RequirementsFile, RequirementsItem};
+///
+/// let file = RequirementsFile::parse(
+///     "# Pinned dependencies\n\
+///      requests==2.31.0 \\\n    --hash=sha256:58cd2187c01e70e6e26505bca751777aa9f2ee0b7f4300988b709f44e013003f\n\
+///      -e ./local/project\n\
+///      --index-url https://pypi.org/simple\n",
+///     "requirements.txt",
+/// )
+/// .unwrap();
+///
+/// let requests = &file.requirements[0];
+/// assert_eq!(requests.line, 2);
+/// assert_eq!(requests.hashes.len(), 1);
+/// assert!(matches!(&requests.item, RequirementsItem::Named(r) if r.name == "requests"));
+///
+/// assert!(file.requirements[1].editable);
+/// assert_eq!(file.options[0].name, "--index-url");
+/// ```
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct RequirementsFile {
+    /// Requirements in the order they appear, included files in place
+    pub requirements: Vec<RequirementEntry>,
+    /// Requirements from `-c` constraint files
+    pub constraints: Vec<RequirementEntry>,
+    /// Global options such as `--index-url`
+    pub options: Vec<RequirementsOption>,
+}
+
+/// A requirement line of a requirements file
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RequirementEntry {
+    pub item: RequirementsItem,
+    /// Whether the requirement was given with `-e`
+    pub editable: bool,
+    /// Allowed `--hash` values (`sha256:...`)
+    pub hashes: Vec<String>,
+    /// The file the requirement was read from
+    pub path: PathBuf,
+    /// Line number (starting at 1) the requirement begins on
+    pub line: usize,
+}
+
+/// What a requirement line refers to
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum RequirementsItem {
+    /// A `PEP-508` requirement, `requests>=2.0`
+    Named(Box<Requirement>),
+    /// A path or URL without a project name, `./downloads/project.whl`
+    Unnamed(String),
+}
+
+/// A global option line of a requirements file
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RequirementsOption {
+    /// The option as written, `--index-url` or `-i`
+    pub name: String,
+    pub value: Option<String>,
+    /// The file the option was read from
+    pub path: PathBuf,
+    /// Line number (starting at 1) of the option
+    pub line: usize,
+}
+
+impl RequirementsFile {
+    /// Reads a requirements file and the files it includes
+    pub fn from_path(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let mut file = Self::default();
+        file.include(path, false, &mut Vec::new(), &env_variable)?;
+        Ok(file)
+    }
+
+    /// Parses the content of a requirements file,
+    /// included files are read relative to `path`
+    pub fn parse(content: &str, path: impl AsRef<Path>) -> Result<Self> {
+        let mut file = Self::default();
+        file.parse_content(
+            content,
+            path.as_ref(),
+            false,
+            &mut Vec::new(),
+            &env_variable,
+        )?;
+        Ok(file)
+    }
+
+    /// Reads an included file, `stack` holds the files currently being read
+    fn include(
+        &mut self,
+        path: &Path,
+        constraint: bool,
+        stack: &mut Vec<PathBuf>,
+        env: &dyn Fn(&str) -> Option<String>,
+    ) -> Result<()> {
+        let canonical = fs::canonicalize(path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        if stack.contains(&canonical) {
+            anyhow::bail!("{} includes itself", path.display());
+        }
+        let content = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+
+        stack.push(canonical);
+        self.parse_content(&content, path, constraint, stack, env)?;
+        stack.pop();
+        Ok(())
+    }
+
+    fn parse_content(
+        &mut self,
+        content: &str,
+        path: &Path,
+        constraint: bool,
+        stack: &mut Vec<PathBuf>,
+        env: &dyn Fn(&str) -> Option<String>,
+    ) -> Result<()> {
+        for (line_number, line) in logical_lines(content) {
+            let line = expand_variables(strip_comment(&line), env);
+            let line = line.trim();
+            if line.is_empty() {
+                continue;
+            }
+
+            self.parse_line(line, path, line_number, constraint, stack, env)
+                .with_context(|| format!("{}:{}", path.display(), line_number))?;
+        }
+        Ok(())
+    }
+
+    fn parse_line(
+        &mut self,
+        line: &str,
+        path: &Path,
+        line_number: usize,
+        constraint: bool,
+        stack: &mut Vec<PathBuf>,
+        env: &dyn Fn(&str) -> Option<String>,
+    ) -> Result<()> {
+        if !line.starts_with('-') {
+            let entry = parse_requirement(line, false, path, line_number)?;
+            match constraint {
+                true => self.constraints.push(entry),
+                false => self.requirements.push(entry),
+            }
+            return Ok(());
+        }
+
+        let (name, value) = split_option(line);
+        match name {
+            "-r" | "--requirement" | "-c" | "--constraint" => {
+                let value = match value {
+                    Some(v) => v,
+                    None => anyhow::bail!("{} needs a file", name),
+                };
+                // Nested requirement files of constraint files are constraints too
+                let nested_constraint =
+                    constraint || matches!(name, "-c" | "--constraint");
+                let included = match path.parent() {
+                    Some(parent) if !value.contains("://") => parent.join(value),
+                    _ => PathBuf::from(value),
+                };
+                self.include(&included, nested_constraint, stack, env)
+            }
+            "-e" | "--editable" => {
+                let value = match value {
+                    Some(v) => v,
+                    None => anyhow::bail!("{} needs a path or URL", name),
+                };
+                let entry = parse_requirement(value, true, path, line_number)?;
+                match constraint {
+                    true => self.constraints.push(entry),
+                    false => self.requirements.push(entry),
+                }
+                Ok(())
+            }
+            _ => {
+                self.options.push(RequirementsOption {
+                    name: name.to_string(),
+                    value: value.map(str::to_string),
+                    path: path.to_path_buf(),
+                    line: line_number,
+                });
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Parses a requirement and its trailing `--hash` options
+fn parse_requirement(
+    line: &str,
+    editable: bool,
+    path: &Path,
+    line_number: usize,
+) -> Result<RequirementEntry> {
+    // The options start at the first word beginning with `-`
+    let mut requirement_end = line.len();
+    let mut offset = 0;
+    for word in line.split_whitespace() {
+        let start = offset + line[offset..].find(word).unwrap_or(0);
+        if word.starts_with('-') && start > 0 {
+            requirement_end = start;
+            break;
+        }
+        offset = start + word.len();
+    }
+    let requirement = line[..requirement_end].trim();
+
+    let mut hashes = Vec::new();
+    let mut words = line[requirement_end..].split_whitespace();
+    while let Some(word) = words.next() {
+        let hash = match word.strip_prefix("--hash") {
+            Some("") => words.next(),
+            Some(hash) => hash.strip_prefix('='),
+            None => anyhow::bail!("Unsupported requirement option {}", word),
+        };
+        match hash {
+            Some(hash) if hash.contains(':') => hashes.push(hash.to_string()),
+            _ => anyhow::bail!("--hash needs an algorithm:digest value"),
+        }
+    }
+
+    let item = match Requirement::new(requirement) {
+        Ok(requirement) => RequirementsItem::Named(Box::new(requirement)),
+        Err(err) => {
+            let first = requirement.split_whitespace().next().unwrap_or("");
+            if first.contains(['/', '\\']) || first.starts_with('.') {
+                RequirementsItem::Unnamed(requirement.to_string())
+            } else {
+                return Err(err);
+            }
+        }
+    };
+
+    Ok(RequirementEntry {
+        item,
+        editable,
+        hashes,
+        path: path.to_path_buf(),
+        line: line_number,
+    })
+}
+
+/// Splits `-r file`, `-rfile`, `--requirement file` and `--requirement=file`
+fn split_option(line: &str) -> (&str, Option<&str>) {
+    let (name, value) = if line.starts_with("--") {
+        match line.find(|c: char| c == '=' || c.is_whitespace()) {
+            Some(i) => (&line[..i], &line[i + 1..]),
+            None => (line, ""),
+        }
+    } else {
+        let split = line.char_indices().nth(2).map_or(line.len(), |(i, _)| i);
+        (&line[..split], line[split..].trim_start_matches('='))
+    };
+
+    let value = value.trim();
+    match value.is_empty() {
+        true => (name, None),
+        false => (name, Some(value)),
+    }
+}
+
+/// Joins lines ending in `\` with the next line,
+/// yielding each logical line with the number of its first line
+fn logical_lines(content: &str) -> Vec<(usize, String)> {
+    let mut lines = Vec::new();
+    let mut current: Option<(usize, String)> = None;
+
+    for (i, line) in content.lines().enumerate() {
+        let (number, mut joined) = current.take().unwrap_or((i + 1, String::new()));
+        match line.strip_suffix('\\') {
+            Some(line) => {
+                joined.push_str(line);
+                current = Some((number, joined));
+            }
+            None => {
+                joined.push_str(line);
+                lines.push((number, joined));
+            }
+        }
+    }
+    if let Some(line) = current {
+        lines.push(line);
+    }
+    lines
+}
+
+/// Removes a `#` comment at the start of the line or after whitespace
+fn strip_comment(line: &str) -> &str {
+    let mut previous = ' ';
+    for (i, c) in line.char_indices() {
+        if c == '#' && previous.is_whitespace() {
+            return &line[..i];
+        }
+        previous = c;
+    }
+    line
+}
+
+/// Replaces `${VARIABLE}` with its value, pip only accepts
+/// uppercase letters, digits and underscores in the name
+fn expand_variables(line: &str, env: &dyn Fn(&str) -> Option<String>) -> String {
+    let mut expanded = String::new();
+    let mut rest = line;
+
+    while let Some(start) = rest.find("${") {
+        expanded.push_str(&rest[..start]);
+        let after = &rest[start + 2..];
+        let name = after.find('}').map(|end| &after[..end]).filter(|name| {
+            !name.is_empty()
+                && name
+                    .chars()
+                    .all(|c| c.is_ascii_uppercase() || c.is_ascii_digit() || c == '_')
+        });
+
+        match name.and_then(|name| env(name).map(|value| (name, value))) {
+            Some((name, value)) => {
+                expanded.push_str(&value);
+                rest = &after[name.len() + 1..];
+            }
+            None => {
+                expanded.push_str("${");
+                rest = after;
+            }
+        }
+    }
+    expanded.push_str(rest);
+    expanded
+}
+
+fn env_variable(name: &str) -> Option<String> {
+    std::env::var(name).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{expand_variables, RequirementsFile, RequirementsItem};
+    use anyhow::Result;
+    use std::fs;
+
+    #[test]
+    fn test_requirements_parsing() -> Result<()> {
+        let content = r#"
+# Comment
+requests [security] >= 2.8.1, == 2.8.* ; python_version < "2.7" # Inline
+urllib3 @ https://github.com/urllib3/urllib3/archive/refs/tags/1.26.8.zip#sha256=abc
+./downloads/numpy-1.9.2-cp34-none-win32.whl
+-e git+https://github.com/pypa/pip.git@22.0#egg=pip
+--editable=.
+pyyaml==6.0.1 \
+    --hash=sha256:0000 \
+    --hash sha256:1111
+-i https://pypi.org/simple
+--pre
+"#;
+        let file = RequirementsFile::parse(content, "requirements.txt")?;
+        assert_eq!(file.requirements.len(), 6);
+        assert!(file.constraints.is_empty());
+
+        let lines: Vec<usize> = file.requirements.iter().map(|r| r.line).collect();
+        assert_eq!(lines, vec![3, 4, 5, 6, 7, 8]);
+
+        match &file.requirements[0].item {
+            RequirementsItem::Named(requirement) => {
+                assert_eq!(requirement.name, "requests");
+                assert!(requirement.marker.is_some());
+            }
+            item => panic!("Oh no {:?}", item),
+        }
+        match &file.requirements[1].item {
+            RequirementsItem::Named(requirement) => assert!(requirement.url.is_some()),
+            item => panic!("Oh no {:?}", item),
+        }
+        assert_eq!(
+            file.requirements[2].item,
+            RequirementsItem::Unnamed(
+                "./downloads/numpy-1.9.2-cp34-none-win32.whl".into()
+            )
+        );
+        assert!(file.requirements[3].editable);
+        assert_eq!(
+            file.requirements[4].item,
+            RequirementsItem::Unnamed(".".into())
+        );
+        assert_eq!(
+            file.requirements[5].hashes,
+            vec!["sha256:0000", "sha256:1111"]
+        );
+
+        assert_eq!(file.options.len(), 2);
+        assert_eq!(file.options[0].name, "-i");
+        assert_eq!(
+            file.options[0].value.as_deref(),
+            Some("https://pypi.org/simple")
+        );
+        assert_eq!(file.options[1].line, 12);
+        Ok(())
+    }
+
+    #[test]
+    fn test_requirements_includes() -> Result<()> {
+        let dir = std::env::temp_dir()
+            .join(format!("pyver-requirements-{}", std::process::id()));
+        fs::create_dir_all(dir.join("nested"))?;
+        fs::write(
+            dir.join("requirements.txt"),
+            "-r nested/base.txt\n-c constraints.txt\nflask\n",
+        )?;
+        fs::write(dir.join("nested/base.txt"), "\n\nclick>=8\n")?;
+        fs::write(dir.join("constraints.txt"), "click<9\n-r nested/base.txt\n")?;
+        fs::write(dir.join("loop.txt"), "-r loop.txt\n")?;
+
+        let file = RequirementsFile::from_path(dir.join("requirements.txt"))?;
+        assert_eq!(file.requirements.len(), 2);
+        assert_eq!(file.requirements[0].line, 3);
+        assert!(file.requirements[0].path.ends_with("nested/base.txt"));
+        assert_eq!(file.constraints.len(), 2);
+
+        let error = RequirementsFile::from_path(dir.join("loop.txt")).unwrap_err();
+        assert!(format!("{:#}", error).contains("includes itself"));
+
+        fs::remove_dir_all(dir)?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_requirements_variables() {
+        let env = |name: &str| match name {
+            "TOKEN" => Some("secret".to_string()),
+            _ => None,
+        };
+        assert_eq!(
+            expand_variables("https://${TOKEN}@host/${MISSING}/${token}", &env),
+            "https://secret@host/${MISSING}/${token}"
+        );
+    }
+
+    #[test]
+    fn test_requirements_negative() {
+        let files = vec![
+            "name >=1.0 <2.0",
+            "-r",
+            "-e",
+            "name --hash",
+            "name --hash=abc",
+            "name --no-binary :all:",
+            "-r does-not-exist.txt",
+        ];
+
+        for content in files {
+            if let Ok(v) = RequirementsFile::parse(content, "requirements.txt") {
+                panic!("Oh no {:?}", v);
+            }
+        }
+    }
+}
@@ -0,0 +1,18 @@
+//! # Requirements Files
+//! Parsing of pip's `requirements.txt` files, including nested
+//! `-r` requirement and `-c` constraint files
+//!
+//! Read more at <https://pip.pypa.io/en/stable/reference/requirements-file-format/>
+//!
+//! Importing Example
+//! ```
+//! use pyver::requirements::{
+//!     ConstraintSet, RequirementEntry, RequirementsFile, RequirementsItem,
+//! };
+//! ```
+
+mod constraints;
+mod file;
+
+pub use constraints::*;
+pub use file::*;
@@ -0,0 +1,309 @@
+//! # RPM versions
+//! The `Epoch`, `Version` and `Release` of RPM packages built from Python
+//! projects, written like Fedora's Python packaging guidelines spell them
+//! (`1.0~rc1`, `1.0^post1`) and compared like `rpmvercmp` does
+//!
+//! Read more at <https://docs.fedoraproject.org/en-US/packaging-guidelines/Versioning/>
+//!
+//! ## Example Usage
+//! ```
+//! use pyver::PackageVersion;
+//!
+//! let rc = PackageVersion::new("1.0rc1").unwrap().to_rpm_version();
+//! let post = PackageVersion::new("1.0.post1").unwrap().to_rpm_version();
+//! assert_eq!(rc.to_string(), "1.0~rc1-1");
+//! assert_eq!(post.version, "1.0^post1");
+//! assert!(rc < post);
+//! ```
+
+use crate::PackageVersion;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::cmp::Ordering;
+use core::fmt::{self, Write};
+
+/// # RPM version
+/// The epoch, version and release of an RPM package, ordered like RPM
+/// orders them
+#[derive(Clone, Debug)]
+pub struct RpmVersion {
+    /// `Epoch`, the epoch of the Python version
+    pub epoch: u32,
+    /// `Version`, the rest of the Python version
+    pub version: String,
+    /// `Release`, counting the builds of the same version (`1`, `2.fc40`)
+    pub release: String,
+}
+
+impl PackageVersion {
+    /// The RPM version of the first build (`Release: 1`) of this version
+    ///
+    /// Pre-releases follow a `~` and developmental releases a `~~`, so they
+    /// sort before the release (`1.0~rc1`, `1.0~~dev2`), post-releases
+    /// follow a `^` so they sort after it and before the next release
+    /// (`1.0^post1`). The local version label is kept after a `+`, which
+    /// RPM compares by its letters, so a local version is only ordered
+    /// right against the release and other local versions of it.
+    ///
+    /// ```
+    ///# use pyver::PackageVersion;
+    /// let rpm = PackageVersion::new("2!1.0b2.post1.dev3").unwrap().to_rpm_version();
+    /// assert_eq!(rpm.epoch, 2);
+    /// assert_eq!(rpm.version, "1.0~b2^post1~~dev3");
+    /// assert_eq!(rpm.to_string(), "2:1.0~b2^post1~~dev3-1");
+    /// ```
+    pub fn to_rpm_version(&self) -> RpmVersion {
+        let segments: Vec<String> = self
+            .release()
+            .segments()
+            .iter()
+            .map(|segment| segment.to_string())
+            .collect();
+        let mut version = segments.join(".");
+
+        if let Some(pre) = self.pre() {
+            let _ = write!(version, "~{}{}", pre.phase(), pre.number().unwrap_or(0));
+        }
+        if let Some(post) = self.post() {
+            let _ = write!(version, "^post{}", post.post_num.unwrap_or(0));
+        }
+        if let Some(dev) = self.dev() {
+            let _ = write!(version, "~~dev{}", dev.dev_num.unwrap_or(0));
+        }
+        if let Some(local) = self.normalized_local() {
+            version.push('+');
+            version.push_str(&local);
+        }
+
+        RpmVersion {
+            epoch: self.epoch(),
+            version,
+            release: "1".to_string(),
+        }
+    }
+}
+
+/// Writes the `EVR` form (`1.0-1`, `2:1.0~rc1-3`), leaving out epoch 0
+impl fmt::Display for RpmVersion {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.epoch != 0 {
+            write!(f, "{}:", self.epoch)?;
+        }
+        write!(f, "{}-{}", self.version, self.release)
+    }
+}
+
+/// Compares the epochs, then the versions and the releases with
+/// [`rpmvercmp`]
+impl Ord for RpmVersion {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.epoch
+            .cmp(&other.epoch)
+            .then_with(|| rpmvercmp(&self.version, &other.version))
+            .then_with(|| rpmvercmp(&self.release, &other.release))
+    }
+}
+
+impl PartialOrd for RpmVersion {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Equal when RPM sees the same version (`1.0-1` and `1.00-01`)
+impl PartialEq for RpmVersion {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for RpmVersion {}
+
+/// Compares two `Version` or `Release` strings like RPM's `rpmvercmp`
+///
+/// The strings are split into runs of digits and runs of letters, other
+/// characters only separate them. Numbers compare by value and sort after
+/// letters, `~` sorts before everything, even the end of the string, and
+/// `^` sorts after the end but before everything else.
+///
+/// ```
+/// use pyver::rpm::rpmvercmp;
+/// use std::cmp::Ordering;
+///
+/// assert_eq!(rpmvercmp("1.0~rc1", "1.0"), Ordering::Less);
+/// assert_eq!(rpmvercmp("1.0^post1", "1.0"), Ordering::Greater);
+/// assert_eq!(rpmvercmp("1.0^post1", "1.0.1"), Ordering::Less);
+/// assert_eq!(rpmvercmp("1.010", "1.10"), Ordering::Equal);
+/// ```
+pub fn rpmvercmp(a: &str, b: &str) -> Ordering {
+    if a == b {
+        return Ordering::Equal;
+    }
+    let (mut one, mut two) = (a.as_bytes(), b.as_bytes());
+    let is_separator = |c: &u8| !c.is_ascii_alphanumeric() && *c != b'~' && *c != b'^';
+
+    loop {
+        while one.first().is_some_and(is_separator) {
+            one = &one[1..];
+        }
+        while two.first().is_some_and(is_separator) {
+            two = &two[1..];
+        }
+
+        match (one.first(), two.first()) {
+            (Some(b'~'), Some(b'~')) | (Some(b'^'), Some(b'^')) => {
+                one = &one[1..];
+                two = &two[1..];
+                continue;
+            }
+            (Some(b'~'), _) => return Ordering::Less,
+            (_, Some(b'~')) => return Ordering::Greater,
+            (None, Some(b'^')) => return Ordering::Less,
+            (Some(b'^'), None) => return Ordering::Greater,
+            (_, Some(b'^')) => return Ordering::Greater,
+            (Some(b'^'), _) => return Ordering::Less,
+            (None, None) => return Ordering::Equal,
+            (None, Some(_)) => return Ordering::Less,
+            (Some(_), None) => return Ordering::Greater,
+            (Some(_), Some(_)) => {}
+        }
+
+        let is_number = one[0].is_ascii_digit();
+        let run = |s: &[u8]| {
+            s.iter()
+                .take_while(|c| {
+                    if is_number {
+                        c.is_ascii_digit()
+                    } else {
+                        c.is_ascii_alphabetic()
+                    }
+                })
+                .count()
+        };
+        let (one_len, two_len) = (run(one), run(two));
+        if two_len == 0 {
+            // Numbers sort after letters
+            return if is_number {
+                Ordering::Greater
+            } else {
+                Ordering::Less
+            };
+        }
+
+        let (mut one_run, mut two_run) = (&one[..one_len], &two[..two_len]);
+        if is_number {
+            while one_run.len() > 1 && one_run[0] == b'0' {
+                one_run = &one_run[1..];
+            }
+            while two_run.len() > 1 && two_run[0] == b'0' {
+                two_run = &two_run[1..];
+            }
+            let by_length = one_run.len().cmp(&two_run.len());
+            if by_length != Ordering::Equal {
+                return by_length;
+            }
+        }
+        let by_bytes = one_run.cmp(two_run);
+        if by_bytes != Ordering::Equal {
+            return by_bytes;
+        }
+        one = &one[one_len..];
+        two = &two[two_len..];
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{rpmvercmp, RpmVersion};
+    use crate::PackageVersion;
+    use std::cmp::Ordering;
+
+    #[test]
+    fn test_rpmvercmp() {
+        // From the tests of rpmvercmp in RPM
+        let cases = [
+            ("1.0", "1.0", Ordering::Equal),
+            ("1.0", "2.0", Ordering::Less),
+            ("2.0.1a", "2.0.1", Ordering::Greater),
+            ("5.5p1", "5.5p2", Ordering::Less),
+            ("5.5p10", "5.5p1", Ordering::Greater),
+            ("10xyz", "10.1xyz", Ordering::Less),
+            ("xyz.4", "8", Ordering::Less),
+            ("8", "xyz.4", Ordering::Greater),
+            ("1.a", "1.1", Ordering::Less),
+            ("2_0", "2.0", Ordering::Equal),
+            ("a+", "a_", Ordering::Equal),
+            ("1.0010", "1.9", Ordering::Greater),
+            ("1.0~rc1", "1.0", Ordering::Less),
+            ("1.0~rc1", "1.0~rc1~git123", Ordering::Greater),
+            ("1.0^", "1.0", Ordering::Greater),
+            ("1.0^git1", "1.0^git2", Ordering::Less),
+            ("1.0^git1", "1.01", Ordering::Less),
+            ("1.0^20160101^git1", "1.0^20160101", Ordering::Greater),
+            ("1.0~rc1^git1", "1.0~rc1", Ordering::Greater),
+            ("1.0^git1~pre", "1.0^git1", Ordering::Less),
+        ];
+        for (a, b, expected) in cases {
+            assert_eq!(rpmvercmp(a, b), expected, "{} and {}", a, b);
+            assert_eq!(rpmvercmp(b, a), expected.reverse(), "{} and {}", b, a);
+        }
+    }
+
+    #[test]
+    fn test_to_rpm_version() {
+        let versions = [
+            ("1.0", "1.0"),
+            ("v1.0-RC1", "1.0~rc1"),
+            ("1.0a", "1.0~a0"),
+            ("1.0.dev2", "1.0~~dev2"),
+            ("1.0.post1", "1.0^post1"),
+            ("1.0rc1.post2", "1.0~rc1^post2"),
+            ("1.0+Ubuntu-1", "1.0+ubuntu.1"),
+        ];
+        for (version, expected) in versions {
+            let rpm = PackageVersion::new(version).unwrap().to_rpm_version();
+            assert_eq!((rpm.epoch, rpm.version.as_str()), (0, expected));
+            assert_eq!(rpm.release, "1");
+        }
+    }
+
+    #[test]
+    fn test_rpm_version_order() {
+        let versions = [
+            "0.9",
+            "1.0.dev0",
+            "1.0a1.dev1",
+            "1.0a1",
+            "1.0a1.post1.dev1",
+            "1.0a1.post1",
+            "1.0a2",
+            "1.0b1",
+            "1.0rc1.dev3",
+            "1.0rc1",
+            "1.0",
+            "1.0.post1.dev1",
+            "1.0.post1",
+            "1.0.post2",
+            "1.0.0.1",
+            "1.0.1",
+            "1!0.1",
+        ];
+        let rpms: Vec<RpmVersion> = versions
+            .iter()
+            .map(|v| PackageVersion::new(v).unwrap().to_rpm_version())
+            .collect();
+        for pair in rpms.windows(2) {
+            assert!(pair[0] < pair[1], "{} and {}", pair[0], pair[1]);
+        }
+
+        let mut rebuilt = rpms[10].clone();
+        rebuilt.release = "2.fc40".to_string();
+        assert!(rpms[10] < rebuilt);
+        let padded = RpmVersion {
+            epoch: 0,
+            version: "1.00".to_string(),
+            release: "01".to_string(),
+        };
+        assert_eq!(rpms[10], padded);
+    }
+}
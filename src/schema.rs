@@ -0,0 +1,196 @@
+//! [`JsonSchema`] implementations, describing each type as the string
+//! it's serialized as along with a pattern of the strings it accepts
+//!
+//! The patterns are plain ECMA-262 regular expressions as JSON Schema
+//! requires, without flags, so letters match in either ASCII case
+//! like they do when parsing.
+
+use crate::specifiers::{Specifier, SpecifierSet};
+use crate::PackageVersion;
+#[cfg(feature = "std")]
+use crate::Requirement;
+use alloc::borrow::Cow;
+use alloc::format;
+use alloc::string::String;
+use schemars::{json_schema, JsonSchema, Schema, SchemaGenerator};
+
+/// The version regex from the appendix of `PEP-440`
+/// with each letter as a class of both cases
+const VERSION_PATTERN: &str = concat!(
+    r"[vV]?(?:[0-9]+!)?[0-9]+(?:\.[0-9]+)*",
+    r"(?:[-_.]?(?:[pP][rR][eE][vV][iI][eE][wW]|[aA][lL][pP][hH][aA]|[bB][eE][tT][aA]",
+    r"|[pP][rR][eE]|[rR][cC]|[aAbBcC])[-_.]?[0-9]*)?",
+    r"(?:-[0-9]+|[-_.]?(?:[pP][oO][sS][tT]|[rR][eE][vV]|[rR])[-_.]?[0-9]*)?",
+    r"(?:[-_.]?[dD][eE][vV][-_.]?[0-9]*)?",
+    r"(?:\+[a-zA-Z0-9]+(?:[-_.][a-zA-Z0-9]+)*)?",
+);
+
+/// An operator and a version, or a prefix for `==` and `!=`,
+/// `===` takes anything up to the end (`stop`) of the specifier
+fn specifier_pattern(stop: &str) -> String {
+    format!(
+        r"(?:===\s*[^\s{}]+|(?:~=|==|!=|<=|>=|<|>)\s*{}(?:\.\*)?)",
+        stop, VERSION_PATTERN
+    )
+}
+
+fn string_schema(description: &str, pattern: &str) -> Schema {
+    json_schema!({
+        "type": "string",
+        "description": description,
+        "pattern": pattern,
+    })
+}
+
+impl JsonSchema for PackageVersion {
+    fn schema_name() -> Cow<'static, str> {
+        "PackageVersion".into()
+    }
+
+    fn schema_id() -> Cow<'static, str> {
+        "pyver::PackageVersion".into()
+    }
+
+    fn json_schema(_: &mut SchemaGenerator) -> Schema {
+        string_schema(
+            "A PEP 440 version",
+            &format!(r"^\s*{}\s*$", VERSION_PATTERN),
+        )
+    }
+}
+
+impl JsonSchema for Specifier {
+    fn schema_name() -> Cow<'static, str> {
+        "Specifier".into()
+    }
+
+    fn schema_id() -> Cow<'static, str> {
+        "pyver::specifiers::Specifier".into()
+    }
+
+    fn json_schema(_: &mut SchemaGenerator) -> Schema {
+        string_schema(
+            "A PEP 440 version specifier",
+            &format!(r"^\s*{}\s*$", specifier_pattern("")),
+        )
+    }
+}
+
+impl JsonSchema for SpecifierSet {
+    fn schema_name() -> Cow<'static, str> {
+        "SpecifierSet".into()
+    }
+
+    fn schema_id() -> Cow<'static, str> {
+        "pyver::specifiers::SpecifierSet".into()
+    }
+
+    fn json_schema(_: &mut SchemaGenerator) -> Schema {
+        let specifier = specifier_pattern(",");
+        string_schema(
+            "Comma separated PEP 440 version specifiers",
+            &format!(r"^\s*(?:{0}(?:\s*,\s*{0})*)?\s*$", specifier),
+        )
+    }
+}
+
+/// Only the project name the requirement starts with is matched,
+/// the rest is checked when deserializing
+#[cfg(feature = "std")]
+impl JsonSchema for Requirement {
+    fn schema_name() -> Cow<'static, str> {
+        "Requirement".into()
+    }
+
+    fn schema_id() -> Cow<'static, str> {
+        "pyver::Requirement".into()
+    }
+
+    fn json_schema(_: &mut SchemaGenerator) -> Schema {
+        string_schema(
+            "A PEP 508 dependency specification",
+            r"^\s*[a-zA-Z0-9](?:[a-zA-Z0-9._-]*[a-zA-Z0-9])?",
+        )
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use crate::specifiers::{Specifier, SpecifierSet};
+    use crate::{PackageVersion, Requirement};
+    use regex::Regex;
+    use schemars::{schema_for, JsonSchema};
+
+    fn pattern<T: JsonSchema>() -> Regex {
+        let schema = schema_for!(T);
+        assert_eq!(schema.get("type").and_then(|t| t.as_str()), Some("string"));
+        let pattern = schema.get("pattern").and_then(|p| p.as_str()).unwrap();
+        Regex::new(pattern).unwrap()
+    }
+
+    #[test]
+    fn test_version_schema() {
+        let pattern = pattern::<PackageVersion>();
+        let versions = vec![
+            "1.0",
+            " V1!2.0-RC1.POST2.dev3+Local.7 ",
+            "1.0-1",
+            "1.0a",
+            "1.0.post.dev",
+            "1.0.",
+            "1.0+",
+            "1.0 junk",
+            "junk",
+            "",
+        ];
+        for version in versions {
+            assert_eq!(
+                pattern.is_match(version),
+                PackageVersion::new(version).is_ok(),
+                "{}",
+                version
+            );
+        }
+    }
+
+    #[test]
+    fn test_specifier_schema() {
+        let specifier = pattern::<Specifier>();
+        let set = pattern::<SpecifierSet>();
+        let specifiers = vec![
+            ">=1.0",
+            " ~= 1.4.5 ",
+            "==1.1.*",
+            "!=2.0rc1",
+            "===foobar",
+            "==1.0+local",
+            "=>1.0",
+            "1.0",
+            "==",
+        ];
+        for spec in specifiers {
+            assert_eq!(
+                specifier.is_match(spec),
+                Specifier::new(spec).is_ok(),
+                "{}",
+                spec
+            );
+        }
+
+        let sets = vec![("", true), (">=1.0, <2.0,!=1.5", true), (">=1.0,", false)];
+        for (spec, valid) in sets {
+            assert_eq!(set.is_match(spec), valid, "{}", spec);
+            assert_eq!(SpecifierSet::new(spec).is_ok(), valid, "{}", spec);
+        }
+    }
+
+    #[test]
+    fn test_requirement_schema() {
+        let pattern = pattern::<Requirement>();
+        for requirement in ["requests[security]>=2.8.1", "pyver ; os_name == 'nt'"] {
+            assert!(pattern.is_match(requirement));
+            assert!(Requirement::new(requirement).is_ok());
+        }
+        assert!(!pattern.is_match(">=1.0"));
+    }
+}
@@ -0,0 +1,246 @@
+//! # Versions from version control
+//! Derives versions from the state of a checkout like `setuptools_scm`,
+//! from the latest tag, the commits since then and uncommitted changes
+//!
+//! Read more at <https://setuptools-scm.readthedocs.io/en/latest/extending/>
+//!
+//! ## Example Usage
+//! ```
+//! use pyver::scm::{LocalScheme, ScmState, VersionScheme};
+//! use pyver::PackageVersion;
+//!
+//! // `git describe --tags --long` printed `v1.4.2-3-g1a2b3c4`
+//! let state = ScmState {
+//!     tag: PackageVersion::new("v1.4.2").unwrap(),
+//!     distance: 3,
+//!     node: Some("1a2b3c4".to_string()),
+//!     dirty: false,
+//!     date: None,
+//! };
+//! let version = state
+//!     .version(VersionScheme::GuessNextDev, LocalScheme::NodeAndDate)
+//!     .unwrap();
+//! assert_eq!(version.to_string(), "1.4.3.dev3+g1a2b3c4");
+//! ```
+
+use crate::PackageVersion;
+use anyhow::Result;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// The state of a checkout relative to its latest tag
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ScmState {
+    /// The version of the latest tag
+    pub tag: PackageVersion,
+    /// Number of commits since the tag
+    pub distance: u32,
+    /// Short hash of the current commit, written as `g<hash>` like git does
+    pub node: Option<String>,
+    /// Whether there are uncommitted changes
+    pub dirty: bool,
+    /// Date of a dirty build as `YYYYMMDD`, `None` for today (UTC)
+    pub date: Option<String>,
+}
+
+/// How the public version is derived from the tag
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub enum VersionScheme {
+    /// `guess-next-dev`, a dev release of the guessed next version
+    /// (`1.4.2` to `1.4.3.dev3`, `1.0rc1` to `1.0rc2.dev3`)
+    GuessNextDev,
+    /// `post-release`, a post-release of the tag (`1.4.2` to `1.4.2.post3`)
+    PostRelease,
+}
+
+/// Which local version label is added to untagged builds
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub enum LocalScheme {
+    /// `node-and-date`, the commit (`+g1a2b3c4`) and for dirty
+    /// checkouts the date as well (`+g1a2b3c4.d20240115`)
+    NodeAndDate,
+    /// `dirty-tag`, `+dirty` for dirty checkouts
+    DirtyTag,
+    /// `no-local-version`, no label at all
+    NoLocalVersion,
+}
+
+impl ScmState {
+    /// Whether the current commit is the tagged one without any changes
+    pub fn is_exact(&self) -> bool {
+        self.distance == 0 && !self.dirty
+    }
+
+    /// The version of the checkout, the tag itself for an exact checkout
+    pub fn version(
+        &self,
+        version_scheme: VersionScheme,
+        local_scheme: LocalScheme,
+    ) -> Result<PackageVersion> {
+        if self.is_exact() {
+            return Ok(self.tag.clone());
+        }
+
+        let version = match version_scheme {
+            VersionScheme::GuessNextDev => {
+                guess_next(&self.tag)?.with_dev(Some(self.distance))
+            }
+            VersionScheme::PostRelease => {
+                if self.tag.is_postrelease() || self.tag.is_devrelease() {
+                    anyhow::bail!("Can't add a post-release to tag {}", self.tag);
+                }
+                self.tag.without_local().with_post(Some(self.distance))
+            }
+        };
+        version.with_local(self.local(local_scheme)?.as_deref())
+    }
+
+    /// The local version label for the scheme, without the `+`
+    fn local(&self, local_scheme: LocalScheme) -> Result<Option<String>> {
+        let local = match local_scheme {
+            LocalScheme::NodeAndDate => {
+                let node = match &self.node {
+                    Some(node) if self.distance > 0 => Some(format!("g{}", node)),
+                    _ => None,
+                };
+                let date = match self.dirty {
+                    true => Some(format!("d{}", self.date()?)),
+                    false => None,
+                };
+                match (node, date) {
+                    (Some(node), Some(date)) => Some(format!("{}.{}", node, date)),
+                    (node, date) => node.or(date),
+                }
+            }
+            LocalScheme::DirtyTag if self.dirty => Some("dirty".to_string()),
+            LocalScheme::DirtyTag | LocalScheme::NoLocalVersion => None,
+        };
+        Ok(local)
+    }
+
+    /// The given build date or today's
+    fn date(&self) -> Result<String> {
+        match &self.date {
+            Some(date)
+                if date.len() == 8 && date.bytes().all(|b| b.is_ascii_digit()) =>
+            {
+                Ok(date.clone())
+            }
+            Some(date) => anyhow::bail!("Date {} isn't written as YYYYMMDD", date),
+            None => {
+                let seconds = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs();
+                let (year, month, day) = civil_from_days((seconds / 86400) as i64);
+                Ok(format!("{:04}{:02}{:02}", year, month, day))
+            }
+        }
+    }
+}
+
+/// The version following a tag as guessed by `setuptools_scm`:
+/// the tag's release for a dev release, otherwise the tag with its
+/// last number incremented
+fn guess_next(tag: &PackageVersion) -> Result<PackageVersion> {
+    let tag = tag.without_local();
+    if tag.is_devrelease() {
+        Ok(tag.with_dev(None))
+    } else if tag.is_postrelease() {
+        tag.bump_post()
+    } else if tag.is_prerelease() {
+        tag.bump_pre()
+    } else {
+        tag.bump_release(tag.release().len() - 1)
+    }
+}
+
+/// Year, month and day of a day count since 1970-01-01
+/// (<http://howardhinnant.github.io/date_algorithms.html#civil_from_days>)
+pub(crate) fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let z = days + 719468;
+    let era = z.div_euclid(146097);
+    let doe = z.rem_euclid(146097);
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    (year, month, day)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{civil_from_days, LocalScheme, ScmState, VersionScheme};
+    use crate::PackageVersion;
+    use anyhow::Result;
+
+    fn state(tag: &str, distance: u32, dirty: bool) -> Result<ScmState> {
+        Ok(ScmState {
+            tag: PackageVersion::new(tag)?,
+            distance,
+            node: Some("1a2b3c4".to_string()),
+            dirty,
+            date: Some("20240115".to_string()),
+        })
+    }
+
+    #[test]
+    fn test_guess_next_dev() -> Result<()> {
+        let versions = vec![
+            (state("1.4.2", 0, false)?, "1.4.2"),
+            (state("1.4.2", 3, false)?, "1.4.3.dev3+g1a2b3c4"),
+            (state("1.4.2", 3, true)?, "1.4.3.dev3+g1a2b3c4.d20240115"),
+            (state("1.4.2", 0, true)?, "1.4.3.dev0+d20240115"),
+            (state("v2", 1, false)?, "3.dev1+g1a2b3c4"),
+            (state("1.0rc1", 2, false)?, "1.0rc2.dev2+g1a2b3c4"),
+            (state("1.0.post1", 2, false)?, "1.0.post2.dev2+g1a2b3c4"),
+            (state("1.0.dev4", 2, false)?, "1.0.dev2+g1a2b3c4"),
+        ];
+        for (state, version) in versions {
+            let derived =
+                state.version(VersionScheme::GuessNextDev, LocalScheme::NodeAndDate)?;
+            assert_eq!(derived.to_string(), version);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_post_release() -> Result<()> {
+        let version = |state: ScmState, local_scheme| {
+            state
+                .version(VersionScheme::PostRelease, local_scheme)
+                .map(|v| v.to_string())
+        };
+        assert_eq!(
+            version(state("1.4.2", 3, false)?, LocalScheme::NoLocalVersion)?,
+            "1.4.2.post3"
+        );
+        assert_eq!(
+            version(state("1.0rc1", 3, true)?, LocalScheme::DirtyTag)?,
+            "1.0rc1.post3+dirty"
+        );
+        assert_eq!(
+            version(state("1.4.2", 3, false)?, LocalScheme::DirtyTag)?,
+            "1.4.2.post3"
+        );
+        assert!(version(state("1.0.post1", 3, false)?, LocalScheme::DirtyTag).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_dates() -> Result<()> {
+        assert_eq!(civil_from_days(0), (1970, 1, 1));
+        assert_eq!(civil_from_days(19737), (2024, 1, 15));
+        assert_eq!(civil_from_days(11016), (2000, 2, 29));
+
+        let mut dirty = state("1.0", 1, true)?;
+        dirty.date = None;
+        let version =
+            dirty.version(VersionScheme::GuessNextDev, LocalScheme::NodeAndDate)?;
+        assert!(version.local().unwrap().starts_with("g1a2b3c4.d20"));
+
+        dirty.date = Some("2024-01-15".to_string());
+        assert!(dirty
+            .version(VersionScheme::GuessNextDev, LocalScheme::NodeAndDate)
+            .is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,217 @@
+use crate::ids::PreHeader;
+use crate::PackageVersion;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::fmt;
+use semver::{BuildMetadata, Prerelease};
+
+/// A part of a version that [`PackageVersion::to_semver`] couldn't carry
+/// over to the `semver` version
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+#[non_exhaustive]
+pub enum SemverWarning {
+    /// The epoch (`1` of `1!2.0`), versions of other epochs compare as equal
+    Epoch(u32),
+    /// Release segments after the patch number (`4` of `1.2.3.4`)
+    ExtraSegments(Vec<u64>),
+    /// The post-release number (`1` of `1.0.post1`)
+    PostRelease(u32),
+    /// The local version label (`ubuntu.1` of `1.0+ubuntu.1`)
+    Local(String),
+}
+
+impl fmt::Display for SemverWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Epoch(epoch) => write!(f, "Dropped the epoch {}", epoch),
+            Self::ExtraSegments(segments) => {
+                write!(f, "Dropped the release segments {:?}", segments)
+            }
+            Self::PostRelease(post) => write!(f, "Dropped the post-release {}", post),
+            Self::Local(local) => write!(f, "Dropped the local version {}", local),
+        }
+    }
+}
+
+impl PackageVersion {
+    /// The closest `semver` version and what it had to leave out
+    ///
+    /// The release is padded or cut to three segments, pre-releases become
+    /// `alpha`, `beta` and `rc` identifiers and developmental releases
+    /// `dev` ones (`1.0a1.dev2` is `1.0.0-alpha.1.dev.2`). Semver has no
+    /// epochs, post-releases or local versions, which are dropped with a
+    /// [`SemverWarning`] each.
+    ///
+    /// Developmental releases sort after the release or pre-release they
+    /// lead up to in semver, `1.0.0-dev.1` is newer than `1.0.0-alpha.1`.
+    ///
+    /// ```
+    ///# use pyver::{PackageVersion, SemverWarning};
+    /// let version = PackageVersion::new("1.2rc1").unwrap();
+    /// let (semver, warnings) = version.to_semver();
+    /// assert_eq!(semver.to_string(), "1.2.0-rc.1");
+    /// assert!(warnings.is_empty());
+    ///
+    /// let version = PackageVersion::new("1!2.0.post1+local").unwrap();
+    /// let (semver, warnings) = version.to_semver();
+    /// assert_eq!(semver.to_string(), "2.0.0");
+    /// assert_eq!(
+    ///     warnings,
+    ///     vec![
+    ///         SemverWarning::Epoch(1),
+    ///         SemverWarning::PostRelease(1),
+    ///         SemverWarning::Local("local".into()),
+    ///     ]
+    /// );
+    /// ```
+    pub fn to_semver(&self) -> (semver::Version, Vec<SemverWarning>) {
+        let mut warnings = Vec::new();
+        if self.epoch() != 0 {
+            warnings.push(SemverWarning::Epoch(self.epoch()));
+        }
+
+        let segments = self.release().segments();
+        let segment = |index: usize| segments.get(index).copied().unwrap_or(0);
+        if segments.len() > 3 {
+            warnings.push(SemverWarning::ExtraSegments(segments[3..].to_vec()));
+        }
+
+        let mut identifiers = Vec::new();
+        if let Some(pre) = self.pre() {
+            let phase = match pre {
+                PreHeader::Alpha(_) => "alpha",
+                PreHeader::Beta(_) => "beta",
+                PreHeader::Preview(_) | PreHeader::ReleaseCandidate(_) => "rc",
+            };
+            identifiers.push(phase.to_string());
+            identifiers.push(pre.number().unwrap_or(0).to_string());
+        }
+        if let Some(dev) = self.dev() {
+            identifiers.push("dev".to_string());
+            identifiers.push(dev.dev_num.unwrap_or(0).to_string());
+        }
+
+        if let Some(post) = self.post() {
+            warnings.push(SemverWarning::PostRelease(post.post_num.unwrap_or(0)));
+        }
+        if let Some(local) = self.normalized_local() {
+            warnings.push(SemverWarning::Local(local));
+        }
+
+        let version = semver::Version {
+            major: segment(0),
+            minor: segment(1),
+            patch: segment(2),
+            // Phases and numbers are always valid identifiers
+            pre: Prerelease::new(&identifiers.join(".")).unwrap_or(Prerelease::EMPTY),
+            build: BuildMetadata::EMPTY,
+        };
+        (version, warnings)
+    }
+}
+
+/// Reads the pre-release as a `PEP-440` pre or developmental release
+/// (`1.0.0-alpha.1`, `1.0.0-rc.2.dev.3`) and the build metadata as the local
+/// version (`1.0.0+build.5`)
+///
+/// Fails for pre-releases `PEP-440` has no spelling for (`1.0.0-x.7`) and
+/// numeric ones (`1.0.0-1`), which would read as post-releases newer than
+/// the release.
+///
+/// ```
+///# use pyver::PackageVersion;
+/// let semver = semver::Version::parse("1.0.0-beta.2+build.5").unwrap();
+/// let version = PackageVersion::try_from(semver).unwrap();
+/// assert_eq!(version.to_string(), "1.0.0b2+build.5");
+///
+/// let semver = semver::Version::parse("1.0.0-1").unwrap();
+/// assert!(PackageVersion::try_from(semver).is_err());
+/// ```
+impl TryFrom<semver::Version> for PackageVersion {
+    type Error = anyhow::Error;
+
+    fn try_from(semver: semver::Version) -> anyhow::Result<Self> {
+        let version = Self::new(&semver.to_string()).map_err(|_| {
+            anyhow::anyhow!("The semver version {} has no PEP 440 spelling", semver)
+        })?;
+        if !semver.pre.is_empty() && !version.is_prerelease() {
+            anyhow::bail!("The semver pre-release {} isn't one in PEP 440", semver);
+        }
+        Ok(version.canonicalize())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{PackageVersion, SemverWarning};
+
+    #[test]
+    fn test_to_semver() {
+        let versions = [
+            ("1", "1.0.0"),
+            ("1.2.3", "1.2.3"),
+            ("1.0a1", "1.0.0-alpha.1"),
+            ("1.0b", "1.0.0-beta.0"),
+            ("1.0pre2", "1.0.0-rc.2"),
+            ("1.0.dev3", "1.0.0-dev.3"),
+            ("1.0rc1.dev2", "1.0.0-rc.1.dev.2"),
+        ];
+        for (version, expected) in versions {
+            let (semver, warnings) = PackageVersion::new(version).unwrap().to_semver();
+            assert_eq!(semver.to_string(), expected);
+            assert!(warnings.is_empty());
+        }
+
+        let (semver, warnings) = PackageVersion::new("1.2.3.4.5").unwrap().to_semver();
+        assert_eq!(semver.to_string(), "1.2.3");
+        assert_eq!(warnings, vec![SemverWarning::ExtraSegments(vec![4, 5])]);
+        assert_eq!(
+            warnings[0].to_string(),
+            "Dropped the release segments [4, 5]"
+        );
+    }
+
+    #[test]
+    fn test_to_semver_order() {
+        let versions = ["0.9", "1.0a1", "1.0a2", "1.0b1", "1.0rc1", "1.0", "1.1"];
+        let semvers: Vec<semver::Version> = versions
+            .iter()
+            .map(|v| PackageVersion::new(v).unwrap().to_semver().0)
+            .collect();
+        assert!(semvers.windows(2).all(|pair| pair[0] < pair[1]));
+    }
+
+    #[test]
+    fn test_from_semver() {
+        let versions = [
+            ("1.2.3", "1.2.3"),
+            ("1.0.0-alpha", "1.0.0a0"),
+            ("1.0.0-alpha.1", "1.0.0a1"),
+            ("1.0.0-rc.1.dev.2", "1.0.0rc1.dev2"),
+            ("1.0.0-dev.3", "1.0.0.dev3"),
+            ("1.0.0+Build-5", "1.0.0+build.5"),
+        ];
+        for (semver, expected) in versions {
+            let semver = semver::Version::parse(semver).unwrap();
+            let version = PackageVersion::try_from(semver).unwrap();
+            assert_eq!(version.to_string(), expected);
+        }
+
+        for version in ["1.0a1", "2.0.0rc1.dev2", "3.1.4"] {
+            let version = PackageVersion::new(version).unwrap();
+            let (semver, _) = version.to_semver();
+            assert_eq!(PackageVersion::try_from(semver).unwrap(), version);
+        }
+    }
+
+    #[test]
+    fn test_from_semver_negative() {
+        for semver in ["1.0.0-1", "1.0.0-post.1", "1.0.0-x.7.z", "1.0.0-alpha.beta"] {
+            let semver = semver::Version::parse(semver).unwrap();
+            match PackageVersion::try_from(semver) {
+                Ok(v) => panic!("Oh no {}", v),
+                Err(_e) => continue,
+            }
+        }
+    }
+}
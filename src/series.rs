@@ -0,0 +1,242 @@
+use crate::PackageVersion;
+use alloc::collections::BTreeMap;
+use alloc::vec::Vec;
+use anyhow::Result;
+use core::fmt;
+use core::str::FromStr;
+
+/// # Release series
+/// The versions sharing an epoch and the first release segments,
+/// such as `1.x` or `1.2.x` for maintenance branches
+///
+/// Series are ordered like the versions in them.
+///
+/// ## Example Usage
+/// ```
+/// use pyver::{PackageVersion, ReleaseSeries};
+///
+/// let series = ReleaseSeries::new("1.2.x").unwrap();
+///
+/// assert!(series.contains(&PackageVersion::new("1.2.9rc1").unwrap()));
+/// assert!(series.contains(&PackageVersion::new("1.2").unwrap()));
+/// assert!(!series.contains(&PackageVersion::new("1.3").unwrap()));
+/// ```
+#[derive(Clone, Debug, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub struct ReleaseSeries {
+    epoch: u32,
+    prefix: Vec<u64>,
+}
+
+impl ReleaseSeries {
+    /// Parses a series like `1.x`, `1!2.0.x` or `1.2.*`
+    pub fn new(series: &str) -> Result<Self> {
+        let series = series.trim();
+        let prefix = match series
+            .strip_suffix(".x")
+            .or_else(|| series.strip_suffix(".*"))
+        {
+            Some(prefix) => prefix,
+            None => anyhow::bail!("Series {} doesn't end with .x", series),
+        };
+        let version = PackageVersion::new(prefix)?;
+        let has_suffix = version.pre().is_some()
+            || version.is_postrelease()
+            || version.is_devrelease()
+            || version.is_local();
+        if has_suffix || version.original_str().starts_with(['v', 'V']) {
+            anyhow::bail!(
+                "Series {} can only have an epoch and release segments",
+                series
+            );
+        }
+        Ok(Self::of(&version, version.release().len()))
+    }
+
+    /// The series of a version with the first `depth` release segments,
+    /// missing segments are zeros (`1` at depth 2 is in `1.0.x`)
+    ///
+    /// ```
+    /// use pyver::{PackageVersion, ReleaseSeries};
+    ///
+    /// let version = PackageVersion::new("2!1.4.2").unwrap();
+    /// assert_eq!(ReleaseSeries::of(&version, 2).to_string(), "2!1.4.x");
+    /// ```
+    pub fn of(version: &PackageVersion, depth: usize) -> Self {
+        Self {
+            epoch: version.epoch(),
+            prefix: (0..depth.max(1))
+                .map(|i| version.release().segment(i))
+                .collect(),
+        }
+    }
+
+    pub fn epoch(&self) -> u32 {
+        self.epoch
+    }
+
+    /// The release segments shared by the versions of the series
+    pub fn prefix(&self) -> &[u64] {
+        &self.prefix
+    }
+
+    /// Whether the version is part of the series
+    pub fn contains(&self, version: &PackageVersion) -> bool {
+        !version.is_max()
+            && version.epoch() == self.epoch
+            && self
+                .prefix
+                .iter()
+                .enumerate()
+                .all(|(i, segment)| version.release().segment(i) == *segment)
+    }
+}
+
+/// The latest version of each series with `depth` release segments,
+/// e.g. the latest patch release of every minor release at depth 2
+///
+/// Pre-releases are grouped like any other version,
+/// filter them out first to only get stable releases
+///
+/// ```
+/// use pyver::{group_by_series, PackageVersion, ReleaseSeries};
+///
+/// let versions: Vec<PackageVersion> = ["1.1.0", "1.2.0", "1.1.3", "1.2.1rc1"]
+///     .iter()
+///     .map(|v| PackageVersion::new(v).unwrap())
+///     .collect();
+///
+/// let latest = group_by_series(&versions, 2);
+/// assert_eq!(latest.len(), 2);
+/// assert_eq!(latest[&ReleaseSeries::new("1.1.x").unwrap()].to_string(), "1.1.3");
+/// assert_eq!(latest[&ReleaseSeries::new("1.2.x").unwrap()].to_string(), "1.2.1rc1");
+/// ```
+pub fn group_by_series<'a>(
+    versions: impl IntoIterator<Item = &'a PackageVersion>,
+    depth: usize,
+) -> BTreeMap<ReleaseSeries, &'a PackageVersion> {
+    let mut latest: BTreeMap<ReleaseSeries, &'a PackageVersion> = BTreeMap::new();
+    for version in versions.into_iter().filter(|version| !version.is_max()) {
+        latest
+            .entry(ReleaseSeries::of(version, depth))
+            .and_modify(|current| {
+                if version > *current {
+                    *current = version;
+                }
+            })
+            .or_insert(version);
+    }
+    latest
+}
+
+impl FromStr for ReleaseSeries {
+    type Err = anyhow::Error;
+
+    fn from_str(series: &str) -> Result<Self> {
+        Self::new(series)
+    }
+}
+
+/// Writes the series with a trailing `.x` (`1!2.0.x`)
+impl fmt::Display for ReleaseSeries {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.epoch != 0 {
+            write!(f, "{}!", self.epoch)?;
+        }
+        for segment in &self.prefix {
+            write!(f, "{}.", segment)?;
+        }
+        write!(f, "x")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{group_by_series, ReleaseSeries};
+    use crate::PackageVersion;
+    use anyhow::Result;
+
+    #[test]
+    fn test_series_parsing() -> Result<()> {
+        let series = vec![
+            ("1.x", "1.x"),
+            (" 1.2.* ", "1.2.x"),
+            ("1!01.2.x", "1!1.2.x"),
+        ];
+        for (series, normalized) in series {
+            assert_eq!(ReleaseSeries::new(series)?.to_string(), normalized);
+        }
+
+        let invalid = vec![
+            "1",
+            "x",
+            "1.x.x",
+            "1rc1.x",
+            "v1.x",
+            "1.2+local.x",
+            "1.2.dev.x",
+        ];
+        for series in invalid {
+            match ReleaseSeries::new(series) {
+                Ok(v) => panic!("Oh no {}", v),
+                Err(_e) => continue,
+            }
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_series_contains() -> Result<()> {
+        let series: ReleaseSeries = "1.2.x".parse()?;
+        let versions = vec![
+            ("1.2", true),
+            ("1.2.0.1", true),
+            ("1.2.3.post1+local", true),
+            ("1.20", false),
+            ("1!1.2", false),
+            ("1", false),
+        ];
+        for (version, contained) in versions {
+            let version = PackageVersion::new(version)?;
+            assert_eq!(series.contains(&version), contained, "{}", version);
+        }
+        assert!(!series.contains(&PackageVersion::MAX));
+        Ok(())
+    }
+
+    #[test]
+    fn test_group_by_series() -> Result<()> {
+        let versions: Vec<PackageVersion> = vec![
+            "2.0",
+            "1.9.1",
+            "1.10.0",
+            "1.9.0",
+            "1!0.1",
+            "2.0.post1",
+            "1.10.0rc1",
+        ]
+        .into_iter()
+        .map(PackageVersion::new)
+        .collect::<Result<_>>()?;
+
+        let latest: Vec<(String, String)> = group_by_series(&versions, 2)
+            .into_iter()
+            .map(|(series, version)| (series.to_string(), version.to_string()))
+            .collect();
+        let expected = vec![
+            ("1.9.x", "1.9.1"),
+            ("1.10.x", "1.10.0"),
+            ("2.0.x", "2.0.post1"),
+            ("1!0.1.x", "1!0.1"),
+        ];
+        let expected: Vec<(String, String)> = expected
+            .into_iter()
+            .map(|(s, v)| (s.to_string(), v.to_string()))
+            .collect();
+        assert_eq!(latest, expected);
+
+        let majors = group_by_series(&versions, 1);
+        assert_eq!(majors.len(), 3);
+        assert_eq!(majors[&"1.x".parse()?].to_string(), "1.10.0");
+        Ok(())
+    }
+}
@@ -0,0 +1,333 @@
+use crate::ids::PreHeader;
+use crate::version::LocalSegment;
+use crate::PackageVersion;
+use alloc::string::String;
+use alloc::vec::Vec;
+use anyhow::Result;
+
+// Markers of the variable length parts, chosen so a part that ends
+// sorts before one that goes on
+const END: u8 = 0;
+const SEGMENT: u8 = 1;
+const LOCAL_STRING: u8 = 1;
+const LOCAL_NUMBER: u8 = 2;
+
+impl PackageVersion {
+    /// Bytes that compare (as by `memcmp`) in the same order as the versions
+    /// they encode, for ordered key-value stores without custom comparators
+    ///
+    /// Equal versions (`1.0` and `v1.0.0`) have the same key, so a key only
+    /// holds what versions are compared by. Numbers are big endian after
+    /// their length in bytes, lists end in a zero byte and the local version
+    /// label is lowercased, which keeps keys of common versions short
+    /// (`1.2.3` takes 15 bytes). Numbers of the local version label can have
+    /// any length, they are their digits after the count of them.
+    ///
+    /// ```
+    ///# use pyver::PackageVersion;
+    /// let versions = ["1.0.dev0", "1.0rc1", "1.0", "1.0+local", "1.0.post1", "1.1"];
+    /// let keys: Vec<Vec<u8>> = versions
+    ///     .iter()
+    ///     .map(|v| PackageVersion::new(v).unwrap().to_sort_key())
+    ///     .collect();
+    ///
+    /// assert!(keys.windows(2).all(|pair| pair[0] < pair[1]));
+    /// assert_eq!(keys[2], PackageVersion::new("v1.0.0").unwrap().to_sort_key());
+    /// ```
+    pub fn to_sort_key(&self) -> Vec<u8> {
+        let mut key = Vec::with_capacity(16);
+        if self.is_max() {
+            // A larger byte than any release marker after the largest epoch
+            push_number(&mut key, u32::MAX.into());
+            key.push(u8::MAX);
+            return key;
+        }
+
+        push_number(&mut key, self.epoch().into());
+        for segment in self.release.significant() {
+            key.push(SEGMENT);
+            push_number(&mut key, *segment);
+        }
+        key.push(END);
+
+        match self.pre_key() {
+            (0, _, _) => key.push(0),
+            (1, phase, number) => {
+                key.push(phase + 1);
+                push_number(&mut key, number.into());
+            }
+            _ => key.push(4),
+        }
+        match self.post_key() {
+            Some(number) => {
+                key.push(1);
+                push_number(&mut key, number.into());
+            }
+            None => key.push(0),
+        }
+        match self.dev_key() {
+            (false, number) => {
+                key.push(0);
+                push_number(&mut key, number.into());
+            }
+            (true, _) => key.push(1),
+        }
+
+        for segment in self.local_segments() {
+            match segment {
+                LocalSegment::String(string) => {
+                    key.push(LOCAL_STRING);
+                    key.extend_from_slice(string.as_bytes());
+                    key.push(END);
+                }
+                LocalSegment::Number(digits) => {
+                    key.push(LOCAL_NUMBER);
+                    push_number(&mut key, digits.len() as u64);
+                    key.extend_from_slice(digits.as_bytes());
+                }
+            }
+        }
+        key.push(END);
+        key
+    }
+
+    /// Decodes a key from [`PackageVersion::to_sort_key`] into the
+    /// shortest version equal to the encoded one (`1` for `v1.0.0`)
+    ///
+    /// Fails for bytes that aren't a key of any version.
+    ///
+    /// ```
+    ///# use pyver::PackageVersion;
+    /// let version = PackageVersion::new("v1!2.0-RC1.post3+Ubuntu-1").unwrap();
+    /// let decoded = PackageVersion::from_sort_key(&version.to_sort_key()).unwrap();
+    ///
+    /// assert_eq!(decoded, version);
+    /// assert_eq!(decoded.to_string(), "1!2rc1.post3+ubuntu.1");
+    /// ```
+    pub fn from_sort_key(key: &[u8]) -> Result<Self> {
+        let mut reader = KeyReader { key };
+        let version = reader.version()?;
+
+        // Reading is lenient, the key has to be the one the version writes
+        if version.to_sort_key() != key {
+            anyhow::bail!("Invalid sort key {:?}", key);
+        }
+        Ok(version)
+    }
+}
+
+/// Writes the number of bytes without leading zeros, then those bytes
+fn push_number(key: &mut Vec<u8>, number: u64) {
+    let bytes = number.to_be_bytes();
+    let skip = number.leading_zeros() as usize / 8;
+    key.push((bytes.len() - skip) as u8);
+    key.extend_from_slice(&bytes[skip..]);
+}
+
+struct KeyReader<'a> {
+    key: &'a [u8],
+}
+
+impl KeyReader<'_> {
+    fn byte(&mut self) -> Result<u8> {
+        match self.key.split_first() {
+            Some((byte, rest)) => {
+                self.key = rest;
+                Ok(*byte)
+            }
+            None => anyhow::bail!("Sort key ends early"),
+        }
+    }
+
+    fn number(&mut self) -> Result<u64> {
+        let len = self.byte()? as usize;
+        if len > 8 || len > self.key.len() {
+            anyhow::bail!("Invalid number in sort key");
+        }
+        let (bytes, rest) = self.key.split_at(len);
+        self.key = rest;
+        Ok(bytes
+            .iter()
+            .fold(0, |number, byte| (number << 8) | *byte as u64))
+    }
+
+    fn small_number(&mut self) -> Result<u32> {
+        Ok(u32::try_from(self.number()?)?)
+    }
+
+    fn version(&mut self) -> Result<PackageVersion> {
+        let epoch = self.small_number()?;
+        let mut release = Vec::new();
+        loop {
+            match self.byte()? {
+                END => break,
+                SEGMENT => release.push(self.number()?),
+                // Only `MAX` has something else after its epoch
+                _ if release.is_empty() && epoch == u32::MAX => {
+                    return Ok(PackageVersion::MAX)
+                }
+                marker => {
+                    anyhow::bail!("Invalid release marker {} in sort key", marker)
+                }
+            }
+        }
+        if release.is_empty() {
+            release.push(0);
+        }
+
+        let pre = match self.byte()? {
+            1 => Some(PreHeader::Alpha(Some(self.small_number()?))),
+            2 => Some(PreHeader::Beta(Some(self.small_number()?))),
+            3 => Some(PreHeader::ReleaseCandidate(Some(self.small_number()?))),
+            0 | 4 => None,
+            marker => {
+                anyhow::bail!("Invalid pre-release marker {} in sort key", marker)
+            }
+        };
+        let post = match self.byte()? {
+            0 => None,
+            _ => Some(self.small_number()?),
+        };
+        let dev = match self.byte()? {
+            0 => Some(self.small_number()?),
+            _ => None,
+        };
+
+        let mut local = Vec::new();
+        loop {
+            match self.byte()? {
+                END => break,
+                LOCAL_NUMBER => {
+                    let len = usize::try_from(self.number()?)?;
+                    if len > self.key.len() {
+                        anyhow::bail!("Sort key ends early");
+                    }
+                    let (digits, rest) = self.key.split_at(len);
+                    local.push(String::from_utf8(digits.to_vec())?);
+                    self.key = rest;
+                }
+                _ => {
+                    let len = match self.key.iter().position(|byte| *byte == END) {
+                        Some(len) => len,
+                        None => anyhow::bail!("Sort key ends early"),
+                    };
+                    let (segment, rest) = self.key.split_at(len);
+                    local.push(String::from_utf8(segment.to_vec())?);
+                    self.key = &rest[1..];
+                }
+            }
+        }
+        let local = (!local.is_empty()).then(|| local.join("."));
+
+        PackageVersion::from_parts(epoch, release, pre, post, dev, local.as_deref())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::PackageVersion;
+    use anyhow::Result;
+
+    #[test]
+    fn test_sort_key_order() -> Result<()> {
+        let versions = vec![
+            "0.dev0",
+            "1.0.dev456",
+            "1.0a1",
+            "1.0a2.dev456",
+            "1.0a12",
+            "1.0b1.dev456",
+            "1.0b2.post345",
+            "1.0c1",
+            "1.0rc2",
+            "1.0",
+            "1.0+abc.5",
+            "1.0+abc.7",
+            "1.0+5",
+            "1.0+18446744073709551616",
+            "1.0+99999999999999999999",
+            "1.0.post456.dev34",
+            "1.0.post456",
+            "1.0.1",
+            "1.1",
+            "1.255",
+            "1.256",
+            "18446744073709551615",
+            "1!0.1",
+            "4294967295!1.0",
+        ];
+        let mut versions = versions
+            .iter()
+            .map(|v| PackageVersion::new(v))
+            .collect::<Result<Vec<_>>>()?;
+        versions.push(PackageVersion::MAX);
+
+        for a in &versions {
+            for b in &versions {
+                assert_eq!(
+                    a.to_sort_key().cmp(&b.to_sort_key()),
+                    a.cmp(b),
+                    "{} {}",
+                    a,
+                    b
+                );
+            }
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_sort_key_roundtrip() -> Result<()> {
+        // (version, decoded)
+        let versions = vec![
+            ("1.2.3", "1.2.3"),
+            ("v1.0.0", "1"),
+            ("0.0", "0"),
+            ("1.0-1", "1.post1"),
+            ("1.0a.dev", "1a0.dev0"),
+            ("1.0+ABC-007_x", "1+abc.7.x"),
+            ("1.0+099999999999999999999", "1+99999999999999999999"),
+        ];
+        for (version, decoded) in versions {
+            let version = PackageVersion::new(version)?;
+            let key = version.to_sort_key();
+            let from_key = PackageVersion::from_sort_key(&key)?;
+            assert_eq!(from_key, version);
+            assert_eq!(from_key.to_string(), decoded);
+        }
+
+        assert_eq!(PackageVersion::new("1.2.3")?.to_sort_key().len(), 15);
+        assert!(
+            PackageVersion::from_sort_key(&PackageVersion::MAX.to_sort_key())?.is_max()
+        );
+        assert_eq!(
+            PackageVersion::from_sort_key(&PackageVersion::MIN.to_sort_key())?,
+            PackageVersion::MIN
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_sort_key_negative() {
+        let key = PackageVersion::new("1.0rc1+abc").unwrap().to_sort_key();
+        let keys = vec![
+            vec![],
+            key[..key.len() - 1].to_vec(),
+            [&key[..], &[0]].concat(),
+            // Leading zero byte in the epoch
+            [&[1, 0], &key[1..]].concat(),
+            // A release of `0` written as a segment
+            vec![0, 1, 0, 0, 4, 0, 1, 0],
+            // `rc` without a number
+            vec![0, 1, 1, 1, 0, 3],
+            // Pre-release marker 0 for a version without a dev segment
+            vec![0, 1, 1, 1, 0, 0, 0, 1, 0],
+        ];
+        for key in keys {
+            match PackageVersion::from_sort_key(&key) {
+                Ok(v) => panic!("Oh no {}", v),
+                Err(_e) => continue,
+            }
+        }
+    }
+}
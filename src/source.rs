@@ -0,0 +1,186 @@
+use crate::PackageVersion;
+use anyhow::Result;
+use std::fs;
+use std::ops::Range;
+use std::path::Path;
+
+/// A version assigned in Python source code
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SourceVersion {
+    pub version: PackageVersion,
+    /// The name it's assigned to, `__version__` or `version`
+    pub name: String,
+    /// Line number (starting at 1) of the assignment
+    pub line: usize,
+    /// Byte range of the version string (without quotes) in the source,
+    /// to replace it in place
+    pub span: Range<usize>,
+}
+
+/// Finds the versions assigned in Python source code without running it
+///
+/// Matches `__version__ = "1.0"` (optionally annotated as `str`)
+/// and `version="1.0"` keyword arguments as passed to `setup()`.
+/// Only string literals are read, assignments in comments and
+/// values which aren't valid versions are skipped.
+///
+/// ```
+/// use pyver::source::find_versions;
+///
+/// let source = r#"
+/// __version__ = '1.2.0'
+///
+/// setup(
+///     name="spam",
+///     version="1.2.0rc1",  # version="0.1"
+/// )
+/// "#;
+/// let versions = find_versions(source);
+///
+/// assert_eq!(versions.len(), 2);
+/// assert_eq!(versions[0].name, "__version__");
+/// assert_eq!(versions[0].line, 2);
+/// assert_eq!(versions[1].version.to_string(), "1.2.0rc1");
+/// assert_eq!(&source[versions[1].span.clone()], "1.2.0rc1");
+/// ```
+pub fn find_versions(source: &str) -> Vec<SourceVersion> {
+    let mut versions = Vec::new();
+    let mut line_start = 0;
+    for (index, line) in source.split_inclusive('\n').enumerate() {
+        for (name, value) in assignments(line) {
+            if line[..name.start].contains('#') {
+                continue;
+            }
+
+            if let Ok(version) = PackageVersion::new(&line[value.clone()]) {
+                versions.push(SourceVersion {
+                    version,
+                    name: line[name].to_string(),
+                    line: index + 1,
+                    span: line_start + value.start..line_start + value.end,
+                });
+            }
+        }
+        line_start += line.len();
+    }
+    versions
+}
+
+/// The byte ranges of the names and string values assigned in a line,
+/// i.e. matches of `\b(__version__|version)[ \t]*(:[ \t]*str[ \t]*)?=[ \t]*`
+/// followed by a single or double quoted string
+fn assignments(line: &str) -> Vec<(Range<usize>, Range<usize>)> {
+    let mut found = Vec::new();
+    let mut start = 0;
+    while let Some(offset) = line[start..].find("version") {
+        let index = start + offset;
+        let end = index + "version".len();
+        let name = match line[..index].ends_with("__") && line[end..].starts_with("__")
+        {
+            true => index - 2..end + 2,
+            false => index..end,
+        };
+
+        // The name has to start a word, `my_version` isn't assigned
+        let starts_word =
+            !line[..name.start].ends_with(|c: char| c.is_alphanumeric() || c == '_');
+        match assigned_string(line, name.end) {
+            Some(value) if starts_word => {
+                start = value.end + 1;
+                found.push((name, value));
+            }
+            _ => start = end,
+        }
+    }
+    found
+}
+
+/// The range of the string assigned after a name ending at `position`,
+/// without its quotes
+fn assigned_string(line: &str, mut position: usize) -> Option<Range<usize>> {
+    let skip_blanks = |position: usize| {
+        position + line[position..].len()
+            - line[position..].trim_start_matches([' ', '\t']).len()
+    };
+
+    // Optional `str` annotation
+    position = skip_blanks(position);
+    if line[position..].starts_with(':') {
+        position = skip_blanks(position + 1);
+        if !line[position..].starts_with("str") {
+            return None;
+        }
+        position = skip_blanks(position + "str".len());
+    }
+
+    if !line[position..].starts_with('=') {
+        return None;
+    }
+    position = skip_blanks(position + 1);
+
+    let quote = line[position..]
+        .chars()
+        .next()
+        .filter(|c| matches!(c, '"' | '\''))?;
+    let value_start = position + 1;
+    let value_end = value_start + line[value_start..].find([quote, '\n'])?;
+    match line[value_end..].starts_with(quote) {
+        true => Some(value_start..value_end),
+        false => None,
+    }
+}
+
+/// Reads a Python file and finds the versions assigned in it,
+/// see [`find_versions`]
+pub fn find_versions_in_file(path: impl AsRef<Path>) -> Result<Vec<SourceVersion>> {
+    Ok(find_versions(&fs::read_to_string(path)?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::find_versions;
+
+    #[test]
+    fn test_find_versions() {
+        let source = "\"\"\"Spam\"\"\"\r\n\
+            __version__: str = \"2!1.0.post1\"\r\n\
+            __version_info__ = (1, 0)\n\
+            # __version__ = '0.9'\n\
+            VERSION = \"3.0\"\n\
+            if version == \"1.0\": pass\n\
+            setup(name='spam', version = '0.1.dev0', python_version='3.8')\n";
+
+        let versions = find_versions(source);
+        let found: Vec<(&str, usize, &str)> = versions
+            .iter()
+            .map(|v| (v.name.as_str(), v.line, &source[v.span.clone()]))
+            .collect();
+        assert_eq!(
+            found,
+            vec![
+                ("__version__", 2, "2!1.0.post1"),
+                ("version", 7, "0.1.dev0")
+            ]
+        );
+    }
+
+    #[test]
+    fn test_find_versions_negative() {
+        let sources = vec![
+            "__version__ = get_version()",
+            "__version__ = \"{version}\"",
+            "__version__ = \"1.0'",
+            "my_version = \"1.0\"",
+            "__version__: int = \"1.0\"",
+            "version == \"1.0\"",
+            "__version__ = (\n\"1.0\")",
+        ];
+
+        for source in sources {
+            let versions = find_versions(source);
+            if !versions.is_empty() {
+                panic!("Oh no {:?}", versions);
+            }
+        }
+    }
+}
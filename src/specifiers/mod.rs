@@ -0,0 +1,26 @@
+//! # Version Specifiers
+//! Handling of `PEP-440` version specifiers such as `>=1.0` or `~=2.2`
+//!
+//! Read more at <https://peps.python.org/pep-0440/#version-specifiers>
+//!
+//! Importing Example
+//! ```
+//! use pyver::specifiers::{
+//!     parse_requires_python, requires_python_matches, translate_range, Inexact, Operator,
+//!     RangeSyntax, RequiresPython, Specifier, SpecifierSet, Translation, VersionRange,
+//! };
+//! ```
+
+#[cfg(feature = "pubgrub")]
+mod pubgrub;
+mod range;
+mod requires_python;
+mod semver_range;
+mod specifier;
+mod specifier_set;
+
+pub use range::*;
+pub use requires_python::*;
+pub use semver_range::*;
+pub use specifier::*;
+pub use specifier_set::*;
@@ -0,0 +1,75 @@
+use super::VersionRange;
+use crate::PackageVersion;
+
+/// Lets [`VersionRange`] be used as the version set of the
+/// [pubgrub](https://docs.rs/pubgrub) solver, with [`PackageVersion`] as its versions
+///
+/// Ranges are exact, so `>1.0` doesn't have to be turned into `>=` some
+/// successor version, and specifiers convert with [`VersionRange::from`]
+///
+/// ```
+/// use pubgrub::VersionSet;
+/// use pyver::specifiers::{SpecifierSet, VersionRange};
+/// use pyver::PackageVersion;
+///
+/// let range = VersionRange::from(SpecifierSet::new(">=1.0, !=1.5").unwrap());
+/// let pinned = VersionRange::singleton(&PackageVersion::new("1.5").unwrap());
+///
+/// assert!(range.is_disjoint(&pinned));
+/// assert!(pinned.subset_of(&range.complement()));
+/// ```
+impl pubgrub::VersionSet for VersionRange {
+    type V = PackageVersion;
+
+    fn empty() -> Self {
+        VersionRange::empty()
+    }
+
+    fn singleton(version: PackageVersion) -> Self {
+        VersionRange::singleton(&version)
+    }
+
+    fn complement(&self) -> Self {
+        VersionRange::complement(self)
+    }
+
+    fn intersection(&self, other: &Self) -> Self {
+        VersionRange::intersection(self, other)
+    }
+
+    fn contains(&self, version: &PackageVersion) -> bool {
+        VersionRange::contains(self, version)
+    }
+
+    fn full() -> Self {
+        VersionRange::full()
+    }
+
+    fn union(&self, other: &Self) -> Self {
+        VersionRange::union(self, other)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::specifiers::{SpecifierSet, VersionRange};
+    use crate::PackageVersion;
+    use anyhow::Result;
+    use pubgrub::VersionSet;
+
+    #[test]
+    fn test_pubgrub_version_set() -> Result<()> {
+        let range = VersionRange::from(SpecifierSet::new(">1.0, <2.0")?);
+        let post = PackageVersion::new("1.0.post1")?;
+
+        assert!(!VersionSet::contains(&range, &post));
+        assert!(VersionSet::contains(&range, &PackageVersion::new("1.0.1")?));
+        assert!(<VersionRange as VersionSet>::singleton(post).is_disjoint(&range));
+        assert!(range.subset_of(&<VersionRange as VersionSet>::full()));
+        assert_eq!(
+            VersionSet::union(&range, &VersionSet::complement(&range)),
+            VersionRange::full()
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,1003 @@
+use super::specifier::{greater_than_depth, less_than_depth};
+use super::{Operator, Specifier, SpecifierSet};
+use crate::ids::{DevHead, PostHead, PostHeader, PreHeader, ReleaseHeader};
+use crate::version::SORT_KEY_LEN;
+use crate::PackageVersion;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use alloc::{format, vec};
+use anyhow::Result;
+use core::cmp::Ordering;
+use core::fmt;
+use core::ops::{Bound, RangeBounds};
+
+/// # Version range
+/// The set of versions matched by specifiers, as sorted and disjoint
+/// half-open intervals
+///
+/// Unlike a [`SpecifierSet`] a range can describe any combination of
+/// constraints, including alternatives such as `<1.0 or >=2.0`.
+///
+/// Ranges only look at where versions sort, so they follow the operator
+/// rules of `PEP-440` but not the pre-release policy of the specifiers
+/// (every pre-release in range is contained). `===` is treated as `==`
+/// without ignoring local versions.
+///
+/// Ranges are normalized, so two ranges containing the same versions
+/// are equal no matter which specifiers they were built from.
+///
+/// ## Example Usage
+/// ```
+/// use pyver::specifiers::{SpecifierSet, VersionRange};
+/// use pyver::PackageVersion;
+///
+/// let old = SpecifierSet::new("<1.0").unwrap();
+/// let new = SpecifierSet::new(">=2.0").unwrap();
+/// let range = old.union(&new);
+///
+/// assert!(range.contains(&PackageVersion::new("0.9").unwrap()));
+/// assert!(range.contains(&PackageVersion::new("2.1").unwrap()));
+/// assert!(!range.contains(&PackageVersion::new("1.5").unwrap()));
+///
+/// // Ranges without alternatives can be turned back into specifiers
+/// assert!(SpecifierSet::try_from(&range).is_err());
+/// let range = VersionRange::from(SpecifierSet::new(">=1.0, <2.0").unwrap())
+///     .intersection(&VersionRange::from(SpecifierSet::new(">=1.5").unwrap()));
+/// assert_eq!(SpecifierSet::try_from(&range).unwrap().to_string(), ">=1.5,<2.0");
+/// ```
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct VersionRange {
+    intervals: Vec<Interval>,
+}
+
+/// The versions above `lower` and below `upper`
+#[derive(Clone, Debug, Eq, PartialEq)]
+struct Interval {
+    lower: Cut,
+    upper: Cut,
+}
+
+/// A position between versions
+///
+/// Cuts are kept in a canonical form where two cuts compare equal
+/// exactly if no version lies between them, which keeps ranges normalized
+#[derive(Clone, Debug)]
+enum Cut {
+    /// Below every version
+    Min,
+    /// Below every version sharing the first `depth` sort key components
+    /// with the version
+    Below(PackageVersion, usize),
+    /// Above every version sharing the first `depth` sort key components
+    /// with the version
+    Above(PackageVersion, usize),
+    /// Above every version
+    Max,
+}
+
+impl VersionRange {
+    /// The range containing every version
+    pub fn full() -> Self {
+        Self {
+            intervals: vec![Interval {
+                lower: Cut::Min,
+                upper: Cut::Max,
+            }],
+        }
+    }
+
+    /// The range containing no version
+    pub fn empty() -> Self {
+        Self {
+            intervals: Vec::new(),
+        }
+    }
+
+    /// The range containing only the version itself
+    /// (unlike `==` without its local versions)
+    pub fn singleton(version: &PackageVersion) -> Self {
+        Self::between(
+            Cut::below(version.clone(), SORT_KEY_LEN),
+            Cut::above(version.clone(), SORT_KEY_LEN),
+        )
+    }
+
+    /// The versions between two bounds, e.g. those of a resolver
+    /// or of a `BTreeMap` range scan
+    ///
+    /// [`PackageVersion::MIN`] and [`PackageVersion::MAX`] work as well as
+    /// unbounded ends, so open-ended intervals can be stored as two versions.
+    ///
+    /// ```
+    /// use pyver::specifiers::VersionRange;
+    /// use pyver::PackageVersion;
+    /// use std::ops::Bound;
+    ///
+    /// let one = PackageVersion::new("1.0").unwrap();
+    /// let two = PackageVersion::new("2.0").unwrap();
+    /// // Unlike `<2.0` the bound is only about sorting, pre-releases are inside
+    /// let range = VersionRange::from_bounds(one.clone()..two.clone());
+    /// assert!(range.contains(&PackageVersion::new("2.0rc1").unwrap()));
+    /// assert!(!range.contains(&two));
+    ///
+    /// let newer = VersionRange::from_bounds((Bound::Excluded(one.clone()), Bound::Unbounded));
+    /// assert!(newer.contains(&PackageVersion::new("1.0+local").unwrap()));
+    /// assert!(!newer.contains(&one));
+    ///
+    /// let all = VersionRange::from_bounds(PackageVersion::MIN..PackageVersion::MAX);
+    /// assert_eq!(all, VersionRange::full());
+    /// ```
+    pub fn from_bounds(bounds: impl RangeBounds<PackageVersion>) -> Self {
+        let lower = match bounds.start_bound() {
+            // Nothing but the synthetic version itself is above it
+            Bound::Included(version) | Bound::Excluded(version) if version.is_max() => {
+                return Self::empty()
+            }
+            Bound::Included(version) => Cut::below(version.clone(), SORT_KEY_LEN),
+            Bound::Excluded(version) => Cut::above(version.clone(), SORT_KEY_LEN),
+            Bound::Unbounded => Cut::Min,
+        };
+        let upper = match bounds.end_bound() {
+            Bound::Included(version) | Bound::Excluded(version) if version.is_max() => {
+                Cut::Max
+            }
+            Bound::Included(version) => Cut::above(version.clone(), SORT_KEY_LEN),
+            Bound::Excluded(version) => Cut::below(version.clone(), SORT_KEY_LEN),
+            Bound::Unbounded => Cut::Max,
+        };
+        Self::between(lower, upper)
+    }
+
+    /// Whether the version is inside the range
+    pub fn contains(&self, version: &PackageVersion) -> bool {
+        self.intervals
+            .iter()
+            .any(|interval| interval.contains(version))
+    }
+
+    /// Whether no version is inside the range
+    pub fn is_empty(&self) -> bool {
+        self.intervals.is_empty()
+    }
+
+    /// The versions inside either range
+    pub fn union(&self, other: &Self) -> Self {
+        let mut intervals: Vec<Interval> = self
+            .intervals
+            .iter()
+            .chain(other.intervals.iter())
+            .cloned()
+            .collect();
+        intervals.sort_by(|a, b| a.lower.cmp(&b.lower));
+
+        let mut merged: Vec<Interval> = Vec::with_capacity(intervals.len());
+        for interval in intervals {
+            match merged.last_mut() {
+                // Overlapping or touching intervals are joined
+                Some(last) if interval.lower <= last.upper => {
+                    if interval.upper > last.upper {
+                        last.upper = interval.upper;
+                    }
+                }
+                _ => merged.push(interval),
+            }
+        }
+        Self { intervals: merged }
+    }
+
+    /// The versions inside both ranges
+    pub fn intersection(&self, other: &Self) -> Self {
+        let mut intervals = Vec::new();
+        let (mut i, mut j) = (0, 0);
+
+        while i < self.intervals.len() && j < other.intervals.len() {
+            let (a, b) = (&self.intervals[i], &other.intervals[j]);
+            let lower = a.lower.clone().max(b.lower.clone());
+            let upper = a.upper.clone().min(b.upper.clone());
+            if lower < upper {
+                intervals.push(Interval { lower, upper });
+            }
+
+            // Move past the interval ending first
+            if a.upper < b.upper {
+                i += 1;
+            } else {
+                j += 1;
+            }
+        }
+        Self { intervals }
+    }
+
+    /// The versions outside of the range
+    pub fn complement(&self) -> Self {
+        let mut intervals = Vec::with_capacity(self.intervals.len() + 1);
+        let mut lower = Cut::Min;
+
+        for interval in &self.intervals {
+            if lower < interval.lower {
+                intervals.push(Interval {
+                    lower,
+                    upper: interval.lower.clone(),
+                });
+            }
+            lower = interval.upper.clone();
+        }
+        if lower < Cut::Max {
+            intervals.push(Interval {
+                lower,
+                upper: Cut::Max,
+            });
+        }
+        Self { intervals }
+    }
+
+    /// The version at the lower end of each interval,
+    /// `None` for an interval without a lower bound
+    pub(crate) fn lower_bounds(&self) -> impl Iterator<Item = Option<&PackageVersion>> {
+        self.intervals.iter().map(|interval| match &interval.lower {
+            Cut::Below(version, _) | Cut::Above(version, _) => Some(version),
+            Cut::Min | Cut::Max => None,
+        })
+    }
+
+    /// A single interval, empty if `lower` isn't below `upper`
+    fn between(lower: Cut, upper: Cut) -> Self {
+        if lower < upper {
+            Self {
+                intervals: vec![Interval { lower, upper }],
+            }
+        } else {
+            Self::empty()
+        }
+    }
+
+    /// Versions starting with the given epoch and release prefix (`==1.1.*`)
+    fn prefix(epoch: u32, prefix: &[u64]) -> Self {
+        Self::between(
+            Cut::below(release(epoch, prefix.to_vec()), 2),
+            prefix_end(epoch, prefix),
+        )
+    }
+}
+
+impl Interval {
+    fn contains(&self, version: &PackageVersion) -> bool {
+        self.lower.is_below(version) && !self.upper.is_below(version)
+    }
+}
+
+impl Cut {
+    /// Below every version sharing the first `depth` components with `version`
+    fn below(version: PackageVersion, mut depth: usize) -> Self {
+        // Below the smallest possible component is the same as
+        // below the previous component
+        while depth > 0 && is_min_component(&version, depth) {
+            depth -= 1;
+        }
+        match depth {
+            0 => Cut::Min,
+            _ => Cut::Below(version, depth),
+        }
+    }
+
+    /// Above every version sharing the first `depth` components with `version`
+    fn above(version: PackageVersion, mut depth: usize) -> Self {
+        while depth > 0 && is_max_component(&version, depth) {
+            depth -= 1;
+        }
+        if depth == 0 {
+            return Cut::Max;
+        }
+        // Above one component value is the same as below the next one,
+        // release numbers and local segments have no next value
+        match next_component(&version, depth) {
+            Some(next) => Cut::below(next, depth),
+            None => Cut::Above(version, depth),
+        }
+    }
+
+    /// Whether the version is above the cut
+    fn is_below(&self, version: &PackageVersion) -> bool {
+        match self {
+            Cut::Min => true,
+            Cut::Below(v, depth) => version.cmp_prefix(v, *depth) != Ordering::Less,
+            Cut::Above(v, depth) => version.cmp_prefix(v, *depth) == Ordering::Greater,
+            Cut::Max => false,
+        }
+    }
+}
+
+impl PartialEq for Cut {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Cut {}
+
+impl PartialOrd for Cut {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Cut {
+    fn cmp(&self, other: &Self) -> Ordering {
+        let (a, a_depth, a_above, b, b_depth, b_above) = match (self, other) {
+            (Cut::Min, Cut::Min) | (Cut::Max, Cut::Max) => return Ordering::Equal,
+            (Cut::Min, _) | (_, Cut::Max) => return Ordering::Less,
+            (_, Cut::Min) | (Cut::Max, _) => return Ordering::Greater,
+            (Cut::Below(a, a_depth), Cut::Below(b, b_depth)) => {
+                (a, a_depth, false, b, b_depth, false)
+            }
+            (Cut::Below(a, a_depth), Cut::Above(b, b_depth)) => {
+                (a, a_depth, false, b, b_depth, true)
+            }
+            (Cut::Above(a, a_depth), Cut::Below(b, b_depth)) => {
+                (a, a_depth, true, b, b_depth, false)
+            }
+            (Cut::Above(a, a_depth), Cut::Above(b, b_depth)) => {
+                (a, a_depth, true, b, b_depth, true)
+            }
+        };
+
+        // With a common prefix a cut below fewer components is lower and
+        // a cut above fewer components is higher, since they span more versions
+        a.cmp_prefix(b, *a_depth.min(b_depth))
+            .then_with(|| match (a_above, b_above) {
+                (false, false) => a_depth.cmp(b_depth),
+                (true, true) => b_depth.cmp(a_depth),
+                (false, true) => Ordering::Less,
+                (true, false) => Ordering::Greater,
+            })
+    }
+}
+
+/// Whether the sort key component at `depth` (1 being the epoch)
+/// has the smallest value possible after the components before it
+fn is_min_component(version: &PackageVersion, depth: usize) -> bool {
+    match depth {
+        1 => version.epoch.unwrap_or(0) == 0,
+        2 => version.release.segments.iter().all(|segment| *segment == 0),
+        3 => version.pre_key() == (0, 0, 0),
+        4 => version.post_key().is_none(),
+        // A final release without a post segment can't have a dev segment
+        5 => {
+            version.dev_key() == (false, 0)
+                || (version.pre_key() == (2, 0, 0) && version.post.is_none())
+        }
+        _ => version.local.is_none(),
+    }
+}
+
+/// Whether the sort key component at `depth` has the largest value possible
+/// after the components before it
+fn is_max_component(version: &PackageVersion, depth: usize) -> bool {
+    match depth {
+        3 => version.pre_key() == (2, 0, 0),
+        // Dev releases of a final release can't have a post segment
+        4 => version.pre_key() == (0, 0, 0),
+        5 => version.dev.is_none(),
+        _ => false,
+    }
+}
+
+/// A version with the same components as `version` before `depth`
+/// and the next possible value at `depth`
+fn next_component(version: &PackageVersion, depth: usize) -> Option<PackageVersion> {
+    let epoch = version.epoch.unwrap_or(0);
+    let segments = version.release.segments.to_vec();
+
+    match depth {
+        1 => Some(release(epoch.checked_add(1)?, vec![0])),
+        3 => {
+            let pre = match &version.pre {
+                None => PreHeader::Alpha(Some(0)),
+                Some(PreHeader::Alpha(n)) => PreHeader::Alpha(next(n)?),
+                Some(PreHeader::Beta(n)) => PreHeader::Beta(next(n)?),
+                Some(PreHeader::ReleaseCandidate(n)) | Some(PreHeader::Preview(n)) => {
+                    PreHeader::ReleaseCandidate(next(n)?)
+                }
+            };
+            Some(build(epoch, segments, Some(pre), None, None))
+        }
+        4 => {
+            let post = match version.post_key() {
+                Some(post) => post.checked_add(1)?,
+                None => 0,
+            };
+            Some(build(epoch, segments, version.pre, Some(post), None))
+        }
+        // Only reached for dev releases, where the next dev release follows
+        5 => version.smallest_greater_than(),
+        _ => None,
+    }
+}
+
+/// The number after an optional pre-release number
+fn next(n: &Option<u32>) -> Option<Option<u32>> {
+    n.unwrap_or(0).checked_add(1).map(Some)
+}
+
+/// The cut above every version starting with the given release prefix
+fn prefix_end(epoch: u32, prefix: &[u64]) -> Cut {
+    match prefix.split_last() {
+        Some((last, rest)) => match last.checked_add(1) {
+            Some(next) => {
+                let mut segments = rest.to_vec();
+                segments.push(next);
+                Cut::below(release(epoch, segments), 2)
+            }
+            None => prefix_end(epoch, rest),
+        },
+        None => Cut::above(release(epoch, vec![0]), 1),
+    }
+}
+
+/// A final release
+fn release(epoch: u32, segments: Vec<u64>) -> PackageVersion {
+    build(epoch, segments, None, None, None)
+}
+
+fn build(
+    epoch: u32,
+    segments: Vec<u64>,
+    pre: Option<PreHeader>,
+    post: Option<u32>,
+    dev: Option<u32>,
+) -> PackageVersion {
+    let version = PackageVersion {
+        original: None,
+        local: None,
+        dev: dev.map(|dev_num| DevHead {
+            dev_num: Some(dev_num),
+        }),
+        post: post.map(|post_num| PostHeader {
+            post_head: Some(PostHead::Post),
+            post_num: Some(post_num),
+        }),
+        pre,
+        release: ReleaseHeader {
+            segments: segments.into(),
+        },
+        epoch: Some(epoch),
+    };
+    version.renormalized()
+}
+
+impl From<&Specifier> for VersionRange {
+    fn from(specifier: &Specifier) -> Self {
+        let version = match specifier.version() {
+            Some(version) => version.clone(),
+            // `===` with a legacy version can't match a `PEP-440` version
+            None => return Self::empty(),
+        };
+        let epoch = version.epoch.unwrap_or(0);
+
+        let equal = || {
+            if specifier.is_wildcard() {
+                Self::prefix(epoch, &version.release.segments)
+            } else if version.local.is_some() {
+                Self::between(
+                    Cut::below(version.clone(), SORT_KEY_LEN),
+                    Cut::above(version.clone(), SORT_KEY_LEN),
+                )
+            } else {
+                // Any local version of a public version is equal to it
+                Self::between(
+                    Cut::below(version.clone(), SORT_KEY_LEN),
+                    Cut::above(version.clone(), SORT_KEY_LEN - 1),
+                )
+            }
+        };
+
+        match specifier.operator() {
+            Operator::Equal => equal(),
+            Operator::NotEqual => equal().complement(),
+            Operator::LessThanEqual => {
+                Self::between(Cut::Min, Cut::above(version.clone(), SORT_KEY_LEN - 1))
+            }
+            Operator::GreaterThanEqual => {
+                Self::between(Cut::below(version.clone(), SORT_KEY_LEN), Cut::Max)
+            }
+            Operator::LessThan => {
+                let depth = less_than_depth(&version);
+                Self::between(Cut::Min, Cut::below(version.clone(), depth))
+            }
+            Operator::GreaterThan => {
+                let depth = greater_than_depth(&version);
+                Self::between(Cut::above(version.clone(), depth), Cut::Max)
+            }
+            Operator::Compatible => {
+                let segments = &version.release.segments;
+                let prefix = Self::prefix(epoch, &segments[..segments.len() - 1]);
+                Self::between(Cut::below(version.clone(), SORT_KEY_LEN), Cut::Max)
+                    .intersection(&prefix)
+            }
+            Operator::ArbitraryEqual => Self::singleton(&version),
+        }
+    }
+}
+
+/// Writes the intervals as specifier sets separated by `||`,
+/// `*` for the full range and `∅` for the empty range
+///
+/// Intervals which can't be written as specifiers (bounded by a local
+/// version) are written with the operators they would need
+impl fmt::Display for VersionRange {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.intervals.is_empty() {
+            return write!(f, "∅");
+        }
+
+        let intervals: Vec<String> = self
+            .intervals
+            .iter()
+            .map(|interval| {
+                let range = VersionRange {
+                    intervals: vec![interval.clone()],
+                };
+                match SpecifierSet::try_from(&range) {
+                    Ok(set) if set.is_empty() => "*".to_string(),
+                    Ok(set) => set.to_string(),
+                    Err(_) => interval.to_string(),
+                }
+            })
+            .collect();
+        write!(f, "{}", intervals.join(" || "))
+    }
+}
+
+/// Each bound as the specifier it is equal to, or written with the
+/// operator it would need if there is none
+impl fmt::Display for Interval {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut bounds = Vec::new();
+        if self.lower != Cut::Min {
+            let range = VersionRange::between(self.lower.clone(), Cut::Max);
+            bounds.push(
+                match find_specifier(&range, bound_candidates(&self.lower)) {
+                    Ok(specifier) => specifier.to_string(),
+                    Err(_) => match &self.lower {
+                        Cut::Above(version, depth) => {
+                            format!(">{}", truncate(version, *depth))
+                        }
+                        Cut::Below(version, depth) => {
+                            format!(">={}", lowest_in(version, *depth))
+                        }
+                        Cut::Min | Cut::Max => String::new(),
+                    },
+                },
+            );
+        }
+        if self.upper != Cut::Max {
+            let range = VersionRange::between(Cut::Min, self.upper.clone());
+            bounds.push(
+                match find_specifier(&range, bound_candidates(&self.upper)) {
+                    Ok(specifier) => specifier.to_string(),
+                    Err(_) => match &self.upper {
+                        Cut::Above(version, depth) => {
+                            format!("<={}", truncate(version, *depth))
+                        }
+                        Cut::Below(version, depth) => {
+                            format!("<{}", lowest_in(version, *depth))
+                        }
+                        Cut::Min | Cut::Max => String::new(),
+                    },
+                },
+            );
+        }
+        write!(f, "{}", bounds.join(","))
+    }
+}
+
+impl From<Specifier> for VersionRange {
+    fn from(specifier: Specifier) -> Self {
+        Self::from(&specifier)
+    }
+}
+
+impl From<&SpecifierSet> for VersionRange {
+    fn from(specifiers: &SpecifierSet) -> Self {
+        specifiers.iter().fold(Self::full(), |range, specifier| {
+            range.intersection(&Self::from(specifier))
+        })
+    }
+}
+
+impl From<SpecifierSet> for VersionRange {
+    fn from(specifiers: SpecifierSet) -> Self {
+        Self::from(&specifiers)
+    }
+}
+
+/// Converts a range back into specifiers
+///
+/// This fails for empty ranges and ranges that need alternatives
+/// (`<1.0 or >=2.0`), gaps between intervals have to be a single
+/// version or prefix to be excluded with `!=`
+impl TryFrom<&VersionRange> for SpecifierSet {
+    type Error = anyhow::Error;
+
+    fn try_from(range: &VersionRange) -> Result<Self> {
+        let (first, last) = match (range.intervals.first(), range.intervals.last()) {
+            (Some(first), Some(last)) => (first, last),
+            _ => anyhow::bail!("An empty range has no specifiers"),
+        };
+
+        // A single version is easier to read as a pin
+        if let (Cut::Below(version, depth), 1) = (&first.lower, range.intervals.len()) {
+            let lowest = lowest_in(version, *depth);
+            let candidates = vec![format!("=={}", lowest), format!("==={}", lowest)];
+            if let Ok(specifier) = find_specifier(range, candidates) {
+                return Ok(specifier.into());
+            }
+        }
+
+        let mut specifiers = Vec::new();
+        if first.lower != Cut::Min {
+            let lower = VersionRange::between(first.lower.clone(), Cut::Max);
+            specifiers.push(find_specifier(&lower, bound_candidates(&first.lower))?);
+        }
+        for pair in range.intervals.windows(2) {
+            let (lower, upper) = (&pair[0].upper, &pair[1].lower);
+            let gap = VersionRange::between(lower.clone(), upper.clone());
+            specifiers.push(find_specifier(
+                &gap.complement(),
+                gap_candidates(lower, upper),
+            )?);
+        }
+        if last.upper != Cut::Max {
+            let upper = VersionRange::between(Cut::Min, last.upper.clone());
+            specifiers.push(find_specifier(&upper, bound_candidates(&last.upper))?);
+        }
+        Ok(specifiers.into_iter().collect())
+    }
+}
+
+impl TryFrom<VersionRange> for SpecifierSet {
+    type Error = anyhow::Error;
+
+    fn try_from(range: VersionRange) -> Result<Self> {
+        Self::try_from(&range)
+    }
+}
+
+/// The first candidate specifier matching exactly the versions in `range`
+fn find_specifier(range: &VersionRange, candidates: Vec<String>) -> Result<Specifier> {
+    candidates
+        .iter()
+        .filter_map(|candidate| Specifier::new(candidate).ok())
+        .find(|specifier| VersionRange::from(specifier) == *range)
+        .ok_or_else(|| anyhow::anyhow!("Range can't be written as specifiers"))
+}
+
+/// Specifiers that could be bounded by the cut
+fn bound_candidates(cut: &Cut) -> Vec<String> {
+    match cut {
+        Cut::Below(version, depth) => {
+            let lowest = lowest_in(version, *depth);
+            let mut candidates = vec![format!(">={}", lowest)];
+            // `<=1.0` ends right before `1.0.post0.dev0`
+            if let (Some(post), (false, 0)) = (lowest.post_key(), lowest.dev_key()) {
+                let epoch = lowest.epoch.unwrap_or(0);
+                let segments = lowest.release.segments.to_vec();
+                let pre = lowest.pre;
+                let previous = build(epoch, segments, pre, post.checked_sub(1), None);
+                candidates.push(format!("<={}", previous));
+            }
+            // `<2.0` already excludes the pre-releases of `2.0`
+            candidates.push(format!("<{}", truncate(version, *depth)));
+            candidates.push(format!("<{}", lowest));
+            candidates
+        }
+        Cut::Above(version, depth) => {
+            let highest = truncate(version, *depth);
+            vec![format!(">{}", highest), format!("<={}", highest)]
+        }
+        Cut::Min | Cut::Max => Vec::new(),
+    }
+}
+
+/// Specifiers that could exclude the versions between the cuts
+fn gap_candidates(lower: &Cut, upper: &Cut) -> Vec<String> {
+    let (version, depth) = match lower {
+        Cut::Below(version, depth) => (version, *depth),
+        _ => return Vec::new(),
+    };
+    let lowest = lowest_in(version, depth);
+    let mut candidates = vec![format!("!={}", truncate(&lowest, SORT_KEY_LEN - 1))];
+
+    // The end of a prefix is the next release of the same length
+    let mut length = version.release.segments.len();
+    if let Cut::Below(end, _) = upper {
+        length = length.max(end.release.segments.len());
+    }
+    for len in 1..=length {
+        let prefix: Vec<String> = (0..len)
+            .map(|i| {
+                let segment = version.release.segments.get(i).copied().unwrap_or(0);
+                segment.to_string()
+            })
+            .collect();
+        let epoch = match version.epoch.unwrap_or(0) {
+            0 => String::new(),
+            epoch => format!("{}!", epoch),
+        };
+        candidates.push(format!("!={}{}.*", epoch, prefix.join(".")));
+    }
+    candidates
+}
+
+/// The lowest version sharing the first `depth` components with `version`
+fn lowest_in(version: &PackageVersion, depth: usize) -> PackageVersion {
+    let epoch = version.epoch.unwrap_or(0);
+    let segments = version.release.segments.to_vec();
+    let pre = version.pre;
+    let post = version.post_key();
+
+    match depth {
+        1 => build(epoch, vec![0], None, None, Some(0)),
+        2 => build(epoch, segments, None, None, Some(0)),
+        // Final releases have no dev releases after their pre-releases
+        3 if pre.is_none() => release(epoch, segments),
+        3 => build(epoch, segments, pre, None, Some(0)),
+        4 => build(epoch, segments, pre, post, Some(0)),
+        5 => truncate(version, depth),
+        _ => version.clone(),
+    }
+}
+
+/// The version without the components after `depth`
+fn truncate(version: &PackageVersion, depth: usize) -> PackageVersion {
+    let epoch = version.epoch.unwrap_or(0);
+    let segments = version.release.segments.to_vec();
+    let pre = version.pre;
+    let post = version.post_key();
+    let dev = version.dev.as_ref().map(|dev| dev.dev_num.unwrap_or(0));
+
+    match depth {
+        1 | 2 => release(epoch, segments),
+        3 => build(epoch, segments, pre, None, None),
+        4 => build(epoch, segments, pre, post, None),
+        5 => build(epoch, segments, pre, post, dev),
+        _ => version.clone(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::VersionRange;
+    use crate::specifiers::{Specifier, SpecifierSet};
+    use crate::PackageVersion;
+    use anyhow::Result;
+
+    /// Versions around the ones used in the specifiers below,
+    /// covering every kind of suffix
+    fn sample_versions() -> Vec<PackageVersion> {
+        let releases = [
+            "0", "0.9", "1.0", "1.0.1", "1.1", "1.4.5", "1.5", "2.0", "2.0.1", "2.1",
+            "3.0", "1!1.0",
+        ];
+        let suffixes = [
+            "",
+            ".dev0",
+            ".dev1",
+            "a1.dev1",
+            "a1",
+            "a1.post1",
+            "a2",
+            "b1",
+            "rc1",
+            "+local",
+            ".post0.dev0",
+            ".post0",
+            ".post1.dev1",
+            ".post1",
+            ".post1+local",
+            ".post2",
+        ];
+
+        releases
+            .iter()
+            .flat_map(|release| {
+                suffixes
+                    .iter()
+                    .map(move |suffix| format!("{}{}", release, suffix))
+            })
+            .map(|v| PackageVersion::new(&v).unwrap())
+            .collect()
+    }
+
+    fn specifier_sets() -> Vec<SpecifierSet> {
+        [
+            "",
+            "==1.0",
+            "==1.0+local",
+            "!=1.0",
+            "==1.*",
+            "!=2.0.*",
+            "==1!1.*",
+            ">=1.0",
+            ">=1.0a1",
+            "<=1.0",
+            "<=1.0.post1",
+            "<2.0",
+            "<2.0rc1",
+            "<1.0.post1",
+            ">1.0",
+            ">1.0a1",
+            ">1.0.post1",
+            ">1.0.dev1",
+            "~=1.4.5",
+            "~=1.0",
+            "~=2.0.post1",
+            "===1.0",
+            "===foobar",
+            ">=1.0, <2.0",
+            ">1.0, !=1.5, <=2.1",
+            ">=2.0, <1.0",
+        ]
+        .iter()
+        .map(|set| SpecifierSet::new(set).unwrap())
+        .collect()
+    }
+
+    #[test]
+    fn test_range_from_specifiers() {
+        let versions = sample_versions();
+
+        for set in specifier_sets() {
+            let range = VersionRange::from(&set);
+            for version in &versions {
+                assert_eq!(
+                    range.contains(version),
+                    set.matches(version),
+                    "{} in {}",
+                    version,
+                    set
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_set_union_and_intersection() {
+        let versions = sample_versions();
+        let sets = specifier_sets();
+
+        for a in &sets {
+            for b in &sets {
+                let union = a.union(b);
+                let intersection = a.intersection(b);
+                for version in &versions {
+                    let (in_a, in_b) = (a.matches(version), b.matches(version));
+                    assert_eq!(
+                        union.contains(version),
+                        in_a || in_b,
+                        "{} in ({}) or ({})",
+                        version,
+                        a,
+                        b
+                    );
+                    assert_eq!(
+                        intersection.matches(version),
+                        in_a && in_b,
+                        "{} in ({}) and ({})",
+                        version,
+                        a,
+                        b
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_range_complement() {
+        let versions = sample_versions();
+
+        for set in specifier_sets() {
+            let range = VersionRange::from(&set);
+            let complement = range.complement();
+            for version in &versions {
+                assert_ne!(complement.contains(version), range.contains(version));
+            }
+            assert_eq!(complement.complement(), range);
+            assert!(complement.intersection(&range).is_empty());
+            assert_eq!(complement.union(&range), VersionRange::full());
+        }
+    }
+
+    #[test]
+    fn test_range_to_specifiers() -> Result<()> {
+        for set in specifier_sets() {
+            let range = VersionRange::from(&set);
+            if range.is_empty() {
+                assert!(SpecifierSet::try_from(&range).is_err());
+                continue;
+            }
+            let converted = SpecifierSet::try_from(&range)?;
+            assert_eq!(VersionRange::from(&converted), range, "{}", set);
+        }
+
+        let convert = |set: &str| -> Result<String> {
+            let range = VersionRange::from(&SpecifierSet::new(set)?);
+            Ok(SpecifierSet::try_from(range)?.to_string())
+        };
+        assert_eq!(convert(">=1.0, <2.0, >=0.5")?, ">=1.0,<2.0");
+        assert_eq!(convert("<=1.0, !=0.5")?, "!=0.5,<=1.0");
+        assert_eq!(convert("==1.5")?, "==1.5");
+        assert_eq!(convert("==1.5+local")?, "==1.5+local");
+        assert_eq!(convert(">=1.5, <=1.5")?, "==1.5");
+        assert_eq!(convert("~=1.4.5")?, ">=1.4.5,<1.5");
+        assert_eq!(convert(">1.0, !=2.*")?, ">1.0,!=2.*");
+        assert_eq!(convert("")?, "");
+
+        let alternatives =
+            SpecifierSet::new("<1.0")?.union(&SpecifierSet::new(">=2.0")?);
+        assert!(SpecifierSet::try_from(&alternatives).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_range_display() -> Result<()> {
+        let ranges = vec![
+            (VersionRange::full(), "*"),
+            (VersionRange::empty(), "∅"),
+            (VersionRange::from(SpecifierSet::new("<1.0, >=2.0")?), "∅"),
+            (
+                VersionRange::from(SpecifierSet::new(">1.0, !=1.5.*, <3")?),
+                ">1.0,<1.5 || >=1.6.dev0,<3",
+            ),
+            (
+                VersionRange::from(SpecifierSet::new("==1.0+local")?),
+                "==1.0+local",
+            ),
+            (
+                VersionRange::from(SpecifierSet::new("<=1.0, !=1.0+local")?),
+                "<1.0+local || >1.0+local,<=1.0",
+            ),
+            (
+                VersionRange::singleton(&PackageVersion::new("1.0")?),
+                "===1.0",
+            ),
+        ];
+
+        for (range, expected) in ranges {
+            assert_eq!(range.to_string(), expected);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_range_normalization() -> Result<()> {
+        let range = |set: &str| VersionRange::from(&SpecifierSet::new(set).unwrap());
+
+        assert!(range(">=2.0, <1.0").is_empty());
+        assert!(range(">1.0, <1.0.post0.dev0").is_empty());
+        assert!(range("<1.0, >=1.0").is_empty());
+        assert!(!range(">1.0, <=1.0.0.1").is_empty());
+        assert_eq!(range(""), VersionRange::full());
+        assert_eq!(range("===foobar"), VersionRange::empty());
+
+        // Touching intervals are merged
+        assert_eq!(range("<1.0").union(&range(">=1.0.dev0")), range(""));
+        assert_eq!(range("<=1.0").union(&range(">=1.0.post0.dev0")), range(""));
+        assert!(!range("<=1.0")
+            .union(&range(">1.0"))
+            .contains(&PackageVersion::new("1.0.post1")?));
+        assert_eq!(range("==1.0").union(&range("!=1.0")), VersionRange::full());
+        assert_eq!(
+            range(">=1.0, <1.1").union(&range(">=1.1.dev0, <2.0")),
+            range(">=1.0, <2.0")
+        );
+        assert_eq!(range("==1.*"), range(">=1.dev0, <2.dev0"));
+
+        let specifier: Specifier = "!=1.5".parse()?;
+        assert!(
+            !VersionRange::from(specifier).contains(&PackageVersion::new("1.5+local")?)
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,264 @@
+use super::{Specifier, SpecifierSet, VersionRange};
+use crate::PackageVersion;
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use anyhow::Result;
+
+/// # Combined `Requires-Python`
+/// The interpreter versions supported by every one of many
+/// `Requires-Python` values, e.g. those of a project's dependencies
+///
+/// ## Example Usage
+/// ```
+/// use pyver::specifiers::{parse_requires_python, RequiresPython};
+///
+/// let values = [">=3.7", ">=3.8.*, <4", "!=3.8.*, !=3.9.0"];
+/// let specs: Vec<_> = values
+///     .iter()
+///     .map(|v| parse_requires_python(v).unwrap())
+///     .collect();
+/// let combined = RequiresPython::combine(&specs);
+///
+/// assert_eq!(combined.minimum_python().unwrap().to_string(), "3.9.1");
+/// assert_eq!(
+///     combined.to_specifiers().unwrap().to_string(),
+///     ">=3.9.dev0,!=3.9.0,<4"
+/// );
+///
+/// let specs = vec![parse_requires_python("<3.8").unwrap(), specs[1].clone()];
+/// assert!(!RequiresPython::combine(&specs).is_satisfiable());
+/// ```
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RequiresPython {
+    range: VersionRange,
+}
+
+impl RequiresPython {
+    /// Intersects the values, no values allow every interpreter
+    pub fn combine<'a>(specs: impl IntoIterator<Item = &'a SpecifierSet>) -> Self {
+        let range = specs.into_iter().fold(VersionRange::full(), |range, spec| {
+            range.intersection(&VersionRange::from(spec))
+        });
+        Self { range }
+    }
+
+    /// The interpreter versions supported by every value
+    pub fn range(&self) -> &VersionRange {
+        &self.range
+    }
+
+    /// Whether any interpreter version is supported by every value
+    pub fn is_satisfiable(&self) -> bool {
+        !self.range.is_empty()
+    }
+
+    /// Whether an interpreter version is supported by every value,
+    /// pre-releases included
+    pub fn contains(&self, interpreter: &PackageVersion) -> bool {
+        self.range.contains(interpreter)
+    }
+
+    /// The combined values as a single specifier set,
+    /// which fails if they allow no version at all
+    pub fn to_specifiers(&self) -> Result<SpecifierSet> {
+        if !self.is_satisfiable() {
+            anyhow::bail!("Requires-Python values have no version in common");
+        }
+        SpecifierSet::try_from(&self.range)
+    }
+
+    /// The lowest CPython release (`major.minor.micro`) supported
+    /// by every value, failing if no such release exists
+    pub fn minimum_python(&self) -> Result<PackageVersion> {
+        if !self.is_satisfiable() {
+            anyhow::bail!("Requires-Python values have no version in common");
+        }
+
+        // The lowest release of an interval is either the release its bound
+        // starts with or the next micro release, later intervals only
+        // have to be checked if an interval has neither
+        for lower in self.range.lower_bounds() {
+            let segments = match lower {
+                Some(version) if version.epoch.unwrap_or(0) != 0 => continue,
+                Some(version) => &version.release.segments[..],
+                None => &[],
+            };
+            let segment = |i: usize| segments.get(i).copied().unwrap_or(0);
+            let (major, minor, micro) = (segment(0), segment(1), segment(2));
+
+            for micro in [Some(micro), micro.checked_add(1)].into_iter().flatten() {
+                let release =
+                    PackageVersion::new(&format!("{}.{}.{}", major, minor, micro))?;
+                if self.range.contains(&release) {
+                    return Ok(release);
+                }
+            }
+        }
+        anyhow::bail!("Requires-Python values allow no CPython release")
+    }
+}
+
+/// Whether an interpreter version satisfies a `Requires-Python` value
+///
+/// Like pip, pre-releases of the interpreter are always allowed,
+/// so `3.13.0rc1` matches `>=3.8`
+///
+/// ```
+/// use pyver::specifiers::{requires_python_matches, SpecifierSet};
+/// use pyver::PackageVersion;
+///
+/// let spec = SpecifierSet::new(">=3.8").unwrap();
+/// assert!(requires_python_matches(&spec, &PackageVersion::new("3.13.0rc1").unwrap()));
+/// assert!(!requires_python_matches(&spec, &PackageVersion::new("3.7.17").unwrap()));
+/// ```
+pub fn requires_python_matches(
+    spec: &SpecifierSet,
+    interpreter: &PackageVersion,
+) -> bool {
+    spec.matches(interpreter)
+}
+
+/// Parses a `Requires-Python` value, tolerating the
+/// non standard values found in published metadata
+///
+/// - Surrounding quotes and empty items (`">=3.6,"`) are dropped
+/// - Wildcards on ordered comparisons are removed (`>=3.6.*` is `>=3.6`)
+/// - A wildcard without its dot is completed (`!=3.0*` is `!=3.0.*`)
+/// - A single `=` is read as `==`
+/// - A bare version (`3.6`) matches that release series (`==3.6.*`)
+///
+/// ```
+/// use pyver::specifiers::parse_requires_python;
+///
+/// let spec = parse_requires_python(">=2.7, !=3.0*, >=3.6.*, ").unwrap();
+/// assert_eq!(spec.to_string(), ">=2.7,!=3.0.*,>=3.6");
+/// ```
+pub fn parse_requires_python(value: &str) -> Result<SpecifierSet> {
+    let value = value.trim().trim_matches(|c| c == '"' || c == '\'');
+
+    let mut specifiers = Vec::new();
+    for item in value.split(',') {
+        let item = item.trim();
+        if item.is_empty() {
+            continue;
+        }
+        specifiers.push(repair_specifier(item));
+    }
+    SpecifierSet::new(&specifiers.join(","))
+}
+
+/// Rewrites a single quirky specifier into a valid one,
+/// valid specifiers are returned unchanged
+fn repair_specifier(item: &str) -> String {
+    if Specifier::new(item).is_ok() {
+        return item.to_string();
+    }
+
+    let operator_end = item
+        .find(|c: char| !matches!(c, '=' | '!' | '<' | '>' | '~'))
+        .unwrap_or(item.len());
+    let operator = match &item[..operator_end] {
+        "" => "==",
+        "=" => "==",
+        operator => operator,
+    };
+    let mut version = item[operator_end..].trim().to_string();
+
+    if version.ends_with('*') && !version.ends_with(".*") {
+        version.insert(version.len() - 1, '.');
+    }
+    let wildcard = operator_end == 0 || version.ends_with(".*");
+    let version = version.strip_suffix(".*").unwrap_or(&version);
+
+    match (operator, wildcard) {
+        ("==" | "!=", true) => format!("{}{}.*", operator, version),
+        (operator, _) => format!("{}{}", operator, version),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_requires_python, requires_python_matches, RequiresPython};
+    use crate::PackageVersion;
+    use anyhow::Result;
+
+    #[test]
+    fn test_requires_python_parsing() -> Result<()> {
+        let values = vec![
+            ("", ""),
+            (">=3.8", ">=3.8"),
+            ("'>=3.6.*'", ">=3.6"),
+            ("~=3.6.*", "~=3.6"),
+            (">=2.7,!=3.0.*,!=3.1*,", ">=2.7,!=3.0.*,!=3.1.*"),
+            ("=3.6", "==3.6"),
+            ("3.6", "==3.6.*"),
+            ("> 3.5 , < 4", ">3.5,<4"),
+        ];
+
+        for (value, expected) in values {
+            assert_eq!(parse_requires_python(value)?.to_string(), expected);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_requires_python_matches() -> Result<()> {
+        let cases = vec![
+            (">=3.6.*", "3.6.0", true),
+            (">=3.6.*", "3.5.10", false),
+            ("3", "3.12.1", true),
+            (">=2.7, !=3.0*, !=3.1*", "3.1.4", false),
+            ("<3.13", "3.13.0a1", false),
+            (">=3.12", "3.12.0b2", false),
+            (">=3.12.0b1", "3.12.0b2", true),
+            ("", "2.7.18", true),
+        ];
+
+        for (spec, interpreter, expected) in cases {
+            let spec = parse_requires_python(spec)?;
+            let interpreter = PackageVersion::new(interpreter)?;
+            assert_eq!(requires_python_matches(&spec, &interpreter), expected);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_requires_python_combine() -> Result<()> {
+        let cases = vec![
+            (vec![], Some("0.0.0")),
+            (vec![">=3.6"], Some("3.6.0")),
+            (vec![">3.6"], Some("3.6.1")),
+            (vec![">=3.6.0.1"], Some("3.6.1")),
+            (vec![">=2.7", "!=3.0.*", ">=3"], Some("3.1.0")),
+            (vec![">=3.8", "<3.12", "~=3.10"], Some("3.10.0")),
+            (vec![">=3.13.0rc1"], Some("3.13.0")),
+            (vec!["<3.8", ">=3.8"], None),
+            (vec![">=3.8.0.1", "<3.8.1"], None),
+            (vec![">=1!3.8"], None),
+            (vec![">3.6.18446744073709551615"], None),
+        ];
+
+        for (values, expected) in cases {
+            let specs = values
+                .iter()
+                .map(|v| parse_requires_python(v))
+                .collect::<Result<Vec<_>>>()?;
+            let combined = RequiresPython::combine(&specs);
+            let minimum = combined.minimum_python().ok().map(|v| v.to_string());
+            assert_eq!(minimum.as_deref(), expected, "{:?}", values);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_requires_python_negative() {
+        let values = vec![">=", "py3", ">=3.6, <", "==3.6.*.*", "~=3"];
+
+        for value in values {
+            if let Ok(v) = parse_requires_python(value) {
+                panic!("Oh no {}", v);
+            }
+        }
+    }
+}
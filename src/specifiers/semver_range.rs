@@ -0,0 +1,423 @@
+use super::{Operator, Specifier, SpecifierSet, VersionRange};
+use crate::PackageVersion;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use alloc::{format, vec};
+use anyhow::Result;
+
+/// Which ecosystem's rules [`translate_range`] reads a range with
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub enum RangeSyntax {
+    /// npm's `node-semver` ranges, a bare version is an exact match and
+    /// alternatives (`^1.0 || ^2.0`) and hyphen ranges (`1.2 - 2.3`) are
+    /// allowed
+    Npm,
+    /// Cargo's version requirements, a bare version is a caret requirement
+    Cargo,
+}
+
+/// A part of a range the translated specifiers don't match exactly the same
+/// versions for
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+#[non_exhaustive]
+pub enum Inexact {
+    /// A comparator with a pre-release (`>=1.2.3-beta.2`), semver only
+    /// matches pre-releases of that release, the specifiers match those of
+    /// every release in range
+    PreRelease(String),
+    /// Alternatives (`^1.0 || ^2.0`) combined into one set, which matches
+    /// the pre-releases between them (`2.0.0-rc.1`)
+    Alternatives(String),
+}
+
+/// # Translated range
+/// The `PEP-440` specifiers for an npm or Cargo version range and the parts
+/// of the range they don't translate exactly, see [`translate_range`]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Translation {
+    pub specifiers: SpecifierSet,
+    pub inexact: Vec<Inexact>,
+}
+
+impl Translation {
+    /// Whether the specifiers match the same versions as the range
+    pub fn is_exact(&self) -> bool {
+        self.inexact.is_empty()
+    }
+}
+
+/// Translates an npm or Cargo version range into specifiers
+///
+/// Caret (`^1.2.3`), tilde (`~1.2`), wildcard (`1.2.x`, `*`) and partial
+/// (`>1.2`) comparators become upper and lower bounds like semver reads
+/// them, `^0.2.3` is `>=0.2.3,<0.3`, `1.2.x` is `>=1.2,<1.3` and `>1.2`
+/// is `>=1.3`. Comparators are separated by whitespace or commas and
+/// build metadata is ignored like semver ignores it.
+///
+/// Ranges the specifiers only approximate are translated anyway and listed
+/// in [`Translation::inexact`], ranges without any equivalent fail, like
+/// alternatives with a gap between them (`<1.0 || >=2.0`).
+///
+/// ```
+/// use pyver::specifiers::{translate_range, RangeSyntax};
+///
+/// let translation = translate_range("^1.2.3", RangeSyntax::Npm).unwrap();
+/// assert_eq!(translation.specifiers.to_string(), ">=1.2.3,<2");
+/// assert!(translation.is_exact());
+///
+/// let translation = translate_range("1.2 - 2.3.4", RangeSyntax::Npm).unwrap();
+/// assert_eq!(translation.specifiers.to_string(), ">=1.2,<=2.3.4");
+///
+/// // A bare version is a caret requirement in Cargo
+/// let translation = translate_range("0.4, <0.4.7", RangeSyntax::Cargo).unwrap();
+/// assert_eq!(translation.specifiers.to_string(), ">=0.4,<0.5,<0.4.7");
+///
+/// let translation = translate_range(">=1.2.3-beta.2", RangeSyntax::Npm).unwrap();
+/// assert_eq!(translation.specifiers.to_string(), ">=1.2.3b2");
+/// assert!(!translation.is_exact());
+///
+/// assert!(translate_range("<1.0 || >=2.0", RangeSyntax::Npm).is_err());
+/// ```
+pub fn translate_range(range: &str, syntax: RangeSyntax) -> Result<Translation> {
+    if syntax == RangeSyntax::Cargo && range.contains("||") {
+        anyhow::bail!("Cargo has no alternatives, use npm syntax for {}", range);
+    }
+
+    let mut inexact = Vec::new();
+    let mut alternatives = Vec::new();
+    for alternative in range.split("||") {
+        alternatives.push(translate_comparators(alternative, syntax, &mut inexact)?);
+    }
+
+    let specifiers = match alternatives.as_slice() {
+        [specifiers] => specifiers.clone(),
+        _ => union_of(range, &alternatives, &mut inexact)?,
+    };
+    Ok(Translation {
+        specifiers,
+        inexact,
+    })
+}
+
+/// One set for alternatives that leave no gap between each other
+fn union_of(
+    range: &str,
+    alternatives: &[SpecifierSet],
+    inexact: &mut Vec<Inexact>,
+) -> Result<SpecifierSet> {
+    let mut union = VersionRange::empty();
+    for specifiers in alternatives {
+        union = union.union(&VersionRange::from(specifiers));
+    }
+
+    // Pre-releases of `2.0` are neither below `<2.0` nor above `>=2.0`, so
+    // `^1.0 || ^2.0` has a gap that only a union of sets could leave out
+    let specifiers = || alternatives.iter().flat_map(SpecifierSet::iter);
+    let mut filled = false;
+    for upper in specifiers().filter(|s| s.operator() == Operator::LessThan) {
+        let adjacent = specifiers().any(|lower| {
+            lower.operator() == Operator::GreaterThanEqual
+                && lower.version() == upper.version()
+        });
+        if adjacent {
+            let lower = Specifier::new(&format!(">={}", upper.version_str()))?;
+            let gap = VersionRange::from(&SpecifierSet::from(upper.clone()))
+                .union(&VersionRange::from(&SpecifierSet::from(lower)))
+                .complement();
+            filled |= !gap.intersection(&union.complement()).is_empty();
+            union = union.union(&gap);
+        }
+    }
+
+    let specifiers = SpecifierSet::try_from(&union).map_err(|_| {
+        anyhow::anyhow!("No specifier set matches the alternatives of {}", range)
+    })?;
+    if filled {
+        inexact.push(Inexact::Alternatives(range.trim().to_string()));
+    }
+    Ok(specifiers)
+}
+
+/// The specifiers for comparators that all have to match (`>=1.2 <2`)
+fn translate_comparators(
+    comparators: &str,
+    syntax: RangeSyntax,
+    inexact: &mut Vec<Inexact>,
+) -> Result<SpecifierSet> {
+    let spaced = comparators.replace(',', " ");
+    let mut tokens = spaced.split_whitespace().peekable();
+    let mut specifiers = Vec::new();
+
+    while let Some(token) = tokens.next() {
+        // Operators may be written apart from their version (`>= 1.2`)
+        let mut comparator = token.to_string();
+        if comparator
+            .trim_start_matches(['<', '>', '=', '^', '~'])
+            .is_empty()
+        {
+            match tokens.next() {
+                Some(version) => comparator.push_str(version),
+                None => anyhow::bail!("The operator {} has no version", comparator),
+            }
+        }
+
+        if tokens.peek() == Some(&"-") {
+            if syntax == RangeSyntax::Cargo {
+                anyhow::bail!("Cargo has no hyphen ranges, use npm syntax");
+            }
+            tokens.next();
+            let upper = match tokens.next() {
+                Some(upper) => upper,
+                None => {
+                    anyhow::bail!("The hyphen range from {} has no end", comparator)
+                }
+            };
+            specifiers
+                .extend(translate_comparator(&format!(">={}", comparator), syntax)?);
+            specifiers.extend(translate_comparator(&format!("<={}", upper), syntax)?);
+        } else {
+            specifiers.extend(translate_comparator(&comparator, syntax)?);
+        }
+    }
+
+    let set: SpecifierSet = specifiers.into_iter().collect();
+    for specifier in set.iter() {
+        let prerelease = specifier
+            .version()
+            .is_some_and(PackageVersion::is_prerelease);
+        if prerelease && specifier.operator() != Operator::Equal {
+            inexact.push(Inexact::PreRelease(specifier.to_string()));
+        }
+    }
+    Ok(set)
+}
+
+/// The specifiers for one comparator (`^1.2`, `>=1.2.3-rc.1`, `1.x`)
+fn translate_comparator(
+    comparator: &str,
+    syntax: RangeSyntax,
+) -> Result<Vec<Specifier>> {
+    let operators = [">=", "<=", ">", "<", "=", "^", "~"];
+    let (operator, version) = operators
+        .iter()
+        .find_map(|operator| {
+            comparator
+                .strip_prefix(operator)
+                .map(|version| (*operator, version))
+        })
+        .unwrap_or(("", comparator));
+    let version = version.strip_prefix(['v', 'V']).unwrap_or(version);
+    let (segments, pre) = parse_partial(version)?;
+
+    let mut written = segments
+        .iter()
+        .map(u64::to_string)
+        .collect::<Vec<String>>()
+        .join(".");
+    if let Some(pre) = pre {
+        written = format!("{}-{}", written, pre);
+    }
+    let partial = segments.len() < 3;
+    let bump = |index: usize| next_release(comparator, &segments, index);
+
+    let operator = match (operator, syntax) {
+        ("", RangeSyntax::Npm) => "=",
+        ("", RangeSyntax::Cargo) => "^",
+        (operator, _) => operator,
+    };
+    let specifiers = match (operator, segments.len()) {
+        (">" | "<", 0) => anyhow::bail!("{} matches no versions", comparator),
+        (_, 0) => Vec::new(),
+        ("^", _) => SpecifierSet::caret(&parse_version(&written)?)?
+            .into_iter()
+            .collect(),
+        ("~", _) => SpecifierSet::tilde(&parse_version(&written)?)?
+            .into_iter()
+            .collect(),
+        ("=", _) if partial => vec![
+            Specifier::new(&format!(">={}", written))?,
+            Specifier::new(&format!("<{}", bump(segments.len() - 1)?))?,
+        ],
+        (">", _) if partial => {
+            vec![Specifier::new(&format!(">={}", bump(segments.len() - 1)?))?]
+        }
+        ("<=", _) if partial => {
+            vec![Specifier::new(&format!("<{}", bump(segments.len() - 1)?))?]
+        }
+        ("=", _) => vec![Specifier::new(&format!(
+            "=={}",
+            parse_version(&written)?.public()
+        ))?],
+        (operator, _) => vec![Specifier::new(&format!(
+            "{}{}",
+            operator,
+            parse_version(&written)?.public()
+        ))?],
+    };
+    Ok(specifiers)
+}
+
+/// The release segments before the first wildcard and the pre-release of
+/// a semver version that may leave out segments (`1.2`, `1.x`, `*`)
+fn parse_partial(version: &str) -> Result<(Vec<u64>, Option<&str>)> {
+    // Build metadata doesn't change which versions match
+    let version = version
+        .split_once('+')
+        .map_or(version, |(version, _)| version);
+    let (release, pre) = match version.split_once('-') {
+        Some((release, pre)) => (release, Some(pre)),
+        None => (version, None),
+    };
+
+    let mut segments = Vec::new();
+    let mut wildcard = false;
+    for (index, segment) in release.split('.').enumerate() {
+        if index == 3 {
+            anyhow::bail!("The version {} has more than three segments", version);
+        }
+        if matches!(segment, "x" | "X" | "*") {
+            wildcard = true;
+        } else if wildcard {
+            anyhow::bail!("The version {} has a number after a wildcard", version);
+        } else {
+            match segment.parse::<u64>() {
+                Ok(segment) => segments.push(segment),
+                Err(_) => {
+                    anyhow::bail!("The version {} isn't a semver version", version)
+                }
+            }
+        }
+    }
+    if pre.is_some() && segments.len() < 3 {
+        anyhow::bail!("The pre-release of {} needs a full version", version);
+    }
+    Ok((segments, pre))
+}
+
+/// The semver version as a `PEP-440` version
+fn parse_version(version: &str) -> Result<PackageVersion> {
+    match PackageVersion::new(version) {
+        Ok(parsed) if version.contains('-') && !parsed.is_prerelease() => {
+            anyhow::bail!("The pre-release of {} isn't one in PEP 440", version)
+        }
+        Ok(parsed) => Ok(parsed),
+        Err(_) => anyhow::bail!("The version {} has no PEP 440 spelling", version),
+    }
+}
+
+/// The release with the segment at the index incremented and the later
+/// ones dropped
+fn next_release(comparator: &str, segments: &[u64], index: usize) -> Result<String> {
+    let mut next = segments[..index].to_vec();
+    match segments[index].checked_add(1) {
+        Some(segment) => next.push(segment),
+        None => anyhow::bail!("There is no version above {}", comparator),
+    }
+    Ok(next
+        .iter()
+        .map(u64::to_string)
+        .collect::<Vec<String>>()
+        .join("."))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{translate_range, Inexact, RangeSyntax};
+
+    #[test]
+    fn test_translate_npm_range() {
+        let ranges = [
+            ("^1.2.3", ">=1.2.3,<2"),
+            ("^0.2.3", ">=0.2.3,<0.3"),
+            ("^0.0.3", ">=0.0.3,<0.0.4"),
+            ("^1.x", ">=1,<2"),
+            ("^0.0", ">=0.0,<0.1"),
+            ("~1.2.3", ">=1.2.3,<1.3"),
+            ("~1.2", ">=1.2,<1.3"),
+            ("~1", ">=1,<2"),
+            ("1.2.x", ">=1.2,<1.3"),
+            ("1.X", ">=1,<2"),
+            ("1.2.3", "==1.2.3"),
+            ("=v1.2.3+build.5", "==1.2.3"),
+            ("*", ""),
+            ("", ""),
+            (">1.2", ">=1.3"),
+            (">1.2.3", ">1.2.3"),
+            ("<=1.2", "<1.3"),
+            ("<1.2", "<1.2"),
+            (">= 1.2.3 < 2", ">=1.2.3,<2"),
+            ("1.2.3 - 2.3.4", ">=1.2.3,<=2.3.4"),
+            ("1.2 - 2.3", ">=1.2,<2.4"),
+            ("1.2.3 - 2", ">=1.2.3,<3"),
+            ("1.2.3 - *", ">=1.2.3"),
+            ("^1.2 || ^2", ">=1.2,<3"),
+        ];
+        for (range, expected) in ranges {
+            let translation = translate_range(range, RangeSyntax::Npm).unwrap();
+            assert_eq!(translation.specifiers.to_string(), expected, "{}", range);
+        }
+    }
+
+    #[test]
+    fn test_translate_cargo_range() {
+        let ranges = [
+            ("1.2.3", ">=1.2.3,<2"),
+            ("0.2", ">=0.2,<0.3"),
+            ("=1.2.3", "==1.2.3"),
+            ("=1.2", ">=1.2,<1.3"),
+            (">=1.2, <1.5", ">=1.2,<1.5"),
+            ("~1.2.3", ">=1.2.3,<1.3"),
+            ("*", ""),
+        ];
+        for (range, expected) in ranges {
+            let translation = translate_range(range, RangeSyntax::Cargo).unwrap();
+            assert_eq!(translation.specifiers.to_string(), expected, "{}", range);
+            assert!(translation.is_exact());
+        }
+    }
+
+    #[test]
+    fn test_translate_range_inexact() {
+        let translation = translate_range("=1.2.3-rc.1", RangeSyntax::Npm).unwrap();
+        assert_eq!(translation.specifiers.to_string(), "==1.2.3rc1");
+        assert!(translation.is_exact());
+
+        let translation =
+            translate_range("^1.2.3-alpha.1", RangeSyntax::Cargo).unwrap();
+        assert_eq!(translation.specifiers.to_string(), ">=1.2.3a1,<2");
+        assert_eq!(
+            translation.inexact,
+            vec![Inexact::PreRelease(">=1.2.3a1".into())]
+        );
+
+        let translation = translate_range("1.x || 2.x", RangeSyntax::Npm).unwrap();
+        assert_eq!(
+            translation.inexact,
+            vec![Inexact::Alternatives("1.x || 2.x".into())]
+        );
+    }
+
+    #[test]
+    fn test_translate_range_negative() {
+        let ranges = [
+            ("<1.0 || >=2.0", RangeSyntax::Npm),
+            ("1.0 || 2.0", RangeSyntax::Cargo),
+            ("1.0 - 2.0", RangeSyntax::Cargo),
+            ("1.2.3.4", RangeSyntax::Npm),
+            ("1.x.3", RangeSyntax::Npm),
+            ("1.2-beta", RangeSyntax::Npm),
+            ("1.2.3-x.7", RangeSyntax::Npm),
+            ("1.2.3-1", RangeSyntax::Npm),
+            ("<*", RangeSyntax::Npm),
+            (">=", RangeSyntax::Npm),
+            ("1.2.3 -", RangeSyntax::Npm),
+            ("latest", RangeSyntax::Npm),
+        ];
+        for (range, syntax) in ranges {
+            match translate_range(range, syntax) {
+                Ok(v) => panic!("Oh no {}", v.specifiers),
+                Err(_e) => continue,
+            }
+        }
+    }
+}
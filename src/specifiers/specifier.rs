@@ -0,0 +1,688 @@
+use crate::version::SORT_KEY_LEN;
+use crate::PackageVersion;
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use anyhow::Result;
+use core::cmp::Ordering;
+use core::fmt;
+use core::str::FromStr;
+use serde::{Deserialize, Serialize};
+
+/// # `PEP-440` Comparison operator
+/// The operator at the start of a version specifier
+#[derive(Hash, Copy, Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+pub enum Operator {
+    /// `==`, Version matching
+    Equal,
+    /// `!=`, Version exclusion
+    NotEqual,
+    /// `<=`, Inclusive ordered comparison
+    LessThanEqual,
+    /// `>=`, Inclusive ordered comparison
+    GreaterThanEqual,
+    /// `<`, Exclusive ordered comparison
+    LessThan,
+    /// `>`, Exclusive ordered comparison
+    GreaterThan,
+    /// `~=`, Compatible release
+    Compatible,
+    /// `===`, Arbitrary equality
+    ArbitraryEqual,
+}
+
+impl Operator {
+    /// The operator as written in a specifier
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Operator::Equal => "==",
+            Operator::NotEqual => "!=",
+            Operator::LessThanEqual => "<=",
+            Operator::GreaterThanEqual => ">=",
+            Operator::LessThan => "<",
+            Operator::GreaterThan => ">",
+            Operator::Compatible => "~=",
+            Operator::ArbitraryEqual => "===",
+        }
+    }
+}
+
+impl fmt::Display for Operator {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+/// # `PEP-440` Version specifier
+/// A single operator and version such as `>=1.0` or `!=1.3.4`
+///
+/// `==` and `!=` also accept a trailing `.*` (`==1.1.*`)
+/// to match every version starting with the given release.
+///
+/// Like pip, pre-releases are only matched when the specifier itself
+/// mentions one (`>=1.0rc1`), see [`Specifier::with_prereleases`]
+/// to override this.
+///
+/// ## Example Usage
+/// ```
+/// use pyver::specifiers::Specifier;
+/// use pyver::PackageVersion;
+///
+/// let specifier = Specifier::new(">=1.0").unwrap();
+///
+/// assert!(specifier.contains(&PackageVersion::new("1.2").unwrap()));
+/// assert!(!specifier.contains(&PackageVersion::new("0.9").unwrap()));
+/// ```
+#[derive(Hash, Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+#[serde(try_from = "String", into = "String")]
+pub struct Specifier {
+    operator: Operator,
+    /// The version as written (surrounding whitespace and `.*` removed)
+    raw: String,
+    /// `None` only for `===` with a non `PEP-440` version
+    version: Option<PackageVersion>,
+    wildcard: bool,
+    /// Explicit pre-release policy, `None` to decide based on the specifier
+    pub(crate) prereleases: Option<bool>,
+}
+
+impl Specifier {
+    pub fn new(specifier: &str) -> Result<Self> {
+        let specifier = specifier.trim();
+
+        // Longer operators have to be checked first since
+        // `==` is a prefix of `===` and `<` a prefix of `<=`
+        let operators = [
+            Operator::ArbitraryEqual,
+            Operator::Equal,
+            Operator::NotEqual,
+            Operator::LessThanEqual,
+            Operator::GreaterThanEqual,
+            Operator::Compatible,
+            Operator::LessThan,
+            Operator::GreaterThan,
+        ];
+        let (operator, version) = match operators.iter().find_map(|operator| {
+            specifier
+                .strip_prefix(operator.as_str())
+                .map(|version| (*operator, version.trim()))
+        }) {
+            Some(v) => v,
+            None => anyhow::bail!("Specifier {} has no valid operator", specifier),
+        };
+
+        // Arbitrary equality accepts any string without whitespace,
+        // so legacy versions can be pinned as well
+        if operator == Operator::ArbitraryEqual {
+            if version.is_empty() || version.contains(char::is_whitespace) {
+                anyhow::bail!("Specifier {} has an invalid version", specifier);
+            }
+            return Ok(Self {
+                operator,
+                raw: version.to_string(),
+                version: PackageVersion::new(version).ok(),
+                wildcard: false,
+                prereleases: None,
+            });
+        }
+
+        let (raw, wildcard) = match version.strip_suffix(".*") {
+            Some(prefix) => (prefix, true),
+            None => (version, false),
+        };
+        let version = PackageVersion::new(raw)?;
+
+        if wildcard {
+            if !matches!(operator, Operator::Equal | Operator::NotEqual) {
+                anyhow::bail!(
+                    "Specifier {} uses a wildcard with {}",
+                    specifier,
+                    operator
+                );
+            }
+            // A prefix can only consist of an epoch and a release
+            let has_suffix = version.pre.is_some()
+                || version.post.is_some()
+                || version.dev.is_some()
+                || version.local.is_some();
+            if has_suffix {
+                anyhow::bail!("Specifier {} uses a wildcard after a suffix", specifier);
+            }
+        }
+
+        // `~=1` would have an empty prefix
+        if operator == Operator::Compatible && version.release.segments.len() < 2 {
+            anyhow::bail!(
+                "Specifier {} needs at least two release segments",
+                specifier
+            );
+        }
+
+        // Local versions are only allowed where they can be matched exactly
+        let allows_local = matches!(operator, Operator::Equal | Operator::NotEqual);
+        if version.local.is_some() && !allows_local {
+            anyhow::bail!(
+                "Specifier {} uses a local version with {}",
+                specifier,
+                operator
+            );
+        }
+
+        Ok(Self {
+            operator,
+            raw: raw.to_string(),
+            version: Some(version),
+            wildcard,
+            prereleases: None,
+        })
+    }
+
+    /// The compatible release specifier of a version (`~=1.4.2`),
+    /// matching later versions with the same release up to the last segment
+    ///
+    /// The local version is dropped and a single release segment
+    /// is padded (`1` gives `~=1.0`), since `~=` needs two of them
+    ///
+    /// ```
+    /// use pyver::specifiers::Specifier;
+    /// use pyver::PackageVersion;
+    ///
+    /// let version = PackageVersion::new("1.4.2").unwrap();
+    /// let specifier = Specifier::compatible_with(&version).unwrap();
+    /// assert_eq!(specifier.to_string(), "~=1.4.2");
+    /// assert!(specifier.contains(&PackageVersion::new("1.4.9").unwrap()));
+    /// assert!(!specifier.contains(&PackageVersion::new("1.5").unwrap()));
+    /// ```
+    pub fn compatible_with(version: &PackageVersion) -> Result<Self> {
+        if version.is_max() {
+            anyhow::bail!("There is no compatible release of {}", version);
+        }
+        let mut segments = version.release.segments.clone();
+        if segments.len() < 2 {
+            segments.resize(2, 0);
+        }
+        let version = version.with_release(&segments)?;
+        Self::new(&format!("~={}", version.public()))
+    }
+
+    /// The prefix match of a version's major and minor release (`==1.4.*`)
+    ///
+    /// ```
+    /// use pyver::specifiers::Specifier;
+    /// use pyver::PackageVersion;
+    ///
+    /// let version = PackageVersion::new("1.4.2rc1").unwrap();
+    /// let specifier = Specifier::same_minor(&version).unwrap();
+    /// assert_eq!(specifier.to_string(), "==1.4.*");
+    /// ```
+    pub fn same_minor(version: &PackageVersion) -> Result<Self> {
+        if version.is_max() {
+            anyhow::bail!("There is no minor release of {}", version);
+        }
+        let prefix = [version.release.segment(0), version.release.segment(1)];
+        Self::new(&format!("=={}.*", release_prefix(version, &prefix)))
+    }
+
+    /// Overrides whether pre-releases are matched
+    ///
+    /// `Some(true)` always allows them, `Some(false)` never does and `None`
+    /// restores the default of allowing them if the specifier mentions one
+    ///
+    /// ```
+    /// use pyver::specifiers::Specifier;
+    /// use pyver::PackageVersion;
+    ///
+    /// let rc = PackageVersion::new("2.0rc1").unwrap();
+    /// let specifier = Specifier::new(">=1.0").unwrap();
+    /// assert!(!specifier.contains(&rc));
+    ///
+    /// let specifier = specifier.with_prereleases(Some(true));
+    /// assert!(specifier.contains(&rc));
+    /// ```
+    pub fn with_prereleases(mut self, prereleases: Option<bool>) -> Self {
+        self.prereleases = prereleases;
+        self
+    }
+
+    /// Whether pre-releases (including dev releases) are matched
+    pub fn prereleases(&self) -> bool {
+        if let Some(prereleases) = self.prereleases {
+            return prereleases;
+        }
+
+        // `!=1.0rc1` excludes a pre-release, it doesn't ask for one
+        match &self.version {
+            Some(version) => {
+                self.operator != Operator::NotEqual && version.is_prerelease()
+            }
+            None => false,
+        }
+    }
+
+    /// The comparison operator
+    pub fn operator(&self) -> Operator {
+        self.operator
+    }
+
+    /// The version the operator compares against
+    /// (without the `.*` of a prefix match)
+    ///
+    /// Only `None` for `===` specifiers pinning a non `PEP-440` version
+    pub fn version(&self) -> Option<&PackageVersion> {
+        self.version.as_ref()
+    }
+
+    /// The version as written in the specifier
+    pub fn version_str(&self) -> &str {
+        &self.raw
+    }
+
+    /// Whether the specifier is a prefix match (`==1.1.*`)
+    pub fn is_wildcard(&self) -> bool {
+        self.wildcard
+    }
+
+    /// Whether a candidate version is matched by the specifier,
+    /// following the `PEP-440` rules for each operator
+    /// and the pre-release policy of the specifier
+    ///
+    /// ```
+    /// use pyver::specifiers::Specifier;
+    /// use pyver::PackageVersion;
+    ///
+    /// // Local versions are ignored unless the specifier has one
+    /// let specifier = Specifier::new("==1.0").unwrap();
+    /// assert!(specifier.contains(&PackageVersion::new("1.0+local").unwrap()));
+    ///
+    /// // `>` doesn't match post-releases of the given version
+    /// let specifier = Specifier::new(">1.0").unwrap();
+    /// assert!(!specifier.contains(&PackageVersion::new("1.0.post1").unwrap()));
+    /// ```
+    pub fn contains(&self, version: &PackageVersion) -> bool {
+        if version.is_prerelease() && !self.prereleases() {
+            return false;
+        }
+        self.matches(version)
+    }
+
+    /// Operator comparison without the pre-release policy
+    pub(crate) fn matches(&self, version: &PackageVersion) -> bool {
+        let spec = match &self.version {
+            Some(v) => v,
+            // Only `===` specifiers have no parsed version
+            None => return self.matches_arbitrary(&version.written()),
+        };
+
+        match self.operator {
+            Operator::Equal => self.matches_exactly(version, spec),
+            Operator::NotEqual => !self.matches_exactly(version, spec),
+            Operator::LessThanEqual => version.without_local() <= *spec,
+            Operator::GreaterThanEqual => version.without_local() >= *spec,
+            Operator::LessThan => {
+                version.cmp_prefix(spec, less_than_depth(spec)) == Ordering::Less
+            }
+            Operator::GreaterThan => {
+                version.cmp_prefix(spec, greater_than_depth(spec)) == Ordering::Greater
+            }
+            Operator::Compatible => {
+                // `~=V.N` is the same as `>=V.N, ==V.*`
+                // (any suffixes of the specifier are dropped from the prefix)
+                let segments = &spec.release.segments;
+                version.without_local() >= *spec
+                    && matches_prefix(version, spec, &segments[..segments.len() - 1])
+            }
+            // `===` compares the strings as written
+            Operator::ArbitraryEqual => self.matches_arbitrary(&version.written()),
+        }
+    }
+
+    /// Whether a candidate version string is matched by the specifier
+    ///
+    /// Unlike [`Specifier::contains`] this accepts legacy (non `PEP-440`)
+    /// versions, which can only ever be matched by `===`
+    ///
+    /// ```
+    /// use pyver::specifiers::Specifier;
+    ///
+    /// let specifier = Specifier::new("===2004d").unwrap();
+    /// assert!(specifier.contains_str(" 2004d "));
+    ///
+    /// let specifier = Specifier::new(">=1.0").unwrap();
+    /// assert!(!specifier.contains_str("2004d"));
+    /// ```
+    pub fn contains_str(&self, version: &str) -> bool {
+        match PackageVersion::new(version) {
+            Ok(v) => self.contains(&v),
+            Err(_) => {
+                self.operator == Operator::ArbitraryEqual
+                    && self.matches_arbitrary(version)
+            }
+        }
+    }
+
+    /// `===` comparison, a case-insensitive string comparison
+    /// ignoring surrounding whitespace
+    fn matches_arbitrary(&self, version: &str) -> bool {
+        version.trim().eq_ignore_ascii_case(&self.raw)
+    }
+
+    /// `==` comparison, which ignores the candidate's local version
+    /// unless the specifier has one
+    ///
+    /// Prefix matches compare the zero padded release of the candidate
+    /// against the specifier's release, ignoring any suffixes,
+    /// so `==1.1.*` matches `1.1`, `1.1.2` and `1.1.post1` but not `1.2`
+    fn matches_exactly(&self, version: &PackageVersion, spec: &PackageVersion) -> bool {
+        if self.wildcard {
+            matches_prefix(version, spec, &spec.release.segments)
+        } else if spec.local.is_some() {
+            version == spec
+        } else {
+            version.without_local() == *spec
+        }
+    }
+}
+
+/// How much of the sort key `<V` compares
+///
+/// `<V` must not match a pre-release of V unless V is a pre-release itself.
+/// Every pre-release of `1.0` shares its epoch and release, every pre-release
+/// of `1.0.post1` its post-release as well, so comparing only those
+/// components excludes them along with V
+pub(crate) fn less_than_depth(spec: &PackageVersion) -> usize {
+    if spec.is_prerelease() {
+        SORT_KEY_LEN
+    } else if spec.post.is_some() {
+        4
+    } else {
+        2
+    }
+}
+
+/// How much of the sort key `>V` compares
+///
+/// `>V` must not match a post-release of V unless V is a post-release itself,
+/// and never a local version of V. Post-releases of `1.0` and `1.0a1`
+/// share their pre-release component, local versions everything but the
+/// local segment
+pub(crate) fn greater_than_depth(spec: &PackageVersion) -> usize {
+    if spec.post.is_some() || spec.dev.is_some() {
+        5
+    } else {
+        3
+    }
+}
+
+/// The epoch of the version followed by the given release segments (`1!1.4`)
+pub(crate) fn release_prefix(version: &PackageVersion, segments: &[u64]) -> String {
+    let release = segments
+        .iter()
+        .map(u64::to_string)
+        .collect::<Vec<String>>()
+        .join(".");
+    match version.epoch() {
+        0 => release,
+        epoch => format!("{}!{}", epoch, release),
+    }
+}
+
+/// `==prefix.*` comparison: the epochs have to be equal and the candidate's
+/// release has to start with `prefix` after padding it with zeros
+fn matches_prefix(
+    version: &PackageVersion,
+    spec: &PackageVersion,
+    prefix: &[u64],
+) -> bool {
+    let segments = &version.release.segments;
+    version.epoch.unwrap_or(0) == spec.epoch.unwrap_or(0)
+        && prefix
+            .iter()
+            .enumerate()
+            .all(|(i, segment)| segments.get(i).copied().unwrap_or(0) == *segment)
+}
+
+impl FromStr for Specifier {
+    type Err = anyhow::Error;
+
+    fn from_str(specifier: &str) -> Result<Self> {
+        Self::new(specifier)
+    }
+}
+
+impl TryFrom<String> for Specifier {
+    type Error = anyhow::Error;
+
+    fn try_from(specifier: String) -> Result<Self> {
+        Self::new(&specifier)
+    }
+}
+
+impl From<Specifier> for String {
+    fn from(specifier: Specifier) -> Self {
+        specifier.to_string()
+    }
+}
+
+impl fmt::Display for Specifier {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}{}", self.operator, self.raw)?;
+        if self.wildcard {
+            write!(f, ".*")?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Operator, Specifier};
+    use crate::PackageVersion;
+    use anyhow::Result;
+
+    fn contains(specifier: &str, version: &str) -> bool {
+        Specifier::new(specifier)
+            .unwrap()
+            .contains(&PackageVersion::new(version).unwrap())
+    }
+
+    /// Like `contains` but always allowing pre-releases
+    fn contains_pre(specifier: &str, version: &str) -> bool {
+        Specifier::new(specifier)
+            .unwrap()
+            .with_prereleases(Some(true))
+            .contains(&PackageVersion::new(version).unwrap())
+    }
+
+    #[test]
+    fn test_specifier_parsing() -> Result<()> {
+        let specifier = Specifier::new(" >= 1.0 ")?;
+        assert_eq!(specifier.operator(), Operator::GreaterThanEqual);
+        assert_eq!(specifier.version(), Some(&PackageVersion::new("1.0")?));
+        assert_eq!(specifier.to_string(), ">=1.0");
+
+        assert_eq!(
+            Specifier::new("===1.0")?.operator(),
+            Operator::ArbitraryEqual
+        );
+        assert_eq!(Specifier::new("<1.0")?.operator(), Operator::LessThan);
+        assert_eq!(Specifier::new("~=1.0")?.operator(), Operator::Compatible);
+        Ok(())
+    }
+
+    #[test]
+    fn test_specifier_negative() {
+        let specifiers =
+            vec!["1.0", "=1.0", "=>1.0", ">=", ">=1.0+local", "~=1.0+local"];
+
+        for specifier in specifiers {
+            if let Ok(v) = Specifier::new(specifier) {
+                panic!("Oh no {}", v);
+            }
+        }
+    }
+
+    #[test]
+    fn test_equality_operators() {
+        assert!(contains("==1.0", "1.0"));
+        assert!(contains("==1.0", "1.0.0"));
+        assert!(contains("==1.0", "1.0+local"));
+        assert!(!contains("==1.0", "1.0.post1"));
+        assert!(!contains("==1.0+a", "1.0+b"));
+        assert!(contains("==1.0+a", "1.0+a"));
+        assert!(!contains("==1.0+a", "1.0"));
+
+        assert!(contains("!=1.0", "1.1"));
+        assert!(!contains("!=1.0", "1.0+local"));
+    }
+
+    #[test]
+    fn test_wildcard_operators() -> Result<()> {
+        let specifier = Specifier::new("==1.1.*")?;
+        assert!(specifier.is_wildcard());
+        assert_eq!(specifier.version(), Some(&PackageVersion::new("1.1")?));
+        assert_eq!(specifier.to_string(), "==1.1.*");
+
+        assert!(contains("==1.1.*", "1.1"));
+        assert!(contains("==1.1.*", "1.1.2"));
+        assert!(contains("==1.1.*", "1.1post1"));
+        assert!(!contains("==1.1.*", "1.1rc1"));
+        assert!(contains_pre("==1.1.*", "1.1rc1"));
+        assert!(contains("==1.1.*", "1.1.0+local"));
+        assert!(!contains("==1.1.*", "1.2"));
+        assert!(!contains("==1.1.*", "1"));
+        assert!(!contains("==1.1.*", "1!1.1"));
+        assert!(contains("==1.0.*", "1"));
+        assert!(contains("==1!1.*", "1!1.5"));
+
+        assert!(contains("!=1.1.*", "1.2"));
+        assert!(!contains("!=1.1.*", "1.1.5"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_prerelease_policy() -> Result<()> {
+        // Pre-releases only match if the specifier mentions one
+        assert!(!contains(">=1.0", "2.0rc1"));
+        assert!(!contains(">=1.0", "2.0.dev1"));
+        assert!(contains(">=1.0rc1", "2.0rc1"));
+        assert!(contains(">=1.0.dev0", "2.0.dev1"));
+        assert!(contains("==1.0rc1", "1.0rc1"));
+        assert!(contains("<2.0rc2", "2.0rc1"));
+        assert!(contains("~=1.4.5a4", "1.4.6b1"));
+        assert!(!contains("!=1.0rc1", "1.0rc2"));
+        assert!(contains("!=1.0rc1", "1.0"));
+
+        let rc = PackageVersion::new("1.0rc1")?;
+        assert!(Specifier::new("<=1.0")?
+            .with_prereleases(Some(true))
+            .contains(&rc));
+        assert!(!Specifier::new("==1.0rc1")?
+            .with_prereleases(Some(false))
+            .contains(&rc));
+        assert!(!Specifier::new("==1.0rc1")?
+            .with_prereleases(Some(true))
+            .with_prereleases(None)
+            .contains(&PackageVersion::new("1.0rc2")?));
+
+        assert!(Specifier::new(">=1.0a1")?.prereleases());
+        assert!(!Specifier::new(">=1.0")?.prereleases());
+        assert!(Specifier::new(">=1.0")?
+            .with_prereleases(Some(true))
+            .prereleases());
+        Ok(())
+    }
+
+    #[test]
+    fn test_ordered_operators() {
+        assert!(contains(">=1.0", "1.0"));
+        assert!(contains(">=1.0", "1.0+local"));
+        assert!(!contains_pre(">=1.0", "1.0rc1"));
+        assert!(contains("<=1.0", "1.0+local"));
+        assert!(contains_pre("<=1.0", "1.0rc1"));
+
+        assert!(contains("<2.0", "1.9"));
+        assert!(!contains("<2.0", "2.0"));
+        assert!(!contains_pre("<2.0", "2.0rc1"));
+        assert!(!contains_pre("<2.0", "2.0.dev1"));
+        assert!(contains_pre("<2.0", "1.9rc1"));
+        assert!(contains("<2.0rc2", "2.0rc1"));
+        assert!(contains("<2.0", "1.9.post1"));
+        // Only pre-releases of the given version are excluded
+        assert!(contains_pre("<1.0.post1", "1.0a1"));
+        assert!(!contains_pre("<1.0.post1", "1.0.post1.dev0"));
+
+        assert!(contains(">1.0", "1.1"));
+        assert!(!contains(">1.0", "1.0"));
+        assert!(!contains(">1.0", "1.0.post1"));
+        assert!(!contains(">1.0", "1.0+local"));
+        assert!(contains(">1.0.post1", "1.0.post2"));
+        assert!(contains(">1.0rc1", "1.0"));
+        assert!(!contains(">1.0a1", "1.0a1.post1"));
+        assert!(contains(">1.0a1", "1.0a2.post1"));
+    }
+
+    #[test]
+    fn test_compatible_operator() {
+        assert!(contains("~=2.2", "2.3"));
+        assert!(contains("~=2.2", "2.2"));
+        assert!(!contains("~=2.2", "3.0"));
+        assert!(!contains("~=2.2", "2.1"));
+        assert!(contains("~=1.4.5", "1.4.9"));
+        assert!(!contains("~=1.4.5", "1.5.0"));
+        assert!(!contains("~=1!2.2", "2.3"));
+        assert!(contains("~=1!2.2", "1!2.3"));
+        assert!(contains("~=2.2.post3", "2.9"));
+        assert!(!contains("~=2.2.post3", "2.2"));
+        assert!(contains("~=1.4.5a4", "1.4.5"));
+        assert!(!contains("~=1.4.5a4", "1.5"));
+        assert!(contains("~=2.2", "2.3+local"));
+        assert!(contains("~=2.2.0", "2.2.1"));
+        assert!(!contains("~=2.2.0", "2.3"));
+    }
+
+    #[test]
+    fn test_generated_specifiers() -> Result<()> {
+        let specifiers = vec![
+            ("1.4.2", "~=1.4.2", "==1.4.*"),
+            ("1", "~=1.0", "==1.0.*"),
+            ("1!2.0rc1+local", "~=1!2.0rc1", "==1!2.0.*"),
+            ("v1.4.2.post1", "~=1.4.2.post1", "==1.4.*"),
+        ];
+        for (version, compatible, same_minor) in specifiers {
+            let version = PackageVersion::new(version)?;
+            assert_eq!(
+                Specifier::compatible_with(&version)?.to_string(),
+                compatible
+            );
+            assert_eq!(Specifier::same_minor(&version)?.to_string(), same_minor);
+        }
+
+        assert!(Specifier::compatible_with(&PackageVersion::MAX).is_err());
+        assert!(Specifier::same_minor(&PackageVersion::MAX).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_arbitrary_equality() -> Result<()> {
+        assert!(contains("===1.0", "1.0"));
+        assert!(contains("=== 1.0 ", " 1.0"));
+        assert!(!contains("===1.0", "1.0.0"));
+        assert!(!contains("===1.0", "v1.0"));
+        assert!(!contains("===1.0", "1.0+local"));
+
+        // Legacy versions can only be pinned, never compared
+        let specifier = Specifier::new("===foobar")?;
+        assert_eq!(specifier.version(), None);
+        assert_eq!(specifier.version_str(), "foobar");
+        assert_eq!(specifier.to_string(), "===foobar");
+        assert!(specifier.contains_str("foobar"));
+        assert!(specifier.contains_str("FooBar"));
+        assert!(!specifier.contains(&PackageVersion::new("1.0")?));
+        assert!(!Specifier::new("==1.0")?.contains_str("foobar"));
+        assert!(Specifier::new("==1.0")?.contains_str("1.0.0"));
+
+        assert!(Specifier::new("===").is_err());
+        assert!(Specifier::new("===foo bar").is_err());
+        Ok(())
+    }
+}
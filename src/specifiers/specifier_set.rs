@@ -0,0 +1,594 @@
+use super::specifier::release_prefix;
+use super::{Specifier, VersionRange};
+use crate::PackageVersion;
+use alloc::collections::VecDeque;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use alloc::{format, vec};
+use anyhow::Result;
+use core::fmt;
+use core::str::FromStr;
+use serde::{Deserialize, Serialize};
+
+/// # `PEP-440` Version specifier set
+/// A comma separated list of specifiers such as `>=1.0, !=1.3.4, <2.0`,
+/// a version has to match every specifier to be contained in the set
+///
+/// An empty set matches every version.
+/// Pre-releases are only matched if one of the specifiers mentions
+/// a pre-release, see [`SpecifierSet::with_prereleases`] to override this.
+///
+/// ## Example Usage
+/// ```
+/// use pyver::specifiers::SpecifierSet;
+/// use pyver::PackageVersion;
+///
+/// let set: SpecifierSet = ">=1.0, !=1.3.4, <2.0".parse().unwrap();
+///
+/// assert!(set.contains(&PackageVersion::new("1.5").unwrap()));
+/// assert!(!set.contains(&PackageVersion::new("1.3.4").unwrap()));
+/// assert_eq!(set.to_string(), ">=1.0,!=1.3.4,<2.0");
+/// ```
+#[derive(Hash, Clone, Debug, Default, Serialize, Deserialize, Eq, PartialEq)]
+#[cfg_attr(
+    feature = "diesel",
+    derive(diesel::expression::AsExpression, diesel::deserialize::FromSqlRow),
+    diesel(sql_type = diesel::sql_types::Text)
+)]
+#[serde(try_from = "String", into = "String")]
+pub struct SpecifierSet {
+    pub(crate) specifiers: Vec<Specifier>,
+    /// Explicit pre-release policy, `None` to decide based on the specifiers
+    pub(crate) prereleases: Option<bool>,
+}
+
+impl SpecifierSet {
+    pub fn new(specifiers: &str) -> Result<Self> {
+        if specifiers.trim().is_empty() {
+            return Ok(Self::default());
+        }
+
+        Ok(Self {
+            specifiers: specifiers
+                .split(',')
+                .map(Specifier::new)
+                .collect::<Result<Vec<Specifier>>>()?,
+            prereleases: None,
+        })
+    }
+
+    /// The set matching a semver caret requirement (`^1.4.2`),
+    /// versions up to the next bump of the first non-zero release segment
+    ///
+    /// `^1.4.2` is `>=1.4.2,<2`, `^0.4.2` is `>=0.4.2,<0.5`
+    /// and `^0.0.2` is `>=0.0.2,<0.0.3`
+    ///
+    /// ```
+    /// use pyver::specifiers::SpecifierSet;
+    /// use pyver::PackageVersion;
+    ///
+    /// let version = PackageVersion::new("0.4.2").unwrap();
+    /// let set = SpecifierSet::caret(&version).unwrap();
+    /// assert_eq!(set.to_string(), ">=0.4.2,<0.5");
+    /// ```
+    pub fn caret(version: &PackageVersion) -> Result<Self> {
+        let segments = version.release().segments();
+        // All zeros bump the last written segment like Cargo (`^0.0` is `<0.1`)
+        let index = segments
+            .iter()
+            .position(|segment| *segment != 0)
+            .unwrap_or_else(|| segments.len().saturating_sub(1));
+        Self::below_bump(version, index)
+    }
+
+    /// The set matching a semver tilde requirement (`~1.4.2`),
+    /// versions up to the next minor release, or the next major
+    /// release if only one segment is given
+    ///
+    /// `~1.4.2` is `>=1.4.2,<1.5` and `~1` is `>=1,<2`
+    ///
+    /// ```
+    /// use pyver::specifiers::SpecifierSet;
+    /// use pyver::PackageVersion;
+    ///
+    /// let version = PackageVersion::new("1.4.2").unwrap();
+    /// let set = SpecifierSet::tilde(&version).unwrap();
+    /// assert_eq!(set.to_string(), ">=1.4.2,<1.5");
+    /// ```
+    pub fn tilde(version: &PackageVersion) -> Result<Self> {
+        let index = version.release().len().clamp(1, 2) - 1;
+        Self::below_bump(version, index)
+    }
+
+    /// `>=version` and below the version with the release segment
+    /// at the index incremented and the later ones dropped
+    fn below_bump(version: &PackageVersion, index: usize) -> Result<Self> {
+        if version.is_max() {
+            anyhow::bail!("There is no version above {}", version);
+        }
+        let release = version.release();
+        let mut upper = release.segments()[..index.min(release.len())].to_vec();
+        upper.resize(index, 0);
+        match release.segment(index).checked_add(1) {
+            Some(segment) => upper.push(segment),
+            None => anyhow::bail!("Release segment {} of {} overflows", index, version),
+        }
+        Self::new(&format!(
+            ">={}, <{}",
+            version.public(),
+            release_prefix(version, &upper)
+        ))
+    }
+
+    /// Overrides whether pre-releases are matched by the set
+    ///
+    /// This takes precedence over the policy of the individual specifiers,
+    /// `None` restores the default
+    ///
+    /// ```
+    /// use pyver::specifiers::SpecifierSet;
+    /// use pyver::PackageVersion;
+    ///
+    /// let rc = PackageVersion::new("1.5rc1").unwrap();
+    /// let set = SpecifierSet::new(">=1.0, <2.0").unwrap();
+    /// assert!(!set.contains(&rc));
+    ///
+    /// let set = set.with_prereleases(Some(true));
+    /// assert!(set.contains(&rc));
+    /// ```
+    pub fn with_prereleases(mut self, prereleases: Option<bool>) -> Self {
+        self.prereleases = prereleases;
+        self
+    }
+
+    /// Whether pre-releases (including dev releases) are matched,
+    /// by default only if any specifier mentions a pre-release
+    pub fn prereleases(&self) -> bool {
+        match self.prereleases {
+            Some(prereleases) => prereleases,
+            None => self.specifiers.iter().any(Specifier::prereleases),
+        }
+    }
+
+    /// Whether a version matches every specifier in the set
+    /// and the pre-release policy of the set
+    pub fn contains(&self, version: &PackageVersion) -> bool {
+        if version.is_prerelease() && !self.prereleases() {
+            return false;
+        }
+        self.matches(version)
+    }
+
+    /// Operator comparison of every specifier without the pre-release policy
+    pub(crate) fn matches(&self, version: &PackageVersion) -> bool {
+        self.specifiers
+            .iter()
+            .all(|specifier| specifier.matches(version))
+    }
+
+    /// The set matching the versions matched by both sets
+    ///
+    /// The specifiers of both sets are combined, an explicit pre-release
+    /// policy of `self` takes precedence over the one of `other`
+    ///
+    /// ```
+    /// use pyver::specifiers::SpecifierSet;
+    ///
+    /// let a = SpecifierSet::new(">=1.0").unwrap();
+    /// let b = SpecifierSet::new("<2.0, >=1.0").unwrap();
+    /// assert_eq!(a.intersection(&b).to_string(), ">=1.0,<2.0");
+    /// ```
+    pub fn intersection(&self, other: &Self) -> Self {
+        let mut specifiers = self.specifiers.clone();
+        for specifier in &other.specifiers {
+            if !specifiers.contains(specifier) {
+                specifiers.push(specifier.clone());
+            }
+        }
+        Self {
+            specifiers,
+            prereleases: self.prereleases.or(other.prereleases),
+        }
+    }
+
+    /// The range of versions matched by either set
+    ///
+    /// A set can't express alternatives, so the result is a [`VersionRange`]
+    /// which (like all ranges) ignores the pre-release policy of the sets
+    ///
+    /// ```
+    /// use pyver::specifiers::SpecifierSet;
+    /// use pyver::PackageVersion;
+    ///
+    /// let a = SpecifierSet::new("<1.0").unwrap();
+    /// let b = SpecifierSet::new(">=1.5, <2.0").unwrap();
+    /// let range = a.union(&b);
+    ///
+    /// assert!(range.contains(&PackageVersion::new("0.5").unwrap()));
+    /// assert!(!range.contains(&PackageVersion::new("1.2").unwrap()));
+    /// assert!(range.contains(&PackageVersion::new("1.7").unwrap()));
+    /// ```
+    pub fn union(&self, other: &Self) -> VersionRange {
+        VersionRange::from(self).union(&VersionRange::from(other))
+    }
+
+    /// Yields the versions matching every specifier in the set
+    ///
+    /// Pre-releases follow the policy of the set, with the same fallback
+    /// as Python's `packaging` library: if the policy wasn't set explicitly
+    /// and no final release matches, the matching pre-releases are yielded.
+    ///
+    /// ```
+    /// use pyver::specifiers::SpecifierSet;
+    /// use pyver::PackageVersion;
+    ///
+    /// let set = SpecifierSet::new(">=1.0").unwrap();
+    /// let versions: Vec<PackageVersion> = ["0.9", "1.0", "1.1rc1", "1.1"]
+    ///     .iter()
+    ///     .map(|v| PackageVersion::new(v).unwrap())
+    ///     .collect();
+    ///
+    /// let matching: Vec<String> = set
+    ///     .filter(versions.iter())
+    ///     .map(|v| v.to_string())
+    ///     .collect();
+    /// assert_eq!(matching, vec!["1.0", "1.1"]);
+    ///
+    /// // Only pre-releases match, so they are used instead
+    /// let set = SpecifierSet::new(">1.0, !=1.1").unwrap();
+    /// let matching: Vec<String> = set
+    ///     .filter(versions.iter())
+    ///     .map(|v| v.to_string())
+    ///     .collect();
+    /// assert_eq!(matching, vec!["1.1rc1"]);
+    /// ```
+    pub fn filter<'s, 'a>(
+        &'s self,
+        versions: impl Iterator<Item = &'a PackageVersion> + 's,
+    ) -> impl Iterator<Item = &'a PackageVersion> + 's
+    where
+        'a: 's,
+    {
+        Filter {
+            set: self,
+            versions,
+            prereleases: VecDeque::new(),
+            yielded: false,
+        }
+    }
+
+    /// Whether any version can match every specifier in the set
+    ///
+    /// Contradicting specifiers are detected without a list of candidates,
+    /// the pre-release policy isn't taken into account
+    ///
+    /// ```
+    /// use pyver::specifiers::SpecifierSet;
+    ///
+    /// assert!(SpecifierSet::new(">=1.0, <2.0").unwrap().is_satisfiable());
+    /// assert!(!SpecifierSet::new(">=2.0, <1.0").unwrap().is_satisfiable());
+    /// assert!(!SpecifierSet::new("==1.5, !=1.*").unwrap().is_satisfiable());
+    /// ```
+    pub fn is_satisfiable(&self) -> bool {
+        !VersionRange::from(self).is_empty()
+    }
+
+    /// Whether every version matched by `other` is matched by `self` as well
+    ///
+    /// Like [`SpecifierSet::is_satisfiable`] this only compares the
+    /// operators, not the pre-release policy
+    ///
+    /// ```
+    /// use pyver::specifiers::SpecifierSet;
+    ///
+    /// let broad = SpecifierSet::new(">=1.0").unwrap();
+    /// let pinned = SpecifierSet::new("==1.4.2").unwrap();
+    /// assert!(broad.subsumes(&pinned));
+    /// assert!(!pinned.subsumes(&broad));
+    /// ```
+    pub fn subsumes(&self, other: &Self) -> bool {
+        VersionRange::from(other)
+            .intersection(&VersionRange::from(self).complement())
+            .is_empty()
+    }
+
+    /// Iterates over the individual specifiers
+    pub fn iter(&self) -> core::slice::Iter<'_, Specifier> {
+        self.specifiers.iter()
+    }
+
+    /// Number of specifiers in the set
+    pub fn len(&self) -> usize {
+        self.specifiers.len()
+    }
+
+    /// Whether the set has no specifiers (and so matches every version)
+    pub fn is_empty(&self) -> bool {
+        self.specifiers.is_empty()
+    }
+}
+
+/// Lazy iterator behind [`SpecifierSet::filter`]
+struct Filter<'s, 'a, I: Iterator<Item = &'a PackageVersion>> {
+    set: &'s SpecifierSet,
+    versions: I,
+    /// Matching pre-releases held back in case no final release matches
+    prereleases: VecDeque<&'a PackageVersion>,
+    yielded: bool,
+}
+
+impl<'s, 'a, I: Iterator<Item = &'a PackageVersion>> Iterator for Filter<'s, 'a, I> {
+    type Item = &'a PackageVersion;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        for version in self.versions.by_ref() {
+            if !self.set.matches(version) {
+                continue;
+            }
+
+            if version.is_stable() || self.set.prereleases() {
+                self.yielded = true;
+                return Some(version);
+            }
+            // Only pre-releases that could still be used are kept
+            if !self.yielded && self.set.prereleases.is_none() {
+                self.prereleases.push_back(version);
+            }
+        }
+
+        if self.yielded {
+            return None;
+        }
+        // Hand out the held back pre-releases in their original order
+        self.prereleases.pop_front()
+    }
+}
+
+impl<'a> IntoIterator for &'a SpecifierSet {
+    type Item = &'a Specifier;
+    type IntoIter = core::slice::Iter<'a, Specifier>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.specifiers.iter()
+    }
+}
+
+impl IntoIterator for SpecifierSet {
+    type Item = Specifier;
+    type IntoIter = alloc::vec::IntoIter<Specifier>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.specifiers.into_iter()
+    }
+}
+
+impl FromIterator<Specifier> for SpecifierSet {
+    fn from_iter<I: IntoIterator<Item = Specifier>>(iter: I) -> Self {
+        Self {
+            specifiers: iter.into_iter().collect(),
+            prereleases: None,
+        }
+    }
+}
+
+impl From<Specifier> for SpecifierSet {
+    fn from(specifier: Specifier) -> Self {
+        Self {
+            specifiers: vec![specifier],
+            prereleases: None,
+        }
+    }
+}
+
+impl FromStr for SpecifierSet {
+    type Err = anyhow::Error;
+
+    fn from_str(specifiers: &str) -> Result<Self> {
+        Self::new(specifiers)
+    }
+}
+
+impl TryFrom<String> for SpecifierSet {
+    type Error = anyhow::Error;
+
+    fn try_from(specifiers: String) -> Result<Self> {
+        Self::new(&specifiers)
+    }
+}
+
+impl From<SpecifierSet> for String {
+    fn from(specifiers: SpecifierSet) -> Self {
+        specifiers.to_string()
+    }
+}
+
+impl fmt::Display for SpecifierSet {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (i, specifier) in self.specifiers.iter().enumerate() {
+            if i > 0 {
+                write!(f, ",")?;
+            }
+            write!(f, "{}", specifier)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SpecifierSet;
+    use crate::specifiers::{Operator, Specifier};
+    use crate::PackageVersion;
+    use anyhow::Result;
+
+    #[test]
+    fn test_specifier_set_parsing() -> Result<()> {
+        let set = SpecifierSet::new(" >=1.0 ,<2.0,!=1.5 ")?;
+        assert_eq!(set.len(), 3);
+        assert_eq!(
+            set.iter().map(Specifier::operator).collect::<Vec<_>>(),
+            vec![
+                Operator::GreaterThanEqual,
+                Operator::LessThan,
+                Operator::NotEqual
+            ]
+        );
+        assert_eq!(set.to_string(), ">=1.0,<2.0,!=1.5");
+        assert_eq!(set.to_string().parse::<SpecifierSet>()?, set);
+
+        assert!(SpecifierSet::new("")?.is_empty());
+        assert!(SpecifierSet::new(">=1.0,").is_err());
+        assert!(SpecifierSet::new(">=1.0,,<2.0").is_err());
+        assert!(SpecifierSet::new(">=1.0 <2.0").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_specifier_set_contains() -> Result<()> {
+        let set = SpecifierSet::new(">=1.0, !=1.3.4, <2.0")?;
+        assert!(set.contains(&PackageVersion::new("1.0")?));
+        assert!(set.contains(&PackageVersion::new("1.9.9")?));
+        assert!(!set.contains(&PackageVersion::new("1.3.4")?));
+        assert!(!set.contains(&PackageVersion::new("2.0")?));
+        assert!(!set.contains(&PackageVersion::new("0.9")?));
+
+        assert!(SpecifierSet::new("")?.contains(&PackageVersion::new("0.1")?));
+        Ok(())
+    }
+
+    #[test]
+    fn test_specifier_set_semver() -> Result<()> {
+        // (version, caret, tilde)
+        let sets = vec![
+            ("1.4.2", ">=1.4.2,<2", ">=1.4.2,<1.5"),
+            ("0.4.2", ">=0.4.2,<0.5", ">=0.4.2,<0.5"),
+            ("0.0.2", ">=0.0.2,<0.0.3", ">=0.0.2,<0.1"),
+            ("0.0", ">=0.0,<0.1", ">=0.0,<0.1"),
+            ("0", ">=0,<1", ">=0,<1"),
+            ("1", ">=1,<2", ">=1,<2"),
+            ("1!1.4rc1", ">=1!1.4rc1,<1!2", ">=1!1.4rc1,<1!1.5"),
+        ];
+        for (version, caret, tilde) in sets {
+            let version = PackageVersion::new(version)?;
+            assert_eq!(SpecifierSet::caret(&version)?.to_string(), caret);
+            assert_eq!(SpecifierSet::tilde(&version)?.to_string(), tilde);
+        }
+
+        let caret = SpecifierSet::caret(&PackageVersion::new("1.4.2")?)?;
+        assert!(caret.contains(&PackageVersion::new("1.9")?));
+        assert!(!caret.contains(&PackageVersion::new("2.0rc1")?));
+        assert!(SpecifierSet::tilde(&PackageVersion::MAX).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_specifier_set_prereleases() -> Result<()> {
+        let rc = PackageVersion::new("1.5rc1")?;
+
+        assert!(!SpecifierSet::new(">=1.0")?.contains(&rc));
+        assert!(!SpecifierSet::new("")?.contains(&rc));
+        // One specifier mentioning a pre-release enables them for the whole set
+        assert!(SpecifierSet::new(">=1.0, !=1.5rc2, <1.6.dev0")?.contains(&rc));
+        assert!(!SpecifierSet::new(">=1.0, !=1.5rc2")?.contains(&rc));
+
+        assert!(SpecifierSet::new("")?
+            .with_prereleases(Some(true))
+            .contains(&rc));
+        assert!(!SpecifierSet::new(">=1.0rc1")?
+            .with_prereleases(Some(false))
+            .contains(&rc));
+        Ok(())
+    }
+
+    #[test]
+    fn test_specifier_set_filter() -> Result<()> {
+        let versions: Vec<PackageVersion> =
+            ["1.0a1", "1.0", "1.5.dev1", "2.0b1", "2.0"]
+                .iter()
+                .map(|v| PackageVersion::new(v))
+                .collect::<Result<_>>()?;
+        let filter = |set: &SpecifierSet| -> Vec<String> {
+            set.filter(versions.iter()).map(|v| v.to_string()).collect()
+        };
+
+        assert_eq!(filter(&SpecifierSet::new(">=1.0")?), vec!["1.0", "2.0"]);
+        assert_eq!(filter(&SpecifierSet::new("")?), vec!["1.0", "2.0"]);
+        assert_eq!(
+            filter(&SpecifierSet::new(">=1.0b1")?),
+            vec!["1.0", "1.5.dev1", "2.0b1", "2.0"]
+        );
+        assert_eq!(filter(&SpecifierSet::new(">1.0, <2.0")?), vec!["1.5.dev1"]);
+        assert_eq!(filter(&SpecifierSet::new(">2.0")?), Vec::<String>::new());
+
+        // Explicitly disabling pre-releases disables the fallback
+        let set = SpecifierSet::new(">1.0, <2.0")?.with_prereleases(Some(false));
+        assert_eq!(filter(&set), Vec::<String>::new());
+
+        let set = SpecifierSet::new("<2.0")?.with_prereleases(Some(true));
+        assert_eq!(filter(&set), vec!["1.0a1", "1.0", "1.5.dev1"]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_specifier_set_satisfiable() -> Result<()> {
+        let satisfiable = vec![
+            "",
+            ">=1.0",
+            ">1.0, <1.0.0.1",
+            "~=1.4.5, !=1.4.6",
+            "==1.0+local",
+        ];
+        let unsatisfiable = vec![
+            ">=2.0, <1.0",
+            ">1.0, <=1.0",
+            "<1.0, >=1.0",
+            ">1.0, <1.0.post0.dev0",
+            "~=1.4.5, >=1.5",
+            "==1.0, !=1.0",
+            "==1.*, !=1.*",
+            "==1.0+local, !=1.0",
+            "===foobar",
+        ];
+
+        for set in satisfiable {
+            assert!(SpecifierSet::new(set)?.is_satisfiable(), "{}", set);
+        }
+        for set in unsatisfiable {
+            assert!(!SpecifierSet::new(set)?.is_satisfiable(), "{}", set);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_specifier_set_subsumes() -> Result<()> {
+        let subsumes = |a: &str, b: &str| -> Result<bool> {
+            Ok(SpecifierSet::new(a)?.subsumes(&SpecifierSet::new(b)?))
+        };
+
+        assert!(subsumes("", ">=1.0")?);
+        assert!(!subsumes(">=1.0", "")?);
+        assert!(subsumes(">=1.0", ">=1.0")?);
+        assert!(subsumes(">=1.0", ">1.0")?);
+        assert!(!subsumes(">1.0", ">=1.0")?);
+        assert!(subsumes(">=1.0, <2.0", "~=1.4")?);
+        assert!(!subsumes("~=1.4", ">=1.0, <2.0")?);
+        assert!(subsumes("==1.*", "~=1.4")?);
+        assert!(subsumes("!=1.5", ">=2.0")?);
+        assert!(!subsumes("!=1.5", ">=1.0")?);
+        assert!(subsumes("==1.0", "==1.0+local")?);
+        assert!(!subsumes("==1.0+local", "==1.0")?);
+        // An unsatisfiable set is subsumed by everything
+        assert!(subsumes("==3.0", ">=2.0, <1.0")?);
+        Ok(())
+    }
+
+    #[test]
+    fn test_specifier_set_serde() -> Result<()> {
+        let set = SpecifierSet::new(">=1.0, <2.0")?;
+        let json = serde_json::to_string(&set)?;
+        assert_eq!(json, r#"">=1.0,<2.0""#);
+        assert_eq!(serde_json::from_str::<SpecifierSet>(&json)?, set);
+        assert!(serde_json::from_str::<SpecifierSet>(r#""=>1.0""#).is_err());
+        Ok(())
+    }
+}
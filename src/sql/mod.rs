@@ -0,0 +1,11 @@
+//! Versions and specifier sets as text columns of SQL databases, written
+//! in normal form (`1.0rc1` for `v1.0-RC1`) and read by parsing
+//!
+//! `ORDER BY` on such a column sorts `10.0` before `9.0`, keep the
+//! [`PackageVersion::to_sort_key`](crate::PackageVersion::to_sort_key)
+//! in a binary column next to it for ordered queries.
+
+#[cfg(feature = "diesel")]
+mod diesel;
+#[cfg(feature = "sqlx")]
+mod sqlx;
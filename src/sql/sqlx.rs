@@ -0,0 +1,122 @@
+use crate::specifiers::SpecifierSet;
+use crate::PackageVersion;
+use sqlx::database::Database;
+use sqlx::decode::Decode;
+use sqlx::encode::{Encode, IsNull};
+use sqlx::error::BoxDynError;
+use sqlx::types::Type;
+
+/// Binds and reads versions wherever strings can be, e.g.
+/// `.bind(&version)` and `row.try_get::<PackageVersion, _>("version")`
+impl<DB: Database> Type<DB> for PackageVersion
+where
+    str: Type<DB>,
+{
+    fn type_info() -> DB::TypeInfo {
+        <str as Type<DB>>::type_info()
+    }
+
+    fn compatible(ty: &DB::TypeInfo) -> bool {
+        <str as Type<DB>>::compatible(ty)
+    }
+}
+
+impl<'q, DB: Database> Encode<'q, DB> for PackageVersion
+where
+    String: Encode<'q, DB>,
+{
+    fn encode_by_ref(
+        &self,
+        buf: &mut <DB as Database>::ArgumentBuffer<'q>,
+    ) -> Result<IsNull, BoxDynError> {
+        self.to_normalized_string().encode(buf)
+    }
+}
+
+impl<'r, DB: Database> Decode<'r, DB> for PackageVersion
+where
+    &'r str: Decode<'r, DB>,
+{
+    fn decode(value: <DB as Database>::ValueRef<'r>) -> Result<Self, BoxDynError> {
+        Ok(Self::new(<&str as Decode<DB>>::decode(value)?)?)
+    }
+}
+
+impl<DB: Database> Type<DB> for SpecifierSet
+where
+    str: Type<DB>,
+{
+    fn type_info() -> DB::TypeInfo {
+        <str as Type<DB>>::type_info()
+    }
+
+    fn compatible(ty: &DB::TypeInfo) -> bool {
+        <str as Type<DB>>::compatible(ty)
+    }
+}
+
+impl<'q, DB: Database> Encode<'q, DB> for SpecifierSet
+where
+    String: Encode<'q, DB>,
+{
+    fn encode_by_ref(
+        &self,
+        buf: &mut <DB as Database>::ArgumentBuffer<'q>,
+    ) -> Result<IsNull, BoxDynError> {
+        self.to_string().encode(buf)
+    }
+}
+
+impl<'r, DB: Database> Decode<'r, DB> for SpecifierSet
+where
+    &'r str: Decode<'r, DB>,
+{
+    fn decode(value: <DB as Database>::ValueRef<'r>) -> Result<Self, BoxDynError> {
+        Ok(Self::new(<&str as Decode<DB>>::decode(value)?)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::specifiers::SpecifierSet;
+    use crate::PackageVersion;
+    use sqlx::any::{Any, AnyArguments, AnyValue};
+    use sqlx::{Arguments, Decode, Encode, Type, Value};
+
+    /// The value a query would bind `value` as
+    fn bind<T>(value: T) -> AnyValue
+    where
+        T: Encode<'static, Any> + Type<Any> + 'static,
+    {
+        let mut arguments = AnyArguments::default();
+        arguments.add(value).unwrap();
+        AnyValue {
+            kind: arguments.values.0.remove(0),
+        }
+    }
+
+    fn decode<T: for<'r> Decode<'r, Any>>(value: &AnyValue) -> Option<T> {
+        T::decode(value.as_ref()).ok()
+    }
+
+    #[test]
+    fn test_sqlx_text() {
+        assert_eq!(
+            <PackageVersion as Type<Any>>::type_info(),
+            <str as Type<Any>>::type_info()
+        );
+
+        let version = PackageVersion::new("v1.0-RC1").unwrap();
+        let value = bind(version.clone());
+        assert_eq!(decode::<String>(&value).unwrap(), "1.0rc1");
+        assert_eq!(decode::<PackageVersion>(&value), Some(version));
+
+        let set = SpecifierSet::new(">=1.0, <2.0").unwrap();
+        let value = bind(set.clone());
+        assert_eq!(decode::<String>(&value).unwrap(), ">=1.0,<2.0");
+        assert_eq!(decode::<SpecifierSet>(&value), Some(set));
+
+        assert!(decode::<PackageVersion>(&bind("1.0 junk".to_string())).is_none());
+        assert!(decode::<SpecifierSet>(&bind(">=1.0,".to_string())).is_none());
+    }
+}
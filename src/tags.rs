@@ -0,0 +1,346 @@
+//! # Platform compatibility tags
+//! Handling of `PEP-425` compatibility tags (including the `PEP-600`
+//! `manylinux_x_y` platform tags) as found in wheel filenames
+//!
+//! Read more at <https://peps.python.org/pep-0425/>
+//!
+//! ## Example Usage
+//! ```
+//! use pyver::tags::{parse_tag_set, Tag, TagEnvironment};
+//!
+//! let env = TagEnvironment::cpython(3, 11, &["manylinux_2_17_x86_64", "linux_x86_64"]);
+//! let tags = parse_tag_set("cp311-cp311-manylinux_2_17_x86_64").unwrap();
+//!
+//! assert!(env.best_priority(&tags).is_some());
+//! ```
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt;
+use std::str::FromStr;
+
+/// # `PEP-425` Compatibility tag
+/// A single `python-abi-platform` triple such as `cp311-abi3-manylinux_2_17_x86_64`
+///
+/// All components are stored lowercased since tags are case-insensitive
+#[derive(
+    Hash, Clone, Debug, Serialize, Deserialize, Eq, PartialEq, Ord, PartialOrd,
+)]
+pub struct Tag {
+    /// Python implementation and version (e.g. `cp311`, `py3`)
+    pub python: String,
+    /// ABI the distribution was built against (e.g. `cp311`, `abi3`, `none`)
+    pub abi: String,
+    /// Platform the distribution was built for (e.g. `win_amd64`, `any`)
+    pub platform: String,
+}
+
+impl Tag {
+    pub fn new(python: &str, abi: &str, platform: &str) -> Self {
+        Self {
+            python: python.to_ascii_lowercase(),
+            abi: abi.to_ascii_lowercase(),
+            platform: platform.to_ascii_lowercase(),
+        }
+    }
+}
+
+impl FromStr for Tag {
+    type Err = anyhow::Error;
+
+    /// Parses a single (uncompressed) tag
+    fn from_str(tag: &str) -> Result<Self> {
+        let tags = parse_tag_set(tag)?;
+        if tags.len() != 1 {
+            anyhow::bail!("Tag {} is a compressed tag set", tag);
+        }
+        Ok(tags.into_iter().next().unwrap())
+    }
+}
+
+impl fmt::Display for Tag {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}-{}-{}", self.python, self.abi, self.platform)
+    }
+}
+
+/// Expands a compressed tag set into every tag it describes
+///
+/// Each component may list several alternatives separated by `.`
+/// (`cp310.cp311-abi3-manylinux_2_17_x86_64.manylinux2014_x86_64`),
+/// and an alternative may use a brace group as shorthand
+/// (`cp3{10,11}` expands to `cp310.cp311`).
+/// Tags are returned in order with duplicates removed.
+///
+/// ```
+/// use pyver::tags::{parse_tag_set, Tag};
+///
+/// let tags = parse_tag_set("cp3{10,11}-abi3-manylinux_2_17_x86_64").unwrap();
+/// assert_eq!(
+///     tags,
+///     vec![
+///         Tag::new("cp310", "abi3", "manylinux_2_17_x86_64"),
+///         Tag::new("cp311", "abi3", "manylinux_2_17_x86_64"),
+///     ]
+/// );
+/// ```
+pub fn parse_tag_set(tag: &str) -> Result<Vec<Tag>> {
+    let components: Vec<&str> = tag.split('-').collect();
+    if components.len() != 3 {
+        anyhow::bail!("Tag {} must have exactly three components", tag);
+    }
+
+    let pythons = expand_component(components[0])?;
+    let abis = expand_component(components[1])?;
+    let platforms = expand_component(components[2])?;
+
+    let mut tags: Vec<Tag> = Vec::new();
+    for python in &pythons {
+        for abi in &abis {
+            for platform in &platforms {
+                let tag = Tag::new(python, abi, platform);
+                if !tags.contains(&tag) {
+                    tags.push(tag);
+                }
+            }
+        }
+    }
+    Ok(tags)
+}
+
+/// Expands one `.` separated tag component (with optional brace groups)
+fn expand_component(component: &str) -> Result<Vec<String>> {
+    let mut expanded: Vec<String> = Vec::new();
+
+    for alternative in split_alternatives(component)? {
+        for value in expand_braces(alternative)? {
+            let valid = !value.is_empty()
+                && value.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+            if !valid {
+                anyhow::bail!("Invalid tag component {}", component);
+            }
+            expanded.push(value);
+        }
+    }
+    Ok(expanded)
+}
+
+/// Splits a component on `.` while leaving dots inside brace groups alone
+fn split_alternatives(component: &str) -> Result<Vec<&str>> {
+    let mut alternatives = Vec::new();
+    let mut depth = 0;
+    let mut start = 0;
+
+    for (i, c) in component.char_indices() {
+        match c {
+            '{' => depth += 1,
+            '}' if depth > 0 => depth -= 1,
+            '}' => anyhow::bail!("Unbalanced braces in tag component {}", component),
+            '.' if depth == 0 => {
+                alternatives.push(&component[start..i]);
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    if depth != 0 {
+        anyhow::bail!("Unbalanced braces in tag component {}", component);
+    }
+    alternatives.push(&component[start..]);
+    Ok(alternatives)
+}
+
+/// Expands brace groups such as `cp3{10,11}` into `cp310` and `cp311`
+fn expand_braces(value: &str) -> Result<Vec<String>> {
+    let open = match value.find('{') {
+        Some(i) => i,
+        None => return Ok(vec![value.to_string()]),
+    };
+    let close = match value[open..].find('}') {
+        Some(i) => open + i,
+        None => anyhow::bail!("Unbalanced braces in tag {}", value),
+    };
+
+    let prefix = &value[..open];
+    let mut expanded = Vec::new();
+    for suffix in expand_braces(&value[close + 1..])? {
+        for choice in value[open + 1..close].split(',') {
+            expanded.push(format!("{}{}{}", prefix, choice.trim(), suffix));
+        }
+    }
+    Ok(expanded)
+}
+
+/// # Target environment
+/// The ordered list of tags an interpreter supports, most preferred first
+///
+/// A lower priority value means a better match, so wheels can be ranked by
+/// the best priority among their tags.
+#[derive(Clone, Debug)]
+pub struct TagEnvironment {
+    tags: Vec<Tag>,
+    priorities: HashMap<Tag, usize>,
+}
+
+impl TagEnvironment {
+    /// Creates an environment from supported tags, most preferred first
+    pub fn new(tags: Vec<Tag>) -> Self {
+        let mut priorities = HashMap::with_capacity(tags.len());
+        for (priority, tag) in tags.iter().enumerate() {
+            // Keep the first (best) priority if a tag is listed twice
+            priorities.entry(tag.clone()).or_insert(priority);
+        }
+        Self { tags, priorities }
+    }
+
+    /// Builds the tags supported by CPython `major.minor` on the given platforms,
+    /// in the same order as `packaging.tags.sys_tags()`
+    ///
+    /// `platforms` should be ordered from most to least specific
+    /// (e.g. `manylinux_2_17_x86_64` before `linux_x86_64`)
+    pub fn cpython(major: u32, minor: u32, platforms: &[&str]) -> Self {
+        let interpreter = format!("cp{}{}", major, minor);
+        let mut tags: Vec<Tag> = Vec::new();
+
+        // Interpreter specific ABI, then the stable ABI, then no ABI
+        for abi in [interpreter.as_str(), "abi3", "none"] {
+            for platform in platforms {
+                tags.push(Tag::new(&interpreter, abi, platform));
+            }
+        }
+        // Stable ABI wheels built for older CPython versions
+        if major == 3 {
+            for older in (2..minor).rev() {
+                for platform in platforms {
+                    tags.push(Tag::new(&format!("cp3{}", older), "abi3", platform));
+                }
+            }
+        }
+
+        // Pure python wheels
+        let python_range = python_range(major, minor);
+        for python in &python_range {
+            for platform in platforms {
+                tags.push(Tag::new(python, "none", platform));
+            }
+        }
+        tags.push(Tag::new(&interpreter, "none", "any"));
+        for python in &python_range {
+            tags.push(Tag::new(python, "none", "any"));
+        }
+
+        Self::new(tags)
+    }
+
+    /// Supported tags, most preferred first
+    pub fn tags(&self) -> &[Tag] {
+        &self.tags
+    }
+
+    /// Priority of a tag in this environment (lower is better),
+    /// `None` if the tag isn't supported
+    pub fn priority(&self, tag: &Tag) -> Option<usize> {
+        self.priorities.get(tag).copied()
+    }
+
+    /// Whether the tag is supported by this environment
+    pub fn is_compatible(&self, tag: &Tag) -> bool {
+        self.priorities.contains_key(tag)
+    }
+
+    /// Best priority among a set of tags (e.g. all tags of one wheel),
+    /// `None` if none of them are supported
+    pub fn best_priority<'a>(
+        &self,
+        tags: impl IntoIterator<Item = &'a Tag>,
+    ) -> Option<usize> {
+        tags.into_iter().filter_map(|tag| self.priority(tag)).min()
+    }
+}
+
+/// `py3X`, `py3`, `py3(X-1)` ... `py30` as in `packaging.tags`
+fn python_range(major: u32, minor: u32) -> Vec<String> {
+    let mut range = vec![format!("py{}{}", major, minor), format!("py{}", major)];
+    for older in (0..minor).rev() {
+        range.push(format!("py{}{}", major, older));
+    }
+    range
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_tag_set, Tag, TagEnvironment};
+    use anyhow::Result;
+
+    #[test]
+    fn test_tag_parsing() -> Result<()> {
+        assert_eq!(
+            "py3-none-any".parse::<Tag>()?,
+            Tag::new("py3", "none", "any")
+        );
+        assert_eq!(
+            "CP311-CP311-Win_AMD64".parse::<Tag>()?.to_string(),
+            "cp311-cp311-win_amd64"
+        );
+        assert!("py2.py3-none-any".parse::<Tag>().is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_compressed_tag_sets() -> Result<()> {
+        let tags = parse_tag_set(
+            "cp310.cp311-abi3-manylinux_2_17_x86_64.manylinux2014_x86_64",
+        )?;
+        assert_eq!(tags.len(), 4);
+        assert_eq!(tags[0], Tag::new("cp310", "abi3", "manylinux_2_17_x86_64"));
+        assert_eq!(tags[3], Tag::new("cp311", "abi3", "manylinux2014_x86_64"));
+
+        assert_eq!(
+            parse_tag_set("cp3{10,11}-abi3-any")?,
+            parse_tag_set("cp310.cp311-abi3-any")?
+        );
+        assert_eq!(parse_tag_set("py2.py3.py2-none-any")?.len(), 2);
+        Ok(())
+    }
+
+    #[test]
+    fn test_compressed_tag_sets_negative() {
+        let tags = vec![
+            "py3-none",
+            "py3-none-any-extra",
+            "py3--any",
+            "py3.-none-any",
+            "cp3{10-abi3-any",
+            "cp3}10-abi3-any",
+        ];
+
+        for tag in tags {
+            if let Ok(v) = parse_tag_set(tag) {
+                panic!("Oh no {:?}", v);
+            }
+        }
+    }
+
+    #[test]
+    fn test_environment_priority() -> Result<()> {
+        let env =
+            TagEnvironment::cpython(3, 11, &["manylinux_2_17_x86_64", "linux_x86_64"]);
+
+        let native = env.priority(&"cp311-cp311-manylinux_2_17_x86_64".parse()?);
+        let stable = env.priority(&"cp39-abi3-manylinux_2_17_x86_64".parse()?);
+        let pure = env.priority(&"py3-none-any".parse()?);
+
+        assert!(native < stable);
+        assert!(stable < pure);
+        assert!(pure.is_some());
+
+        assert!(!env.is_compatible(&"cp312-cp312-manylinux_2_17_x86_64".parse()?));
+        assert!(!env.is_compatible(&"cp311-cp311-win_amd64".parse()?));
+        assert_eq!(
+            env.tags()[0].to_string(),
+            "cp311-cp311-manylinux_2_17_x86_64"
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,226 @@
+//! Swift and Kotlin bindings generated with
+//! [UniFFI](https://mozilla.github.io/uniffi-rs/), so apps and IDE plugins
+//! order and match versions exactly like pip does
+//!
+//! Build the crate as a `cdylib` and generate the bindings from it:
+//!
+//! ```sh
+//! cargo rustc --release --features uniffi --crate-type cdylib
+//! uniffi-bindgen generate --library target/release/libpyver.so --language kotlin --out-dir out
+//! ```
+//!
+//! ```kotlin
+//! import uniffi.pyver.*
+//!
+//! compareVersions("1.0rc1", "1.0") // -1
+//! satisfies("1.5", ">=1.0, <2.0") // true
+//! Version("v1.0-RC1").toString() // "1.0rc1"
+//! ```
+//!
+//! Invalid versions and specifiers throw a [`PyverError`].
+
+use crate::specifiers;
+use crate::PackageVersion;
+use core::cmp::Ordering;
+use core::fmt;
+use std::sync::Arc;
+
+/// # UniFFI error
+/// A version or specifiers that aren't valid according to `PEP-440`
+#[derive(Debug, ::uniffi::Error)]
+pub enum PyverError {
+    InvalidVersion { message: String },
+    InvalidSpecifier { message: String },
+}
+
+impl fmt::Display for PyverError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidVersion { message } | Self::InvalidSpecifier { message } => {
+                write!(f, "{}", message)
+            }
+        }
+    }
+}
+
+impl std::error::Error for PyverError {}
+
+fn parse(version: &str) -> Result<PackageVersion, PyverError> {
+    PackageVersion::new(version).map_err(|e| PyverError::InvalidVersion {
+        message: e.to_string(),
+    })
+}
+
+fn parse_specifiers(set: &str) -> Result<specifiers::SpecifierSet, PyverError> {
+    specifiers::SpecifierSet::new(set).map_err(|e| PyverError::InvalidSpecifier {
+        message: e.to_string(),
+    })
+}
+
+fn ordering(ordering: Ordering) -> i32 {
+    match ordering {
+        Ordering::Less => -1,
+        Ordering::Equal => 0,
+        Ordering::Greater => 1,
+    }
+}
+
+/// Whether the string is a valid version
+#[::uniffi::export]
+pub fn is_valid_version(version: String) -> bool {
+    PackageVersion::new(&version).is_ok()
+}
+
+/// The version in normal form (`1.0rc1` for `v1.0-RC1`)
+#[::uniffi::export]
+pub fn normalize_version(version: String) -> Result<String, PyverError> {
+    Ok(parse(&version)?.to_normalized_string())
+}
+
+/// `-1`, `0` or `1` as `a` sorts before, the same as or after `b`
+#[::uniffi::export]
+pub fn compare_versions(a: String, b: String) -> Result<i32, PyverError> {
+    Ok(ordering(parse(&a)?.cmp(&parse(&b)?)))
+}
+
+/// Whether the version matches every specifier (`>=1.0, <2.0`)
+#[::uniffi::export]
+pub fn satisfies(version: String, specifiers: String) -> Result<bool, PyverError> {
+    Ok(parse_specifiers(&specifiers)?.contains(&parse(&version)?))
+}
+
+/// # UniFFI version
+/// A [`PackageVersion`] as the class `Version`, equal and hashed like the
+/// Rust version and printed in normal form
+#[derive(Debug, PartialEq, Eq, Hash, ::uniffi::Object)]
+#[uniffi::export(Display, Eq, Hash)]
+pub struct Version(PackageVersion);
+
+impl fmt::Display for Version {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0.to_normalized_string())
+    }
+}
+
+#[::uniffi::export]
+impl Version {
+    #[uniffi::constructor]
+    pub fn new(version: String) -> Result<Self, PyverError> {
+        parse(&version).map(Self)
+    }
+
+    pub fn epoch(&self) -> u32 {
+        self.0.epoch()
+    }
+
+    pub fn release(&self) -> Vec<u64> {
+        self.0.release().segments().to_vec()
+    }
+
+    pub fn is_prerelease(&self) -> bool {
+        self.0.is_prerelease()
+    }
+
+    pub fn is_postrelease(&self) -> bool {
+        self.0.is_postrelease()
+    }
+
+    pub fn is_devrelease(&self) -> bool {
+        self.0.is_devrelease()
+    }
+
+    pub fn local(&self) -> Option<String> {
+        self.0.local().map(|local| local.to_string())
+    }
+
+    /// `-1`, `0` or `1` as this version sorts before, the same as or
+    /// after `other`
+    pub fn compare(&self, other: Arc<Version>) -> i32 {
+        ordering(self.0.cmp(&other.0))
+    }
+}
+
+/// # UniFFI specifier set
+/// A [`specifiers::SpecifierSet`] as the class `SpecifierSet`
+#[derive(Debug, ::uniffi::Object)]
+#[uniffi::export(Display)]
+pub struct SpecifierSet(specifiers::SpecifierSet);
+
+impl fmt::Display for SpecifierSet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+#[::uniffi::export]
+impl SpecifierSet {
+    #[uniffi::constructor]
+    pub fn new(specifiers: String) -> Result<Self, PyverError> {
+        parse_specifiers(&specifiers).map(Self)
+    }
+
+    pub fn contains(&self, version: Arc<Version>) -> bool {
+        self.0.contains(&version.0)
+    }
+
+    /// The matching versions, pre-releases only if nothing else matches,
+    /// see [`specifiers::SpecifierSet::filter`]
+    pub fn filter(&self, versions: Vec<Arc<Version>>) -> Vec<Arc<Version>> {
+        let matching: Vec<&PackageVersion> = self
+            .0
+            .filter(versions.iter().map(|version| &version.0))
+            .collect();
+        versions
+            .iter()
+            .filter(|version| matching.iter().any(|m| core::ptr::eq(*m, &version.0)))
+            .cloned()
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{compare_versions, is_valid_version, normalize_version, satisfies};
+    use super::{PyverError, SpecifierSet, Version};
+    use std::sync::Arc;
+
+    #[test]
+    fn test_uniffi_functions() {
+        assert!(is_valid_version("v1.0-RC1".into()));
+        assert!(!is_valid_version("1.0 junk".into()));
+        assert_eq!(normalize_version("v1.0-RC1".into()).unwrap(), "1.0rc1");
+        assert_eq!(compare_versions("1.0rc1".into(), "1.0".into()).unwrap(), -1);
+        assert_eq!(compare_versions("1.0".into(), "1.0.0".into()).unwrap(), 0);
+        assert!(satisfies("1.5".into(), ">=1.0, <2.0".into()).unwrap());
+        assert!(!satisfies("1.5rc1".into(), ">=1.0, <2.0".into()).unwrap());
+
+        assert!(matches!(
+            compare_versions("1.0 junk".into(), "1.0".into()),
+            Err(PyverError::InvalidVersion { .. })
+        ));
+        assert!(matches!(
+            satisfies("1.0".into(), ">=1.0,".into()),
+            Err(PyverError::InvalidSpecifier { .. })
+        ));
+    }
+
+    #[test]
+    fn test_uniffi_objects() {
+        let version = Arc::new(Version::new("v1!2.0rc1+Local".into()).unwrap());
+        assert_eq!(version.to_string(), "1!2.0rc1+local");
+        assert_eq!((version.epoch(), version.release()), (1, vec![2, 0]));
+        assert!(version.is_prerelease() && !version.is_devrelease());
+        assert_eq!(version.local().as_deref(), Some("Local"));
+
+        let other = Arc::new(Version::new("2.0".into()).unwrap());
+        assert_eq!(version.compare(other.clone()), 1);
+        assert_eq!(*other, Version::new("2.0.0".into()).unwrap());
+
+        let set = SpecifierSet::new(">1.0, !=1.1".into()).unwrap();
+        assert!(set.contains(other.clone()));
+        let old = Arc::new(Version::new("0.9".into()).unwrap());
+        let matching = set.filter(vec![old, version.clone(), other.clone()]);
+        assert_eq!(matching, vec![other]);
+        assert_eq!(set.to_string(), ">1.0,!=1.1");
+    }
+}
@@ -1,11 +1,35 @@
+use crate::parser::VersionParts;
+use alloc::vec::Vec;
 use anyhow::Result;
+#[cfg(any(test, feature = "regex-parser", feature = "regex-lite"))]
 use lazy_static::lazy_static;
+#[cfg(any(test, feature = "regex-parser", feature = "regex-lite"))]
 use pomsky_macro::pomsky;
-use regex::Captures;
+#[cfg(all(any(test, feature = "regex-parser"), not(feature = "regex-lite")))]
+pub(crate) use regex::{Captures, Regex};
+#[cfg(feature = "regex-lite")]
+pub(crate) use regex_lite::{Captures, Regex};
+
+#[cfg(any(test, feature = "regex-parser", feature = "regex-lite"))]
+lazy_static! {
+    // Safe to unwrap since Regex is predefined
+    // Regex as defined in PEP-0440, anchored so that
+    // the whole string has to be a version (case-insensitive like PEP-0440)
+    static ref VERSION_VALIDATOR: Regex =
+        Regex::new(&format!("^(?i:{})$", VALIDATION_REGEX)).unwrap();
+}
 
 /// Utility Function for Checking if a `PEP-440` Version String is valid
 /// and getting it's groups
 ///
+/// The regex is compiled on the first call, to only check versions
+/// use [`Validator`] which doesn't need it. Only available with the
+/// `regex-parser` or `regex-lite` feature.
+///
+/// With the `regex-lite` feature the regex is compiled by `regex-lite`,
+/// which takes a fraction of the time for tools that check a single
+/// version and exit, and the groups are `regex_lite::Captures`.
+///
 /// # Example Usage
 /// ```
 /// use pyver::validate_440_version;
@@ -15,28 +39,75 @@ use regex::Captures;
 ///     Err(e) => panic!("Not Valid!"),
 /// }
 /// ```
-pub fn validate_440_version(version: &str) -> Result<Captures> {
-    lazy_static! {
-        // Safe to unwrap since Regex is predefined
-        // Regex as defined in PEP-0440
-        static ref VERSION_VALIDATOR: regex::Regex =
-            regex::Regex::new(VALIDATION_REGEX).unwrap();
-    }
-
+#[cfg(any(test, feature = "regex-parser", feature = "regex-lite"))]
+pub fn validate_440_version(version: &str) -> Result<Captures<'_>> {
     // Capture each group of the regex
     // Groups are:
     // epoch, release, pre, pre_l, pre_n, post, post_l, post_n1, post_n2,
     // dev, dev_l, dev_n, local
-    let version_match: Captures = match VERSION_VALIDATOR.captures(version) {
+    // (surrounding whitespace is ignored as described in PEP-0440)
+    let version_match: Captures = match VERSION_VALIDATOR.captures(version.trim()) {
         Some(v) => v,
         None => anyhow::bail!("Failed to decode version {}", version),
     };
     Ok(version_match)
 }
 
+/// # Batch validator
+/// Checks many `PEP-440` version strings without compiling the regex
+///
+/// Unlike `validate_440_version` this uses the hand-written parser of
+/// [`crate::PackageVersion`], which borrows the parts of each version from
+/// the input, so valid versions are checked without allocating anything.
+/// It accepts exactly the versions [`crate::PackageVersion::new`] accepts,
+/// so a number too large for its component (an epoch above `u32::MAX`) is
+/// invalid even though the `PEP-440` regex matches it.
+///
+/// ## Example Usage
+/// ```
+/// use pyver::Validator;
+///
+/// let validator = Validator::new();
+/// let results = validator.validate_many(&["1.0", "v2.0rc1", "1.0 junk"]);
+///
+/// assert!(results[0].is_ok() && results[1].is_ok());
+/// assert!(results[2].is_err());
+/// ```
+#[derive(Clone, Debug)]
+pub struct Validator {
+    _private: (),
+}
+
+impl Validator {
+    pub fn new() -> Self {
+        Self { _private: () }
+    }
+
+    /// Whether a single version string is valid,
+    /// ignoring surrounding whitespace
+    pub fn validate(&self, version: &str) -> Result<()> {
+        VersionParts::parse(version)?.check_numbers()
+    }
+
+    /// Whether each version string is valid, in the order of `versions`
+    pub fn validate_many(&self, versions: &[&str]) -> Vec<Result<()>> {
+        versions
+            .iter()
+            .map(|version| self.validate(version))
+            .collect()
+    }
+}
+
+impl Default for Validator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 /// Rulex version of
 /// Python's PEP-440 Regex
 /// (<https://peps.python.org/pep-0440/#appendix-b-parsing-version-strings-with-regular-expressions>)
+#[cfg(any(test, feature = "regex-parser", feature = "regex-lite"))]
 static VALIDATION_REGEX: &str = pomsky!(
     // Version String may start with v<version_number>
     // Example:
@@ -111,3 +182,55 @@ static VALIDATION_REGEX: &str = pomsky!(
     )
     )?
 );
+
+#[cfg(test)]
+mod tests {
+    use super::{validate_440_version, Validator};
+    use crate::PackageVersion;
+
+    #[test]
+    fn test_validator_agrees() {
+        let versions = vec![
+            "1.0",
+            " v1!2.0RC1.post2.dev3+Local.7 ",
+            "1.0-1",
+            "1.0.",
+            "1.0+",
+            "junk",
+            "",
+        ];
+        let results = Validator::default().validate_many(&versions);
+        for (version, result) in versions.iter().zip(results) {
+            assert_eq!(
+                result.is_ok(),
+                validate_440_version(version).is_ok(),
+                "{}",
+                version
+            );
+        }
+    }
+
+    #[test]
+    fn test_validator_overflow() {
+        let release = format!("1.{}0", u64::MAX);
+        let versions = vec![
+            "4294967295!1.0",
+            "4294967296!1.0",
+            "1.0rc4294967296",
+            "1.0.post4294967296",
+            "1.0-4294967296",
+            "1.0.dev4294967296",
+            release.as_str(),
+        ];
+        let validator = Validator::new();
+        for version in versions {
+            assert_eq!(
+                validator.validate(version).is_ok(),
+                PackageVersion::new(version).is_ok(),
+                "{}",
+                version
+            );
+        }
+        assert!(validator.validate("4294967296!1.0").is_err());
+    }
+}
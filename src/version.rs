@@ -1,30 +1,57 @@
 use super::ids::{DevHead, PostHead, PostHeader, PreHeader, ReleaseHeader};
-use super::validate_440_version;
+use super::parser::VersionParts;
+use alloc::borrow::Cow;
+use alloc::boxed::Box;
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
 use anyhow::Result;
+use core::cmp::Ordering;
+use core::fmt;
+use core::hash::{Hash, Hasher};
+use core::str::FromStr;
 use serde::{Deserialize, Serialize};
-use std::fmt;
-use std::hash::{Hash, Hasher};
+use smallvec::SmallVec;
 
 /// `PEP-440` Compliant versioning system
 ///
-//# This struct is sorted so that PartialOrd
-//# correctly interprets priority
-//# Lower == More important
+/// Versions are ordered and compared as described in `PEP-440`
+/// (e.g. `1.0.dev0 < 1.0a1 < 1.0 < 1.0.post1`), which means
+/// equal versions may have been written differently (`1.0` and `v1.0.0`).
+///
+/// A version takes 104 bytes on 64-bit targets (112 bytes and a second
+/// allocation for the release before), releases of up to three segments
+/// are stored inline so the string it was written as is usually the only
+/// allocation. With the `compact` feature that string is only kept if it
+/// isn't in normal form, so most versions from an index allocate nothing.
+///
+/// Versions are serialized as their normal form (`"1.0rc1"` for `v1.0-RC1`)
+/// and deserialized by parsing the string, so they can also be the keys of
+/// JSON objects and TOML tables. Keys that are equal versions (`1.0` and
+/// `1.0.0`) are the same key, the last one of them wins.
 ///
 /// # Example Usage
 /// ```
 ///# use pyver::PackageVersion;
 /// let _ = PackageVersion::new("v1.0");
 /// ```
-#[derive(Clone, Derivative, Debug, Serialize, Deserialize)]
-#[derivative(PartialOrd, Ord)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[cfg_attr(
+    feature = "rkyv",
+    derive(rkyv::Archive, rkyv::Serialize, rkyv::Deserialize)
+)]
+#[cfg_attr(
+    feature = "diesel",
+    derive(diesel::expression::AsExpression, diesel::deserialize::FromSqlRow),
+    diesel(sql_type = diesel::sql_types::Text)
+)]
+#[serde(try_from = "String", into = "String")]
 pub struct PackageVersion {
     /// ## Original String
     /// Just holds the original string passed in when creating
     /// the `PackageVersion` as some formating data is lost
     /// when parsing the string
-    #[derivative(PartialOrd = "ignore", PartialEq = "ignore")]
-    pub original: String,
+    pub(crate) original: Option<Box<str>>,
 
     /// ## `PEP-440` Local version identifier
     /// Local version sorting will have to be it's own issue
@@ -38,173 +65,958 @@ pub struct PackageVersion {
     ///  ['a'-'z' '0'-'9']+
     ///  ((["-" "_" "."] ['a'-'z' '0'-'9']+)+)?
     /// ```
-    #[derivative(PartialOrd = "ignore", PartialEq = "ignore")]
-    pub local: Option<String>,
+    pub(crate) local: Option<Box<str>>,
 
     /// ## `PEP-440` Developmental release identifier
-    pub dev: Option<DevHead>,
+    pub(crate) dev: Option<DevHead>,
 
     /// ## `PEP-440` Post-Release identifier
-    pub post: Option<PostHeader>,
+    pub(crate) post: Option<PostHeader>,
 
     /// ## `PEP-440` Pre-Release identifier
-    pub pre: Option<PreHeader>,
+    pub(crate) pre: Option<PreHeader>,
 
     /// ## `PEP-440` Release number
-    pub release: ReleaseHeader,
+    pub(crate) release: ReleaseHeader,
 
     /// ## `PEP-440` Version-Epoch
-    pub epoch: Option<u32>,
+    pub(crate) epoch: Option<u32>,
 }
 
 impl PackageVersion {
+    /// The smallest possible version, `0.dev0`
+    ///
+    /// ```
+    ///# use pyver::PackageVersion;
+    /// assert_eq!(PackageVersion::MIN, PackageVersion::new("0.dev0").unwrap());
+    /// assert!(PackageVersion::MIN < PackageVersion::new("0a0").unwrap());
+    /// ```
+    pub const MIN: Self = Self {
+        original: None,
+        local: None,
+        dev: Some(DevHead { dev_num: Some(0) }),
+        post: None,
+        pre: None,
+        // Compares equal to the release `0`
+        release: ReleaseHeader {
+            segments: SmallVec::new_const(),
+        },
+        epoch: Some(0),
+    };
+
+    /// A synthetic version above every other version
+    ///
+    /// It can't be parsed or matched by specifiers and only exists to
+    /// close open-ended intervals, e.g. for range scans of a `BTreeMap`
+    ///
+    /// ```
+    ///# use pyver::PackageVersion;
+    /// use std::collections::BTreeSet;
+    ///
+    /// let versions: BTreeSet<PackageVersion> = ["1.0", "2.0", "99999!1.0"]
+    ///     .iter()
+    ///     .map(|v| PackageVersion::new(v).unwrap())
+    ///     .collect();
+    ///
+    /// let newer = PackageVersion::new("1.5").unwrap()..PackageVersion::MAX;
+    /// assert_eq!(versions.range(newer).count(), 2);
+    /// assert_eq!(PackageVersion::MAX.to_string(), "MAX");
+    /// ```
+    pub const MAX: Self = Self {
+        original: None,
+        local: None,
+        dev: None,
+        post: None,
+        pre: None,
+        release: ReleaseHeader {
+            segments: SmallVec::new_const(),
+        },
+        epoch: Some(u32::MAX),
+    };
+
     pub fn new(version: &str) -> Result<Self> {
-        let version_match = validate_440_version(version)?;
+        Self::parse(version.to_string())
+    }
 
-        let epoch: Option<u32> = match version_match.name("epoch") {
-            // Convert Epoch String to Epoch Number
-            Some(v) => Some(v.as_str().parse::<u32>()?),
-            None => None,
+    /// Creates a version from its components without parsing a string,
+    /// e.g. for versions stored component by component
+    ///
+    /// The `original` string is the normal form. Fails without release
+    /// segments or with an invalid local version label.
+    ///
+    /// ```
+    ///# use pyver::PackageVersion;
+    /// use pyver::ids::PreHeader;
+    ///
+    /// let pre = Some(PreHeader::Beta(Some(2)));
+    /// let version = PackageVersion::from_parts(0, vec![3, 1], pre, None, Some(1), None);
+    /// assert_eq!(version.unwrap().to_string(), "3.1b2.dev1");
+    /// ```
+    pub fn from_parts(
+        epoch: u32,
+        release: Vec<u64>,
+        pre: Option<PreHeader>,
+        post: Option<u32>,
+        dev: Option<u32>,
+        local: Option<&str>,
+    ) -> Result<Self> {
+        if release.is_empty() {
+            anyhow::bail!("A version needs at least one release segment");
+        }
+        if let Some(local) = local {
+            validate_local(local)?;
+        }
+
+        let version = Self {
+            original: None,
+            local: local.map(Into::into),
+            dev: dev.map(|dev_num| DevHead {
+                dev_num: Some(dev_num),
+            }),
+            post: post.map(|post_num| PostHeader {
+                post_head: Some(PostHead::Post),
+                post_num: Some(post_num),
+            }),
+            pre,
+            release: ReleaseHeader {
+                segments: release.into(),
+            },
+            epoch: Some(epoch),
         };
+        Ok(version.renormalized())
+    }
 
-        let release: ReleaseHeader = match version_match.name("release") {
-            Some(v) => {
-                // Does Release String contain minor version
-                if v.as_str().contains('.') {
-                    let split: Vec<&str> = v.as_str().split('.').into_iter().collect();
-                    ReleaseHeader {
-                        major: split[0].parse::<u32>()?,
-                        minor: split[1].parse::<u32>()?,
-                    }
-                } else {
-                    ReleaseHeader {
-                        major: v.as_str().parse::<u32>()?,
-                        minor: 0,
-                    }
-                }
-            }
-            // There always has to be at least a major version
-            None => anyhow::bail!("Failed to decode version {}", version),
+    /// Used by the `pyver!` macro for a literal it parsed while compiling
+    #[doc(hidden)]
+    pub fn __from_literal(
+        original: &str,
+        epoch: Option<u32>,
+        release: &[u64],
+        pre: Option<PreHeader>,
+        post: Option<PostHeader>,
+        dev: Option<DevHead>,
+        local: Option<&str>,
+    ) -> Self {
+        let mut version = Self {
+            original: None,
+            local: local.map(Into::into),
+            dev,
+            post,
+            pre,
+            release: ReleaseHeader {
+                segments: release.into(),
+            },
+            epoch,
         };
+        version.set_original(original.to_string());
+        version
+    }
 
-        let pre: Option<PreHeader> = match version_match.name("pre") {
-            Some(_) => {
-                let pre_n = match version_match.name("pre_n") {
-                    Some(v) => Some(v.as_str().parse::<u32>()?),
-                    None => None,
-                };
-
-                // Should be safe to unwrap since we already checked if pre has a value
-                // since pre_n has to exist
-                match version_match.name("pre_l").unwrap().as_str() {
-                    "alpha" => Some(PreHeader::Alpha(pre_n)),
-                    "a" => Some(PreHeader::Alpha(pre_n)),
-                    "beta" => Some(PreHeader::Beta(pre_n)),
-                    "b" => Some(PreHeader::Beta(pre_n)),
-                    "rc" => Some(PreHeader::ReleaseCandidate(pre_n)),
-                    "c" => Some(PreHeader::ReleaseCandidate(pre_n)),
-                    "preview" => Some(PreHeader::Preview(pre_n)),
-                    "pre" => Some(PreHeader::Preview(pre_n)),
-                    _ => None,
-                }
-            }
-            None => None,
+    /// Starts putting a version together from its components
+    ///
+    /// ```
+    ///# use pyver::PackageVersion;
+    /// use pyver::ids::PreHeader;
+    ///
+    /// let version = PackageVersion::builder()
+    ///     .release([1, 2, 3])
+    ///     .pre(PreHeader::ReleaseCandidate(Some(1)))
+    ///     .build()
+    ///     .unwrap();
+    ///
+    /// assert_eq!(version.to_string(), "1.2.3rc1");
+    /// assert!(PackageVersion::builder().local("ubuntu.1").build().is_err());
+    /// ```
+    pub fn builder() -> PackageVersionBuilder {
+        PackageVersionBuilder::default()
+    }
+
+    /// Parses the version, keeping the string as the `original`
+    fn parse(version: String) -> Result<Self> {
+        let parts = VersionParts::new(&version)?;
+        let release = ReleaseHeader {
+            segments: parts.release_segments().collect::<Result<_, _>>()?,
         };
 
-        let post: Option<PostHeader> = match version_match.name("post") {
-            Some(_) => {
-                let post_num: Option<u32> = match version_match.name("post_n1") {
-                    Some(v) => Some(v.as_str().parse::<u32>()?),
-                    None => match version_match.name("post_n2") {
-                        Some(v) => Some(v.as_str().parse::<u32>()?),
-                        _ => None,
-                    },
-                };
-
-                let post_head: Option<PostHead> = match version_match.name("post_l") {
-                    Some(v) => {
-                        match v.as_str() {
-                            "post" => Some(PostHead::Post),
-                            "rev" => Some(PostHead::Rev),
-                            "r" => Some(PostHead::Rev),
-                            // This branch Should be impossible (see regex-group post_l)
-                            _ => None,
-                        }
-                    }
-                    None => None,
-                };
-
-                Some(PostHeader {
-                    post_head,
-                    post_num,
-                })
-            }
-            None => None,
+        let mut parsed = Self {
+            epoch: parts.epoch_number()?,
+            release,
+            pre: parts.pre_header()?,
+            post: parts.post_header()?,
+            dev: parts.dev_head()?,
+            local: parts.local.map(Into::into),
+            original: None,
         };
+        parsed.set_original(version);
+        Ok(parsed)
+    }
 
-        let dev: Option<DevHead> = match version_match.name("dev") {
-            Some(_) => {
-                let dev_num = match version_match.name("dev_n") {
-                    Some(v) => Some(v.as_str().parse::<u32>()?),
-                    None => None,
-                };
-                Some(DevHead { dev_num })
-            }
-            None => None,
+    /// The epoch, `0` if the version has none
+    pub fn epoch(&self) -> u32 {
+        self.epoch.unwrap_or(0)
+    }
+
+    /// The release segments (`1.2.3`)
+    pub fn release(&self) -> &ReleaseHeader {
+        &self.release
+    }
+
+    /// The pre-release segment (`rc1`)
+    pub fn pre(&self) -> Option<&PreHeader> {
+        self.pre.as_ref()
+    }
+
+    /// The post-release segment (`.post2`)
+    pub fn post(&self) -> Option<&PostHeader> {
+        self.post.as_ref()
+    }
+
+    /// The developmental release segment (`.dev3`)
+    pub fn dev(&self) -> Option<&DevHead> {
+        self.dev.as_ref()
+    }
+
+    /// The local version label as written, without the `+`
+    pub fn local(&self) -> Option<&str> {
+        self.local.as_deref()
+    }
+
+    /// The string the version was parsed from
+    ///
+    /// With the `compact` feature only strings that aren't in normal
+    /// form are kept and this is empty otherwise, use `to_string()`
+    /// for the version as written
+    ///
+    /// ```
+    ///# use pyver::PackageVersion;
+    /// let version = PackageVersion::new("v1!2.3-RC1+ubuntu-1").unwrap();
+    ///
+    /// assert_eq!(version.original_str(), "v1!2.3-RC1+ubuntu-1");
+    /// assert_eq!(version.epoch(), 1);
+    /// assert_eq!(version.release().segments(), [2, 3]);
+    /// assert_eq!(version.local(), Some("ubuntu-1"));
+    /// assert!(version.post().is_none());
+    /// ```
+    pub fn original_str(&self) -> &str {
+        self.original.as_deref().unwrap_or("")
+    }
+
+    /// The version as written, the `original` string or the normal form
+    pub(crate) fn written(&self) -> Cow<'_, str> {
+        match &self.original {
+            Some(original) => Cow::Borrowed(original),
+            None => Cow::Owned(self.to_normalized_string()),
+        }
+    }
+
+    /// Whether this is a pre-release, which includes developmental releases
+    /// (`1.0a1`, `1.0.dev0`, `1.0.post1.dev0`)
+    ///
+    /// ```
+    ///# use pyver::PackageVersion;
+    /// let version = PackageVersion::new("1.0.post1.dev0+local").unwrap();
+    ///
+    /// assert!(version.is_prerelease());
+    /// assert!(version.is_postrelease());
+    /// assert!(version.is_devrelease());
+    /// assert!(version.is_local());
+    /// assert!(!version.is_stable());
+    /// ```
+    pub fn is_prerelease(&self) -> bool {
+        self.pre.is_some() || self.dev.is_some()
+    }
+
+    /// Whether this is a post-release (`1.0.post1`)
+    pub fn is_postrelease(&self) -> bool {
+        self.post.is_some()
+    }
+
+    /// Whether this is a developmental release (`1.0.dev0`)
+    pub fn is_devrelease(&self) -> bool {
+        self.dev.is_some()
+    }
+
+    /// Whether the version has a local version label (`1.0+ubuntu.1`)
+    pub fn is_local(&self) -> bool {
+        self.local.is_some()
+    }
+
+    /// Whether this is neither a pre-release nor a developmental release,
+    /// post-releases and local versions are stable
+    pub fn is_stable(&self) -> bool {
+        !self.is_prerelease()
+    }
+
+    /// The version with only its epoch and release (`1!1.2.3` for
+    /// `1!1.2.3rc1.post2.dev3+local`), written in normal form
+    ///
+    /// ```
+    ///# use pyver::PackageVersion;
+    /// let version = PackageVersion::new("1!1.2.3rc1.post2.dev3+local").unwrap();
+    /// assert_eq!(version.base_version().to_string(), "1!1.2.3");
+    /// ```
+    pub fn base_version(&self) -> Self {
+        let base = Self {
+            original: None,
+            local: None,
+            dev: None,
+            post: None,
+            pre: None,
+            release: self.release.clone(),
+            epoch: self.epoch,
         };
+        base.renormalized()
+    }
 
-        let local: Option<String> =
-            version_match.name("local").map(|v| v.as_str().to_string());
+    /// The version without its local segment, as written apart from that
+    ///
+    /// ```
+    ///# use pyver::PackageVersion;
+    /// let version = PackageVersion::new("v1.0.post1+ubuntu.2").unwrap();
+    /// assert_eq!(version.without_local().to_string(), "v1.0.post1");
+    /// assert_eq!(version.to_string(), "v1.0.post1+ubuntu.2");
+    /// ```
+    pub fn without_local(&self) -> Self {
+        let mut public = self.clone();
+        if let Some((original, _)) = self.original_str().split_once('+') {
+            public.set_original(original.to_string());
+        }
+        public.local = None;
+        public
+    }
 
-        Ok(Self {
-            original: version.to_string(),
-            epoch,
-            release,
-            pre,
-            post,
+    /// The public version in normal form, i.e. without the local segment
+    /// (`1.0.post1` for `v1.0-1+ubuntu.2`)
+    pub fn public(&self) -> String {
+        self.without_local().to_normalized_string()
+    }
+
+    /// The version with another epoch
+    ///
+    /// Like the other `with_` methods this leaves `self` alone
+    /// and writes the new version in normal form
+    ///
+    /// ```
+    ///# use pyver::PackageVersion;
+    /// use pyver::ids::PreHeader;
+    ///
+    /// let version = PackageVersion::new("v1.0").unwrap();
+    /// let next = version
+    ///     .with_release(&[1, 1])
+    ///     .unwrap()
+    ///     .with_pre(Some(PreHeader::ReleaseCandidate(Some(1))))
+    ///     .with_local(Some("build-7"))
+    ///     .unwrap();
+    ///
+    /// assert_eq!(next.to_string(), "1.1rc1+build.7");
+    /// assert_eq!(version.to_string(), "v1.0");
+    /// ```
+    pub fn with_epoch(&self, epoch: u32) -> Self {
+        let mut version = self.clone();
+        version.epoch = Some(epoch);
+        version.renormalized()
+    }
+
+    /// The version with other release segments, which can't be empty
+    pub fn with_release(&self, segments: &[u64]) -> Result<Self> {
+        if segments.is_empty() {
+            anyhow::bail!("A release needs at least one segment");
+        }
+        let mut version = self.clone();
+        version.release = ReleaseHeader {
+            segments: segments.into(),
+        };
+        Ok(version.renormalized())
+    }
+
+    /// The version with another or without a pre-release segment
+    pub fn with_pre(&self, pre: Option<PreHeader>) -> Self {
+        let mut version = self.clone();
+        version.pre = pre;
+        version.renormalized()
+    }
+
+    /// The version with another or without a post-release number
+    pub fn with_post(&self, post: Option<u32>) -> Self {
+        let mut version = self.clone();
+        version.post = post.map(|post_num| PostHeader {
+            post_head: Some(PostHead::Post),
+            post_num: Some(post_num),
+        });
+        version.renormalized()
+    }
+
+    /// The version with another or without a developmental release number
+    pub fn with_dev(&self, dev: Option<u32>) -> Self {
+        let mut version = self.clone();
+        version.dev = dev.map(|dev_num| DevHead {
+            dev_num: Some(dev_num),
+        });
+        version.renormalized()
+    }
+
+    /// The version with another or without a local version label,
+    /// which has to be letters and digits separated by `.`, `-` or `_`
+    pub fn with_local(&self, local: Option<&str>) -> Result<Self> {
+        if let Some(local) = local {
+            validate_local(local)?;
+        }
+        let mut version = self.clone();
+        version.local = local.map(Into::into);
+        Ok(version.renormalized())
+    }
+
+    /// Replaces the `original` string with the normal form
+    pub(crate) fn renormalized(mut self) -> Self {
+        self.set_original(self.to_normalized_string());
+        self
+    }
+
+    /// Keeps the string the version is written as, with the `compact`
+    /// feature only if it isn't the normal form
+    pub(crate) fn set_original(&mut self, original: String) {
+        #[cfg(feature = "compact")]
+        if original == self.to_normalized_string() {
+            self.original = None;
+            return;
+        }
+        self.original = Some(original.into_boxed_str());
+    }
+
+    /// The key the version is sorted by, for sorting many versions
+    ///
+    /// ```
+    ///# use pyver::PackageVersion;
+    /// let mut versions: Vec<PackageVersion> = ["1.0", "1.0rc1", "0.9.post1"]
+    ///     .iter()
+    ///     .map(|v| PackageVersion::new(v).unwrap())
+    ///     .collect();
+    /// versions.sort_by_cached_key(|v| v.cmp_key());
+    ///
+    /// assert_eq!(versions[0].to_string(), "0.9.post1");
+    /// assert_eq!(versions[2].to_string(), "1.0");
+    /// ```
+    pub fn cmp_key(&self) -> VersionKey {
+        VersionKey {
+            max: self.is_max(),
+            epoch: self.epoch(),
+            release: self.release.significant().to_vec(),
+            pre: self.pre_key(),
+            post: self.post_key(),
+            dev: self.dev_key(),
+            local: self.local_segments(),
+        }
+    }
+
+    /// Whether this is the synthetic [`PackageVersion::MAX`],
+    /// parsed versions always have a release segment
+    pub fn is_max(&self) -> bool {
+        self.release.segments.is_empty() && self.epoch == Some(u32::MAX)
+    }
+
+    /// The smallest version without a local segment that sorts after `self`
+    ///
+    /// No version lies between `self` (or any of its local versions) and the
+    /// successor, so `> V` over sorted versions is the same as
+    /// `>= V.smallest_greater_than()` once the local versions of `V` are left out.
+    /// The next dev release is the successor of a dev release,
+    /// otherwise it's the first dev release of the next post-release.
+    ///
+    /// `None` for [`PackageVersion::MAX`] and when a number would overflow.
+    ///
+    /// ```
+    ///# use pyver::PackageVersion;
+    /// let successor = |v: &str| PackageVersion::new(v).unwrap().smallest_greater_than();
+    ///
+    /// assert_eq!(successor("1.2").unwrap().to_string(), "1.2.post0.dev0");
+    /// assert_eq!(successor("1.2rc1.post3").unwrap().to_string(), "1.2rc1.post4.dev0");
+    /// assert_eq!(successor("1.2.dev1+local").unwrap().to_string(), "1.2.dev2");
+    /// ```
+    pub fn smallest_greater_than(&self) -> Option<Self> {
+        if self.is_max() {
+            return None;
+        }
+        let (post, dev) = match self.dev_key() {
+            (false, dev) => (self.post_key(), dev.checked_add(1)?),
+            (true, _) => match self.post_key() {
+                Some(post) => (Some(post.checked_add(1)?), 0),
+                None => (Some(0), 0),
+            },
+        };
+
+        let successor = Self {
+            original: None,
+            local: None,
+            dev: Some(DevHead { dev_num: Some(dev) }),
+            post: post.map(|post| PostHeader {
+                post_head: Some(PostHead::Post),
+                post_num: Some(post),
+            }),
+            pre: self.pre,
+            release: self.release.clone(),
+            epoch: self.epoch,
+        };
+        Some(successor.renormalized())
+    }
+
+    /// A version of only the pre, post and dev segments, to reuse their
+    /// sort keys for borrowed and archived versions
+    pub(crate) fn suffixes(
+        pre: Option<PreHeader>,
+        post: Option<PostHeader>,
+        dev: Option<DevHead>,
+    ) -> Self {
+        PackageVersion {
+            original: None,
+            local: None,
             dev,
-            local,
-        })
+            post,
+            pre,
+            release: ReleaseHeader {
+                segments: SmallVec::new(),
+            },
+            epoch: None,
+        }
+    }
+
+    /// Sort key for the pre-release segment
+    ///
+    /// A version with only a dev segment (`1.0.dev0`) sorts before all
+    /// pre-releases of the same release, a final release sorts after them.
+    /// `pre`/`preview` are the same phase as `rc`.
+    pub(crate) fn pre_key(&self) -> (u8, u8, u32) {
+        match &self.pre {
+            Some(PreHeader::Alpha(n)) => (1, 0, n.unwrap_or(0)),
+            Some(PreHeader::Beta(n)) => (1, 1, n.unwrap_or(0)),
+            Some(PreHeader::ReleaseCandidate(n)) | Some(PreHeader::Preview(n)) => {
+                (1, 2, n.unwrap_or(0))
+            }
+            None if self.post.is_none() && self.dev.is_some() => (0, 0, 0),
+            None => (2, 0, 0),
+        }
+    }
+
+    /// Sort key for the post-release segment (an implicit number is `0`)
+    pub(crate) fn post_key(&self) -> Option<u32> {
+        self.post.as_ref().map(|post| post.post_num.unwrap_or(0))
+    }
+
+    /// Sort key for the dev segment, versions without one sort last
+    pub(crate) fn dev_key(&self) -> (bool, u32) {
+        match &self.dev {
+            Some(dev) => (false, dev.dev_num.unwrap_or(0)),
+            None => (true, 0),
+        }
+    }
+
+    /// Compares only the first `depth` components of the sort key
+    /// (epoch, release, pre, post, dev and local in that order)
+    ///
+    /// Every version sharing those components with `self` compares equal,
+    /// which lets ranges talk about e.g. "all post-releases of 1.0"
+    pub(crate) fn cmp_prefix(&self, other: &Self, depth: usize) -> Ordering {
+        match (self.is_max(), other.is_max()) {
+            (true, true) => return Ordering::Equal,
+            (true, false) => return Ordering::Greater,
+            (false, true) => return Ordering::Less,
+            (false, false) => {}
+        }
+        (0..depth)
+            .map(|component| match component {
+                0 => self.epoch.unwrap_or(0).cmp(&other.epoch.unwrap_or(0)),
+                1 => self.release.cmp(&other.release),
+                2 => self.pre_key().cmp(&other.pre_key()),
+                3 => self.post_key().cmp(&other.post_key()),
+                4 => self.dev_key().cmp(&other.dev_key()),
+                // A version without a local segment sorts before any local version
+                _ => self.local_segments().cmp(&other.local_segments()),
+            })
+            .find(|ordering| ordering.is_ne())
+            .unwrap_or(Ordering::Equal)
+    }
+
+    /// The version in its `PEP-440` normal form (`1.0a1.post2.dev3+abc.4`),
+    /// also written by the alternate `Display` flag (`{:#}`)
+    ///
+    /// ```
+    ///# use pyver::PackageVersion;
+    /// let version = PackageVersion::new("v0!1.0-Preview_2.POST-3.dev+Ubuntu-1").unwrap();
+    /// assert_eq!(version.to_normalized_string(), "1.0rc2.post3.dev0+ubuntu.1");
+    /// assert_eq!(format!("{:#}", version), "1.0rc2.post3.dev0+ubuntu.1");
+    /// assert_eq!(version.to_string(), "v0!1.0-Preview_2.POST-3.dev+Ubuntu-1");
+    /// ```
+    pub fn to_normalized_string(&self) -> String {
+        let mut normalized = String::new();
+        if let Some(epoch) = self.epoch.filter(|epoch| *epoch != 0) {
+            normalized.push_str(&format!("{}!", epoch));
+        }
+        normalized.push_str(&self.release.to_string());
+        if let Some(pre) = &self.pre {
+            normalized.push_str(&pre.to_string());
+        }
+        if let Some(post) = &self.post {
+            normalized.push_str(&post.to_string());
+        }
+        if let Some(dev) = &self.dev {
+            normalized.push_str(&dev.to_string());
+        }
+        if let Some(local) = self.normalized_local() {
+            normalized.push('+');
+            normalized.push_str(&local);
+        }
+        normalized
+    }
+
+    /// The local version label in normal form, lowercase with `.` between
+    /// its segments and numbers without leading zeros (`ubuntu.1` for
+    /// `Ubuntu-01`)
+    pub(crate) fn normalized_local(&self) -> Option<String> {
+        let local = self.local.as_ref()?.to_ascii_lowercase();
+        let segments: Vec<&str> = local
+            .split(['.', '-', '_'])
+            .map(|segment| local_number(segment).unwrap_or(segment))
+            .collect();
+        Some(segments.join("."))
+    }
+
+    /// Whether the `original` string is already in the normal form,
+    /// which is what upload checks expect
+    ///
+    /// ```
+    ///# use pyver::PackageVersion;
+    /// assert!(PackageVersion::new("1.0rc1.post2").unwrap().is_canonical());
+    /// assert!(!PackageVersion::new("v1.0-RC1-2").unwrap().is_canonical());
+    /// ```
+    pub fn is_canonical(&self) -> bool {
+        match &self.original {
+            Some(original) => **original == self.to_normalized_string(),
+            // Only dropped if it was the normal form
+            None => cfg!(feature = "compact"),
+        }
+    }
+
+    /// The same version with its normal form as the `original` string
+    ///
+    /// ```
+    ///# use pyver::PackageVersion;
+    /// let version = PackageVersion::new("v1.0-RC1-2").unwrap().canonicalize();
+    /// assert_eq!(version.to_string(), "1.0rc1.post2");
+    /// assert!(version.is_canonical());
+    /// ```
+    pub fn canonicalize(&self) -> Self {
+        self.clone().renormalized()
+    }
+
+    /// Local version segments split on `.`, `-` and `_`
+    pub(crate) fn local_segments(&self) -> Vec<LocalSegment> {
+        match &self.local {
+            Some(local) => local
+                .split(['.', '-', '_'])
+                .map(LocalSegment::new)
+                .collect(),
+            None => Vec::new(),
+        }
+    }
+}
+
+/// Local version labels are letters and digits separated by `.`, `-` or `_`
+fn validate_local(local: &str) -> Result<()> {
+    let valid = local.split(['.', '-', '_']).all(|segment| {
+        !segment.is_empty() && segment.chars().all(|c| c.is_ascii_alphanumeric())
+    });
+    if !valid {
+        anyhow::bail!("Invalid local version label {}", local);
+    }
+    Ok(())
+}
+
+/// # Package version builder
+/// Sets the components of a [`PackageVersion`] one by one,
+/// [`PackageVersionBuilder::build`] checks them and writes the
+/// version in normal form
+#[derive(Clone, Debug, Default)]
+pub struct PackageVersionBuilder {
+    epoch: Option<u32>,
+    release: Vec<u64>,
+    pre: Option<PreHeader>,
+    post: Option<u32>,
+    dev: Option<u32>,
+    local: Option<String>,
+}
+
+impl PackageVersionBuilder {
+    pub fn epoch(mut self, epoch: u32) -> Self {
+        self.epoch = Some(epoch);
+        self
+    }
+
+    /// The release segments, at least one is needed
+    pub fn release(mut self, segments: impl Into<Vec<u64>>) -> Self {
+        self.release = segments.into();
+        self
+    }
+
+    pub fn pre(mut self, pre: PreHeader) -> Self {
+        self.pre = Some(pre);
+        self
+    }
+
+    pub fn post(mut self, post: u32) -> Self {
+        self.post = Some(post);
+        self
+    }
+
+    pub fn dev(mut self, dev: u32) -> Self {
+        self.dev = Some(dev);
+        self
+    }
+
+    /// The local version label without the `+`
+    pub fn local(mut self, local: &str) -> Self {
+        self.local = Some(local.to_string());
+        self
+    }
+
+    /// Fails without release segments or with an invalid local version label
+    pub fn build(self) -> Result<PackageVersion> {
+        PackageVersion::from_parts(
+            self.epoch.unwrap_or(0),
+            self.release,
+            self.pre,
+            self.post,
+            self.dev,
+            self.local.as_deref(),
+        )
+    }
+}
+
+/// # Version sort key
+/// Everything `PEP-440` compares a version by, computed once by
+/// [`PackageVersion::cmp_key`]
+///
+/// Keys order, compare and hash exactly like their versions
+/// but don't need to look at optional segments again.
+#[derive(Hash, Clone, Debug, Ord, Eq, PartialEq, PartialOrd)]
+pub struct VersionKey {
+    max: bool,
+    epoch: u32,
+    release: Vec<u64>,
+    pre: (u8, u8, u32),
+    post: Option<u32>,
+    dev: (bool, u32),
+    local: Vec<LocalSegment>,
+}
+
+/// Number of components in the sort key, see [`PackageVersion::cmp_prefix`]
+pub(crate) const SORT_KEY_LEN: usize = 6;
+
+/// A single segment of a local version, numbers sort after strings
+#[derive(Hash, Clone, Debug, Eq, PartialEq)]
+pub(crate) enum LocalSegment {
+    /// Lowercase letters and digits
+    String(String),
+    /// The digits without leading zeros, of any length
+    Number(String),
+}
+
+impl LocalSegment {
+    fn new(segment: &str) -> Self {
+        match local_number(segment) {
+            Some(digits) => Self::Number(digits.to_string()),
+            None => Self::String(segment.to_ascii_lowercase()),
+        }
+    }
+}
+
+impl PartialOrd for LocalSegment {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for LocalSegment {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self, other) {
+            (Self::String(a), Self::String(b)) => a.cmp(b),
+            (Self::String(_), Self::Number(_)) => Ordering::Less,
+            (Self::Number(_), Self::String(_)) => Ordering::Greater,
+            (Self::Number(a), Self::Number(b)) => cmp_local_numbers(a, b),
+        }
+    }
+}
+
+/// The digits of a local segment that is a number without its leading
+/// zeros (`0` for `000`), `None` if the segment isn't only ASCII digits
+pub(crate) fn local_number(segment: &str) -> Option<&str> {
+    if segment.is_empty() || !segment.bytes().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    Some(match segment.trim_start_matches('0') {
+        "" => "0",
+        digits => digits,
+    })
+}
+
+/// Compares local labels segment by segment like `LocalSegment`,
+/// numbers after strings and strings ignoring case, without allocating
+pub(crate) fn cmp_local(a: Option<&str>, b: Option<&str>) -> Ordering {
+    let mut a = a.into_iter().flat_map(|local| local.split(['.', '-', '_']));
+    let mut b = b.into_iter().flat_map(|local| local.split(['.', '-', '_']));
+    loop {
+        let ordering = match (a.next(), b.next()) {
+            (None, None) => return Ordering::Equal,
+            (None, Some(_)) => return Ordering::Less,
+            (Some(_), None) => return Ordering::Greater,
+            (Some(a), Some(b)) => cmp_local_segment(a, b),
+        };
+        if ordering.is_ne() {
+            return ordering;
+        }
     }
 }
 
+fn cmp_local_segment(a: &str, b: &str) -> Ordering {
+    match (local_number(a), local_number(b)) {
+        (Some(a), Some(b)) => cmp_local_numbers(a, b),
+        (Some(_), None) => Ordering::Greater,
+        (None, Some(_)) => Ordering::Less,
+        (None, None) => a
+            .bytes()
+            .map(|c| c.to_ascii_lowercase())
+            .cmp(b.bytes().map(|c| c.to_ascii_lowercase())),
+    }
+}
+
+/// Compares numbers from [`local_number`], which can be too large for any
+/// integer type, without leading zeros the longer one is larger
+fn cmp_local_numbers(a: &str, b: &str) -> Ordering {
+    (a.len(), a).cmp(&(b.len(), b))
+}
+
+/// Writes the `original` string, or the normal form with the
+/// alternate flag (`{:#}`)
 impl fmt::Display for PackageVersion {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "{}", self.original)
+        if self.is_max() {
+            write!(f, "MAX")
+        } else {
+            match &self.original {
+                Some(original) if !f.alternate() => write!(f, "{}", original),
+                _ => write!(f, "{}", self.to_normalized_string()),
+            }
+        }
+    }
+}
+
+impl FromStr for PackageVersion {
+    type Err = anyhow::Error;
+
+    fn from_str(version: &str) -> Result<Self> {
+        Self::new(version)
+    }
+}
+
+impl TryFrom<&str> for PackageVersion {
+    type Error = anyhow::Error;
+
+    fn try_from(version: &str) -> Result<Self> {
+        Self::new(version)
+    }
+}
+
+/// Takes over the string as the `original`, shrinking it to its length
+impl TryFrom<String> for PackageVersion {
+    type Error = anyhow::Error;
+
+    fn try_from(version: String) -> Result<Self> {
+        Self::parse(version)
+    }
+}
+
+/// The normal form, see [`PackageVersion::to_normalized_string`]
+impl From<PackageVersion> for String {
+    fn from(version: PackageVersion) -> Self {
+        version.to_normalized_string()
     }
 }
 
 impl PartialEq<Self> for PackageVersion {
     fn eq(&self, other: &Self) -> bool {
-        self.release == other.release
-            && self.local == other.local
-            && self.dev == other.dev
-            && self.epoch == other.epoch
-            && self.post == other.post
-            && self.pre == other.pre
+        self.cmp(other) == Ordering::Equal
     }
 }
 
 impl Eq for PackageVersion {}
 
-/// The hash of the `PackageVersion` is calculated only from the `original` field.
+/// Parses the string to compare with, an invalid version is never equal
+///
+/// ```
+///# use pyver::PackageVersion;
+/// let version = PackageVersion::new("1.0-1").unwrap();
+///
+/// assert!(version == "1.0.post1");
+/// assert!(version >= "1.0" && version < "2.0");
+/// assert!(version != "not a version");
+/// assert_eq!(version.partial_cmp("not a version"), None);
+/// ```
+impl PartialEq<str> for PackageVersion {
+    fn eq(&self, other: &str) -> bool {
+        self.partial_cmp(other) == Some(Ordering::Equal)
+    }
+}
+
+impl PartialEq<&str> for PackageVersion {
+    fn eq(&self, other: &&str) -> bool {
+        self == *other
+    }
+}
+
+/// Parses the string to compare with, `None` for an invalid version
+impl PartialOrd<str> for PackageVersion {
+    fn partial_cmp(&self, other: &str) -> Option<Ordering> {
+        let other = Self::new(other).ok()?;
+        Some(self.cmp(&other))
+    }
+}
+
+impl PartialOrd<&str> for PackageVersion {
+    fn partial_cmp(&self, other: &&str) -> Option<Ordering> {
+        self.partial_cmp(*other)
+    }
+}
+
+impl PartialOrd<Self> for PackageVersion {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for PackageVersion {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.cmp_prefix(other, SORT_KEY_LEN)
+    }
+}
+
+/// The hash of the `PackageVersion` is calculated from the same
+/// components used for comparisons, so equal versions hash the same.
 impl Hash for PackageVersion {
     fn hash<H: Hasher>(&self, state: &mut H) {
+        self.epoch.unwrap_or(0).hash(state);
         self.release.hash(state);
-        self.local.hash(state);
-        self.dev.hash(state);
-        self.epoch.hash(state);
-        self.post.hash(state);
-        self.pre.hash(state);
+        self.pre_key().hash(state);
+        self.post_key().hash(state);
+        self.dev_key().hash(state);
+        self.local_segments().hash(state);
     }
 }
 
 #[cfg(test)]
 mod tests {
+    use crate::ids::PreHeader;
     use crate::PackageVersion;
     use anyhow::Result;
     use std::collections::hash_map::DefaultHasher;
-    use std::collections::HashMap;
+    use std::collections::{BTreeMap, HashMap};
     use std::hash::{Hash, Hasher};
 
     fn default_hash<T: Hash>(value: &T) -> u64 {
@@ -238,6 +1050,98 @@ mod tests {
         assert_eq!(PackageVersion::new("1.0r")?, PackageVersion::new("1.0rev")?);
         assert_eq!(PackageVersion::new("1.0c")?, PackageVersion::new("1.0rc")?);
         assert_eq!(PackageVersion::new("v1.0")?, PackageVersion::new("1.0")?);
+        assert_eq!(
+            PackageVersion::new("V1.0RC1.POST2+Local")?,
+            PackageVersion::new("1.0rc1.post2+local")?
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_pep440_full_ordering() -> Result<()> {
+        // Sorted example from the "Summary of permitted suffixes and relative
+        // ordering" section of pep-440, extended with epochs and local versions
+        let versions = vec![
+            "1.0.dev456",
+            "1.0a1",
+            "1.0a2.dev456",
+            "1.0a12.dev456",
+            "1.0a12",
+            "1.0b1.dev456",
+            "1.0b2",
+            "1.0b2.post345.dev456",
+            "1.0b2.post345",
+            "1.0rc1.dev456",
+            "1.0rc1",
+            "1.0",
+            "1.0+abc.5",
+            "1.0+abc.7",
+            "1.0+5",
+            "1.0+18446744073709551616",
+            "1.0+99999999999999999999",
+            "1.0.post456.dev34",
+            "1.0.post456",
+            "1.0.15",
+            "1.1.dev1",
+            "2013.10",
+            "1!0.1",
+        ];
+
+        for pair in versions.windows(2) {
+            assert!(
+                PackageVersion::new(pair[0])? < PackageVersion::new(pair[1])?,
+                "{} < {}",
+                pair[0],
+                pair[1]
+            );
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_pep440_normalized_equality() -> Result<()> {
+        assert_eq!(PackageVersion::new("1.0")?, PackageVersion::new("1.0.0")?);
+        assert_eq!(PackageVersion::new("1.0")?, PackageVersion::new("0!1.0")?);
+        assert_eq!(
+            PackageVersion::new("1.0-1")?,
+            PackageVersion::new("1.0.post1")?
+        );
+        assert_eq!(
+            PackageVersion::new("1.0pre1")?,
+            PackageVersion::new("1.0rc1")?
+        );
+        assert_eq!(
+            PackageVersion::new("1.0+abc-5")?,
+            PackageVersion::new("1.0+abc.5")?
+        );
+        assert_ne!(PackageVersion::new("1.0")?, PackageVersion::new("1.0+0")?);
+        assert_eq!(
+            PackageVersion::new("1.0+05")?,
+            PackageVersion::new("1.0+5")?
+        );
+        assert_eq!(
+            PackageVersion::new("1.0+099999999999999999999")?,
+            PackageVersion::new("1.0+99999999999999999999")?
+        );
+        assert_eq!(
+            default_hash(&PackageVersion::new("1.0+099999999999999999999")?),
+            default_hash(&PackageVersion::new("1.0+99999999999999999999")?)
+        );
+
+        // Equal versions have the same normal form
+        let normalized = [
+            ("1.0+05", "1.0+5"),
+            ("1.0+00", "1.0+0"),
+            ("1.0+Ubuntu-010_a0", "1.0+ubuntu.10.a0"),
+        ];
+        for (version, expected) in normalized {
+            let version = PackageVersion::new(version)?;
+            assert_eq!(version.to_normalized_string(), expected, "{}", version);
+        }
+        assert_eq!(
+            default_hash(&PackageVersion::new("1.0.0")?),
+            default_hash(&PackageVersion::new("1.0")?)
+        );
         Ok(())
     }
 
@@ -269,6 +1173,8 @@ mod tests {
             "1.0+abc.5",
             "1.0+abc.7",
             "1.0+5",
+            "1.0+18446744073709551616",
+            "1.0+99999999999999999999",
             "1.0.post456.dev34",
             "1.0.post456",
             "1.0.15",
@@ -285,7 +1191,7 @@ mod tests {
 
     #[test]
     fn test_pep440_negative() {
-        let versions = vec!["not a version"];
+        let versions = vec!["not a version", "1.0 junk", "junk1.0", "1.0.", "1.0+"];
 
         for version in versions {
             match PackageVersion::new(version) {
@@ -322,6 +1228,8 @@ mod tests {
             "1.0+abc.5",
             "1.0+abc.7",
             "1.0+5",
+            "1.0+18446744073709551616",
+            "1.0+99999999999999999999",
             "1.0.post456.dev34",
             "1.0.post456",
             "1.0.15",
@@ -364,4 +1272,310 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn test_smallest_greater_than() -> Result<()> {
+        let successors = vec![
+            ("1.0.dev0", "1.0.dev1"),
+            ("1.0a1.dev0", "1.0a1.dev1"),
+            ("1.0a1", "1.0a1.post0.dev0"),
+            ("1.0a1.post0", "1.0a1.post1.dev0"),
+            ("1.0", "1.0.post0.dev0"),
+            ("1.0+local", "1.0.post0.dev0"),
+            ("1.0.post0.dev1", "1.0.post0.dev2"),
+            ("1!1.0.post-7", "1!1.0.post8.dev0"),
+        ];
+        for (version, expected) in successors {
+            let version = PackageVersion::new(version)?;
+            let successor = version.smallest_greater_than().unwrap();
+            assert!(version < successor);
+            assert_eq!(successor.to_string(), expected);
+        }
+        assert!(PackageVersion::new("1.0.dev4294967295")?
+            .smallest_greater_than()
+            .is_none());
+        assert!(PackageVersion::MAX.smallest_greater_than().is_none());
+        Ok(())
+    }
+
+    #[test]
+    fn test_conversions() -> Result<()> {
+        let parsed: PackageVersion = "v1.0rc1".parse()?;
+        let from_str = PackageVersion::try_from("v1.0rc1")?;
+        let from_string = PackageVersion::try_from(String::from("v1.0rc1"))?;
+        assert_eq!(parsed, from_str);
+        assert_eq!(from_str, from_string);
+        assert_eq!(from_string.to_string(), "v1.0rc1");
+        assert!(PackageVersion::try_from(String::from("1.0 junk")).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_canonical() -> Result<()> {
+        let canonical =
+            vec!["1.0", "1!2.0.1a3.post4.dev5+abc.6", "0.1rc1", "2.0.post1"];
+        for version in canonical {
+            assert!(PackageVersion::new(version)?.is_canonical(), "{}", version);
+        }
+
+        let not_canonical = vec![
+            "v1.0",
+            "0!1.0",
+            "1.0-1",
+            "1.0alpha1",
+            "1.0.dev",
+            "1.0+A_b",
+            "1.0+05",
+            "1.0+00",
+            " 1.0",
+        ];
+        for version in not_canonical {
+            let version = PackageVersion::new(version)?;
+            assert!(!version.is_canonical(), "{}", version);
+            assert!(version.canonicalize().is_canonical(), "{}", version);
+            assert_eq!(version.canonicalize(), version);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_predicates() -> Result<()> {
+        // (version, pre, post, dev, local)
+        let versions = vec![
+            ("1.0", false, false, false, false),
+            ("1.0a1", true, false, false, false),
+            ("1.0.dev1", true, false, true, false),
+            ("1.0rc1.post1", true, true, false, false),
+            ("1.0.post1", false, true, false, false),
+            ("1.0+local", false, false, false, true),
+        ];
+        for (version, pre, post, dev, local) in versions {
+            let version = PackageVersion::new(version)?;
+            assert_eq!(version.is_prerelease(), pre, "{}", version);
+            assert_eq!(version.is_postrelease(), post, "{}", version);
+            assert_eq!(version.is_devrelease(), dev, "{}", version);
+            assert_eq!(version.is_local(), local, "{}", version);
+            assert_eq!(version.is_stable(), !pre, "{}", version);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_base_version() -> Result<()> {
+        let versions = vec![
+            ("1.2.3", "1.2.3"),
+            ("v1.2rc1", "1.2"),
+            ("0!1.0.post1.dev2", "1.0"),
+            ("2!3.0a1+local", "2!3.0"),
+        ];
+        for (version, base) in versions {
+            let version = PackageVersion::new(version)?;
+            assert_eq!(version.base_version().to_string(), base);
+            assert_eq!(version.base_version(), PackageVersion::new(base)?);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_without_local() -> Result<()> {
+        let version = PackageVersion::new("1.0rc1-1+Local_Build")?;
+        assert_eq!(
+            version.without_local(),
+            PackageVersion::new("1.0rc1.post1")?
+        );
+        assert_eq!(version.without_local().to_string(), "1.0rc1-1");
+        assert_eq!(version.public(), "1.0rc1.post1");
+        assert!(version.is_local());
+        assert_eq!(PackageVersion::new("2.0")?.public(), "2.0");
+        Ok(())
+    }
+
+    #[test]
+    fn test_with_setters() -> Result<()> {
+        let version = PackageVersion::new("1!2.0b1.post3.dev4+abc")?;
+        assert_eq!(version.with_epoch(0).to_string(), "2.0b1.post3.dev4+abc");
+        assert_eq!(version.with_pre(None).to_string(), "1!2.0.post3.dev4+abc");
+        assert_eq!(
+            version.with_post(Some(0)).to_string(),
+            "1!2.0b1.post0.dev4+abc"
+        );
+        assert_eq!(version.with_post(None).to_string(), "1!2.0b1.dev4+abc");
+        assert_eq!(version.with_dev(None).to_string(), "1!2.0b1.post3+abc");
+        assert_eq!(version.with_local(None)?.to_string(), "1!2.0b1.post3.dev4");
+        assert_eq!(
+            version.with_release(&[3, 0, 1])?,
+            PackageVersion::new("1!3.0.1b1.post3.dev4+abc")?
+        );
+
+        assert!(version.with_release(&[]).is_err());
+        let locals = vec!["", "a..b", "a+b", "-a", "ä"];
+        for local in locals {
+            match version.with_local(Some(local)) {
+                Ok(v) => panic!("Oh no {}", v),
+                Err(_e) => continue,
+            }
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_builder() -> Result<()> {
+        let version = PackageVersion::builder()
+            .epoch(2)
+            .release(vec![1, 0])
+            .pre(PreHeader::Alpha(None))
+            .post(3)
+            .dev(4)
+            .local("Build-5")
+            .build()?;
+        assert_eq!(version.to_string(), "2!1.0a0.post3.dev4+build.5");
+        assert_eq!(version, PackageVersion::new(&version.to_string())?);
+
+        assert!(PackageVersion::builder().build().is_err());
+        assert!(PackageVersion::builder()
+            .release([1])
+            .local("a+b")
+            .build()
+            .is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_from_parts() -> Result<()> {
+        let pre = Some(PreHeader::ReleaseCandidate(Some(1)));
+        let version =
+            PackageVersion::from_parts(1, vec![2, 0], pre, Some(0), None, Some("x"))?;
+        assert_eq!(version.to_string(), "1!2.0rc1.post0+x");
+        assert_eq!(version, PackageVersion::new("1!2rc1-0+X")?);
+
+        assert!(PackageVersion::from_parts(0, vec![], None, None, None, None).is_err());
+        assert!(
+            PackageVersion::from_parts(0, vec![1], None, None, None, Some("")).is_err()
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_compare_with_str() -> Result<()> {
+        let version = PackageVersion::new("2.0rc1")?;
+        assert!(version == "2.0c1");
+        assert!(version > "2.0b5");
+        assert!(version < "2.0");
+        assert!(version != "2.0");
+        assert!(version != "2.0 junk");
+        assert_eq!(version.partial_cmp("junk"), None);
+        Ok(())
+    }
+
+    #[test]
+    fn test_cmp_key() -> Result<()> {
+        let versions = vec![
+            "1.0.dev456",
+            "1.0a1",
+            "1.0a12.dev456",
+            "1.0b2.post345.dev456",
+            "1.0rc1",
+            "1.0",
+            "1.0.0",
+            "1.0+abc.5",
+            "1.0+5",
+            "1.0.post456",
+            "1.0.15",
+            "1!0.1",
+        ];
+        let mut all: Vec<PackageVersion> = versions
+            .iter()
+            .map(|v| PackageVersion::new(v))
+            .collect::<Result<_>>()?;
+        all.push(PackageVersion::MIN);
+        all.push(PackageVersion::MAX);
+
+        for a in &all {
+            for b in &all {
+                assert_eq!(a.cmp_key().cmp(&b.cmp_key()), a.cmp(b), "{} {}", a, b);
+                if a == b {
+                    assert_eq!(default_hash(&a.cmp_key()), default_hash(&b.cmp_key()));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_min_and_max() -> Result<()> {
+        let versions =
+            vec!["0.dev0", "0a0.dev0", "0", "1.0", "1!0.1", "4294967295!1.0"];
+        for version in versions {
+            let version = PackageVersion::new(version)?;
+            assert!(PackageVersion::MIN <= version, "{}", version);
+            assert!(version < PackageVersion::MAX, "{}", version);
+            assert!(!version.is_max());
+        }
+        assert_eq!(PackageVersion::MIN.to_string(), "0.dev0");
+        assert!(PackageVersion::MAX.is_max());
+        assert_eq!(PackageVersion::MAX, PackageVersion::MAX);
+        assert_eq!(
+            default_hash(&PackageVersion::MIN),
+            default_hash(&PackageVersion::new("0.dev0")?)
+        );
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(target_pointer_width = "64")]
+    fn test_size() {
+        assert_eq!(std::mem::size_of::<PackageVersion>(), 104);
+    }
+
+    #[test]
+    fn test_original_storage() -> Result<()> {
+        // (version, whether it's kept with the `compact` feature)
+        let versions = vec![("1.0", false), ("v1.0", true), ("1.0+local", false)];
+        for (version, kept) in versions {
+            let parsed = PackageVersion::new(version)?;
+            assert_eq!(parsed.to_string(), version);
+            assert_eq!(
+                parsed.original.is_some(),
+                kept || !cfg!(feature = "compact"),
+                "{}",
+                version
+            );
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_serde() -> Result<()> {
+        let version = PackageVersion::new("v1.0-RC1.post2")?;
+        let json = serde_json::to_string(&version)?;
+        assert_eq!(json, r#""1.0rc1.post2""#);
+        assert_eq!(serde_json::from_str::<PackageVersion>(&json)?, version);
+
+        let versions: Vec<PackageVersion> =
+            serde_json::from_str(r#"["1.0", "2!3.0.dev1"]"#)?;
+        assert_eq!(versions[1].epoch(), 2);
+        assert!(serde_json::from_str::<PackageVersion>(r#""1.0 junk""#).is_err());
+        assert!(serde_json::from_str::<PackageVersion>(r#"{"epoch": 1}"#).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_serde_map_key() -> Result<()> {
+        let mut yanked = HashMap::new();
+        yanked.insert(PackageVersion::new("v2.0-RC1")?, true);
+        let json = serde_json::to_string(&yanked)?;
+        assert_eq!(json, r#"{"2.0rc1":true}"#);
+        assert_eq!(serde_json::from_str::<HashMap<_, _>>(&json)?, yanked);
+
+        let json = r#"{"1.0": "old", "1.10": "new", "1.9": "older", "1.0.0": "same"}"#;
+        let notes: BTreeMap<PackageVersion, String> = serde_json::from_str(json)?;
+        let keys: Vec<String> = notes.keys().map(|v| v.to_string()).collect();
+        assert_eq!(keys, ["1.0", "1.9", "1.10"]);
+        assert_eq!(notes[&PackageVersion::new("1.0")?], "same");
+        assert!(serde_json::from_str::<BTreeMap<PackageVersion, bool>>(
+            r#"{"x": true}"#
+        )
+        .is_err());
+        Ok(())
+    }
 }
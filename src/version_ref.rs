@@ -0,0 +1,370 @@
+use crate::ids::{DevHead, PostHeader, PreHeader, ReleaseHeader};
+use crate::parser::VersionParts;
+use crate::version::{cmp_local, local_number};
+use crate::PackageVersion;
+use alloc::string::ToString;
+use anyhow::Result;
+use core::cmp::Ordering;
+use core::fmt;
+use core::hash::{Hash, Hasher};
+
+/// # Borrowed version
+/// A `PEP-440` version parsed without allocating, its release and local
+/// label are slices of the string it was parsed from
+///
+/// Meant for scanning many versions while keeping only a few of them,
+/// which can be turned into a [`PackageVersion`] with
+/// [`PackageVersionRef::to_version`].
+///
+/// ## Example Usage
+/// ```
+/// use pyver::{PackageVersion, PackageVersionRef};
+///
+/// let versions = ["1.0", "2.0rc1", "v2.0.post1+local", "1.5"];
+/// let latest = versions
+///     .iter()
+///     .map(|v| PackageVersionRef::new(v).unwrap())
+///     .filter(|v| v.is_stable())
+///     .max_by_key(|v| v.release_segments().next())
+///     .unwrap();
+///
+/// assert_eq!(latest.local(), Some("local"));
+/// assert_eq!(
+///     latest.to_version(),
+///     PackageVersion::new("2.0.post1+local").unwrap()
+/// );
+/// ```
+///
+/// Borrowed versions compare and hash like owned ones, so `1.0` equals
+/// `v1.0.0`.
+#[derive(Clone, Copy, Debug)]
+pub struct PackageVersionRef<'a> {
+    original: &'a str,
+    epoch: Option<u32>,
+    /// The release as written, every segment fits into a `u64`
+    release: &'a str,
+    pre: Option<PreHeader>,
+    post: Option<PostHeader>,
+    dev: Option<DevHead>,
+    local: Option<&'a str>,
+}
+
+impl<'a> PackageVersionRef<'a> {
+    pub fn new(version: &'a str) -> Result<Self> {
+        let parts = VersionParts::new(version)?;
+        parts.check_release()?;
+
+        Ok(Self {
+            original: version,
+            epoch: parts.epoch_number()?,
+            release: parts.release,
+            pre: parts.pre_header()?,
+            post: parts.post_header()?,
+            dev: parts.dev_head()?,
+            local: parts.local,
+        })
+    }
+
+    /// The string the version was parsed from
+    pub fn original_str(&self) -> &'a str {
+        self.original
+    }
+
+    /// The epoch, `0` if the version has none
+    pub fn epoch(&self) -> u32 {
+        self.epoch.unwrap_or(0)
+    }
+
+    /// The release segments (`1`, `2` and `3` for `1.2.3`)
+    ///
+    /// ```
+    ///# use pyver::PackageVersionRef;
+    /// let version = PackageVersionRef::new("1!01.2.30rc1").unwrap();
+    ///
+    /// assert_eq!(version.release_segments().collect::<Vec<_>>(), vec![1, 2, 30]);
+    /// assert_eq!(version.epoch(), 1);
+    /// ```
+    pub fn release_segments(&self) -> impl Iterator<Item = u64> + 'a {
+        // Safe to unwrap since the segments were checked when parsing
+        self.release
+            .split('.')
+            .map(|segment| segment.parse::<u64>().unwrap())
+    }
+
+    /// The pre-release segment (`rc1`)
+    pub fn pre(&self) -> Option<&PreHeader> {
+        self.pre.as_ref()
+    }
+
+    /// The post-release segment (`.post2`)
+    pub fn post(&self) -> Option<&PostHeader> {
+        self.post.as_ref()
+    }
+
+    /// The developmental release segment (`.dev3`)
+    pub fn dev(&self) -> Option<&DevHead> {
+        self.dev.as_ref()
+    }
+
+    /// The local version label as written, without the `+`
+    pub fn local(&self) -> Option<&'a str> {
+        self.local
+    }
+
+    /// Whether this is a pre-release, which includes developmental releases
+    pub fn is_prerelease(&self) -> bool {
+        self.pre.is_some() || self.dev.is_some()
+    }
+
+    /// Whether this is a post-release (`1.0.post1`)
+    pub fn is_postrelease(&self) -> bool {
+        self.post.is_some()
+    }
+
+    /// Whether this is a developmental release (`1.0.dev0`)
+    pub fn is_devrelease(&self) -> bool {
+        self.dev.is_some()
+    }
+
+    /// Whether the version has a local version label (`1.0+ubuntu.1`)
+    pub fn is_local(&self) -> bool {
+        self.local.is_some()
+    }
+
+    /// Whether this is neither a pre-release nor a developmental release
+    pub fn is_stable(&self) -> bool {
+        !self.is_prerelease()
+    }
+
+    /// The owned version, copying the strings
+    pub fn to_version(&self) -> PackageVersion {
+        let mut version = PackageVersion {
+            original: None,
+            local: self.local.map(Into::into),
+            dev: self.dev,
+            post: self.post,
+            pre: self.pre,
+            release: ReleaseHeader {
+                segments: self.release_segments().collect(),
+            },
+            epoch: self.epoch,
+        };
+        version.set_original(self.original.to_string());
+        version
+    }
+}
+
+impl PartialEq for PackageVersionRef<'_> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other).is_eq()
+    }
+}
+
+impl Eq for PackageVersionRef<'_> {}
+
+impl PartialOrd for PackageVersionRef<'_> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// The same order as [`PackageVersion::cmp`], without allocating
+impl Ord for PackageVersionRef<'_> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        // Trailing zeros are insignificant, so the shorter release is padded
+        let mut a = self.release_segments();
+        let mut b = other.release_segments();
+        let release = loop {
+            let ordering = match (a.next(), b.next()) {
+                (None, None) => break Ordering::Equal,
+                (a, b) => a.unwrap_or(0).cmp(&b.unwrap_or(0)),
+            };
+            if ordering.is_ne() {
+                break ordering;
+            }
+        };
+
+        let suffixes = PackageVersion::suffixes(self.pre, self.post, self.dev);
+        let other_suffixes = PackageVersion::suffixes(other.pre, other.post, other.dev);
+        self.epoch()
+            .cmp(&other.epoch())
+            .then(release)
+            .then_with(|| suffixes.pre_key().cmp(&other_suffixes.pre_key()))
+            .then_with(|| suffixes.post_key().cmp(&other_suffixes.post_key()))
+            .then_with(|| suffixes.dev_key().cmp(&other_suffixes.dev_key()))
+            .then_with(|| cmp_local(self.local, other.local))
+    }
+}
+
+/// Hashes the components versions are compared by, so equal versions
+/// hash the same
+impl Hash for PackageVersionRef<'_> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.epoch().hash(state);
+        let significant = self
+            .release_segments()
+            .enumerate()
+            .filter(|(_, segment)| *segment != 0)
+            .last()
+            .map_or(0, |(index, _)| index + 1);
+        for segment in self.release_segments().take(significant) {
+            segment.hash(state);
+        }
+
+        let suffixes = PackageVersion::suffixes(self.pre, self.post, self.dev);
+        suffixes.pre_key().hash(state);
+        suffixes.post_key().hash(state);
+        suffixes.dev_key().hash(state);
+        for segment in self
+            .local
+            .into_iter()
+            .flat_map(|local| local.split(['.', '-', '_']))
+        {
+            match local_number(segment) {
+                Some(digits) => digits.hash(state),
+                None => segment
+                    .bytes()
+                    .for_each(|c| c.to_ascii_lowercase().hash(state)),
+            }
+            // Ends the segment, so `1.0+ab.c` and `1.0+a.bc` differ
+            0xffu8.hash(state);
+        }
+    }
+}
+
+impl<'a> TryFrom<&'a str> for PackageVersionRef<'a> {
+    type Error = anyhow::Error;
+
+    fn try_from(version: &'a str) -> Result<Self> {
+        Self::new(version)
+    }
+}
+
+impl From<PackageVersionRef<'_>> for PackageVersion {
+    fn from(version: PackageVersionRef<'_>) -> Self {
+        version.to_version()
+    }
+}
+
+/// Writes the `original` string
+impl fmt::Display for PackageVersionRef<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.original)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::PackageVersionRef;
+    use crate::PackageVersion;
+    use anyhow::Result;
+    use std::collections::hash_map::DefaultHasher;
+    use std::hash::{Hash, Hasher};
+
+    fn default_hash<T: Hash>(value: &T) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        value.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    #[test]
+    fn test_version_ref_matches_owned() -> Result<()> {
+        let versions = vec![
+            "1.0",
+            " v1!2.0RC1.post2.dev3+Local.7 ",
+            "1.0-1",
+            "1.0a",
+            "1.0.post.dev",
+            "20231004123456.1",
+            "1.0+ubuntu-1",
+        ];
+
+        for version in versions {
+            let borrowed = PackageVersionRef::new(version)?;
+            let owned = PackageVersion::new(version)?;
+            assert_eq!(borrowed.original_str(), version);
+            assert_eq!(borrowed.epoch(), owned.epoch());
+            assert_eq!(
+                borrowed.release_segments().collect::<Vec<_>>(),
+                owned.release().segments()
+            );
+            assert_eq!(borrowed.pre(), owned.pre());
+            assert_eq!(borrowed.post(), owned.post());
+            assert_eq!(borrowed.dev(), owned.dev());
+            assert_eq!(borrowed.local(), owned.local());
+            assert_eq!(borrowed.is_stable(), owned.is_stable());
+
+            let converted = borrowed.to_version();
+            assert_eq!(converted, owned);
+            assert_eq!(converted.to_string(), owned.to_string());
+            assert_eq!(converted.local(), owned.local());
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_version_ref_ordering() -> Result<()> {
+        assert_eq!(
+            PackageVersionRef::new("1.0")?,
+            PackageVersionRef::new("1.0.0")?
+        );
+        assert_eq!(
+            default_hash(&PackageVersionRef::new("1.0")?),
+            default_hash(&PackageVersionRef::new("v1.0.0")?)
+        );
+
+        let versions = vec![
+            "1.0.dev0",
+            "1.0a1",
+            "1.0RC1",
+            "1.0",
+            "v1.0.0",
+            "1.0+abc.5",
+            "1.0+ABC-05",
+            "1.0+abc.x",
+            "1.0+99999999999999999999",
+            "1.0.post1",
+            "1.0.1",
+            "1!0.1",
+        ];
+        for a in &versions {
+            for b in &versions {
+                let (borrowed_a, borrowed_b) =
+                    (PackageVersionRef::new(a)?, PackageVersionRef::new(b)?);
+                let (owned_a, owned_b) =
+                    (PackageVersion::new(a)?, PackageVersion::new(b)?);
+                assert_eq!(
+                    borrowed_a.cmp(&borrowed_b),
+                    owned_a.cmp(&owned_b),
+                    "{} {}",
+                    a,
+                    b
+                );
+                if borrowed_a == borrowed_b {
+                    assert_eq!(default_hash(&borrowed_a), default_hash(&borrowed_b));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_version_ref_negative() {
+        let overflow = format!("1.{}0", u64::MAX);
+        let versions = vec![
+            "",
+            "1.0 junk",
+            "1.0+",
+            "4294967296!1.0",
+            "1.0rc4294967296",
+            overflow.as_str(),
+        ];
+
+        for version in versions {
+            match PackageVersionRef::new(version) {
+                Ok(v) => panic!("Oh no {}", v),
+                Err(_e) => continue,
+            }
+        }
+    }
+}
@@ -0,0 +1,212 @@
+//! JavaScript bindings built with `wasm-bindgen`, for ordering and
+//! matching versions in the browser or Node exactly like pip does
+//!
+//! ```js
+//! import { compare, satisfies, sortVersions, Version } from "pyver";
+//!
+//! compare("1.0rc1", "1.0"); // -1
+//! satisfies("1.5", ">=1.0, <2.0"); // true
+//! sortVersions(["1.0", "1.0rc1", "0.9.post1"]); // ["0.9.post1", "1.0rc1", "1.0"]
+//! new Version("v1.0-RC1").toString(); // "1.0rc1"
+//! ```
+//!
+//! Invalid versions and specifiers throw an `Error`.
+
+use crate::collections::sort_versions;
+use crate::specifiers::SpecifierSet;
+use crate::PackageVersion;
+use core::cmp::Ordering;
+use wasm_bindgen::prelude::*;
+
+fn parse(version: &str) -> Result<PackageVersion, JsError> {
+    PackageVersion::new(version).map_err(|e| JsError::new(&e.to_string()))
+}
+
+fn ordering(ordering: Ordering) -> i32 {
+    match ordering {
+        Ordering::Less => -1,
+        Ordering::Equal => 0,
+        Ordering::Greater => 1,
+    }
+}
+
+/// Whether the string is a valid version
+#[wasm_bindgen(js_name = isValid)]
+pub fn is_valid(version: &str) -> bool {
+    PackageVersion::new(version).is_ok()
+}
+
+/// The version in normal form (`1.0rc1` for `v1.0-RC1`)
+#[wasm_bindgen]
+pub fn normalize(version: &str) -> Result<String, JsError> {
+    Ok(parse(version)?.to_normalized_string())
+}
+
+/// `-1`, `0` or `1` as `a` sorts before, the same as or after `b`,
+/// so it can be passed to `Array.prototype.sort`
+#[wasm_bindgen]
+pub fn compare(a: &str, b: &str) -> Result<i32, JsError> {
+    Ok(ordering(parse(a)?.cmp(&parse(b)?)))
+}
+
+/// Whether the version matches every specifier (`>=1.0, <2.0`)
+#[wasm_bindgen]
+pub fn satisfies(version: &str, specifiers: &str) -> Result<bool, JsError> {
+    let set =
+        SpecifierSet::new(specifiers).map_err(|e| JsError::new(&e.to_string()))?;
+    Ok(set.contains(&parse(version)?))
+}
+
+/// The versions from oldest to newest as written, leaving out invalid ones
+#[wasm_bindgen(js_name = sortVersions)]
+pub fn sort_versions_js(versions: Vec<String>) -> Vec<String> {
+    sort_versions(versions.iter().map(String::as_str))
+        .value
+        .iter()
+        .map(|version| version.to_string())
+        .collect()
+}
+
+/// # JavaScript version
+/// A [`PackageVersion`] as the JavaScript class `Version`
+#[wasm_bindgen(js_name = Version)]
+pub struct JsVersion(PackageVersion);
+
+#[wasm_bindgen(js_class = Version)]
+impl JsVersion {
+    #[wasm_bindgen(constructor)]
+    pub fn new(version: &str) -> Result<JsVersion, JsError> {
+        parse(version).map(Self)
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn epoch(&self) -> u32 {
+        self.0.epoch()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn release(&self) -> Vec<u64> {
+        self.0.release().segments().to_vec()
+    }
+
+    #[wasm_bindgen(getter, js_name = isPrerelease)]
+    pub fn is_prerelease(&self) -> bool {
+        self.0.is_prerelease()
+    }
+
+    #[wasm_bindgen(getter, js_name = isPostrelease)]
+    pub fn is_postrelease(&self) -> bool {
+        self.0.is_postrelease()
+    }
+
+    #[wasm_bindgen(getter, js_name = isDevrelease)]
+    pub fn is_devrelease(&self) -> bool {
+        self.0.is_devrelease()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn local(&self) -> Option<String> {
+        self.0.local().map(|local| local.to_string())
+    }
+
+    /// `-1`, `0` or `1` as this version sorts before, the same as
+    /// or after `other`
+    pub fn compare(&self, other: &JsVersion) -> i32 {
+        ordering(self.0.cmp(&other.0))
+    }
+
+    pub fn equals(&self, other: &JsVersion) -> bool {
+        self.0 == other.0
+    }
+
+    /// The version in normal form
+    #[wasm_bindgen(js_name = toString)]
+    pub fn to_string_js(&self) -> String {
+        self.0.to_normalized_string()
+    }
+}
+
+/// # JavaScript specifier set
+/// A [`SpecifierSet`] as the JavaScript class `SpecifierSet`
+#[wasm_bindgen(js_name = SpecifierSet)]
+pub struct JsSpecifierSet(SpecifierSet);
+
+#[wasm_bindgen(js_class = SpecifierSet)]
+impl JsSpecifierSet {
+    #[wasm_bindgen(constructor)]
+    pub fn new(specifiers: &str) -> Result<JsSpecifierSet, JsError> {
+        SpecifierSet::new(specifiers)
+            .map(Self)
+            .map_err(|e| JsError::new(&e.to_string()))
+    }
+
+    /// Whether a version string matches, an invalid one never does
+    pub fn contains(&self, version: &str) -> bool {
+        PackageVersion::new(version).is_ok_and(|version| self.0.contains(&version))
+    }
+
+    /// The matching version strings, pre-releases only if nothing
+    /// else matches, see [`SpecifierSet::filter`]
+    pub fn filter(&self, versions: Vec<String>) -> Vec<String> {
+        let parsed: Vec<(PackageVersion, String)> = versions
+            .into_iter()
+            .filter_map(|version| Some((PackageVersion::new(&version).ok()?, version)))
+            .collect();
+        let matching: Vec<&PackageVersion> = self
+            .0
+            .filter(parsed.iter().map(|(version, _)| version))
+            .collect();
+        parsed
+            .iter()
+            .filter(|(version, _)| matching.iter().any(|m| core::ptr::eq(*m, version)))
+            .map(|(_, written)| written.clone())
+            .collect()
+    }
+
+    #[wasm_bindgen(js_name = toString)]
+    pub fn to_string_js(&self) -> String {
+        self.0.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{compare, is_valid, normalize, satisfies, sort_versions_js};
+    use super::{JsSpecifierSet, JsVersion};
+
+    #[test]
+    fn test_wasm_functions() {
+        assert!(is_valid("v1.0-RC1") && !is_valid("1.0 junk"));
+        assert_eq!(normalize("v1.0-RC1").unwrap(), "1.0rc1");
+        assert_eq!(compare("1.0rc1", "1.0").unwrap(), -1);
+        assert_eq!(compare("1.0", "1.0.0").unwrap(), 0);
+        assert_eq!(compare("1.0.post1", "1.0").unwrap(), 1);
+        assert!(satisfies("1.5", ">=1.0, <2.0").unwrap());
+        assert!(!satisfies("1.5rc1", ">=1.0, <2.0").unwrap());
+
+        let versions = ["1.0", "1.0rc1", "junk", "0.9.post1"];
+        assert_eq!(
+            sort_versions_js(versions.iter().map(|v| v.to_string()).collect()),
+            vec!["0.9.post1", "1.0rc1", "1.0"]
+        );
+    }
+
+    #[test]
+    fn test_wasm_classes() {
+        let version = JsVersion::new("v1!2.0rc1+Local").unwrap();
+        assert_eq!(version.to_string_js(), "1!2.0rc1+local");
+        assert_eq!((version.epoch(), version.release()), (1, vec![2, 0]));
+        assert!(version.is_prerelease() && !version.is_devrelease());
+        assert_eq!(version.local().as_deref(), Some("Local"));
+
+        let other = JsVersion::new("2.0").unwrap();
+        assert_eq!(version.compare(&other), 1);
+        assert!(other.equals(&JsVersion::new("2.0.0").unwrap()));
+
+        let set = JsSpecifierSet::new(">1.0, !=1.1").unwrap();
+        assert!(set.contains("1.2") && !set.contains("junk"));
+        let versions = vec!["0.9".to_string(), "1.1rc1".into(), "junk".into()];
+        assert_eq!(set.filter(versions), vec!["1.1rc1"]);
+        assert_eq!(set.to_string_js(), ">1.0,!=1.1");
+    }
+}